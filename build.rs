@@ -0,0 +1,29 @@
+//! Records the commit the tool is built from, so that results can be traced
+//! back to the code that produced them. `EMBARK_OSS_GIT_SHA` can be set
+//! instead when building outside of a git checkout.
+
+use std::{path::Path, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=EMBARK_OSS_GIT_SHA");
+    if std::env::var_os("EMBARK_OSS_GIT_SHA").is_some() {
+        return;
+    }
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).output().ok()?;
+        let output = String::from_utf8(output.stdout).ok()?;
+        Some(output.trim().to_string()).filter(|output| !output.is_empty())
+    };
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        // Rebuild when a commit is made or checked out
+        for path in ["HEAD", "refs/heads", "packed-refs"] {
+            let path = Path::new(&git_dir).join(path);
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+    if let Some(sha) = git(&["rev-parse", "HEAD"]) {
+        println!("cargo:rustc-env=EMBARK_OSS_GIT_SHA={}", sha);
+    }
+}
@@ -0,0 +1,158 @@
+//! Incidents in an alerting service for failing checks, for organisations
+//! that treat regressions in their open source projects as operational
+//! incidents, and heartbeats for monitoring that runs happen at all.
+
+use crate::{config::Alerting, http::Traced};
+use eyre::{eyre, WrapErr};
+use serde_json::json;
+use std::time::Duration;
+
+/// How long to wait for a heartbeat to be received, so that a monitoring
+/// service that is down doesn't hold up the run.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+const OPSGENIE_API_URL: &str = "https://api.opsgenie.com";
+
+/// Identifies the incident for a check of a project, so that it can be
+/// resolved when the check passes again.
+pub fn incident_key(project: &str, check: &str) -> String {
+    format!("embark-oss:{}:{}", project, check)
+}
+
+/// Open an incident for a failing check. Services merge incidents with the
+/// same key so opening one that is already open does no harm.
+pub async fn trigger(
+    config: &Alerting,
+    project: &str,
+    check: &str,
+    error: &str,
+) -> eyre::Result<()> {
+    let key = incident_key(project, check);
+    let summary = format!("{} fails the {} check", project, check);
+    let request = match config {
+        Alerting::Pagerduty { routing_key_env } => {
+            let body = json!({
+                "routing_key": secret(routing_key_env)?,
+                "event_action": "trigger",
+                "dedup_key": key,
+                "payload": {
+                    "summary": summary,
+                    "source": "embark-oss",
+                    "severity": "warning",
+                    "custom_details": { "error": error },
+                },
+            });
+            Request::new(PAGERDUTY_EVENTS_URL.to_string(), None, body)
+        }
+        Alerting::Opsgenie {
+            api_key_env,
+            api_url,
+        } => {
+            let url = format!("{}/v2/alerts", opsgenie_url(api_url));
+            let body = json!({
+                "message": summary,
+                "alias": key,
+                "description": error,
+                "source": "embark-oss",
+            });
+            Request::new(url, Some(secret(api_key_env)?), body)
+        }
+    };
+    request
+        .send(&format!("open incident {}", key))
+        .await
+        .wrap_err_with(|| format!("Unable to open incident {}", key))
+}
+
+/// Resolve the incident for a check that passes again.
+pub async fn resolve(config: &Alerting, project: &str, check: &str) -> eyre::Result<()> {
+    let key = incident_key(project, check);
+    let request = match config {
+        Alerting::Pagerduty { routing_key_env } => {
+            let body = json!({
+                "routing_key": secret(routing_key_env)?,
+                "event_action": "resolve",
+                "dedup_key": key,
+            });
+            Request::new(PAGERDUTY_EVENTS_URL.to_string(), None, body)
+        }
+        Alerting::Opsgenie {
+            api_key_env,
+            api_url,
+        } => {
+            let url = format!(
+                "{}/v2/alerts/{}/close?identifierType=alias",
+                opsgenie_url(api_url),
+                key
+            );
+            let body = json!({ "source": "embark-oss" });
+            Request::new(url, Some(secret(api_key_env)?), body)
+        }
+    };
+    request
+        .send(&format!("resolve incident {}", key))
+        .await
+        .wrap_err_with(|| format!("Unable to resolve incident {}", key))
+}
+
+/// Ping a dead man's switch such as healthchecks.io, which alerts when it
+/// hasn't been pinged for a while because runs stopped completing.
+pub async fn heartbeat(url: &str) -> eyre::Result<()> {
+    if crate::dry_run::is_enabled() {
+        // The URL identifies the check, so anyone with it could ping it
+        crate::dry_run::print_skipped("ping heartbeat", &json!({ "url": "<redacted>" }));
+        return Ok(());
+    }
+    let _ = crate::http::client()
+        .get(url)
+        .timeout(HEARTBEAT_TIMEOUT)
+        .send_traced()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .wrap_err("Unable to ping the heartbeat URL")?;
+    Ok(())
+}
+
+fn secret(env: &str) -> eyre::Result<String> {
+    std::env::var(env).map_err(|_| eyre!("The {} environment variable is not set", env))
+}
+
+fn opsgenie_url(api_url: &Option<String>) -> &str {
+    api_url
+        .as_deref()
+        .unwrap_or(OPSGENIE_API_URL)
+        .trim_end_matches('/')
+}
+
+struct Request {
+    url: String,
+    /// The API key for services that take it in a header rather than the body.
+    api_key: Option<String>,
+    body: serde_json::Value,
+}
+
+impl Request {
+    fn new(url: String, api_key: Option<String>, body: serde_json::Value) -> Self {
+        Self { url, api_key, body }
+    }
+
+    async fn send(self, action: &str) -> eyre::Result<()> {
+        if crate::dry_run::is_enabled() {
+            // Keep the routing key out of the printed payload
+            let mut body = self.body;
+            if let Some(key) = body.get_mut("routing_key") {
+                *key = json!("<redacted>");
+            }
+            crate::dry_run::print_skipped(action, &body);
+            return Ok(());
+        }
+
+        let mut request = crate::http::client().post(&self.url).json(&self.body);
+        if let Some(key) = self.api_key {
+            request = request.header("Authorization", format!("GenieKey {}", key));
+        }
+        let _ = request.send_traced().await?.error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,2694 @@
+//! Configuration loaded from an `embark-oss.toml` file, for settings that
+//! may differ between organisations or change more frequently than the code.
+
+use crate::template::Template;
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The file configuration is read from when no path is given explicitly.
+const DEFAULT_PATH: &str = "embark-oss.toml";
+
+/// The organisation validated when no others are configured.
+pub const DEFAULT_ORGANISATION: &str = "EmbarkStudios";
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub organisations: Vec<Organisation>,
+    pub org_defaults: OrgDefaults,
+    pub branches: Branches,
+    pub tiers: Tiers,
+    pub scorecard: Scorecard,
+    pub codeowners: CodeOwnersPolicy,
+    pub required_files: Vec<RequiredFiles>,
+    pub external_checks: Vec<ExternalCheck>,
+    pub wasm_checks: Vec<WasmCheck>,
+    pub http: HttpConfig,
+    pub checks: CheckSettings,
+    pub email: Option<EmailConfig>,
+    pub matrix: Option<MatrixConfig>,
+    pub slack_routes: Vec<SlackRoute>,
+    pub notifications: Notifications,
+    pub slack_templates: SlackTemplates,
+    pub slack_attachment: Option<SlackAttachment>,
+    pub digest_post: Option<DigestPost>,
+    pub escalation: Option<Escalation>,
+    pub celebrations: Option<Celebrations>,
+    pub exemptions: ExemptionPolicy,
+    pub expiring_exemptions: ExpiringExemptions,
+    pub check_runs: Option<CheckRuns>,
+    pub alerting: Option<Alerting>,
+    pub telemetry: Option<Telemetry>,
+    pub result_cache: Option<ResultCache>,
+    pub result_upload: Option<ResultUpload>,
+    pub topics: Option<TopicsPolicy>,
+    pub workflow_audit: Option<WorkflowAudit>,
+    pub actions_pinning: Option<ActionsPinning>,
+    pub secret_scanning: Option<SecretScanning>,
+    pub dependabot: Option<Dependabot>,
+    pub signed_releases: Option<SignedReleases>,
+    pub contribution_agreement: Option<ContributionAgreementPolicy>,
+    pub coverage: Option<Coverage>,
+    pub secret_patterns: Option<SecretPatterns>,
+    pub internal_references: Option<InternalReferences>,
+    pub readme_links: Option<ReadmeLinks>,
+    pub good_first_issues: Option<GoodFirstIssues>,
+    pub issue_response: Option<IssueResponse>,
+    pub release_drift: Option<ReleaseDrift>,
+    pub cargo_metadata: Option<CargoMetadata>,
+    pub msrv: Option<Msrv>,
+    pub unsafe_code: Option<UnsafeCode>,
+    pub license_year: Option<LicenseYear>,
+    pub third_party_licenses: Option<ThirdPartyLicenses>,
+    pub release_checksums: Option<ReleaseChecksums>,
+    pub container_images: Option<ContainerImages>,
+    pub install_instructions: Option<InstallInstructions>,
+    pub first_time_contributors: Option<FirstTimeContributors>,
+    pub package_json: Option<PackageJson>,
+    pub cpp_license_headers: Option<CppLicenseHeaders>,
+    pub template_drift: Option<TemplateDrift>,
+    pub maintenance_banner: Option<MaintenanceBanner>,
+    pub badges: Option<Badges>,
+    pub preflight: Preflight,
+    /// The SHA-256 of the config file, or `None` for the default config.
+    #[serde(skip)]
+    pub hash: Option<String>,
+}
+
+/// Settings for the `cpp-license-headers` check of C++ projects.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CppLicenseHeaders {
+    /// A regular expression matching a line of the license header, which must
+    /// be within the first lines of each sampled file.
+    pub header: Pattern,
+    /// How many source files to check.
+    pub sample: usize,
+    /// Globs matching paths of vendored code, which keeps its own headers.
+    pub exclude: Vec<Glob>,
+    /// Whether a .clang-format must be in the root of the repo.
+    pub clang_format: bool,
+}
+
+impl Default for CppLicenseHeaders {
+    fn default() -> Self {
+        let glob = |glob: &str| Glob::try_from(glob.to_string()).unwrap();
+        Self {
+            header: Pattern(Regex::new(r"(?i)copyright|SPDX-License-Identifier").unwrap()),
+            sample: 10,
+            exclude: vec![
+                glob("third_party/*"),
+                glob("external/*"),
+                glob("extern/*"),
+                glob("vendor/*"),
+            ],
+            clang_format: true,
+        }
+    }
+}
+
+/// Settings for the `template-drift` check of boilerplate files.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TemplateDrift {
+    /// The template repo, as `owner/name`.
+    pub repo: String,
+    /// The branch of the template repo to compare with.
+    pub branch: String,
+    /// The paths of the boilerplate files, which are compared with those of
+    /// the template if the project has them.
+    pub files: Vec<String>,
+    /// How many lines of a file may be added or removed before it has
+    /// drifted from the template.
+    pub max_changed_lines: usize,
+    /// How many pull requests `cargo run sync-templates` opens in one run.
+    pub sync_limit: usize,
+}
+
+impl Default for TemplateDrift {
+    fn default() -> Self {
+        Self {
+            repo: "EmbarkStudios/opensource-template".to_string(),
+            branch: "main".to_string(),
+            files: vec![
+                ".github/ISSUE_TEMPLATE/bug_report.md".to_string(),
+                ".github/ISSUE_TEMPLATE/feature_request.md".to_string(),
+                ".github/pull_request_template.md".to_string(),
+                "CODE_OF_CONDUCT.md".to_string(),
+            ],
+            max_changed_lines: 0,
+            sync_limit: 10,
+        }
+    }
+}
+
+/// Settings for the `maintenance-banner` check of READMEs, whose banners are
+/// also used by `cargo run archive`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct MaintenanceBanner {
+    /// A regular expression matching the banner of actively developed
+    /// projects.
+    pub active: Pattern,
+    /// A regular expression matching the banner of projects in maintenance
+    /// mode, which only get fixes.
+    pub maintenance: Pattern,
+    /// A regular expression matching the banner of archived projects.
+    pub archived: Pattern,
+    /// How many days a repo may go without a push before its banner can no
+    /// longer say it is actively developed.
+    pub inactive_days: u64,
+}
+
+impl Default for MaintenanceBanner {
+    fn default() -> Self {
+        let pattern = |status: &str| {
+            Pattern(
+                Regex::new(&format!(
+                    r"(?i)img\.shields\.io/badge/maintenance-({})",
+                    status
+                ))
+                .unwrap(),
+            )
+        };
+        Self {
+            active: pattern("actively--developed"),
+            maintenance: pattern("passively--maintained|maintenance--mode"),
+            archived: pattern("archived|as--is"),
+            inactive_days: 365,
+        }
+    }
+}
+
+/// Where the standard badges of READMEs are defined, for the `badges` check
+/// and the banners of the `maintenance-banner` check.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Badges {
+    /// The repo of the badge definitions, as `owner/name`.
+    pub repo: String,
+    pub branch: String,
+    /// The path of the badge definitions in the repo, see `badges.toml`.
+    pub path: String,
+}
+
+impl Default for Badges {
+    fn default() -> Self {
+        Self {
+            repo: "EmbarkStudios/opensource".to_string(),
+            branch: "main".to_string(),
+            path: "badges.toml".to_string(),
+        }
+    }
+}
+
+/// Settings for the `package-json` check of JavaScript and TypeScript
+/// projects.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PackageJson {
+    /// The fields packages that aren't `private` must set.
+    pub fields: Vec<String>,
+    /// Whether a lockfile must be committed.
+    pub lockfile: bool,
+}
+
+impl Default for PackageJson {
+    fn default() -> Self {
+        Self {
+            fields: vec!["license".to_string(), "repository".to_string()],
+            lockfile: true,
+        }
+    }
+}
+
+/// Settings for the `first-time-contributors` check, which flags projects
+/// that leave pull requests from new contributors without a response.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FirstTimeContributors {
+    /// The check only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// How long a maintainer has to respond to a pull request.
+    pub max_wait_days: u64,
+    /// How far back to look at pull requests.
+    pub window_days: u64,
+}
+
+impl Default for FirstTimeContributors {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            max_wait_days: 7,
+            window_days: 90,
+        }
+    }
+}
+
+impl FirstTimeContributors {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    pub fn max_wait(&self) -> Duration {
+        Duration::from_secs(self.max_wait_days * 24 * 60 * 60)
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_days * 24 * 60 * 60)
+    }
+}
+
+/// Settings for the `install-instructions` check of command line tools.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct InstallInstructions {
+    /// The check applies to projects with one of these tags in the opensource
+    /// website data. If empty it applies to Rust projects that build a
+    /// binary.
+    pub tags: Vec<String>,
+}
+
+/// Settings for the `container-images` check of projects with a Dockerfile.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ContainerImages {
+    /// The policy only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// The registry namespaces images must be published to, such as
+    /// `ghcr.io/embarkstudios`.
+    pub namespaces: Vec<String>,
+}
+
+impl Default for ContainerImages {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            namespaces: vec!["ghcr.io/embarkstudios".to_string()],
+        }
+    }
+}
+
+impl ContainerImages {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    /// Whether an image, including its registry, is in an allowed namespace.
+    pub fn allows(&self, image: &str) -> bool {
+        let image = image.to_ascii_lowercase();
+        self.namespaces.iter().any(|namespace| {
+            let namespace = namespace.trim_end_matches('/').to_ascii_lowercase();
+            image.starts_with(&format!("{}/", namespace))
+        })
+    }
+}
+
+/// Settings for the `release-checksums` check of release assets.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReleaseChecksums {
+    /// The policy only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// Whether every binary asset must also be signed.
+    pub require_signatures: bool,
+}
+
+impl ReleaseChecksums {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `third-party-licenses` check of projects that release
+/// binaries, which must distribute the licenses of their dependencies.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ThirdPartyLicenses {
+    /// The policy only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// Globs matching the lowercased names of release assets that list
+    /// third-party licenses.
+    pub assets: Vec<Glob>,
+    /// Globs matching the lowercased paths of files in the repo that list
+    /// third-party licenses, or that they are generated from.
+    pub files: Vec<Glob>,
+}
+
+impl Default for ThirdPartyLicenses {
+    fn default() -> Self {
+        let globs = |globs: &[&str]| {
+            globs
+                .iter()
+                .map(|glob| Glob::try_from(glob.to_string()).unwrap())
+                .collect()
+        };
+        Self {
+            tags: Vec::new(),
+            assets: globs(&["*third-party*", "*third_party*", "*licenses*"]),
+            files: globs(&[
+                "about.toml",
+                "*/about.toml",
+                "*third-party-licenses*",
+                "*third_party_licenses*",
+            ]),
+        }
+    }
+}
+
+impl ThirdPartyLicenses {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `license-year` check of copyright years.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LicenseYear {
+    /// The files whose copyright years are checked.
+    pub files: Vec<String>,
+}
+
+impl Default for LicenseYear {
+    fn default() -> Self {
+        Self {
+            files: vec![
+                "LICENSE".to_string(),
+                "LICENSE-MIT".to_string(),
+                "LICENSE-APACHE".to_string(),
+                "COPYRIGHT".to_string(),
+            ],
+        }
+    }
+}
+
+/// Settings for the `unsafe-code` check, for the projects that must forbid
+/// unsafe code or document how they use it.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UnsafeCode {
+    /// Globs matching the names of the projects the policy applies to.
+    pub projects: Vec<Glob>,
+    /// The policy also applies to projects with one of these tags in the
+    /// opensource website data. It applies to every project if both are
+    /// empty.
+    pub tags: Vec<String>,
+}
+
+impl UnsafeCode {
+    pub fn applies_to(&self, name: &str, tags: &HashSet<String>) -> bool {
+        (self.projects.is_empty() && self.tags.is_empty())
+            || self.projects.iter().any(|glob| glob.matches(name))
+            || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `msrv` check of Rust projects.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Msrv {
+    /// Report problems as warnings rather than failing projects, while
+    /// projects are given time to declare their MSRV.
+    pub warn_only: bool,
+}
+
+impl Default for Msrv {
+    fn default() -> Self {
+        Self { warn_only: true }
+    }
+}
+
+/// Settings for the `cargo-metadata` check of the crates in Rust projects.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CargoMetadata {
+    /// The `[package]` fields publishable crates must set. A `license-file`
+    /// counts as a `license`, and `readme` requires the README to exist.
+    pub fields: Vec<String>,
+}
+
+impl Default for CargoMetadata {
+    fn default() -> Self {
+        Self {
+            fields: vec![
+                "license".to_string(),
+                "description".to_string(),
+                "readme".to_string(),
+            ],
+        }
+    }
+}
+
+/// Settings for the `release-drift` check, which encourages projects to
+/// release regularly.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReleaseDrift {
+    /// The check only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// How many commits the default branch may be ahead of the latest
+    /// release.
+    pub max_commits: u64,
+}
+
+impl Default for ReleaseDrift {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            max_commits: 100,
+        }
+    }
+}
+
+impl ReleaseDrift {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `issue-response` check, which flags projects whose
+/// maintainers are slow to respond to issues.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct IssueResponse {
+    /// The check only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// The longest the median time to a maintainer's first response may be.
+    pub max_median_hours: u64,
+    /// How far back to look at issues.
+    pub window_days: u64,
+    /// Projects with fewer issues in the window than this pass, as there
+    /// are too few for the median to mean much.
+    pub minimum_issues: usize,
+}
+
+impl Default for IssueResponse {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            max_median_hours: 72,
+            window_days: 90,
+            minimum_issues: 3,
+        }
+    }
+}
+
+impl IssueResponse {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    pub fn max_median(&self) -> Duration {
+        Duration::from_secs(self.max_median_hours * 60 * 60)
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_days * 24 * 60 * 60)
+    }
+}
+
+/// Settings for the `good-first-issues` check, which keeps projects welcoming
+/// to new contributors.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GoodFirstIssues {
+    /// The check only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// The label of issues suitable for new contributors.
+    pub label: String,
+    /// How many open issues must have the label.
+    pub minimum: usize,
+}
+
+impl Default for GoodFirstIssues {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            label: "good first issue".to_string(),
+            minimum: 1,
+        }
+    }
+}
+
+impl GoodFirstIssues {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `readme-links` check, which requests every link in
+/// projects' READMEs.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReadmeLinks {
+    /// Globs matching links that are not checked, such as of sites that
+    /// reject automated requests.
+    pub ignore: Vec<Glob>,
+    /// How many links are requested at once.
+    pub concurrency: usize,
+    /// How long to wait for a response before giving up on a link.
+    pub timeout_seconds: u64,
+}
+
+impl Default for ReadmeLinks {
+    fn default() -> Self {
+        Self {
+            ignore: Vec::new(),
+            concurrency: 8,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+/// Settings for the `internal-references` check, which looks for mentions of
+/// things internal to the organisation in the files most often copied from
+/// internal repos.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct InternalReferences {
+    /// Host names that are only reachable inside the organisation, matched
+    /// along with their subdomains.
+    pub hostnames: Vec<String>,
+    /// Regular expressions such as for the URLs of an internal ticket system.
+    pub patterns: Vec<Pattern>,
+    /// Internal code names, matched as whole words ignoring case.
+    pub code_names: Vec<String>,
+    /// The files scanned in addition to the workflows.
+    pub files: Vec<String>,
+}
+
+impl Default for InternalReferences {
+    fn default() -> Self {
+        Self {
+            hostnames: Vec::new(),
+            patterns: Vec::new(),
+            code_names: Vec::new(),
+            files: vec![
+                "README.md".to_string(),
+                "CONTRIBUTING.md".to_string(),
+                "Cargo.toml".to_string(),
+                "package.json".to_string(),
+                ".cargo/config.toml".to_string(),
+            ],
+        }
+    }
+}
+
+/// Settings for the `secret-patterns` check, which scans every file of a
+/// repo for likely credentials.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SecretPatterns {
+    /// Globs matching the paths of files not to scan, such as test fixtures
+    /// with fake keys.
+    pub ignore: Vec<Glob>,
+    /// Files larger than this many bytes are not scanned.
+    pub max_file_size: u64,
+}
+
+impl Default for SecretPatterns {
+    fn default() -> Self {
+        Self {
+            ignore: Vec::new(),
+            max_file_size: 1_000_000,
+        }
+    }
+}
+
+/// Settings for the `preflight` command, run before a repo is open sourced.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Preflight {
+    /// Host names that are only reachable inside the organisation, which
+    /// must not be mentioned in the files of a public repo.
+    pub internal_hostnames: Vec<String>,
+    /// Files larger than this many bytes are not scanned.
+    pub max_file_size: u64,
+}
+
+impl Default for Preflight {
+    fn default() -> Self {
+        Self {
+            internal_hostnames: Vec::new(),
+            max_file_size: 1_000_000,
+        }
+    }
+}
+
+/// Settings for the `coverage` check of Rust projects.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Coverage {
+    /// Globs matching the names of the projects that must report coverage,
+    /// or every Rust project if empty.
+    pub projects: Vec<Glob>,
+}
+
+impl Coverage {
+    pub fn applies_to(&self, name: &str, tags: &HashSet<String>) -> bool {
+        tags.contains("rust")
+            && (self.projects.is_empty() || self.projects.iter().any(|glob| glob.matches(name)))
+    }
+}
+
+/// Settings for the `contribution-agreement` check.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ContributionAgreementPolicy {
+    /// The agreement projects use unless they are listed in `projects`.
+    pub default: ContributionAgreement,
+    /// The agreement of individual projects, by name.
+    pub projects: BTreeMap<String, ContributionAgreement>,
+}
+
+impl ContributionAgreementPolicy {
+    pub fn agreement(&self, project: &str) -> ContributionAgreement {
+        self.projects.get(project).copied().unwrap_or(self.default)
+    }
+}
+
+/// How contributors agree to the terms their contributions are made under.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContributionAgreement {
+    /// The Developer Certificate of Origin, enforced by the DCO app.
+    #[default]
+    Dco,
+    /// A Contributor License Agreement, enforced by CLA assistant.
+    Cla,
+    /// Contributions don't need an agreement.
+    None,
+}
+
+/// Settings for the `signed-releases` check.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SignedReleases {
+    /// The policy only applies to projects with one of these tags in the
+    /// opensource website data, or to every project if empty.
+    pub tags: Vec<String>,
+    /// Whether branch protection of the default branch must also require
+    /// signed commits. This requires admin access to the repos.
+    pub require_signed_commits: bool,
+}
+
+impl SignedReleases {
+    pub fn applies_to(&self, tags: &HashSet<String>) -> bool {
+        self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// Settings for the `dependabot` check, which requires Dependabot alerts to
+/// be enabled. Reading the settings of a repo requires an API token or
+/// GitHub App with admin access to it.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Dependabot {
+    /// Whether security updates, pull requests that fix vulnerable
+    /// dependencies, must also be enabled.
+    pub security_updates: bool,
+}
+
+impl Default for Dependabot {
+    fn default() -> Self {
+        Self {
+            security_updates: true,
+        }
+    }
+}
+
+/// Settings for the `secret-scanning` check. Reading the settings of a repo
+/// requires an API token or GitHub App with admin access to it.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SecretScanning {
+    /// Whether push protection, which blocks pushes containing secrets, must
+    /// also be enabled.
+    pub push_protection: bool,
+}
+
+impl Default for SecretScanning {
+    fn default() -> Self {
+        Self {
+            push_protection: true,
+        }
+    }
+}
+
+/// Settings for the `actions-pinning` check, which requires actions used in
+/// workflows to be pinned to full commit SHAs. The `workflow-audit` check
+/// uses the same allowed actions.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ActionsPinning {
+    /// Globs matching actions that may be used without being pinned, such as
+    /// `actions/*`.
+    pub allowed: Vec<Glob>,
+}
+
+impl Default for ActionsPinning {
+    fn default() -> Self {
+        let glob = |glob: &str| Glob::try_from(glob.to_string()).unwrap();
+        Self {
+            allowed: vec![glob("actions/*"), glob("github/*"), glob("EmbarkStudios/*")],
+        }
+    }
+}
+
+/// Settings for the `workflow-audit` check of GitHub Actions workflows, which
+/// has none of its own. Its actions that may be used without being pinned are
+/// those of `ActionsPinning`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WorkflowAudit {}
+
+/// The GitHub topics repos must and must not have, checked by the `topics`
+/// check.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TopicsPolicy {
+    /// Topics every repo must have.
+    pub required: Vec<String>,
+    /// Topics repos with a tag in the opensource website data must have.
+    pub required_by_tag: BTreeMap<String, Vec<String>>,
+    /// Topics no repo may have.
+    pub forbidden: Vec<String>,
+}
+
+/// Reuse the results of projects whose default branch has not moved since
+/// they were last validated, storing them with `--publish-results`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ResultCache {
+    /// How many hours results are reused for, so that changes outside the
+    /// project such as organisation membership are eventually noticed.
+    #[serde(default = "default_cache_max_age_hours")]
+    pub max_age_hours: u64,
+}
+
+fn default_cache_max_age_hours() -> u64 {
+    7 * 24
+}
+
+impl ResultCache {
+    pub fn max_age(&self) -> Duration {
+        Duration::from_secs(self.max_age_hours * 60 * 60)
+    }
+}
+
+/// A bucket that `validate-all` uploads its JSON and HTML reports to after
+/// each run, with the credentials of the environment it runs in.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(tag = "provider", deny_unknown_fields, rename_all = "kebab-case")]
+pub enum ResultUpload {
+    #[serde(rename_all = "kebab-case")]
+    S3 {
+        bucket: String,
+        region: String,
+        /// Prepended to the name of each uploaded file, such as `reports/`.
+        #[serde(default)]
+        prefix: String,
+        /// The endpoint of an S3 compatible store to use instead of AWS.
+        endpoint: Option<String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    Gcs {
+        bucket: String,
+        /// Prepended to the name of each uploaded file, such as `reports/`.
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+/// Export of OpenTelemetry traces of `validate-all` runs over OTLP/HTTP.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Telemetry {
+    /// The base URL of the collector, such as `http://localhost:4318`.
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT` is used if not given.
+    pub endpoint: Option<String>,
+    /// The environment variable holding headers to send with each export,
+    /// as comma separated `name=value` pairs.
+    pub headers_env: Option<String>,
+    pub service_name: String,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            headers_env: None,
+            service_name: "embark-oss".to_string(),
+        }
+    }
+}
+
+/// An alerting service in which `validate-all` opens an incident for each
+/// failing check of a project, and resolves it once the check passes.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "service", deny_unknown_fields, rename_all = "kebab-case")]
+pub enum Alerting {
+    #[serde(rename_all = "kebab-case")]
+    Pagerduty {
+        /// The environment variable holding the integration key of an
+        /// Events API v2 integration.
+        routing_key_env: String,
+    },
+    #[serde(rename_all = "kebab-case")]
+    Opsgenie {
+        /// The environment variable holding the key of an API integration.
+        api_key_env: String,
+        /// The API to use instead of the US one, such as
+        /// `https://api.eu.opsgenie.com`.
+        api_url: Option<String>,
+    },
+}
+
+/// Projects that keep failing the same check are escalated rather than
+/// included in the usual notifications, using the history of runs published
+/// with `--publish-results`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Escalation {
+    /// How many consecutive runs a check must fail for to be escalated.
+    pub after_runs: usize,
+    /// The environment variable holding the Slack webhook URL to send
+    /// escalations to. The usual destinations are used if not given.
+    pub webhook_url_env: Option<String>,
+    /// Text to start Slack escalations with, such as `<!here>`.
+    pub mention: Option<String>,
+}
+
+/// Posts to a Slack channel when projects start to conform to our guidelines
+/// or pass their first validation.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Celebrations {
+    /// The environment variable holding the Slack webhook URL to post to.
+    pub webhook_url_env: String,
+}
+
+/// Which checks repos may exempt themselves from in their exemptions file.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ExemptionPolicy {
+    /// The ids of the checks that may be exempted, where the id of a check
+    /// such as `required-files` also covers `required-files:license`. Every
+    /// check may be exempted when unset.
+    pub exemptable: Option<Vec<String>>,
+}
+
+impl ExemptionPolicy {
+    /// Whether repos may exempt themselves from the check.
+    pub fn allows(&self, check: &str) -> bool {
+        self.exemptable
+            .as_ref()
+            .is_none_or(|exemptable| exemptable.iter().any(|id| matches_check(id, check)))
+    }
+}
+
+/// Reports the exemptions that expire soon, and notifies about them on the
+/// schedule of the other notifications.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ExpiringExemptions {
+    /// How many days before it expires an exemption is reported.
+    pub within_days: u64,
+    /// The environment variable holding the Slack webhook URL to post to. The
+    /// `--slack-webhook-url` webhook is used otherwise.
+    pub webhook_url_env: Option<String>,
+}
+
+impl Default for ExpiringExemptions {
+    fn default() -> Self {
+        Self {
+            within_days: 30,
+            webhook_url_env: None,
+        }
+    }
+}
+
+/// Publishes the results of each project as a check run on the head of its
+/// default branch, so maintainers see them on their own repo.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CheckRuns {
+    /// The name of the check runs, as shown on commits.
+    pub name: String,
+}
+
+impl Default for CheckRuns {
+    fn default() -> Self {
+        Self {
+            name: "Open source guidelines".to_string(),
+        }
+    }
+}
+
+/// When notifications are sent by `validate-all`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Notifications {
+    pub schedule: Schedule,
+    /// How many days there are between digests.
+    pub digest_interval_days: u64,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            schedule: Schedule::default(),
+            digest_interval_days: 7,
+        }
+    }
+}
+
+impl Notifications {
+    pub fn digest_interval(&self) -> Duration {
+        Duration::from_secs(self.digest_interval_days * 24 * 60 * 60)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Schedule {
+    /// Notify about the failures of every run.
+    #[default]
+    Immediate,
+    /// Notify about the current failures once every digest interval, using
+    /// the history of runs published with `--publish-results`.
+    Digest,
+}
+
+/// The wording of Slack notifications, so that it can be changed without a
+/// new release of the tooling. The heads of each kind of notification use
+/// built-in wording unless a template is given.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SlackTemplates {
+    pub immediate: Option<Template>,
+    pub digest: Option<Template>,
+    pub escalation: Option<Template>,
+    pub unvalidated: Option<Template>,
+    pub celebration: Option<Template>,
+    /// The heading above the projects of each organisation, when a
+    /// notification has projects from several.
+    pub organisation: Template,
+    /// The block of a project with failures.
+    pub project: Template,
+    /// The block of a project without failures, in celebrations.
+    pub celebrated_project: Template,
+    pub foot: Template,
+}
+
+impl SlackTemplates {
+    /// The placeholders of the heads, and of the foot.
+    pub const HEAD_PLACEHOLDERS: &'static [&'static str] = &["count", "runs"];
+    pub const ORGANISATION_PLACEHOLDERS: &'static [&'static str] = &["organisation"];
+    pub const PROJECT_PLACEHOLDERS: &'static [&'static str] =
+        &["emoji", "url", "title", "name", "organisation", "errors"];
+
+    fn check(&self) -> eyre::Result<()> {
+        let heads = [
+            ("immediate", &self.immediate),
+            ("digest", &self.digest),
+            ("escalation", &self.escalation),
+            ("unvalidated", &self.unvalidated),
+            ("celebration", &self.celebration),
+        ];
+        for (name, head) in heads.iter() {
+            if let Some(head) = head {
+                head.check(Self::HEAD_PLACEHOLDERS)
+                    .wrap_err_with(|| format!("Invalid `slack-templates` `{}`", name))?;
+            }
+        }
+        let templates = [
+            (
+                "organisation",
+                &self.organisation,
+                Self::ORGANISATION_PLACEHOLDERS,
+            ),
+            ("project", &self.project, Self::PROJECT_PLACEHOLDERS),
+            (
+                "celebrated-project",
+                &self.celebrated_project,
+                Self::PROJECT_PLACEHOLDERS,
+            ),
+            ("foot", &self.foot, Self::HEAD_PLACEHOLDERS),
+        ];
+        for (name, template, placeholders) in templates.iter() {
+            template
+                .check(placeholders)
+                .wrap_err_with(|| format!("Invalid `slack-templates` `{}`", name))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for SlackTemplates {
+    fn default() -> Self {
+        let template = |source: &str| Template::parse(source).unwrap();
+        Self {
+            immediate: None,
+            digest: None,
+            escalation: None,
+            unvalidated: None,
+            celebration: None,
+            organisation: template("*{organisation}*"),
+            project: template("{emoji} *<{url}|{title}>*\n```{errors}```"),
+            celebrated_project: template("{emoji} *<{url}|{title}>*"),
+            foot: template(&crate::locale::text("notification-slack-foot", &[])),
+        }
+    }
+}
+
+/// Posts each digest in a repo as a discussion or an issue, giving a public,
+/// linkable record of the health of the projects over time.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DigestPost {
+    /// The repo to post in, as `owner/name`.
+    pub repo: String,
+    pub post_as: PostKind,
+    /// The name of the discussion category, for discussions.
+    pub category: String,
+    /// The labels of issues.
+    pub labels: Vec<String>,
+}
+
+impl Default for DigestPost {
+    fn default() -> Self {
+        Self {
+            repo: "EmbarkStudios/opensource".to_string(),
+            post_as: PostKind::default(),
+            category: "Reports".to_string(),
+            labels: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PostKind {
+    #[default]
+    Discussion,
+    Issue,
+}
+
+/// Attaches a machine-readable summary of the projects to Slack
+/// notifications, so that it can be piped into scripts.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SlackAttachment {
+    pub format: AttachmentFormat,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttachmentFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Sends Slack notifications about some projects to a different webhook
+/// than the one given with `--slack-webhook-url`, so that teams are alerted
+/// in their own channel.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SlackRoute {
+    /// The environment variable holding the webhook URL for the channel.
+    pub webhook_url_env: String,
+    /// Globs matching the names of the projects to route.
+    #[serde(default)]
+    pub projects: Vec<Glob>,
+    /// Projects with any of these tags in the opensource website data are
+    /// routed.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl SlackRoute {
+    pub fn matches(&self, name: &str, tags: &HashSet<String>) -> bool {
+        self.projects.iter().any(|glob| glob.matches(name))
+            || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+}
+
+/// A Matrix room to post notifications to.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct MatrixConfig {
+    /// The base URL of the homeserver, such as `https://matrix.example.com`.
+    pub homeserver: String,
+    /// The environment variable holding the access token of the account
+    /// that posts the messages.
+    pub access_token_env: String,
+    /// The internal id of the room, such as `!abcdefg:example.com`.
+    pub room_id: String,
+}
+
+/// An SMTP server to send email notifications through.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    /// The environment variable holding the password, so that it doesn't
+    /// have to be written in the config file.
+    pub password_env: Option<String>,
+    /// The sender, such as `Embark OSS <oss-bot@example.com>`.
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmtpTls {
+    /// Connect with TLS from the start, usually on port 465.
+    Implicit,
+    /// Upgrade a plain connection with STARTTLS, usually on port 587.
+    #[default]
+    Starttls,
+    /// Don't encrypt the connection, only for local testing.
+    None,
+}
+
+/// Settings for how every check is run.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CheckSettings {
+    /// How long a check that makes requests may take before it fails.
+    pub timeout_seconds: u64,
+}
+
+impl Default for CheckSettings {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 300,
+        }
+    }
+}
+
+impl CheckSettings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds)
+    }
+}
+
+/// Settings for the HTTP client, for use behind corporate proxies.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct HttpConfig {
+    /// The proxy to send all requests through, overriding `HTTPS_PROXY`.
+    pub proxy: Option<String>,
+    /// Comma separated hosts that bypass `proxy`, overriding `NO_PROXY`.
+    pub no_proxy: Option<String>,
+    /// A file of PEM encoded certificates to trust in addition to the
+    /// built in roots, for networks that intercept TLS.
+    pub ca_bundle: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CodeOwnersPolicy {
+    /// Whether maintainers may be listed by email address rather than by
+    /// GitHub handle. Email owners cannot be checked against organisation
+    /// membership so they are forbidden by default.
+    pub allow_email_owners: bool,
+}
+
+/// GitHub lets an organisation provide default community health files in a
+/// repository named `.github`, which are used for any repository that does
+/// not have its own.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct OrgDefaults {
+    /// Whether a file in the organisation's `.github` repository satisfies a
+    /// requirement for a project that does not have the file itself.
+    pub enabled: bool,
+    /// The branch of the `.github` repository to read default files from.
+    pub branch: String,
+}
+
+impl Default for OrgDefaults {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            branch: "main".to_string(),
+        }
+    }
+}
+
+/// A GitHub organisation whose projects are validated.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Organisation {
+    pub name: String,
+    /// The website data listing the organisation's projects, if it has one.
+    #[serde(default)]
+    pub website_data: Option<WebsiteData>,
+    /// Projects to validate even if they are not in the website data or are
+    /// not public repos.
+    #[serde(default)]
+    pub repos: Vec<String>,
+}
+
+impl Organisation {
+    /// An organisation with no website data or listed repos, so only its
+    /// public repos are validated.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            website_data: None,
+            repos: Vec::new(),
+        }
+    }
+}
+
+impl Default for Organisation {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_ORGANISATION.to_string(),
+            website_data: Some(WebsiteData {
+                repo: "opensource-website".to_string(),
+                branch: "main".to_string(),
+                path: "static/data/data.json".to_string(),
+            }),
+            repos: Vec::new(),
+        }
+    }
+}
+
+/// A file in the organisation's repos in the format of the opensource
+/// website's data.json.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WebsiteData {
+    pub repo: String,
+    #[serde(default = "default_website_data_branch")]
+    pub branch: String,
+    pub path: String,
+}
+
+fn default_website_data_branch() -> String {
+    "main".to_string()
+}
+
+/// The branches files of projects are read from.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Branches {
+    /// Branches to try in order, using the first that has the file.
+    pub candidates: Vec<String>,
+    /// Branches to use instead of the candidates for individual projects,
+    /// by name.
+    pub overrides: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Branches {
+    fn default() -> Self {
+        Self {
+            candidates: vec!["main".to_string(), "master".to_string()],
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl Branches {
+    /// The branches to read the files of a project from, in order.
+    pub fn of(&self, project: &str) -> &[String] {
+        self.overrides.get(project).unwrap_or(&self.candidates)
+    }
+}
+
+/// Tiers of projects, such as flagship or experimental, which are held to
+/// different standards.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Tiers {
+    /// The tier of projects that are not given one.
+    pub default: Option<String>,
+    /// The tiers of individual projects, by name. A `tier` in the opensource
+    /// website data takes precedence.
+    pub projects: BTreeMap<String, String>,
+    /// The checks each tier leaves out or only warns about, by tier name.
+    pub policies: BTreeMap<String, TierPolicy>,
+}
+
+impl Tiers {
+    /// The tier of a project, given the tier it has in the website data.
+    pub fn of<'a>(&'a self, project: &str, website_tier: Option<&'a str>) -> Option<&'a str> {
+        website_tier
+            .or_else(|| self.projects.get(project).map(String::as_str))
+            .or(self.default.as_deref())
+    }
+}
+
+/// How the checks of projects in one tier are treated.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TierPolicy {
+    /// The ids of checks that are left out of the results.
+    pub skip: Vec<String>,
+    /// The ids of checks whose failures are only warnings.
+    pub warn: Vec<String>,
+}
+
+/// How `cargo run scorecard` weighs and groups the checks.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Scorecard {
+    /// How much each check counts towards the score, by check id. Checks not
+    /// listed count 1.
+    pub severities: BTreeMap<String, f64>,
+    /// How much the checks of projects in each tier count, by tier name.
+    /// Projects in tiers not listed, or without a tier, count 1.
+    pub tier_weights: BTreeMap<String, f64>,
+    /// The ids of the checks in each category. Checks in no category are in
+    /// `other`.
+    pub categories: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Scorecard {
+    fn default() -> Self {
+        let categories: &[(&str, &[&str])] = &[
+            (
+                "community",
+                &[
+                    "contribution-agreement",
+                    "first-time-contributors",
+                    "good-first-issues",
+                    "issue-response",
+                    "template-drift",
+                ],
+            ),
+            (
+                "discoverability",
+                &[
+                    "repo-name",
+                    "rust-ecosystem-registration",
+                    "topics",
+                    "website-data-inclusion",
+                ],
+            ),
+            (
+                "documentation",
+                &[
+                    "badges",
+                    "install-instructions",
+                    "maintenance-banner",
+                    "readme-links",
+                    "required-files",
+                ],
+            ),
+            (
+                "licensing",
+                &[
+                    "cpp-license-headers",
+                    "license-year",
+                    "third-party-licenses",
+                ],
+            ),
+            (
+                "ownership",
+                &["exemptions", "maintainers", "tier", "website-owners"],
+            ),
+            (
+                "packaging",
+                &[
+                    "cargo-metadata",
+                    "container-images",
+                    "coverage",
+                    "msrv",
+                    "package-json",
+                    "release-drift",
+                ],
+            ),
+            (
+                "security",
+                &[
+                    "actions-pinning",
+                    "dependabot",
+                    "internal-references",
+                    "release-checksums",
+                    "secret-patterns",
+                    "secret-scanning",
+                    "signed-releases",
+                    "unsafe-code",
+                    "workflow-audit",
+                ],
+            ),
+        ];
+        Self {
+            severities: BTreeMap::new(),
+            tier_weights: BTreeMap::new(),
+            categories: categories
+                .iter()
+                .map(|(name, ids)| {
+                    let ids = ids.iter().map(ToString::to_string).collect();
+                    (name.to_string(), ids)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Scorecard {
+    /// How much a check counts in a project of the given tier.
+    pub fn weight(&self, check: &str, tier: Option<&str>) -> f64 {
+        let severity = self
+            .severities
+            .iter()
+            .find(|(id, _)| matches_check(id, check))
+            .map_or(1.0, |(_, severity)| *severity);
+        let tier_weight = tier
+            .and_then(|tier| self.tier_weights.get(tier))
+            .copied()
+            .unwrap_or(1.0);
+        severity * tier_weight
+    }
+
+    /// The category of a check.
+    pub fn category(&self, check: &str) -> &str {
+        self.categories
+            .iter()
+            .find(|(_, ids)| ids.iter().any(|id| matches_check(id, check)))
+            .map_or("other", |(name, _)| name.as_str())
+    }
+}
+
+/// Whether a check id given in the config refers to a check. Ids without a
+/// `:` also refer to every check they prefix, so `required-files` covers
+/// `required-files:license`.
+pub fn matches_check(id: &str, check: &str) -> bool {
+    id == check
+        || check
+            .strip_prefix(id)
+            .is_some_and(|rest| !id.contains(':') && rest.starts_with(':'))
+}
+
+/// A check implemented by an external program, see `validate::checks::external`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ExternalCheck {
+    /// Identifies the check in reports as `external:<id>`.
+    pub id: String,
+    /// The program to run followed by its arguments.
+    pub command: Vec<String>,
+}
+
+/// A check implemented by a WebAssembly module, see `validate::checks::wasm`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WasmCheck {
+    /// Identifies the check in reports as `wasm:<id>`.
+    pub id: String,
+    /// The path of the `.wasm` module to run.
+    pub module: PathBuf,
+    /// How much work the module may do per project before it is stopped.
+    #[serde(default = "default_wasm_fuel")]
+    pub fuel: u64,
+}
+
+fn default_wasm_fuel() -> u64 {
+    1_000_000_000
+}
+
+/// A requirement for projects to contain certain files, checked by the
+/// `required-files:<id>` check.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RequiredFiles {
+    /// Identifies the requirement in reports.
+    pub id: String,
+    /// Files that must all be present. Shorthand for a single alternative.
+    #[serde(default)]
+    pub files: Vec<RequiredFile>,
+    /// Sets of files, any one of which satisfies the requirement when all
+    /// the files in the set are present.
+    #[serde(default)]
+    pub alternatives: Vec<Vec<RequiredFile>>,
+    /// The files are only required of projects whose main languages, as
+    /// detected by GitHub, include one of these, or of every project if empty.
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+impl RequiredFiles {
+    /// Every set of files that would satisfy the requirement.
+    pub fn all_alternatives(&self) -> impl Iterator<Item = &[RequiredFile]> {
+        std::iter::once(self.files.as_slice())
+            .filter(|files| !files.is_empty())
+            .chain(self.alternatives.iter().map(Vec::as_slice))
+    }
+}
+
+/// A file that must be present in a project, written either as just the path
+/// or as a table with patterns the contents must match.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(untagged)]
+pub enum RequiredFile {
+    Path(String),
+    Matching {
+        path: String,
+        #[serde(default)]
+        matches: Vec<Pattern>,
+    },
+}
+
+impl RequiredFile {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Path(path) | Self::Matching { path, .. } => path,
+        }
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        match self {
+            Self::Path(_) => &[],
+            Self::Matching { matches, .. } => matches,
+        }
+    }
+}
+
+/// A regular expression that is compiled when the config is loaded, so that
+/// mistakes are reported before any validation is done.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pattern(pub Regex);
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Regex::new(&source).map(Self)
+    }
+}
+
+/// A pattern where `*` matches any sequence of characters, compiled to a
+/// regular expression when the config is loaded.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Glob(Regex);
+
+impl Glob {
+    pub fn matches(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl TryFrom<String> for Glob {
+    type Error = regex::Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        let pattern = source.split('*').map(regex::escape).join(".*");
+        Regex::new(&format!("^{}$", pattern)).map(Self)
+    }
+}
+
+impl Config {
+    /// Load the configuration from the given path, or from `embark-oss.toml`
+    /// if it exists when no path is given.
+    pub fn load(path: Option<&Path>) -> eyre::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None if Path::new(DEFAULT_PATH).exists() => PathBuf::from(DEFAULT_PATH),
+            None => return Ok(Self::default()),
+        };
+        let source = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Unable to read config file {}", path.display()))?;
+        Self::parse(&source)
+            .wrap_err_with(|| format!("Unable to parse config file {}", path.display()))
+    }
+
+    /// The organisations to validate, which is only the default organisation
+    /// unless others are configured.
+    pub fn organisations(&self) -> Vec<Organisation> {
+        if self.organisations.is_empty() {
+            vec![Organisation::default()]
+        } else {
+            self.organisations.clone()
+        }
+    }
+
+    /// The organisation with the given name, or the first organisation if no
+    /// name is given.
+    pub fn organisation(&self, name: Option<&str>) -> eyre::Result<Organisation> {
+        let mut organisations = self.organisations();
+        match name {
+            Some(name) => organisations
+                .into_iter()
+                .find(|candidate| candidate.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| eyre!("Organisation `{}` is not configured", name)),
+            None => Ok(organisations.remove(0)),
+        }
+    }
+
+    /// Find the organisation of a project given by name, or as
+    /// `organisation/name` for a project outside the first organisation.
+    /// Organisations that are not configured have no website data.
+    pub fn project_organisation(&self, name: &str) -> (Organisation, String) {
+        let mut organisations = self.organisations();
+        match name.split_once('/') {
+            Some((organisation, name)) => {
+                let organisation = organisations
+                    .into_iter()
+                    .find(|candidate| candidate.name.eq_ignore_ascii_case(organisation))
+                    .unwrap_or_else(|| Organisation::new(organisation.to_string()));
+                (organisation, name.to_string())
+            }
+            None => (organisations.remove(0), name.to_string()),
+        }
+    }
+
+    pub fn parse(source: &str) -> eyre::Result<Self> {
+        let mut config: Self = toml::from_str(source)?;
+        if let Some(name) = config
+            .organisations
+            .iter()
+            .map(|organisation| organisation.name.to_ascii_lowercase())
+            .duplicates()
+            .next()
+        {
+            return Err(eyre!(
+                "Organisation `{}` is configured more than once",
+                name
+            ));
+        }
+        for requirement in &config.required_files {
+            if requirement.all_alternatives().next().is_none() {
+                return Err(eyre!("Required files `{}` lists no files", requirement.id));
+            }
+        }
+        if config.branches.candidates.is_empty() {
+            return Err(eyre!("At least one branch `candidates` is required"));
+        }
+        if let Some((project, _)) = config
+            .branches
+            .overrides
+            .iter()
+            .find(|(_, branches)| branches.is_empty())
+        {
+            return Err(eyre!("Branch override for `{}` lists no branches", project));
+        }
+        let tiers = &config.tiers;
+        if let Some(tier) = tiers
+            .default
+            .iter()
+            .chain(tiers.projects.values())
+            .find(|tier| !tiers.policies.contains_key(*tier))
+        {
+            return Err(eyre!("Tier `{}` has no `tiers.policies` entry", tier));
+        }
+        if let Some(references) = &config.internal_references {
+            if references.hostnames.is_empty()
+                && references.patterns.is_empty()
+                && references.code_names.is_empty()
+            {
+                return Err(eyre!(
+                    "`internal-references` lists no hostnames, patterns, or code names"
+                ));
+            }
+        }
+        if config
+            .readme_links
+            .as_ref()
+            .is_some_and(|links| links.concurrency == 0)
+        {
+            return Err(eyre!("`readme-links` concurrency must be at least 1"));
+        }
+        config.slack_templates.check()?;
+        if config.digest_post.is_some() && config.notifications.schedule != Schedule::Digest {
+            return Err(eyre!(
+                "`digest-post` requires the `digest` notification schedule"
+            ));
+        }
+        for route in &config.slack_routes {
+            if route.projects.is_empty() && route.tags.is_empty() {
+                return Err(eyre!(
+                    "Slack route `{}` matches no projects",
+                    route.webhook_url_env
+                ));
+            }
+        }
+        if let Some(escalation) = &config.escalation {
+            if escalation.after_runs < 2 {
+                return Err(eyre!(
+                    "Escalation `after-runs` must be at least 2, or every failure would be escalated"
+                ));
+            }
+        }
+        if let Some(topics) = &config.topics {
+            let required = topics
+                .required
+                .iter()
+                .chain(topics.required_by_tag.values().flatten());
+            if let Some(topic) = required
+                .clone()
+                .find(|topic| topics.forbidden.contains(topic))
+            {
+                return Err(eyre!("Topic `{}` is both required and forbidden", topic));
+            }
+        }
+        for external in &config.external_checks {
+            if external.command.is_empty() {
+                return Err(eyre!("External check `{}` has no command", external.id));
+            }
+        }
+        let hash = ring::digest::digest(&ring::digest::SHA256, source.as_bytes());
+        config.hash = Some(
+            hash.as_ref()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        );
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let config = Config::parse("").unwrap();
+        assert!(config.org_defaults.enabled);
+        assert_eq!(config.org_defaults.branch, "main");
+
+        let config = Config::parse("[org-defaults]\nenabled = false").unwrap();
+        assert!(!config.org_defaults.enabled);
+        assert_eq!(config.org_defaults.branch, "main");
+
+        assert!(!config.codeowners.allow_email_owners);
+
+        let config = Config::parse("[codeowners]\nallow-email-owners = true").unwrap();
+        assert!(config.codeowners.allow_email_owners);
+
+        assert_eq!(config.branches.of("ash"), ["main", "master"]);
+
+        assert!(Config::parse("unknown-key = 1").is_err());
+    }
+
+    #[test]
+    fn parsing_email() {
+        assert!(Config::parse("").unwrap().email.is_none());
+
+        let config = Config::parse(
+            r#"
+[email]
+host = "smtp.example.com"
+username = "oss-bot"
+password-env = "SMTP_PASSWORD"
+from = "oss-bot@example.com"
+to = ["a@example.com", "b@example.com"]
+"#,
+        )
+        .unwrap();
+        let email = config.email.unwrap();
+        assert_eq!(email.port, 587);
+        assert_eq!(email.tls, SmtpTls::Starttls);
+        assert_eq!(email.to.len(), 2);
+
+        assert!(Config::parse("[email]\nhost = \"a\"\ntls = \"ssl\"").is_err());
+    }
+
+    #[test]
+    fn slack_routes() {
+        let config = Config::parse(
+            r#"
+[[slack-routes]]
+webhook-url-env = "SLACK_GAME_TECH_WEBHOOK"
+projects = ["texture-*", "physx-rs"]
+
+[[slack-routes]]
+webhook-url-env = "SLACK_RUST_WEBHOOK"
+tags = ["rust"]
+"#,
+        )
+        .unwrap();
+        let routes = &config.slack_routes;
+        let no_tags = HashSet::new();
+        let rust: HashSet<_> = vec!["rust".to_string()].into_iter().collect();
+
+        assert!(routes[0].matches("texture-synthesis", &no_tags));
+        assert!(routes[0].matches("physx-rs", &no_tags));
+        assert!(!routes[0].matches("physx-rs-sys", &no_tags));
+        assert!(!routes[0].matches("a-texture-synthesis", &no_tags));
+        assert!(!routes[1].matches("texture-synthesis", &no_tags));
+        assert!(routes[1].matches("texture-synthesis", &rust));
+
+        // Routes must match something
+        assert!(Config::parse("[[slack-routes]]\nwebhook-url-env = \"A\"").is_err());
+    }
+
+    #[test]
+    fn parsing_notifications() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.notifications.schedule, Schedule::Immediate);
+
+        let config = Config::parse("[notifications]\nschedule = \"digest\"").unwrap();
+        assert_eq!(config.notifications.schedule, Schedule::Digest);
+        assert_eq!(
+            config.notifications.digest_interval(),
+            Duration::from_secs(7 * 24 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn parsing_escalation() {
+        let config = Config::parse("[escalation]\nafter-runs = 5\nmention = \"<!here>\"").unwrap();
+        let escalation = config.escalation.unwrap();
+        assert_eq!(escalation.after_runs, 5);
+        assert!(escalation.webhook_url_env.is_none());
+
+        assert!(Config::parse("[escalation]\nafter-runs = 1").is_err());
+    }
+
+    #[test]
+    fn config_hashes() {
+        assert_eq!(Config::default().hash, None);
+        assert_eq!(
+            Config::parse("").unwrap().hash.as_deref(),
+            Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+    }
+
+    #[test]
+    fn parsing_telemetry() {
+        let config = Config::parse("[telemetry]").unwrap();
+        assert_eq!(config.telemetry, Some(Telemetry::default()));
+
+        let config = Config::parse(
+            "[telemetry]\nendpoint = \"http://localhost:4318\"\nheaders-env = \"OTLP_HEADERS\"",
+        )
+        .unwrap();
+        let telemetry = config.telemetry.unwrap();
+        assert_eq!(telemetry.endpoint.as_deref(), Some("http://localhost:4318"));
+        assert_eq!(telemetry.headers_env.as_deref(), Some("OTLP_HEADERS"));
+        assert_eq!(telemetry.service_name, "embark-oss");
+    }
+
+    #[test]
+    fn parsing_result_upload() {
+        let config = Config::parse(
+            "[result-upload]\nprovider = \"s3\"\nbucket = \"reports\"\nregion = \"eu-north-1\"",
+        )
+        .unwrap();
+        assert_eq!(
+            config.result_upload,
+            Some(ResultUpload::S3 {
+                bucket: "reports".to_string(),
+                region: "eu-north-1".to_string(),
+                prefix: String::new(),
+                endpoint: None,
+            })
+        );
+
+        let config = Config::parse(
+            "[result-upload]\nprovider = \"gcs\"\nbucket = \"reports\"\nprefix = \"oss/\"",
+        )
+        .unwrap();
+        assert_eq!(
+            config.result_upload,
+            Some(ResultUpload::Gcs {
+                bucket: "reports".to_string(),
+                prefix: "oss/".to_string(),
+            })
+        );
+
+        assert!(Config::parse("[result-upload]\nprovider = \"gcs\"").is_err());
+        assert!(
+            Config::parse("[result-upload]\nprovider = \"azure\"\nbucket = \"reports\"").is_err()
+        );
+    }
+
+    #[test]
+    fn parsing_digest_post() {
+        let config =
+            Config::parse("[notifications]\nschedule = \"digest\"\n[digest-post]").unwrap();
+        let post = config.digest_post.unwrap();
+        assert_eq!(post.repo, "EmbarkStudios/opensource");
+        assert_eq!(post.post_as, PostKind::Discussion);
+        assert_eq!(post.category, "Reports");
+
+        let config = Config::parse(
+            "[notifications]\nschedule = \"digest\"\n\
+[digest-post]\npost-as = \"issue\"\nlabels = [\"report\"]",
+        )
+        .unwrap();
+        let post = config.digest_post.unwrap();
+        assert_eq!(post.post_as, PostKind::Issue);
+        assert_eq!(post.labels, vec!["report"]);
+
+        assert!(Config::parse("[digest-post]").is_err());
+    }
+
+    #[test]
+    fn parsing_slack_attachment() {
+        assert!(Config::parse("").unwrap().slack_attachment.is_none());
+        let config = Config::parse("[slack-attachment]").unwrap();
+        assert_eq!(
+            config.slack_attachment.unwrap().format,
+            AttachmentFormat::Json
+        );
+        let config = Config::parse("[slack-attachment]\nformat = \"csv\"").unwrap();
+        assert_eq!(
+            config.slack_attachment.unwrap().format,
+            AttachmentFormat::Csv
+        );
+        assert!(Config::parse("[slack-attachment]\nformat = \"xml\"").is_err());
+    }
+
+    #[test]
+    fn parsing_slack_templates() {
+        let config = Config::parse("").unwrap();
+        assert!(config.slack_templates.immediate.is_none());
+        let config = Config::parse(
+            "[slack-templates]\nimmediate = \"{count} projects need help\"\nfoot = \"Bye\"",
+        )
+        .unwrap();
+        let templates = config.slack_templates;
+        assert_eq!(
+            templates.immediate.unwrap().render(&[("count", "3")]),
+            "3 projects need help"
+        );
+        assert_eq!(templates.foot.render(&[]), "Bye");
+
+        let error = Config::parse("[slack-templates]\nproject = \"{name} {eror}\"").unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown placeholder `{eror}`"));
+        assert!(Config::parse("[slack-templates]\nfoot = \"{unclosed\"").is_err());
+        let error = Config::parse(
+            "[slack-templates]\nproject = \"{% for error in errors %}{eror}{% endfor %}\"",
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown placeholder `{eror}`"));
+        assert!(Config::parse("[slack-templates]\nproject = \"{% if errors %}\"").is_err());
+    }
+
+    #[test]
+    fn parsing_alerting() {
+        let config =
+            Config::parse("[alerting]\nservice = \"pagerduty\"\nrouting-key-env = \"KEY\"")
+                .unwrap();
+        assert!(matches!(
+            config.alerting,
+            Some(Alerting::Pagerduty { routing_key_env }) if routing_key_env == "KEY"
+        ));
+
+        let config =
+            Config::parse("[alerting]\nservice = \"opsgenie\"\napi-key-env = \"KEY\"").unwrap();
+        assert!(matches!(
+            config.alerting,
+            Some(Alerting::Opsgenie { api_url: None, .. })
+        ));
+
+        assert!(
+            Config::parse("[alerting]\nservice = \"pagerduty\"\napi-key-env = \"KEY\"").is_err()
+        );
+    }
+
+    #[test]
+    fn parsing_topics() {
+        let config = Config::parse(
+            "[topics]\nrequired = [\"embark\"]\nrequired-by-tag = { rust = [\"rust\"] }",
+        )
+        .unwrap();
+        let topics = config.topics.unwrap();
+        assert_eq!(topics.required, vec!["embark"]);
+        assert_eq!(topics.required_by_tag["rust"], vec!["rust"]);
+
+        assert!(Config::parse("[topics]\nrequired = [\"a\"]\nforbidden = [\"a\"]").is_err());
+    }
+
+    #[test]
+    fn parsing_organisations() {
+        let config = Config::parse("").unwrap();
+        let organisations = config.organisations();
+        assert_eq!(organisations.len(), 1);
+        assert_eq!(organisations[0].name, "EmbarkStudios");
+        assert!(organisations[0].website_data.is_some());
+
+        let config = Config::parse(
+            r#"
+[[organisations]]
+name = "EmbarkStudios"
+website-data = { repo = "opensource-website", path = "static/data/data.json" }
+
+[[organisations]]
+name = "EmbarkLabs"
+repos = ["prototype"]
+"#,
+        )
+        .unwrap();
+        let organisations = config.organisations();
+        assert_eq!(organisations.len(), 2);
+        assert_eq!(
+            organisations[0].website_data.as_ref().unwrap().branch,
+            "main"
+        );
+        assert!(organisations[1].website_data.is_none());
+        assert_eq!(organisations[1].repos, ["prototype"]);
+
+        let (organisation, name) = config.project_organisation("ash");
+        assert_eq!(
+            (organisation.name.as_str(), name.as_str()),
+            ("EmbarkStudios", "ash")
+        );
+        let (organisation, name) = config.project_organisation("embarklabs/prototype");
+        assert_eq!(
+            (organisation.name.as_str(), name.as_str()),
+            ("EmbarkLabs", "prototype")
+        );
+        let (organisation, _) = config.project_organisation("Other/thing");
+        assert_eq!(organisation.name, "Other");
+
+        assert!(Config::parse(
+            "[[organisations]]\nname = \"EmbarkLabs\"\n[[organisations]]\nname = \"embarklabs\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parsing_branches() {
+        let config = Config::parse(
+            r#"
+[branches]
+candidates = ["main"]
+overrides = { kajiya = ["develop", "main"] }
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.branches.of("ash"), ["main"]);
+        assert_eq!(config.branches.of("kajiya"), ["develop", "main"]);
+        assert!(Config::parse("[branches]\ncandidates = []").is_err());
+        assert!(Config::parse("[branches]\noverrides = { ash = [] }").is_err());
+    }
+
+    #[test]
+    fn parsing_coverage() {
+        assert!(Config::parse("").unwrap().coverage.is_none());
+        let rust: HashSet<_> = vec!["rust".to_string()].into_iter().collect();
+        let coverage = Config::parse("[coverage]").unwrap().coverage.unwrap();
+        assert!(coverage.applies_to("physx-rs", &rust));
+        assert!(!coverage.applies_to("opensource-website", &HashSet::new()));
+
+        let config = Config::parse("[coverage]\nprojects = [\"kajiya\", \"physx*\"]").unwrap();
+        let coverage = config.coverage.unwrap();
+        assert!(coverage.applies_to("physx-rs", &rust));
+        assert!(!coverage.applies_to("cargo-about", &rust));
+    }
+
+    #[test]
+    fn parsing_secret_patterns() {
+        assert!(Config::parse("").unwrap().secret_patterns.is_none());
+        let config = Config::parse("[secret-patterns]").unwrap();
+        let secret_patterns = config.secret_patterns.unwrap();
+        assert!(secret_patterns.ignore.is_empty());
+        assert_eq!(secret_patterns.max_file_size, 1_000_000);
+        assert!(Config::parse("[secret-patterns]\npatterns = []").is_err());
+    }
+
+    #[test]
+    fn parsing_internal_references() {
+        assert!(Config::parse("").unwrap().internal_references.is_none());
+        assert!(Config::parse("[internal-references]").is_err());
+        assert!(Config::parse("[internal-references]\npatterns = [\"(\"]").is_err());
+
+        let config = Config::parse(
+            r#"
+[internal-references]
+hostnames = ["corp.embark.net"]
+patterns = ['jira\.embark\.net/browse/']
+"#,
+        )
+        .unwrap();
+        let references = config.internal_references.unwrap();
+        assert_eq!(references.hostnames, vec!["corp.embark.net"]);
+        assert!(references.patterns[0]
+            .0
+            .is_match("https://jira.embark.net/browse/TECH-1"));
+        assert!(references.files.contains(&"README.md".to_string()));
+    }
+
+    #[test]
+    fn parsing_readme_links() {
+        assert!(Config::parse("").unwrap().readme_links.is_none());
+        let links = Config::parse("[readme-links]")
+            .unwrap()
+            .readme_links
+            .unwrap();
+        assert_eq!((links.concurrency, links.timeout_seconds), (8, 10));
+
+        let config = Config::parse("[readme-links]\nignore = [\"https://twitter.com/*\"]").unwrap();
+        let links = config.readme_links.unwrap();
+        assert!(links.ignore[0].matches("https://twitter.com/EmbarkStudios"));
+        assert!(Config::parse("[readme-links]\nconcurrency = 0").is_err());
+    }
+
+    #[test]
+    fn parsing_good_first_issues() {
+        assert!(Config::parse("").unwrap().good_first_issues.is_none());
+        let config = Config::parse("[good-first-issues]\ntags = [\"flagship\"]").unwrap();
+        let policy = config.good_first_issues.unwrap();
+        assert_eq!(
+            (policy.label.as_str(), policy.minimum),
+            ("good first issue", 1)
+        );
+        let flagship: HashSet<_> = vec!["flagship".to_string()].into_iter().collect();
+        assert!(policy.applies_to(&flagship));
+        assert!(!policy.applies_to(&HashSet::new()));
+    }
+
+    #[test]
+    fn parsing_issue_response() {
+        assert!(Config::parse("").unwrap().issue_response.is_none());
+        let config = Config::parse("[issue-response]\nmax-median-hours = 24").unwrap();
+        let policy = config.issue_response.unwrap();
+        assert_eq!(policy.max_median(), Duration::from_secs(24 * 60 * 60));
+        assert_eq!(policy.window(), Duration::from_secs(90 * 24 * 60 * 60));
+        assert_eq!(policy.minimum_issues, 3);
+    }
+
+    #[test]
+    fn parsing_release_drift() {
+        assert!(Config::parse("").unwrap().release_drift.is_none());
+        let drift = Config::parse("[release-drift]")
+            .unwrap()
+            .release_drift
+            .unwrap();
+        assert_eq!(drift.max_commits, 100);
+        let config = Config::parse("[release-drift]\nmax-commits = 50").unwrap();
+        assert_eq!(config.release_drift.unwrap().max_commits, 50);
+    }
+
+    #[test]
+    fn parsing_cargo_metadata() {
+        assert!(Config::parse("").unwrap().cargo_metadata.is_none());
+        let metadata = Config::parse("[cargo-metadata]")
+            .unwrap()
+            .cargo_metadata
+            .unwrap();
+        assert_eq!(metadata.fields, vec!["license", "description", "readme"]);
+        let config = Config::parse("[cargo-metadata]\nfields = [\"repository\"]").unwrap();
+        assert_eq!(config.cargo_metadata.unwrap().fields, vec!["repository"]);
+    }
+
+    #[test]
+    fn parsing_msrv() {
+        assert!(Config::parse("").unwrap().msrv.is_none());
+        assert!(Config::parse("[msrv]").unwrap().msrv.unwrap().warn_only);
+        let config = Config::parse("[msrv]\nwarn-only = false").unwrap();
+        assert!(!config.msrv.unwrap().warn_only);
+    }
+
+    #[test]
+    fn parsing_unsafe_code() {
+        assert!(Config::parse("").unwrap().unsafe_code.is_none());
+        let policy = Config::parse("[unsafe-code]").unwrap().unsafe_code.unwrap();
+        assert!(policy.applies_to("ash", &HashSet::new()));
+
+        let config =
+            Config::parse("[unsafe-code]\nprojects = [\"cargo-*\"]\ntags = [\"web\"]").unwrap();
+        let policy = config.unsafe_code.unwrap();
+        let web: HashSet<_> = vec!["web".to_string()].into_iter().collect();
+        assert!(policy.applies_to("cargo-deny", &HashSet::new()));
+        assert!(policy.applies_to("opensource-website", &web));
+        assert!(!policy.applies_to("ash", &HashSet::new()));
+    }
+
+    #[test]
+    fn parsing_license_year() {
+        assert!(Config::parse("").unwrap().license_year.is_none());
+        let config = Config::parse("[license-year]").unwrap();
+        assert!(config
+            .license_year
+            .unwrap()
+            .files
+            .contains(&"LICENSE-MIT".to_string()));
+        let config = Config::parse("[license-year]\nfiles = [\"NOTICE\"]").unwrap();
+        assert_eq!(config.license_year.unwrap().files, vec!["NOTICE"]);
+    }
+
+    #[test]
+    fn parsing_third_party_licenses() {
+        assert!(Config::parse("").unwrap().third_party_licenses.is_none());
+        let config = Config::parse("[third-party-licenses]").unwrap();
+        assert!(!config.third_party_licenses.unwrap().files.is_empty());
+        let config =
+            Config::parse("[third-party-licenses]\ntags = [\"cli\"]\nassets = [\"notice.txt\"]")
+                .unwrap();
+        let policy = config.third_party_licenses.unwrap();
+        assert!(policy.applies_to(&["cli".to_string()].iter().cloned().collect()));
+        assert!(!policy.applies_to(&HashSet::new()));
+        assert!(policy.assets[0].matches("notice.txt"));
+    }
+
+    #[test]
+    fn parsing_release_checksums() {
+        assert!(Config::parse("").unwrap().release_checksums.is_none());
+        let config = Config::parse("[release-checksums]").unwrap();
+        assert!(!config.release_checksums.unwrap().require_signatures);
+        let config = Config::parse("[release-checksums]\nrequire-signatures = true").unwrap();
+        assert!(config.release_checksums.unwrap().require_signatures);
+    }
+
+    #[test]
+    fn parsing_container_images() {
+        assert!(Config::parse("").unwrap().container_images.is_none());
+        let policy = Config::parse("[container-images]")
+            .unwrap()
+            .container_images
+            .unwrap();
+        assert!(policy.allows("ghcr.io/EmbarkStudios/tool"));
+        assert!(!policy.allows("ghcr.io/embarkstudios-fork/tool"));
+        let config =
+            Config::parse("[container-images]\nnamespaces = [\"docker.io/embarkstudios/\"]")
+                .unwrap();
+        let policy = config.container_images.unwrap();
+        assert!(policy.allows("docker.io/embarkstudios/server"));
+        assert!(!policy.allows("ghcr.io/embarkstudios/tool"));
+    }
+
+    #[test]
+    fn parsing_install_instructions() {
+        assert!(Config::parse("").unwrap().install_instructions.is_none());
+        let config = Config::parse("[install-instructions]").unwrap();
+        assert!(config.install_instructions.unwrap().tags.is_empty());
+        let config = Config::parse("[install-instructions]\ntags = [\"cli\"]").unwrap();
+        assert_eq!(config.install_instructions.unwrap().tags, vec!["cli"]);
+    }
+
+    #[test]
+    fn parsing_first_time_contributors() {
+        assert!(Config::parse("").unwrap().first_time_contributors.is_none());
+        let config = Config::parse("[first-time-contributors]").unwrap();
+        let policy = config.first_time_contributors.unwrap();
+        assert_eq!(policy.max_wait(), Duration::from_secs(7 * 24 * 60 * 60));
+        let config = Config::parse("[first-time-contributors]\nmax-wait-days = 3").unwrap();
+        assert_eq!(config.first_time_contributors.unwrap().max_wait_days, 3);
+    }
+
+    #[test]
+    fn parsing_package_json() {
+        assert!(Config::parse("").unwrap().package_json.is_none());
+        let config = Config::parse("[package-json]").unwrap();
+        let policy = config.package_json.unwrap();
+        assert_eq!(policy.fields, vec!["license", "repository"]);
+        assert!(policy.lockfile);
+        let config = Config::parse("[package-json]\nlockfile = false").unwrap();
+        assert!(!config.package_json.unwrap().lockfile);
+    }
+
+    #[test]
+    fn parsing_badges() {
+        assert!(Config::parse("").unwrap().badges.is_none());
+        let config = Config::parse("[badges]").unwrap();
+        let badges = config.badges.unwrap();
+        assert_eq!(badges.repo, "EmbarkStudios/opensource");
+        assert_eq!(badges.path, "badges.toml");
+        let config =
+            Config::parse("[badges]\nrepo = \"Ark/standards\"\nbranch = \"trunk\"").unwrap();
+        let badges = config.badges.unwrap();
+        assert_eq!(badges.repo, "Ark/standards");
+        assert_eq!(badges.branch, "trunk");
+    }
+
+    #[test]
+    fn parsing_maintenance_banner() {
+        assert!(Config::parse("").unwrap().maintenance_banner.is_none());
+        let config = Config::parse("[maintenance-banner]").unwrap();
+        let policy = config.maintenance_banner.unwrap();
+        assert_eq!(policy.inactive_days, 365);
+        let config = Config::parse(
+            "[maintenance-banner]\narchived = \"(?i)deprecated\"\ninactive-days = 180",
+        )
+        .unwrap();
+        let policy = config.maintenance_banner.unwrap();
+        assert!(policy.archived.0.is_match("This crate is DEPRECATED"));
+        assert_eq!(policy.inactive_days, 180);
+    }
+
+    #[test]
+    fn parsing_template_drift() {
+        assert!(Config::parse("").unwrap().template_drift.is_none());
+        let config = Config::parse("[template-drift]").unwrap();
+        let policy = config.template_drift.unwrap();
+        assert_eq!(policy.repo, "EmbarkStudios/opensource-template");
+        assert_eq!(policy.files.len(), 4);
+        assert_eq!(policy.sync_limit, 10);
+        let config = Config::parse(
+            "[template-drift]\nfiles = [\".github/workflows/rust-ci.yml\"]\nmax-changed-lines = 4",
+        )
+        .unwrap();
+        let policy = config.template_drift.unwrap();
+        assert_eq!(policy.files, vec![".github/workflows/rust-ci.yml"]);
+        assert_eq!(policy.max_changed_lines, 4);
+    }
+
+    #[test]
+    fn parsing_cpp_license_headers() {
+        assert!(Config::parse("").unwrap().cpp_license_headers.is_none());
+        let config = Config::parse("[cpp-license-headers]").unwrap();
+        let policy = config.cpp_license_headers.unwrap();
+        assert_eq!(policy.sample, 10);
+        assert!(policy.header.0.is_match("// Copyright Embark Studios"));
+        let config = Config::parse(
+            "[cpp-license-headers]\nheader = \"Licensed under\"\nexclude = [\"deps/*\"]",
+        )
+        .unwrap();
+        let policy = config.cpp_license_headers.unwrap();
+        assert!(!policy.header.0.is_match("// Copyright Embark Studios"));
+        assert!(policy.exclude[0].matches("deps/imgui/imgui.h"));
+        assert!(Config::parse("[cpp-license-headers]\nheader = \"(\"").is_err());
+    }
+
+    #[test]
+    fn parsing_celebrations() {
+        assert!(Config::parse("").unwrap().celebrations.is_none());
+        let config = Config::parse("[celebrations]\nwebhook-url-env = \"SLACK_WINS\"").unwrap();
+        assert_eq!(config.celebrations.unwrap().webhook_url_env, "SLACK_WINS");
+        assert!(Config::parse("[celebrations]").is_err());
+    }
+
+    #[test]
+    fn parsing_exemption_policy() {
+        let policy = Config::parse("").unwrap().exemptions;
+        assert!(policy.allows("msrv"));
+        let policy = Config::parse(
+            "[exemptions]\nexemptable = [\"msrv\", \"required-files\", \"badges:crates-io\"]",
+        )
+        .unwrap()
+        .exemptions;
+        assert!(policy.allows("msrv"));
+        assert!(policy.allows("required-files:license"));
+        assert!(policy.allows("badges:crates-io"));
+        assert!(!policy.allows("badges"));
+        assert!(!policy.allows("maintainers"));
+        assert!(Config::parse("[exemptions]\nexempt = []").is_err());
+    }
+
+    #[test]
+    fn parsing_expiring_exemptions() {
+        let expiring = Config::parse("").unwrap().expiring_exemptions;
+        assert_eq!(expiring.within_days, 30);
+        assert!(expiring.webhook_url_env.is_none());
+        let config = Config::parse(
+            "[expiring-exemptions]\nwithin-days = 7\nwebhook-url-env = \"SLACK_EXEMPTIONS\"",
+        )
+        .unwrap();
+        assert_eq!(config.expiring_exemptions.within_days, 7);
+        assert_eq!(
+            config.expiring_exemptions.webhook_url_env.as_deref(),
+            Some("SLACK_EXEMPTIONS")
+        );
+    }
+
+    #[test]
+    fn parsing_scorecard() {
+        let scorecard = Config::parse("").unwrap().scorecard;
+        assert_eq!(scorecard.weight("maintainers", Some("flagship")), 1.0);
+        assert_eq!(
+            scorecard.category("required-files:license"),
+            "documentation"
+        );
+        assert_eq!(scorecard.category("external:typos"), "other");
+
+        let config = Config::parse(
+            r#"
+[scorecard]
+severities = { required-files = 2.0, "external:typos" = 0.5 }
+tier-weights = { flagship = 3 }
+categories = { hygiene = ["external"] }
+"#,
+        )
+        .unwrap();
+        let scorecard = config.scorecard;
+        assert_eq!(scorecard.weight("required-files:license", None), 2.0);
+        assert_eq!(scorecard.weight("required-files", Some("flagship")), 6.0);
+        assert_eq!(
+            scorecard.weight("external:typos", Some("experimental")),
+            0.5
+        );
+        assert_eq!(scorecard.weight("external:typos-extra", None), 1.0);
+        assert_eq!(scorecard.category("external:typos"), "hygiene");
+        assert_eq!(scorecard.category("maintainers"), "other");
+    }
+
+    #[test]
+    fn parsing_check_runs() {
+        assert!(Config::parse("").unwrap().check_runs.is_none());
+        let config = Config::parse("[check-runs]").unwrap();
+        assert_eq!(config.check_runs.unwrap().name, "Open source guidelines");
+        let config = Config::parse("[check-runs]\nname = \"Embark OSS\"").unwrap();
+        assert_eq!(config.check_runs.unwrap().name, "Embark OSS");
+    }
+
+    #[test]
+    fn parsing_tiers() {
+        let config = Config::parse(
+            r#"
+[tiers]
+default = "maintained"
+projects = { puffin = "flagship" }
+
+[tiers.policies.flagship]
+[tiers.policies.maintained]
+warn = ["msrv"]
+[tiers.policies.experimental]
+skip = ["coverage"]
+"#,
+        )
+        .unwrap();
+        let tiers = &config.tiers;
+        assert_eq!(tiers.of("puffin", None), Some("flagship"));
+        assert_eq!(
+            tiers.of("puffin", Some("experimental")),
+            Some("experimental")
+        );
+        assert_eq!(tiers.of("ash", None), Some("maintained"));
+        assert_eq!(tiers.policies["maintained"].warn, vec!["msrv"]);
+        assert_eq!(tiers.policies["experimental"].skip, vec!["coverage"]);
+
+        assert_eq!(Config::parse("").unwrap().tiers.of("ash", None), None);
+        assert!(Config::parse("[tiers]\ndefault = \"maintained\"").is_err());
+        assert!(Config::parse("[tiers.projects]\npuffin = \"flagship\"").is_err());
+    }
+
+    #[test]
+    fn parsing_check_settings() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.checks.timeout(), Duration::from_secs(300));
+        let config = Config::parse("[checks]\ntimeout-seconds = 60").unwrap();
+        assert_eq!(config.checks.timeout(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn parsing_preflight() {
+        let preflight = Config::parse("").unwrap().preflight;
+        assert!(preflight.internal_hostnames.is_empty());
+        assert_eq!(preflight.max_file_size, 1_000_000);
+
+        let config = Config::parse(
+            r#"
+[preflight]
+internal-hostnames = ["corp.embark.net"]
+max-file-size = 5000
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.preflight.internal_hostnames, vec!["corp.embark.net"]);
+        assert_eq!(config.preflight.max_file_size, 5000);
+    }
+
+    #[test]
+    fn parsing_contribution_agreement() {
+        assert!(Config::parse("").unwrap().contribution_agreement.is_none());
+        let config = Config::parse(
+            r#"
+[contribution-agreement]
+projects = { ash = "cla", sandbox = "none" }
+"#,
+        )
+        .unwrap();
+        let policy = config.contribution_agreement.unwrap();
+        assert_eq!(policy.agreement("physx-rs"), ContributionAgreement::Dco);
+        assert_eq!(policy.agreement("ash"), ContributionAgreement::Cla);
+        assert_eq!(policy.agreement("sandbox"), ContributionAgreement::None);
+        assert!(Config::parse("[contribution-agreement]\ndefault = \"icla\"").is_err());
+    }
+
+    #[test]
+    fn parsing_signed_releases() {
+        assert!(Config::parse("").unwrap().signed_releases.is_none());
+        let config = Config::parse("[signed-releases]").unwrap();
+        let policy = config.signed_releases.unwrap();
+        assert!(!policy.require_signed_commits);
+        assert!(policy.applies_to(&HashSet::new()));
+
+        let config = Config::parse(
+            r#"
+[signed-releases]
+tags = ["rust"]
+require-signed-commits = true
+"#,
+        )
+        .unwrap();
+        let policy = config.signed_releases.unwrap();
+        assert!(policy.require_signed_commits);
+        assert!(!policy.applies_to(&HashSet::new()));
+        assert!(policy.applies_to(&["rust".to_string()].iter().cloned().collect()));
+    }
+
+    #[test]
+    fn parsing_dependabot() {
+        assert!(Config::parse("").unwrap().dependabot.is_none());
+        let config = Config::parse("[dependabot]").unwrap();
+        assert!(config.dependabot.unwrap().security_updates);
+        let config = Config::parse("[dependabot]\nsecurity-updates = false").unwrap();
+        assert!(!config.dependabot.unwrap().security_updates);
+    }
+
+    #[test]
+    fn parsing_secret_scanning() {
+        assert!(Config::parse("").unwrap().secret_scanning.is_none());
+        let config = Config::parse("[secret-scanning]").unwrap();
+        assert!(config.secret_scanning.unwrap().push_protection);
+        let config = Config::parse("[secret-scanning]\npush-protection = false").unwrap();
+        assert!(!config.secret_scanning.unwrap().push_protection);
+    }
+
+    #[test]
+    fn parsing_actions_pinning() {
+        assert!(Config::parse("").unwrap().actions_pinning.is_none());
+        let allowed = Config::parse("[actions-pinning]")
+            .unwrap()
+            .actions_pinning
+            .unwrap()
+            .allowed;
+        assert!(allowed
+            .iter()
+            .any(|glob| glob.matches("github/codeql-action")));
+        assert!(!allowed.iter().any(|glob| glob.matches("someone/action")));
+        let config = Config::parse(
+            r#"
+[actions-pinning]
+allowed = ["actions/*", "EmbarkStudios/cargo-deny-action"]
+"#,
+        )
+        .unwrap();
+        let allowed = config.actions_pinning.unwrap().allowed;
+        assert!(allowed[0].matches("actions/checkout"));
+        assert!(!allowed[0].matches("someone/actions/checkout"));
+        assert!(allowed[1].matches("EmbarkStudios/cargo-deny-action"));
+    }
+
+    #[test]
+    fn parsing_workflow_audit() {
+        assert!(Config::parse("").unwrap().workflow_audit.is_none());
+        assert!(Config::parse("[workflow-audit]")
+            .unwrap()
+            .workflow_audit
+            .is_some());
+        assert!(Config::parse("[workflow-audit]\ntrusted-owners = [\"actions\"]").is_err());
+    }
+
+    #[test]
+    fn parsing_matrix() {
+        let config = Config::parse(
+            r#"
+[matrix]
+homeserver = "https://matrix.example.com"
+access-token-env = "MATRIX_TOKEN"
+room-id = "!abc:example.com"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.matrix.unwrap().room_id, "!abc:example.com");
+
+        // The access token can't be written in the config file
+        assert!(Config::parse(
+            "[matrix]\nhomeserver = \"a\"\naccess-token = \"b\"\nroom-id = \"c\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parsing_required_files() {
+        let config = Config::parse(
+            r#"
+[[required-files]]
+id = "readme"
+files = ["README.md"]
+
+[[required-files]]
+id = "license"
+alternatives = [
+    ["LICENSE-MIT", "LICENSE-APACHE"],
+    [{ path = "LICENSE", matches = ["MIT", "Apache"] }],
+]
+
+[[required-files]]
+id = "cargo-deny"
+files = ["deny.toml"]
+languages = ["Rust"]
+"#,
+        )
+        .unwrap();
+        let paths: Vec<Vec<(&str, usize)>> = config
+            .required_files
+            .iter()
+            .flat_map(RequiredFiles::all_alternatives)
+            .map(|files| {
+                files
+                    .iter()
+                    .map(|file| (file.path(), file.patterns().len()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![("README.md", 0)],
+                vec![("LICENSE-MIT", 0), ("LICENSE-APACHE", 0)],
+                vec![("LICENSE", 2)],
+                vec![("deny.toml", 0)],
+            ]
+        );
+        assert!(config.required_files[0].languages.is_empty());
+        assert_eq!(config.required_files[2].languages, vec!["Rust"]);
+
+        // Requirements must list files
+        assert!(Config::parse("[[required-files]]\nid = \"empty\"").is_err());
+
+        // Patterns must be valid regular expressions
+        assert!(Config::parse(
+            "[[required-files]]\nid = \"bad\"\nfiles = [{ path = \"a\", matches = [\"(\"] }]"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Line based diffs of files, printed in the unified format so that they can be
+//! applied with `git apply` or `patch`.
+
+/// How many unchanged lines are shown around each change.
+const CONTEXT: usize = 3;
+
+/// The most cells the table of common subsequence lengths may have, which
+/// takes 4 bytes each. The changed part of larger files is diffed as all of
+/// its old lines being replaced by all of its new ones.
+const MAX_TABLE_CELLS: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+impl Edit<'_> {
+    fn is_change(&self) -> bool {
+        !matches!(self, Self::Same(_))
+    }
+}
+
+/// A unified diff turning `old` into `new`, with a header naming them `a/path`
+/// and `b/path`. The diff is empty if they are the same.
+pub fn unified(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+    let edits = edits(&old, &new);
+    let changes: Vec<_> = (0..edits.len()).filter(|&i| edits[i].is_change()).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    // Changes close enough for their context to overlap share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &change in &changes {
+        match hunks.last_mut() {
+            Some((_, last)) if change - *last <= 2 * CONTEXT + 1 => *last = change,
+            _ => hunks.push((change, change)),
+        }
+    }
+    for (first, last) in hunks {
+        let start = first.saturating_sub(CONTEXT);
+        let end = (last + CONTEXT + 1).min(edits.len());
+        let (old_start, new_start) = line_numbers(&edits[..start]);
+        let (old_count, new_count) = line_numbers(&edits[start..end]);
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        ));
+        for edit in &edits[start..end] {
+            let (prefix, line) = match edit {
+                Edit::Same(line) => (' ', line),
+                Edit::Removed(line) => ('-', line),
+                Edit::Added(line) => ('+', line),
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+/// How many lines are added and removed when turning `old` into `new`.
+pub fn line_changes(old: &str, new: &str) -> (usize, usize) {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+    edits(&old, &new)
+        .iter()
+        .fold((0, 0), |(added, removed), edit| match edit {
+            Edit::Same(_) => (added, removed),
+            Edit::Removed(_) => (added, removed + 1),
+            Edit::Added(_) => (added + 1, removed),
+        })
+}
+
+/// How many lines of the old and new files the edits span.
+fn line_numbers(edits: &[Edit<'_>]) -> (usize, usize) {
+    edits.iter().fold((0, 0), |(old, new), edit| match edit {
+        Edit::Same(_) => (old + 1, new + 1),
+        Edit::Removed(_) => (old + 1, new),
+        Edit::Added(_) => (old, new + 1),
+    })
+}
+
+/// The range of a hunk, which starts at the line before an empty range.
+fn range(start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", start)
+    } else {
+        format!("{},{}", start + 1, count)
+    }
+}
+
+/// The shortest edits turning `old` into `new`, found with the longest common
+/// subsequence of their lines. The lines the files start and end with are the
+/// same in both are left out of the search, as most changes are small.
+fn edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let mut edits: Vec<_> = old[..prefix].iter().map(|line| Edit::Same(line)).collect();
+    edits.extend(changed_edits(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    ));
+    edits.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| Edit::Same(line)),
+    );
+    edits
+}
+
+/// The shortest edits turning `old` into `new`, or all of `old` removed and
+/// all of `new` added if the table for finding them would be too large.
+fn changed_edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_TABLE_CELLS {
+        edits.extend(old.iter().map(|line| Edit::Removed(line)));
+        edits.extend(new.iter().map(|line| Edit::Added(line)));
+        return edits;
+    }
+
+    // The length of the longest common subsequence of `old[i..]` and `new[j..]`
+    let width = new.len() + 1;
+    let mut lengths = vec![0_u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            edits.push(Edit::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            edits.push(Edit::Removed(old[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(new[j]));
+            j += 1;
+        }
+    }
+    edits.extend(old[i..].iter().map(|line| Edit::Removed(line)));
+    edits.extend(new[j..].iter().map(|line| Edit::Added(line)));
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diffs() {
+        assert_eq!(unified("same.txt", "a\nb\n", "a\nb\n"), "");
+
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n";
+        assert_eq!(
+            unified("numbers.txt", old, new),
+            "--- a/numbers.txt\n+++ b/numbers.txt\n\
+@@ -1,7 +1,7 @@\n 1\n 2\n 3\n-4\n+four\n 5\n 6\n 7\n\
+@@ -13,3 +13,4 @@\n 13\n 14\n 15\n+16\n"
+        );
+
+        assert_eq!(line_changes(old, new), (2, 1));
+
+        assert_eq!(
+            unified("new.txt", "", "hello\n"),
+            "--- a/new.txt\n+++ b/new.txt\n@@ -0,0 +1,1 @@\n+hello\n"
+        );
+    }
+
+    #[test]
+    fn large_diffs() {
+        // Too many changed lines for the table, so they are all replaced
+        let old: String = (0..5000).map(|i| format!("old {}\n", i)).collect();
+        let new: String = (0..5000).map(|i| format!("new {}\n", i % 7)).collect();
+        assert!((old.lines().count() + 1) * (new.lines().count() + 1) > MAX_TABLE_CELLS);
+        let (head, tail) = ("head\n".repeat(10_000), "tail\n".repeat(10_000));
+        assert_eq!(
+            line_changes(
+                &format!("{}{}{}", head, old, tail),
+                &format!("{}{}{}", head, new, tail)
+            ),
+            (5000, 5000)
+        );
+
+        // Unchanged lines around a small change are not searched
+        let lines: String = (0..100_000).map(|i| format!("{}\n", i)).collect();
+        let changed = lines.replacen("500\n", "five hundred\n", 1);
+        assert_eq!(line_changes(&lines, &changed), (1, 1));
+        assert!(unified("lines.txt", &lines, &changed).contains("-500\n+five hundred\n"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Diagnosis of the environment the tool runs in, so that problems such as an
+//! expired token can be found before they cause a confusing validation run.
+
+use crate::{config::Config, github, slack, Doctor};
+use eyre::eyre;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Below this many remaining requests a validation run is likely to fail.
+const LOW_RATE_LIMIT: u64 = 500;
+
+pub async fn run(options: Doctor) -> eyre::Result<()> {
+    let Doctor {
+        github,
+        config,
+        slack_webhook_url,
+    } = options;
+    let mut problems = 0;
+    let mut report = |name: &str, result: eyre::Result<String>| match result {
+        Ok(info) => println!("✔️ {}: {}", name, info),
+        Err(error) => {
+            problems += 1;
+            print!(
+                "❌ {}\n{}",
+                name,
+                crate::error::cause_string(error.as_ref(), true)
+            );
+        }
+    };
+
+    // Config
+    let config = match Config::load(config.as_deref()) {
+        Ok(config) => {
+            report("Config", Ok("valid".to_string()));
+            config
+        }
+        Err(error) => {
+            report("Config", Err(error));
+            Config::default()
+        }
+    };
+    let http = crate::http::init(&config.http).map(|_| "configured".to_string());
+    report("HTTP client", http);
+
+    // GitHub API, authentication, and rate limit
+    let rate_limit = match github.into_client() {
+        Ok(client) => check_rate_limit(&client).await,
+        Err(error) => Err(error),
+    };
+    report("GitHub API", rate_limit);
+
+    // Raw file downloads, used for files in project repos
+    let raw = github::download_repo_file("EmbarkStudios", "opensource", "main", "README.md")
+        .await
+        .map(|_| "reachable".to_string())
+        .map_err(|error| {
+            eyre::Report::from(error)
+                .wrap_err("Unable to download files from raw.githubusercontent.com")
+        });
+    report("GitHub file downloads", raw);
+
+    // Slack
+    if let Some(url) = &slack_webhook_url {
+        let slack = slack::check_webhook(url)
+            .await
+            .map(|_| "webhook is valid".to_string())
+            .map_err(|error| error.wrap_err("Unable to use the Slack webhook"));
+        report("Slack", slack);
+    }
+
+    if problems == 0 {
+        Ok(())
+    } else {
+        Err(eyre!("Found {} problems with the environment", problems))
+    }
+}
+
+async fn check_rate_limit(client: &github::Client) -> eyre::Result<String> {
+    let (rate_limit, scopes) = client.rate_limit().await.map_err(|error| {
+        error.wrap_err(format!(
+            "Unable to query the GitHub API with {} authentication, check the credentials are valid",
+            client.auth_description()
+        ))
+    })?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let minutes_until_reset = rate_limit.reset.saturating_sub(now) / 60;
+    if rate_limit.remaining < LOW_RATE_LIMIT {
+        return Err(eyre!(
+            "Only {} of {} requests remain, resetting in {} minutes. Give a token to raise the limit",
+            rate_limit.remaining,
+            rate_limit.limit,
+            minutes_until_reset
+        ));
+    }
+
+    let scopes = match scopes {
+        Some(scopes) if scopes.is_empty() => ", token has no scopes".to_string(),
+        Some(scopes) => format!(", token has scopes {}", scopes.join(", ")),
+        None => String::new(),
+    };
+    Ok(format!(
+        "{} authentication, {} of {} requests remaining{}",
+        client.auth_description(),
+        rate_limit.remaining,
+        rate_limit.limit,
+        scopes
+    ))
+}
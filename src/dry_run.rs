@@ -0,0 +1,21 @@
+//! A global dry run mode, in which operations with external side effects
+//! print what they would have done instead of doing it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Print a description of a skipped operation along with the payload that
+/// would have been sent.
+pub fn print_skipped(action: &str, payload: &serde_json::Value) {
+    let payload = serde_json::to_string_pretty(payload).unwrap_or_else(|_| payload.to_string());
+    eprintln!("[dry run] Would {}:\n{}", action, payload);
+}
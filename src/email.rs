@@ -0,0 +1,68 @@
+use crate::config::{EmailConfig, SmtpTls};
+use eyre::{eyre, WrapErr};
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+/// Send an HTML email to all the configured recipients.
+pub async fn send(config: &EmailConfig, subject: &str, html: String) -> eyre::Result<()> {
+    if crate::dry_run::is_enabled() {
+        let payload = serde_json::json!({
+            "to": config.to,
+            "subject": subject,
+            "html": html,
+        });
+        crate::dry_run::print_skipped("send email", &payload);
+        return Ok(());
+    }
+
+    let mut message = Message::builder()
+        .from(config.from.parse().wrap_err("Invalid email sender")?)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML);
+    for to in &config.to {
+        message = message.to(to
+            .parse()
+            .wrap_err_with(|| format!("Invalid email recipient {}", to))?);
+    }
+    let message = message.body(html).wrap_err("Unable to build email")?;
+
+    let _ = transport(config)?
+        .send(message)
+        .await
+        .wrap_err("Unable to send email")?;
+    Ok(())
+}
+
+/// Check that the SMTP server accepts connections with the configured
+/// credentials, without sending anything.
+pub async fn check(config: &EmailConfig) -> eyre::Result<()> {
+    let connected = transport(config)?
+        .test_connection()
+        .await
+        .wrap_err_with(|| format!("Unable to connect to {}", config.host))?;
+    if connected {
+        Ok(())
+    } else {
+        Err(eyre!("{} did not accept the connection", config.host))
+    }
+}
+
+fn transport(config: &EmailConfig) -> eyre::Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let transport = match config.tls {
+        SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+        SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+    };
+    let mut transport = transport.port(config.port);
+    if let Some(username) = &config.username {
+        let password = match &config.password_env {
+            Some(name) => std::env::var(name)
+                .map_err(|_| eyre!("The {} environment variable is not set", name))?,
+            None => String::new(),
+        };
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    Ok(transport.build())
+}
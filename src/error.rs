@@ -1,4 +1,4 @@
-use eyre::Chain;
+use eyre::{eyre, Chain};
 use std::{error::Error, fmt::Write};
 
 /// Write the error and the cause
@@ -26,3 +26,24 @@ pub fn cause_string(error: &(dyn Error + 'static), should_indent: bool) -> Strin
     }
     f
 }
+
+/// A single error reporting every one of `errors`, if there are any, headed by
+/// the `summary` of their count when there are several.
+pub fn combine(
+    errors: Vec<eyre::Report>,
+    summary: impl FnOnce(usize) -> String,
+) -> eyre::Result<()> {
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.into_iter().next().unwrap()),
+        count => Err(eyre!(
+            "{}:\n{}",
+            summary(count),
+            errors
+                .iter()
+                .map(|error| format!("{:?}", error))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
@@ -0,0 +1,713 @@
+//! Exports of data gathered by validation for use in other tools.
+
+use crate::{
+    config::Config,
+    github,
+    validate::{Format, Metrics, Report},
+    ExportContributors, ExportEnrich, ExportGraph, ExportMetrics,
+};
+use eyre::{eyre, WrapErr};
+use futures::{stream, StreamExt, TryStreamExt};
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How many repos have their latest release looked up at once.
+const CONCURRENT_REQUESTS: usize = 8;
+
+/// The file format a graph is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+}
+
+impl FromStr for GraphFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            _ => Err(eyre!("Unknown graph format `{}`", s)),
+        }
+    }
+}
+
+/// The file format metrics are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for MetricsFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(eyre!("Unknown metrics format `{}`", s)),
+        }
+    }
+}
+
+/// Maintainers and the projects they maintain. Teams listed as maintainers
+/// may have their members included.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Graph {
+    /// The maintainers of each project.
+    pub projects: BTreeMap<String, Vec<String>>,
+    /// The members of each team, by `org/team` handle.
+    pub teams: BTreeMap<String, Vec<String>>,
+}
+
+pub async fn graph(options: ExportGraph) -> eyre::Result<()> {
+    let ExportGraph {
+        results,
+        format,
+        teams,
+        github,
+    } = options;
+    let report = Report::read(&results)?;
+    let mut graph = Graph::new(&report);
+
+    if teams {
+        let client = github.into_client()?;
+        let handles: Vec<_> = graph
+            .projects
+            .values()
+            .flatten()
+            .filter(|handle| is_team(handle))
+            .cloned()
+            .collect();
+        for handle in handles {
+            let (org, team) = handle.split_once('/').unwrap_or_default();
+            let members = client.team_members(org, team).await?;
+            let _ = graph.teams.insert(handle, members);
+        }
+    }
+
+    match format {
+        GraphFormat::Dot => print!("{}", graph.to_dot()),
+        GraphFormat::GraphMl => print!("{}", graph.to_graphml()),
+    }
+    Ok(())
+}
+
+/// Print the website data.json of an organisation with a `github` object
+/// added to each project, holding metadata the website would otherwise have
+/// to scrape. Every other field of the data is kept as it is.
+pub async fn enrich(options: ExportEnrich) -> eyre::Result<()> {
+    let ExportEnrich {
+        organisation,
+        output,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let organisation = config.organisation(organisation.as_deref())?;
+    let source = organisation
+        .website_data
+        .as_ref()
+        .ok_or_else(|| eyre!("Organisation `{}` has no website data", organisation.name))?;
+    let client = github.client(&organisation.name)?;
+
+    let mut data: Value = github::download_repo_json_file(
+        &organisation.name,
+        &source.repo,
+        &source.branch,
+        &source.path,
+    )
+    .await
+    .wrap_err_with(|| format!("Unable to get the {} website data", organisation.name))?;
+    let repos = client.organisation_repos(&organisation.name).await?;
+    let names: Vec<_> = website_projects(&data)?
+        .iter()
+        .filter_map(repo_name)
+        .filter(|name| repos.contains_key(name))
+        .collect();
+    let (client, organisation) = (&client, &organisation.name);
+    let releases: HashMap<_, _> = stream::iter(names)
+        .map(|name| async move {
+            let tag = client.latest_release_tag(organisation, &name).await?;
+            Ok::<_, eyre::Report>((name, tag))
+        })
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .try_collect()
+        .await?;
+    add_github_metadata(&mut data, &repos, &releases)?;
+
+    let json = serde_json::to_string_pretty(&data).wrap_err("Unable to serialise website data")?;
+    match output {
+        Some(path) => std::fs::write(&path, json)
+            .wrap_err_with(|| format!("Unable to write {}", path.display())),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+/// Print the adoption metrics each run recorded in the published history,
+/// as a time series per project.
+pub async fn metrics(options: ExportMetrics) -> eyre::Result<()> {
+    let ExportMetrics {
+        results,
+        project,
+        format,
+        github,
+    } = options;
+    let client = github.into_client()?;
+    let history = results.download_history(&client).await?;
+    let mut series = history.metrics();
+    if let Some(project) = &project {
+        series.retain(|name, _| name == project);
+        if series.is_empty() {
+            return Err(eyre!("No metrics have been recorded for {}", project));
+        }
+    }
+    match format {
+        MetricsFormat::Csv => print!("{}", metrics_csv(&series)),
+        MetricsFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&metrics_json(&series))
+                .wrap_err("Unable to serialise metrics")?
+        ),
+    }
+    Ok(())
+}
+
+/// Print the unique contributors to each public project of every configured
+/// organisation over a time window, leaving out members of the organisation
+/// and bots.
+pub async fn contributors(options: ExportContributors) -> eyre::Result<()> {
+    let ExportContributors {
+        since,
+        until,
+        format,
+        github,
+        config,
+    } = options;
+    let parse = |date: &str| {
+        github::parse_date(date)
+            .ok_or_else(|| eyre!("Invalid date `{}`, expected YYYY-MM-DD", date))
+    };
+    let since = parse(&since)?;
+    let until = match until {
+        Some(until) => parse(&until)?,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+    };
+    if until <= since {
+        return Err(eyre!("The window must end after it starts"));
+    }
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+
+    let mut projects = BTreeMap::new();
+    // The organisations whose concealed members may be counted as external
+    // contributors, as the credentials only see their public members
+    let mut public_members_only = Vec::new();
+    for organisation in config.organisations() {
+        let client = github.client(&organisation.name)?;
+        let (members, repos) = futures::try_join!(
+            client.visible_organisation_members(&organisation.name),
+            client.organisation_repos(&organisation.name)
+        )?;
+        if !members.includes_concealed {
+            public_members_only.push(organisation.name.clone());
+        }
+        let members = members.logins;
+        let names = repos
+            .into_values()
+            .filter(github::Repo::is_public_active_source_project)
+            .map(|repo| repo.name);
+        let (client, organisation, members) = (&client, &organisation.name, &members);
+        let authors: Vec<_> = stream::iter(names)
+            .map(|name| async move {
+                let authors = client
+                    .commit_authors(organisation, &name, since, until)
+                    .await?;
+                Ok::<_, eyre::Report>((name, external_contributors(authors, members)))
+            })
+            .buffer_unordered(CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        projects.extend(authors);
+    }
+    let total = projects.values().flatten().collect::<HashSet<_>>().len();
+
+    match format {
+        Format::Text => {
+            println!(
+                "External contributors from {} to {}:",
+                github::format_timestamp(since),
+                github::format_timestamp(until)
+            );
+            for (project, contributors) in projects
+                .iter()
+                .filter(|(_, contributors)| !contributors.is_empty())
+                .sorted_by_key(|(_, contributors)| std::cmp::Reverse(contributors.len()))
+            {
+                println!("    {}: {}", project, contributors.len());
+            }
+            println!("{} unique external contributors in total", total);
+            for organisation in &public_members_only {
+                println!(
+                    "Only the public members of {} were left out, as the credentials can't see \
+its concealed members",
+                    organisation
+                );
+            }
+        }
+        Format::Json => {
+            let json = json!({
+                "since": github::format_timestamp(since),
+                "until": github::format_timestamp(until),
+                "total": total,
+                "projects": projects,
+                "public_members_only": public_members_only,
+            });
+            let json =
+                serde_json::to_string_pretty(&json).wrap_err("Unable to serialise contributors")?;
+            println!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+/// The authors who are neither members of the organisation nor bots.
+fn external_contributors(authors: HashSet<String>, members: &HashSet<String>) -> BTreeSet<String> {
+    let members: HashSet<_> = members
+        .iter()
+        .map(|member| member.to_ascii_lowercase())
+        .collect();
+    authors
+        .into_iter()
+        .filter(|author| {
+            !members.contains(&author.to_ascii_lowercase()) && !author.ends_with("[bot]")
+        })
+        .collect()
+}
+
+type Series<'a> = BTreeMap<&'a str, Vec<(u64, Metrics)>>;
+
+fn metrics_csv(series: &Series<'_>) -> String {
+    let mut csv = "time,project,stars,forks,contributors\n".to_string();
+    for (project, points) in series {
+        for (time, metrics) in points {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                github::format_timestamp(*time),
+                project,
+                metrics.stars,
+                metrics.forks,
+                metrics
+                    .contributors
+                    .map(|count| count.to_string())
+                    .unwrap_or_default()
+            ));
+        }
+    }
+    csv
+}
+
+fn metrics_json(series: &Series<'_>) -> Value {
+    series
+        .iter()
+        .map(|(project, points)| {
+            let points = points
+                .iter()
+                .map(|(time, metrics)| {
+                    json!({
+                        "time": github::format_timestamp(*time),
+                        "stars": metrics.stars,
+                        "forks": metrics.forks,
+                        "contributors": metrics.contributors,
+                    })
+                })
+                .collect();
+            (project.to_string(), Value::Array(points))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn website_projects(data: &Value) -> eyre::Result<&Vec<Value>> {
+    data.get("projects")
+        .and_then(Value::as_array)
+        .ok_or_else(|| eyre!("The website data has no projects list"))
+}
+
+/// The name of a project's repo, from its repo URL or else its name, as
+/// `Context::all_projects` finds it.
+fn repo_name(project: &Value) -> Option<String> {
+    let name = match project.get("repo").and_then(Value::as_str) {
+        Some(url) => url.trim_end_matches('/').rsplit('/').next()?,
+        None => project.get("name")?.as_str()?,
+    };
+    Some(name.to_string())
+}
+
+/// Add a `github` object to each project in the website data, which is null
+/// for projects without a repo in the organisation.
+fn add_github_metadata(
+    data: &mut Value,
+    repos: &HashMap<String, github::Repo>,
+    releases: &HashMap<String, Option<String>>,
+) -> eyre::Result<()> {
+    let projects = data
+        .get_mut("projects")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| eyre!("The website data has no projects list"))?;
+    for project in projects {
+        let metadata =
+            repo_name(project)
+                .and_then(|name| repos.get(&name))
+                .map_or(Value::Null, |repo| {
+                    json!({
+                        "stars": repo.stargazers_count,
+                        "language": repo.language,
+                        "archived": repo.archived,
+                        "latest_release": releases.get(&repo.name).cloned().flatten(),
+                    })
+                });
+        if let Some(project) = project.as_object_mut() {
+            let _ = project.insert("github".to_string(), metadata);
+        }
+    }
+    Ok(())
+}
+
+/// Teams are written `@org/team` in CODEOWNERS files.
+fn is_team(handle: &str) -> bool {
+    handle.contains('/')
+}
+
+impl Graph {
+    pub fn new(report: &Report) -> Self {
+        Self {
+            projects: report
+                .projects
+                .iter()
+                .map(|project| (project.name.clone(), project.maintainers.clone()))
+                .collect(),
+            teams: BTreeMap::new(),
+        }
+    }
+
+    /// Projects that only one person maintains, which are at risk if that
+    /// person leaves. Teams are assumed to have several members.
+    fn is_single_point_of_failure(&self, maintainers: &[String]) -> bool {
+        match maintainers {
+            [] => true,
+            [maintainer] if is_team(maintainer) => self
+                .teams
+                .get(maintainer)
+                .is_some_and(|members| members.len() < 2),
+            [_] => true,
+            _ => false,
+        }
+    }
+
+    /// The people and teams in the graph along with the projects with the
+    /// edges from maintainers to projects and from members to teams.
+    fn nodes_and_edges(&self) -> (Vec<Node<'_>>, Vec<(String, String)>) {
+        let mut nodes = Vec::new();
+        let mut maintainers = BTreeSet::new();
+        let mut edges = Vec::new();
+        for (project, project_maintainers) in &self.projects {
+            nodes.push(Node {
+                id: format!("project:{}", project),
+                label: project,
+                kind: if self.is_single_point_of_failure(project_maintainers) {
+                    "at-risk-project"
+                } else {
+                    "project"
+                },
+            });
+            for maintainer in project_maintainers {
+                let _ = maintainers.insert(maintainer.as_str());
+                edges.push((maintainer_id(maintainer), format!("project:{}", project)));
+            }
+        }
+        for (team, members) in &self.teams {
+            for member in members {
+                let _ = maintainers.insert(member.as_str());
+                edges.push((maintainer_id(member), maintainer_id(team)));
+            }
+        }
+        for maintainer in maintainers {
+            nodes.push(Node {
+                id: maintainer_id(maintainer),
+                label: maintainer,
+                kind: if is_team(maintainer) {
+                    "team"
+                } else {
+                    "person"
+                },
+            });
+        }
+        (nodes, edges)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let (nodes, edges) = self.nodes_and_edges();
+        let mut dot = String::from("digraph maintainers {\n    rankdir=LR;\n");
+        for node in nodes {
+            let attributes = match node.kind {
+                "project" => "shape=box",
+                "at-risk-project" => "shape=box, color=red",
+                "team" => "shape=ellipse, style=dashed",
+                _ => "shape=ellipse",
+            };
+            dot.push_str(&format!(
+                "    {} [label={}, {}];\n",
+                dot_string(&node.id),
+                dot_string(node.label),
+                attributes
+            ));
+        }
+        for (from, to) in edges {
+            dot.push_str(&format!(
+                "    {} -> {};\n",
+                dot_string(&from),
+                dot_string(&to)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let (nodes, edges) = self.nodes_and_edges();
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="kind" for="node" attr.name="kind" attr.type="string"/>
+  <graph id="maintainers" edgedefault="directed">
+"#,
+        );
+        for node in nodes {
+            xml.push_str(&format!(
+                "    <node id=\"{}\"><data key=\"label\">{}</data><data key=\"kind\">{}</data></node>\n",
+                xml_escape(&node.id),
+                xml_escape(node.label),
+                node.kind
+            ));
+        }
+        for (from, to) in edges {
+            xml.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"/>\n",
+                xml_escape(&from),
+                xml_escape(&to)
+            ));
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[derive(Debug)]
+struct Node<'a> {
+    id: String,
+    label: &'a str,
+    kind: &'static str,
+}
+
+fn maintainer_id(maintainer: &str) -> String {
+    if is_team(maintainer) {
+        format!("team:{}", maintainer)
+    } else {
+        format!("person:{}", maintainer)
+    }
+}
+
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_graph() -> Graph {
+        let mut graph = Graph::default();
+        for (project, maintainers) in [
+            ("ash", vec!["lpil", "arirawr"]),
+            ("cargo-deny", vec!["Jake-Shadle"]),
+            ("kajiya", vec!["EmbarkStudios/rendering"]),
+        ] {
+            let maintainers = maintainers.into_iter().map(String::from).collect();
+            let _ = graph.projects.insert(project.to_string(), maintainers);
+        }
+        graph
+    }
+
+    #[test]
+    fn dot() {
+        let dot = make_graph().to_dot();
+        assert!(dot.contains("\"project:ash\" [label=\"ash\", shape=box];"));
+        assert!(
+            dot.contains("\"project:cargo-deny\" [label=\"cargo-deny\", shape=box, color=red];")
+        );
+        assert!(dot.contains("\"person:lpil\" -> \"project:ash\";"));
+        assert!(dot.contains("\"team:EmbarkStudios/rendering\" -> \"project:kajiya\";"));
+    }
+
+    #[test]
+    fn teams_with_one_member_are_single_points_of_failure() {
+        let mut graph = make_graph();
+        let rendering = "EmbarkStudios/rendering".to_string();
+        let _ = graph
+            .teams
+            .insert(rendering.clone(), vec!["h3r2tic".to_string()]);
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"project:kajiya\" [label=\"kajiya\", shape=box, color=red];"));
+        assert!(dot.contains("\"person:h3r2tic\" -> \"team:EmbarkStudios/rendering\";"));
+
+        let _ = graph
+            .teams
+            .insert(rendering, vec!["h3r2tic".to_string(), "repi".to_string()]);
+        assert!(graph
+            .to_dot()
+            .contains("\"project:kajiya\" [label=\"kajiya\", shape=box];"));
+    }
+
+    #[test]
+    fn enriched_website_data() {
+        let mut data = json!({
+            "projects": [
+                {"name": "Ash", "repo": "https://github.com/EmbarkStudios/ash", "tags": ["rust"]},
+                {"name": "cargo-deny"},
+                {"name": "Website", "repo": "https://github.com/EmbarkStudios/missing/"},
+            ]
+        });
+        let repo = |name: &str, stars, language: Option<&str>| github::Repo {
+            name: name.to_string(),
+            archived: false,
+            private: false,
+            fork: false,
+            topics: Vec::new(),
+            pushed_at: None,
+            stargazers_count: stars,
+            forks_count: 0,
+            language: language.map(String::from),
+        };
+        let repos: HashMap<_, _> = vec![
+            repo("ash", 1500, Some("Rust")),
+            repo("cargo-deny", 1200, None),
+        ]
+        .into_iter()
+        .map(|repo| (repo.name.clone(), repo))
+        .collect();
+        let releases: HashMap<_, _> = vec![
+            ("ash".to_string(), Some("0.37.0".to_string())),
+            ("cargo-deny".to_string(), None),
+        ]
+        .into_iter()
+        .collect();
+        add_github_metadata(&mut data, &repos, &releases).unwrap();
+        assert_eq!(
+            data,
+            json!({
+                "projects": [
+                    {
+                        "name": "Ash",
+                        "repo": "https://github.com/EmbarkStudios/ash",
+                        "tags": ["rust"],
+                        "github": {
+                            "stars": 1500,
+                            "language": "Rust",
+                            "archived": false,
+                            "latest_release": "0.37.0",
+                        },
+                    },
+                    {
+                        "name": "cargo-deny",
+                        "github": {
+                            "stars": 1200,
+                            "language": null,
+                            "archived": false,
+                            "latest_release": null,
+                        },
+                    },
+                    {
+                        "name": "Website",
+                        "repo": "https://github.com/EmbarkStudios/missing/",
+                        "github": null,
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn metrics_series() {
+        let history: crate::validate::History = serde_json::from_value(json!({
+            "runs": [
+                {
+                    "time": 0,
+                    "failures": {},
+                    "metrics": {"ash": {"stars": 10, "forks": 1, "contributors": 2}},
+                },
+                {"time": 3600, "failures": {}},
+                {
+                    "time": 86_400,
+                    "failures": {},
+                    "metrics": {"ash": {"stars": 12, "forks": 1}},
+                },
+            ]
+        }))
+        .unwrap();
+        let series = history.metrics();
+        assert_eq!(
+            metrics_csv(&series),
+            "time,project,stars,forks,contributors\n\
+1970-01-01T00:00:00Z,ash,10,1,2\n\
+1970-01-02T00:00:00Z,ash,12,1,\n"
+        );
+        assert_eq!(
+            metrics_json(&series)["ash"][1],
+            json!({"time": "1970-01-02T00:00:00Z", "stars": 12, "forks": 1, "contributors": null})
+        );
+    }
+
+    #[test]
+    fn external_contributors_leave_out_members_and_bots() {
+        let set = |logins: &[&str]| -> HashSet<String> {
+            logins.iter().map(ToString::to_string).collect()
+        };
+        let authors = set(&["repi", "someone", "dependabot[bot]", "Another"]);
+        let members = set(&["Repi"]);
+        assert_eq!(
+            external_contributors(authors, &members),
+            vec!["Another".to_string(), "someone".to_string()]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn graphml() {
+        let xml = make_graph().to_graphml();
+        assert!(xml.contains(
+            "<node id=\"person:lpil\"><data key=\"label\">lpil</data><data key=\"kind\">person</data></node>"
+        ));
+        assert!(xml.contains("<edge source=\"person:lpil\" target=\"project:ash\"/>"));
+    }
+}
@@ -1,63 +1,1501 @@
+mod app;
 mod codeowners;
+pub mod memo;
+pub mod tarball;
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryFrom,
+    sync::Mutex,
+};
 
-pub use codeowners::CodeOwners;
+pub use app::App;
+pub use codeowners::{CodeOwners, Owners};
+
+use crate::http::Traced;
 
 use eyre::{eyre, WrapErr};
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::de::DeserializeOwned;
 
 /// A GitHub API client that optionally authenticates requests.
 pub struct Client {
-    github_api_token: Option<String>,
+    auth: Auth,
+}
+
+/// How requests to the GitHub API are authenticated.
+pub enum Auth {
+    Anonymous,
+    /// A personal access token or the `GITHUB_TOKEN` of an Actions workflow.
+    Token(String),
+    App(App),
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("auth", &self.auth_description())
+            .finish()
+    }
 }
 
 impl Client {
-    pub fn new(github_api_token: Option<String>) -> Self {
-        Self { github_api_token }
+    pub fn new(auth: Auth) -> Self {
+        Self { auth }
+    }
+
+    /// Whether requests are made with credentials, without which GitHub
+    /// doesn't share repo settings.
+    pub fn is_authenticated(&self) -> bool {
+        !matches!(self.auth, Auth::Anonymous)
+    }
+
+    /// Whether requests are made as a GitHub App, which is the only way to
+    /// create check runs.
+    pub fn is_app(&self) -> bool {
+        matches!(self.auth, Auth::App(_))
+    }
+
+    /// Fail with `NeedsAuth` if requests are anonymous.
+    fn require_auth(&self) -> Result<(), NeedsAuth> {
+        if self.is_authenticated() {
+            Ok(())
+        } else {
+            Err(NeedsAuth)
+        }
+    }
+
+    /// A description of how requests are authenticated.
+    pub fn auth_description(&self) -> &'static str {
+        match self.auth {
+            Auth::Anonymous => "anonymous",
+            Auth::Token(_) => "API token",
+            Auth::App(_) => "GitHub App",
+        }
+    }
+
+    // https://docs.github.com/en/rest/rate-limit#get-rate-limit-status-for-the-authenticated-user
+    /// Get the core API rate limit, which doesn't itself count against the
+    /// limit. Also returns the OAuth scopes of the token, if a token with
+    /// scopes is used.
+    pub async fn rate_limit(&self) -> eyre::Result<(RateLimit, Option<Vec<String>>)> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Response {
+            resources: Resources,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Resources {
+            core: RateLimit,
+        }
+
+        let response = self
+            .api_get_response("https://api.github.com/rate_limit")
+            .await?;
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|scopes| scopes.to_str().ok())
+            .map(|scopes| {
+                scopes
+                    .split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            });
+        let response: Response = response
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok((response.resources.core, scopes))
+    }
+
+    /// The members of an organisation visible to the credentials, and whether
+    /// they include concealed members, which only a member's credentials can
+    /// see. Anonymous requests only see the public members.
+    pub async fn visible_organisation_members(
+        &self,
+        organisation: &str,
+    ) -> eyre::Result<OrganisationMembers> {
+        let logins = self.public_organisation_members(organisation).await?;
+        if !self.is_authenticated() {
+            return Ok(OrganisationMembers {
+                logins,
+                includes_concealed: false,
+            });
+        }
+        // https://docs.github.com/en/rest/orgs/members#list-public-organization-members
+        let url = format!(
+            "https://api.github.com/orgs/{}/public_members?per_page=100",
+            organisation
+        );
+        let public: Vec<serde::de::IgnoredAny> = self
+            .api_list(url)
+            .await
+            .wrap_err("Unable to get public members for organisation")?;
+        Ok(OrganisationMembers {
+            includes_concealed: logins.len() > public.len(),
+            logins,
+        })
+    }
+
+    // https://docs.github.com/en/free-pro-team@latest/rest/reference/orgs#members
+    pub async fn public_organisation_members(
+        &self,
+        organisation: &str,
+    ) -> eyre::Result<HashSet<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Member {
+            login: String,
+        }
+
+        let url = format!(
+            "https://api.github.com/orgs/{}/members?per_page=100",
+            organisation
+        );
+        Ok(self
+            .api_list(url)
+            .await
+            .wrap_err("Unable to get public members for organisation")?
+            .into_iter()
+            .map(|member: Member| member.login)
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/teams/members#list-team-members
+    /// Get the usernames of the members of a team, which requires the
+    /// `read:org` scope.
+    pub async fn team_members(&self, organisation: &str, team: &str) -> eyre::Result<Vec<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Member {
+            login: String,
+        }
+
+        self.require_auth()?;
+        let url = format!(
+            "https://api.github.com/orgs/{}/teams/{}/members?per_page=100",
+            organisation, team
+        );
+        Ok(self
+            .api_list(url)
+            .await
+            .wrap_err_with(|| format!("Unable to get members of team {}/{}", organisation, team))?
+            .into_iter()
+            .map(|member: Member| member.login)
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/activity/events#list-public-organization-events
+    /// Get the recent public events of an organisation, newest first. GitHub
+    /// only returns up to 300 events from the last 90 days.
+    pub async fn organisation_events(&self, organisation: &str) -> eyre::Result<Vec<Event>> {
+        let url = format!(
+            "https://api.github.com/orgs/{}/events?per_page=100",
+            organisation
+        );
+        self.api_list(url)
+            .await
+            .wrap_err("Unable to get events for organisation")
+    }
+
+    // https://docs.github.com/en/rest/repos/contents#get-repository-content
+    /// Get the paths of the files in a directory on the default branch of a
+    /// repo, which are empty if the directory doesn't exist.
+    pub async fn directory_files(
+        &self,
+        organisation: &str,
+        repo: &str,
+        path: &str,
+    ) -> eyre::Result<Vec<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Entry {
+            path: String,
+            #[serde(rename = "type")]
+            kind: String,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}",
+            organisation, repo, path
+        );
+        let entries: Vec<Entry> = self.api_get_optional_json(&url).await?.unwrap_or_default();
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.kind == "file")
+            .map(|entry| entry.path)
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#get-a-repository
+    /// Get the security settings of a repo. GitHub only includes them for
+    /// clients with admin access to the repo.
+    pub async fn security_and_analysis(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<SecurityAndAnalysis> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Response {
+            security_and_analysis: Option<SecurityAndAnalysis>,
+        }
+
+        self.require_auth()?;
+        let url = format!("https://api.github.com/repos/{}/{}", organisation, repo);
+        let response: Response = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        response.security_and_analysis.ok_or_else(|| {
+            eyre!(
+                "Security settings of {} are not visible, admin access to the repo is required",
+                repo
+            )
+        })
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#check-if-vulnerability-alerts-are-enabled-for-a-repository
+    /// Whether Dependabot alerts are enabled for a repo. Requires admin
+    /// access to the repo.
+    pub async fn vulnerability_alerts_enabled(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<bool> {
+        self.require_auth()?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/vulnerability-alerts",
+            organisation, repo
+        );
+        let response = self
+            .api_request(reqwest::Method::GET, &url)
+            .await?
+            .send_traced()
+            .await
+            .wrap_err(format!("Failed to get {}", url))?;
+        if is_refused(response.status()) {
+            return self.refused_admin_setting(organisation, repo).await;
+        }
+        let _ = response.error_for_status()?;
+        Ok(true)
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#get-a-repository
+    /// Whether the credentials have admin access to a repo.
+    pub async fn is_repo_admin(&self, organisation: &str, repo: &str) -> eyre::Result<bool> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Permissions {
+            admin: bool,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Response {
+            permissions: Option<Permissions>,
+        }
+
+        let url = format!("https://api.github.com/repos/{}/{}", organisation, repo);
+        let response: Response = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(response
+            .permissions
+            .is_some_and(|permissions| permissions.admin))
+    }
+
+    /// Whether an admin only setting that GitHub refused to show is off. As
+    /// it refuses both when the setting is off and when the credentials
+    /// can't see it, the setting is only off to credentials with admin access
+    /// to the repo, and otherwise such credentials are needed.
+    async fn refused_admin_setting(&self, organisation: &str, repo: &str) -> eyre::Result<bool> {
+        if self.is_repo_admin(organisation, repo).await? {
+            Ok(false)
+        } else {
+            Err(eyre::Report::new(NeedsAuth).wrap_err(format!(
+                "Admin access to {}/{} is needed to see its settings",
+                organisation, repo
+            )))
+        }
+    }
+
+    /// Get the tag of the latest release of a repo, if it has been released.
+    pub async fn latest_release_tag(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Option<String>> {
+        let release = self.latest_release(organisation, repo).await?;
+        Ok(release.map(|release| release.tag_name))
+    }
+
+    // https://docs.github.com/en/rest/releases/releases#get-the-latest-release
+    /// Get the latest release of a repo, if it has been released.
+    pub async fn latest_release(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Option<Release>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/releases/latest",
+            organisation, repo
+        );
+        self.api_get_optional_json(&url).await
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#list-repository-contributors
+    /// Count the contributors to a repo, including anonymous ones, with one
+    /// request by listing one contributor per page and reading the number of
+    /// the last page.
+    pub async fn contributor_count(&self, organisation: &str, repo: &str) -> eyre::Result<u64> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contributors?per_page=1&anon=true",
+            organisation, repo
+        );
+        let response = self.api_get_response(&url).await?;
+        // Empty repos have no content
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(0);
+        }
+        let last_page = response
+            .headers()
+            .get("link")
+            .and_then(|link| link.to_str().ok())
+            .and_then(last_page_from_link_header);
+        match last_page {
+            Some(page) => Ok(page),
+            None => {
+                let contributors: Vec<serde_json::Value> = response
+                    .json()
+                    .await
+                    .wrap_err("Unable to parse JSON response")?;
+                Ok(contributors.len() as u64)
+            }
+        }
+    }
+
+    // https://docs.github.com/en/rest/commits/commits#list-commits
+    /// Get the logins of the authors of the commits to the default branch of
+    /// a repo between two times, in seconds since the Unix epoch. Commits by
+    /// authors without a GitHub account are left out.
+    pub async fn commit_authors(
+        &self,
+        organisation: &str,
+        repo: &str,
+        since: u64,
+        until: u64,
+    ) -> eyre::Result<HashSet<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Commit {
+            author: Option<Author>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Author {
+            login: String,
+        }
+
+        let url = reqwest::Url::parse_with_params(
+            &format!(
+                "https://api.github.com/repos/{}/{}/commits",
+                organisation, repo
+            ),
+            &[
+                ("since", format_timestamp(since)),
+                ("until", format_timestamp(until)),
+                ("per_page", "100".to_string()),
+            ],
+        )?;
+        let commits: Vec<Commit> = match self.api_list(url.to_string()).await {
+            Ok(commits) => commits,
+            // Empty repos have no commits to list
+            Err(error) if is_status(&error, reqwest::StatusCode::CONFLICT) => Vec::new(),
+            Err(error) => {
+                return Err(error.wrap_err(format!("Unable to get the commits of {}", repo)))
+            }
+        };
+        Ok(commits
+            .into_iter()
+            .filter_map(|commit| Some(commit.author?.login))
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/commits/commits#compare-two-commits
+    /// Count the commits the default branch of a repo is ahead of a tag.
+    pub async fn commits_ahead_of(
+        &self,
+        organisation: &str,
+        repo: &str,
+        tag: &str,
+    ) -> eyre::Result<u64> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Comparison {
+            ahead_by: u64,
+        }
+
+        let branch = self.default_branch(organisation, repo).await?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/compare/{}...{}?per_page=1",
+            organisation, repo, tag, branch
+        );
+        let comparison: Comparison = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(comparison.ahead_by)
+    }
+
+    // https://docs.github.com/en/rest/git/refs#get-a-reference
+    // https://docs.github.com/en/rest/git/tags#get-a-tag
+    /// Whether a tag is an annotated tag with a signature GitHub could
+    /// verify. Lightweight tags can't be signed.
+    pub async fn is_tag_verified(
+        &self,
+        organisation: &str,
+        repo: &str,
+        tag: &str,
+    ) -> eyre::Result<bool> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Ref {
+            object: Object,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Object {
+            #[serde(rename = "type")]
+            kind: String,
+            sha: String,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Tag {
+            verification: Verification,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Verification {
+            verified: bool,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/ref/tags/{}",
+            organisation, repo, tag
+        );
+        let tag_ref: Ref = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        if tag_ref.object.kind != "tag" {
+            return Ok(false);
+        }
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/tags/{}",
+            organisation, repo, tag_ref.object.sha
+        );
+        let tag: Tag = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(tag.verification.verified)
+    }
+
+    // https://docs.github.com/en/rest/branches/branch-protection#get-commit-signature-protection
+    /// Whether branch protection of the default branch of a repo requires
+    /// commits to be signed. Requires admin access to the repo.
+    pub async fn requires_signed_commits(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<bool> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Protection {
+            enabled: bool,
+        }
+
+        self.require_auth()?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}/protection/required_signatures",
+            organisation,
+            repo,
+            self.default_branch(organisation, repo).await?
+        );
+        let response = self
+            .api_request(reqwest::Method::GET, &url)
+            .await?
+            .send_traced()
+            .await
+            .wrap_err(format!("Failed to get {}", url))?;
+        if is_refused(response.status()) {
+            return self.refused_admin_setting(organisation, repo).await;
+        }
+        let protection: Protection = response
+            .error_for_status()?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(protection.enabled)
+    }
+
+    // https://docs.github.com/en/rest/branches/branch-protection#get-status-checks-protection
+    /// The names of the status checks that must pass before pull requests
+    /// can be merged into the default branch of a repo, which are empty if
+    /// the branch isn't protected.
+    pub async fn required_status_checks(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Vec<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct StatusChecks {
+            #[serde(default)]
+            contexts: Vec<String>,
+            #[serde(default)]
+            checks: Vec<StatusCheck>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct StatusCheck {
+            context: String,
+        }
+
+        self.require_auth()?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}/protection/required_status_checks",
+            organisation,
+            repo,
+            self.default_branch(organisation, repo).await?
+        );
+        let status_checks: Option<StatusChecks> = self.api_get_optional_json(&url).await?;
+        Ok(status_checks
+            .map(|status_checks| {
+                status_checks
+                    .contexts
+                    .into_iter()
+                    .chain(status_checks.checks.into_iter().map(|check| check.context))
+                    .unique()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    // https://docs.github.com/en/rest/issues/issues#list-repository-issues
+    /// Count the open issues of a repo that have a label, up to 100. Pull
+    /// requests with the label are not counted.
+    pub async fn open_issues_with_label(
+        &self,
+        organisation: &str,
+        repo: &str,
+        label: &str,
+    ) -> eyre::Result<usize> {
+        let url = reqwest::Url::parse_with_params(
+            &format!(
+                "https://api.github.com/repos/{}/{}/issues",
+                organisation, repo
+            ),
+            &[("state", "open"), ("labels", label), ("per_page", "100")],
+        )?;
+        let issues: Vec<Issue> = self
+            .api_get_response(url.as_str())
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(issues
+            .iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .count())
+    }
+
+    // https://docs.github.com/en/rest/issues/issues#list-repository-issues
+    /// Get the most recently opened issues of a repo, newest first, up to
+    /// `count` of them. Pull requests are left out.
+    pub async fn recent_issues(
+        &self,
+        organisation: &str,
+        repo: &str,
+        count: usize,
+    ) -> eyre::Result<Vec<Issue>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues?state=all&sort=created&direction=desc&per_page=100",
+            organisation, repo
+        );
+        let issues: Vec<Issue> = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .take(count)
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/issues/comments#list-issue-comments
+    /// Get the first comments on an issue, oldest first.
+    pub async fn issue_comments(
+        &self,
+        organisation: &str,
+        repo: &str,
+        issue: u64,
+    ) -> eyre::Result<Vec<IssueComment>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}/comments?per_page=100",
+            organisation, repo, issue
+        );
+        self.api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#list-repository-languages
+    /// Get the languages of a repo's code, with the number of bytes of each,
+    /// as detected by GitHub.
+    pub async fn languages(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<HashMap<String, u64>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/languages",
+            organisation, repo
+        );
+        self.api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")
+    }
+
+    // https://docs.github.com/en/rest/pulls/pulls#list-pull-requests
+    /// Get the most recently opened pull requests of a repo, newest first, up
+    /// to `count` of them.
+    pub async fn recent_pull_requests(
+        &self,
+        organisation: &str,
+        repo: &str,
+        count: usize,
+    ) -> eyre::Result<Vec<PullRequest>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls?state=all&sort=created&direction=desc&per_page={}",
+            organisation,
+            repo,
+            count.min(100)
+        );
+        self.api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")
+    }
+
+    // https://docs.github.com/en/rest/pulls/reviews#list-reviews-for-a-pull-request
+    /// Get the submitted reviews of a pull request as comments, oldest first.
+    pub async fn pull_request_reviews(
+        &self,
+        organisation: &str,
+        repo: &str,
+        pull_request: u64,
+    ) -> eyre::Result<Vec<IssueComment>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Review {
+            submitted_at: Option<String>,
+            author_association: String,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            organisation, repo, pull_request
+        );
+        let reviews: Vec<Review> = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(reviews
+            .into_iter()
+            .filter_map(|review| {
+                Some(IssueComment {
+                    created_at: review.submitted_at?,
+                    author_association: review.author_association,
+                })
+            })
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/git/trees#get-a-tree
+    /// Get the name of the default branch of a repo and every file on it.
+    pub async fn repo_files(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<(String, Vec<RepoFile>)> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Tree {
+            tree: Vec<Entry>,
+            truncated: bool,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Entry {
+            path: String,
+            #[serde(rename = "type")]
+            kind: String,
+            size: Option<u64>,
+        }
+
+        let branch = self.default_branch(organisation, repo).await?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
+            organisation, repo, branch
+        );
+        let tree: Tree = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        if tree.truncated {
+            return Err(eyre!("{} has too many files to list", repo));
+        }
+        let files = tree
+            .tree
+            .into_iter()
+            .filter(|entry| entry.kind == "blob")
+            .map(|entry| RepoFile {
+                path: entry.path,
+                size: entry.size.unwrap_or_default(),
+            })
+            .collect();
+        Ok((branch, files))
+    }
+
+    // https://docs.github.com/en/rest/repos/contents#download-a-repository-archive-tar
+    /// Download every file on a branch of a repo in one archive, rather than
+    /// making a request for each. The files of private repos can be read too.
+    pub async fn repo_archive(
+        &self,
+        organisation: &str,
+        repo: &str,
+        branch: &str,
+    ) -> eyre::Result<RepoArchive> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/tarball/{}",
+            organisation, repo, branch
+        );
+        let name = format!("the archive of {}/{}", organisation, repo);
+        let response = self.api_get_response(&url).await?;
+        let gzipped = read_body(&name, response, MAX_ARCHIVE_SIZE).await?;
+
+        // Decompressing and reading the archive is CPU bound so keep it off
+        // the async runtime
+        tokio::task::spawn_blocking(move || RepoArchive::unpack(&name, &gzipped)).await?
+    }
+
+    // https://docs.github.com/en/rest/repos/contents#get-repository-content
+    /// Download a file with the API rather than from
+    /// raw.githubusercontent.com, so that the files of private repos can be
+    /// read. Failures are as for `download_repo_file`.
+    pub async fn repo_file(
+        &self,
+        organisation: &str,
+        repo: &str,
+        branch: &str,
+        path: &str,
+    ) -> Result<String, DownloadError> {
+        let name = format!("{}/{}:{}", organisation, repo, path);
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
+            organisation,
+            repo,
+            percent_encode(path, "/"),
+            percent_encode(branch, "")
+        );
+        let network = |error: &dyn std::fmt::Display| DownloadError::Network {
+            name: name.clone(),
+            message: error.to_string(),
+        };
+        let response = self
+            .api_request_accepting(reqwest::Method::GET, &url, "application/vnd.github.raw")
+            .await
+            .map_err(|error| network(&error))?
+            .send_traced()
+            .await
+            .map_err(|error| network(&error))?;
+        match response.status().as_u16() {
+            200 => {}
+            404 => return Err(DownloadError::NotFound { name }),
+            status => return Err(DownloadError::Http { name, status }),
+        }
+        let body = read_body(&name, response, MAX_FILE_SIZE).await?;
+        decode_text(name, None, body)
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#get-a-repository
+    pub async fn default_branch(&self, organisation: &str, repo: &str) -> eyre::Result<String> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Repo {
+            default_branch: String,
+        }
+
+        let url = format!("https://api.github.com/repos/{}/{}", organisation, repo);
+        let details: Repo = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(details.default_branch)
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#get-a-repository
+    /// The `org/repo` name of a repo, or `None` if it doesn't exist. The name
+    /// differs from the one asked for if the repo was renamed or transferred,
+    /// as GitHub redirects requests for its old name.
+    pub async fn repo_full_name(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Option<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Repo {
+            full_name: String,
+        }
+
+        let url = format!("https://api.github.com/repos/{}/{}", organisation, repo);
+        let details: Option<Repo> = self.api_get_optional_json(&url).await?;
+        Ok(details.map(|repo| repo.full_name))
+    }
+
+    // https://docs.github.com/en/rest/branches/branches#get-a-branch
+    pub async fn has_branch(
+        &self,
+        organisation: &str,
+        repo: &str,
+        branch: &str,
+    ) -> eyre::Result<bool> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}",
+            organisation, repo, branch
+        );
+        let branch: Option<serde_json::Value> = self.api_get_optional_json(&url).await?;
+        Ok(branch.is_some())
+    }
+
+    // https://docs.github.com/en/rest/branches/branches#list-branches
+    // https://docs.github.com/en/rest/repos/repos#list-repository-tags
+    /// Get the name and commit SHA of every branch and tag of a repo.
+    pub async fn refs(
+        &self,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Vec<(String, String)>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Ref {
+            name: String,
+            commit: Commit,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Commit {
+            sha: String,
+        }
+
+        let mut refs = Vec::new();
+        for kind in ["branches", "tags"] {
+            let url = format!(
+                "https://api.github.com/repos/{}/{}/{}?per_page=100",
+                organisation, repo, kind
+            );
+            let listed: Vec<Ref> = self
+                .api_list(url)
+                .await
+                .wrap_err_with(|| format!("Unable to get the {} of {}", kind, repo))?;
+            refs.extend(
+                listed
+                    .into_iter()
+                    .map(|listed| (listed.name, listed.commit.sha)),
+            );
+        }
+        Ok(refs)
+    }
+
+    // https://docs.github.com/en/rest/commits/commits#list-commits
+    /// Get the SHA of the latest commit on the default branch of a repo.
+    pub async fn head_commit_sha(&self, organisation: &str, repo: &str) -> eyre::Result<String> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Commit {
+            sha: String,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/commits?per_page=1",
+            organisation, repo
+        );
+        let commits: Vec<Commit> = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        commits
+            .into_iter()
+            .next()
+            .map(|commit| commit.sha)
+            .ok_or_else(|| eyre!("{} has no commits", repo))
+    }
+
+    // https://docs.github.com/en/free-pro-team@latest/rest/reference/repos#list-organization-repositories
+    pub async fn organisation_repos(
+        &self,
+        organisation: &str,
+    ) -> eyre::Result<HashMap<String, Repo>> {
+        let url = format!(
+            "https://api.github.com/orgs/{}/repos?type=archived&per_page=100",
+            organisation
+        );
+        Ok(self
+            .api_list(url)
+            .await
+            .wrap_err("Unable to get archived repos for organisation")?
+            .into_iter()
+            .map(|repo: Repo| (repo.name.clone(), repo))
+            .collect())
+    }
+
+    // https://docs.github.com/en/rest/gists/gists#get-a-gist
+    /// Get the contents of a file in a gist, returning `None` if the gist has
+    /// no file with that name.
+    pub async fn gist_file(&self, gist_id: &str, file_name: &str) -> eyre::Result<Option<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Gist {
+            files: HashMap<String, GistFile>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct GistFile {
+            content: String,
+            truncated: bool,
+            raw_url: String,
+        }
+
+        let url = format!("https://api.github.com/gists/{}", gist_id);
+        let gist: Gist = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        let file = match gist.files.get(file_name) {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+
+        // Large files are truncated in the API response so they must be
+        // downloaded separately.
+        if !file.truncated {
+            return Ok(Some(file.content.clone()));
+        }
+        let content = self
+            .api_get_response(&file.raw_url)
+            .await?
+            .text()
+            .await
+            .wrap_err("Unable to read gist file")?;
+        Ok(Some(content))
+    }
+
+    // https://docs.github.com/en/rest/gists/gists#update-a-gist
+    /// Create or replace a file in a gist. Requires an API token with the
+    /// `gist` scope.
+    pub async fn update_gist_file(
+        &self,
+        gist_id: &str,
+        file_name: &str,
+        content: String,
+    ) -> eyre::Result<()> {
+        let url = format!("https://api.github.com/gists/{}", gist_id);
+        let body = serde_json::json!({ "files": { file_name: { "content": content } } });
+        if crate::dry_run::is_enabled() {
+            crate::dry_run::print_skipped(&format!("update gist {}", gist_id), &body);
+            return Ok(());
+        }
+        let _ = self
+            .api_request(reqwest::Method::PATCH, &url)
+            .await?
+            .json(&body)
+            .send_traced()
+            .await
+            .wrap_err(format!("Failed to patch {}", url))?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    // https://docs.github.com/en/rest/repos/repos#replace-all-repository-topics
+    /// Replace the topics of a repo. Requires admin access to the repo.
+    pub async fn set_repo_topics(
+        &self,
+        organisation: &str,
+        repo: &str,
+        topics: &[String],
+    ) -> eyre::Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/topics",
+            organisation, repo
+        );
+        let body = serde_json::json!({ "names": topics });
+        if crate::dry_run::is_enabled() {
+            crate::dry_run::print_skipped(&format!("set the topics of {}", repo), &body);
+            return Ok(());
+        }
+        let _ = self
+            .api_request(reqwest::Method::PUT, &url)
+            .await?
+            .json(&body)
+            .send_traced()
+            .await
+            .wrap_err(format!("Failed to put {}", url))?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    // https://docs.github.com/en/rest/checks/runs#create-a-check-run
+    /// Create a check run on a commit of a repo. Requires authenticating as a
+    /// GitHub App with write access to checks.
+    pub async fn create_check_run(
+        &self,
+        organisation: &str,
+        repo: &str,
+        check_run: &serde_json::Value,
+    ) -> eyre::Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/check-runs",
+            organisation, repo
+        );
+        self.api_post(&url, &format!("create a check run on {}", repo), check_run)
+            .await
+    }
+
+    // https://docs.github.com/en/rest/pulls/pulls#get-a-pull-request
+    /// The commit at the head of a pull request and the repo it is in, which
+    /// is a fork for pull requests from outside the organisation.
+    pub async fn pull_request_head(
+        &self,
+        organisation: &str,
+        repo: &str,
+        number: u64,
+    ) -> eyre::Result<PullRequestHead> {
+        #[derive(Debug, serde::Deserialize)]
+        struct Pull {
+            head: Head,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Head {
+            sha: String,
+            repo: Option<HeadRepo>,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct HeadRepo {
+            full_name: String,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}",
+            organisation, repo, number
+        );
+        let pull: Pull = self
+            .api_get_response(&url)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        let head_repo = pull
+            .head
+            .repo
+            .ok_or_else(|| eyre!("The repo pull request #{} is from was deleted", number))?;
+        Ok(PullRequestHead {
+            sha: pull.head.sha,
+            repo: head_repo.full_name,
+        })
+    }
+
+    // https://docs.github.com/en/rest/commits/statuses#create-a-commit-status
+    pub async fn create_commit_status(
+        &self,
+        organisation: &str,
+        repo: &str,
+        sha: &str,
+        status: &serde_json::Value,
+    ) -> eyre::Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/statuses/{}",
+            organisation, repo, sha
+        );
+        self.api_post(
+            &url,
+            &format!("set the status of {} in {}", sha, repo),
+            status,
+        )
+        .await
+    }
+
+    // https://docs.github.com/en/rest/issues/comments#create-an-issue-comment
+    pub async fn create_issue_comment(
+        &self,
+        organisation: &str,
+        repo: &str,
+        number: u64,
+        body: &str,
+    ) -> eyre::Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}/comments",
+            organisation, repo, number
+        );
+        let action = format!("comment on #{} in {}", number, repo);
+        self.api_post(&url, &action, &serde_json::json!({ "body": body }))
+            .await
+    }
+
+    // https://docs.github.com/en/rest/pulls/reviews#create-a-review-for-a-pull-request
+    pub async fn create_pull_request_review(
+        &self,
+        organisation: &str,
+        repo: &str,
+        number: u64,
+        review: &serde_json::Value,
+    ) -> eyre::Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/reviews",
+            organisation, repo, number
+        );
+        self.api_post(&url, &format!("review #{} in {}", number, repo), review)
+            .await
+    }
+
+    /// Open an issue, returning its URL, or `None` in a dry run.
+    // https://docs.github.com/en/rest/issues/issues#create-an-issue
+    pub async fn create_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue: &serde_json::Value,
+    ) -> eyre::Result<Option<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct Issue {
+            html_url: String,
+        }
+
+        if crate::dry_run::is_enabled() {
+            crate::dry_run::print_skipped(&format!("open an issue in {}/{}", owner, repo), issue);
+            return Ok(None);
+        }
+        let url = format!("https://api.github.com/repos/{}/{}/issues", owner, repo);
+        let issue: Issue = self
+            .api_post_response(&url, issue)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(Some(issue.html_url))
+    }
+
+    /// Start a discussion in the category with the given name, returning its
+    /// URL, or `None` in a dry run. Discussions are only in the GraphQL API.
+    pub async fn create_discussion(
+        &self,
+        owner: &str,
+        repo: &str,
+        category: &str,
+        title: &str,
+        body: &str,
+    ) -> eyre::Result<Option<String>> {
+        if crate::dry_run::is_enabled() {
+            let discussion = serde_json::json!({
+                "category": category,
+                "title": title,
+                "body": body,
+            });
+            let action = format!("start a discussion in {}/{}", owner, repo);
+            crate::dry_run::print_skipped(&action, &discussion);
+            return Ok(None);
+        }
+
+        // https://docs.github.com/en/graphql/reference/objects#repository
+        let query = "query($owner: String!, $name: String!) {
+            repository(owner: $owner, name: $name) {
+                id
+                discussionCategories(first: 100) { nodes { id name } }
+            }
+        }";
+        let data = self
+            .graphql(query, serde_json::json!({ "owner": owner, "name": repo }))
+            .await?;
+        let repository = &data["repository"];
+        let repository_id = repository["id"]
+            .as_str()
+            .ok_or_else(|| eyre!("{}/{} was not found", owner, repo))?;
+        let categories = repository["discussionCategories"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let category_id = categories
+            .iter()
+            .find(|node| {
+                node["name"]
+                    .as_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(category))
+            })
+            .and_then(|node| node["id"].as_str())
+            .ok_or_else(|| {
+                eyre!(
+                    "{}/{} has no discussion category named `{}`, or discussions are disabled",
+                    owner,
+                    repo,
+                    category
+                )
+            })?;
+
+        // https://docs.github.com/en/graphql/reference/mutations#creatediscussion
+        let mutation =
+            "mutation($repository: ID!, $category: ID!, $title: String!, $body: String!) {
+            createDiscussion(input: {
+                repositoryId: $repository, categoryId: $category, title: $title, body: $body
+            }) { discussion { url } }
+        }";
+        let variables = serde_json::json!({
+            "repository": repository_id,
+            "category": category_id,
+            "title": title,
+            "body": body,
+        });
+        let data = self.graphql(mutation, variables).await?;
+        let url = data["createDiscussion"]["discussion"]["url"]
+            .as_str()
+            .ok_or_else(|| eyre!("GitHub did not return the URL of the discussion"))?;
+        Ok(Some(url.to_string()))
+    }
+
+    /// Perform a GraphQL request, returning its data. GraphQL errors are
+    /// returned with a successful status, so they are turned into errors here.
+    // https://docs.github.com/en/graphql/guides/forming-calls-with-graphql
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> eyre::Result<serde_json::Value> {
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let mut response: serde_json::Value = self
+            .api_post_response("https://api.github.com/graphql", &body)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        if let Some(errors) = response["errors"].as_array() {
+            let messages: Vec<_> = errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect();
+            return Err(eyre!(messages.join("\n")).wrap_err("GitHub GraphQL request failed"));
+        }
+        Ok(response["data"].take())
+    }
+
+    /// Open a pull request that commits changes to files on a new branch,
+    /// returning its URL, or `None` in a dry run. If the branch is left from
+    /// an earlier pull request that is still open, the branch is replaced by
+    /// the changes and the pull request is updated instead.
+    pub async fn open_pull_request(
+        &self,
+        organisation: &str,
+        repo: &str,
+        changes: &FileChanges,
+    ) -> eyre::Result<Option<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct Ref {
+            object: Object,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Object {
+            sha: String,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Commit {
+            sha: String,
+            tree: Object,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Pull {
+            number: u64,
+            html_url: String,
+        }
+
+        if crate::dry_run::is_enabled() {
+            let action = format!("open a pull request to {}", repo);
+            crate::dry_run::print_skipped(&action, &serde_json::to_value(changes)?);
+            return Ok(None);
+        }
+        let repo_url = format!("https://api.github.com/repos/{}/{}", organisation, repo);
+
+        // https://docs.github.com/en/rest/git/refs#get-a-reference
+        let base: Ref = self
+            .api_get_response(&format!("{}/git/ref/heads/{}", repo_url, changes.base))
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        // https://docs.github.com/en/rest/git/commits#get-a-commit-object
+        let parent: Commit = self
+            .api_get_response(&format!("{}/git/commits/{}", repo_url, base.object.sha))
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        // https://docs.github.com/en/rest/git/trees#create-a-tree
+        let files: Vec<_> = changes
+            .files
+            .iter()
+            .map(|(path, content)| {
+                serde_json::json!({
+                    "path": path,
+                    "mode": "100644",
+                    "type": "blob",
+                    "content": content,
+                })
+            })
+            .collect();
+        let tree_body = serde_json::json!({ "base_tree": parent.tree.sha, "tree": files });
+        let tree: Object = self
+            .api_post_response(&format!("{}/git/trees", repo_url), &tree_body)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        // https://docs.github.com/en/rest/git/commits#create-a-commit
+        let commit_body = serde_json::json!({
+            "message": changes.title,
+            "tree": tree.sha,
+            "parents": [parent.sha],
+        });
+        let commit: Object = self
+            .api_post_response(&format!("{}/git/commits", repo_url), &commit_body)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        if self.has_branch(organisation, repo, &changes.branch).await? {
+            // https://docs.github.com/en/rest/git/refs#update-a-reference
+            let ref_body = serde_json::json!({ "sha": commit.sha, "force": true });
+            let ref_url = format!("{}/git/refs/heads/{}", repo_url, changes.branch);
+            let _ = self.api_patch_response(&ref_url, &ref_body).await?;
+            // https://docs.github.com/en/rest/pulls/pulls#list-pull-requests
+            let url = format!(
+                "{}/pulls?state=open&head={}:{}",
+                repo_url,
+                organisation,
+                percent_encode(&changes.branch, "")
+            );
+            let pulls: Vec<Pull> = self
+                .api_get_response(&url)
+                .await?
+                .json()
+                .await
+                .wrap_err("Unable to parse JSON response")?;
+            if let Some(pull) = pulls.into_iter().next() {
+                // https://docs.github.com/en/rest/pulls/pulls#update-a-pull-request
+                let pull_body = serde_json::json!({
+                    "title": changes.title,
+                    "body": changes.body,
+                });
+                let pull_url = format!("{}/pulls/{}", repo_url, pull.number);
+                let _ = self.api_patch_response(&pull_url, &pull_body).await?;
+                return Ok(Some(pull.html_url));
+            }
+        } else {
+            // https://docs.github.com/en/rest/git/refs#create-a-reference
+            let ref_body = serde_json::json!({
+                "ref": format!("refs/heads/{}", changes.branch),
+                "sha": commit.sha,
+            });
+            let _ = self
+                .api_post_response(&format!("{}/git/refs", repo_url), &ref_body)
+                .await?;
+        }
+        // https://docs.github.com/en/rest/pulls/pulls#create-a-pull-request
+        let pull_body = serde_json::json!({
+            "title": changes.title,
+            "body": changes.body,
+            "head": changes.branch,
+            "base": changes.base,
+        });
+        let pull: Pull = self
+            .api_post_response(&format!("{}/pulls", repo_url), &pull_body)
+            .await?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(Some(pull.html_url))
     }
 
-    // https://docs.github.com/en/free-pro-team@latest/rest/reference/orgs#members
-    pub async fn public_organisation_members(
+    /// Perform a POST request with a JSON body, or print it instead in a dry
+    /// run, describing it as `action`.
+    async fn api_post(
         &self,
-        organisation: &str,
-    ) -> eyre::Result<HashSet<String>> {
-        #[derive(Debug, serde::Deserialize)]
-        pub struct Member {
-            login: String,
+        url: &str,
+        action: &str,
+        body: &serde_json::Value,
+    ) -> eyre::Result<()> {
+        if crate::dry_run::is_enabled() {
+            crate::dry_run::print_skipped(action, body);
+            return Ok(());
         }
+        let _ = self.api_post_response(url, body).await?;
+        Ok(())
+    }
 
-        let url = format!(
-            "https://api.github.com/orgs/{}/members?per_page=100",
-            organisation
-        );
-        Ok(self
-            .api_list(url)
+    async fn api_post_response(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> eyre::Result<reqwest::Response> {
+        let response = self
+            .api_request(reqwest::Method::POST, url)
+            .await?
+            .json(body)
+            .send_traced()
             .await
-            .wrap_err("Unable to get public members for organisation")?
-            .into_iter()
-            .map(|member: Member| member.login)
-            .collect())
+            .wrap_err(format!("Failed to post {}", url))?
+            .error_for_status()?;
+        Ok(response)
     }
 
-    // https://docs.github.com/en/free-pro-team@latest/rest/reference/repos#list-organization-repositories
-    pub async fn organisation_repos(
+    async fn api_patch_response(
         &self,
-        organisation: &str,
-    ) -> eyre::Result<HashMap<String, Repo>> {
-        let url = format!(
-            "https://api.github.com/orgs/{}/repos?type=archived&per_page=100",
-            organisation
-        );
-        Ok(self
-            .api_list(url)
+        url: &str,
+        body: &serde_json::Value,
+    ) -> eyre::Result<reqwest::Response> {
+        let response = self
+            .api_request(reqwest::Method::PATCH, url)
+            .await?
+            .json(body)
+            .send_traced()
             .await
-            .wrap_err("Unable to get archived repos for organisation")?
-            .into_iter()
-            .map(|repo: Repo| (repo.name.clone(), repo))
-            .collect())
+            .wrap_err(format!("Failed to patch {}", url))?
+            .error_for_status()?;
+        Ok(response)
     }
 
     /// Perform a GET request to a paginated GitHub URL that returns a JSON array per
@@ -78,17 +1516,61 @@ impl Client {
         Ok(collection)
     }
 
-    async fn api_get_response(&self, url: &str) -> eyre::Result<reqwest::Response> {
-        let request = reqwest::Client::new()
-            .get(url)
-            .header("accept", "application/vnd.github.v3+json")
+    async fn api_request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+    ) -> eyre::Result<reqwest::RequestBuilder> {
+        self.api_request_accepting(method, url, "application/vnd.github.v3+json")
+            .await
+    }
+
+    async fn api_request_accepting(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        accept: &str,
+    ) -> eyre::Result<reqwest::RequestBuilder> {
+        let request = crate::http::client()
+            .request(method, url)
+            .header("accept", accept)
             .header("user-agent", "embark-oss");
-        let request = match &self.github_api_token {
-            Some(token) => request.header("authorization", format!("token {}", token)),
-            _ => request,
+        let token = match &self.auth {
+            Auth::Anonymous => return Ok(request),
+            Auth::Token(token) => token.clone(),
+            Auth::App(app) => app.installation_token().await?,
         };
-        let response = request
-            .send()
+        Ok(request.header("authorization", format!("token {}", token)))
+    }
+
+    /// Perform a GET request returning JSON, or `None` if the resource was
+    /// not found.
+    async fn api_get_optional_json<Json: DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> eyre::Result<Option<Json>> {
+        let response = self
+            .api_request(reqwest::Method::GET, url)
+            .await?
+            .send_traced()
+            .await
+            .wrap_err(format!("Failed to get {}", url))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let json = response
+            .error_for_status()?
+            .json()
+            .await
+            .wrap_err("Unable to parse JSON response")?;
+        Ok(Some(json))
+    }
+
+    async fn api_get_response(&self, url: &str) -> eyre::Result<reqwest::Response> {
+        let response = self
+            .api_request(reqwest::Method::GET, url)
+            .await?
+            .send_traced()
             .await
             .wrap_err(format!("Failed to get {}", url))?
             .error_for_status()?;
@@ -96,15 +1578,349 @@ impl Client {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, serde::Deserialize)]
+/// A published release of a repo.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    /// The files uploaded to the release, not including the source archives
+    /// GitHub generates for every release.
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(default)]
+    pub browser_download_url: String,
+}
+
+impl ReleaseAsset {
+    /// Download a text asset, such as a list of checksums, giving up if it is
+    /// over `MAX_FILE_SIZE` or binary.
+    pub async fn download_text(&self) -> eyre::Result<String> {
+        let name = format!("release asset {}", self.name);
+        let response = crate::http::client()
+            .get(&self.browser_download_url)
+            .header("user-agent", "embark-oss")
+            .send_traced()
+            .await
+            .wrap_err_with(|| format!("Failed to download {}", name))?
+            .error_for_status()?;
+        let body = read_body(&name, response, MAX_FILE_SIZE).await?;
+        Ok(decode_text(name, None, body)?)
+    }
+}
+
+/// The members of an organisation, as listed by
+/// `Client::visible_organisation_members`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganisationMembers {
+    pub logins: HashSet<String>,
+    /// Whether the concealed members were visible as well as the public ones.
+    pub includes_concealed: bool,
+}
+
+/// The files on a branch of a repo, as downloaded by `Client::repo_archive`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoArchive {
+    /// The decompressed archive, which holds the contents of the files.
+    pub tar: Vec<u8>,
+    /// The path of each file and where its contents are in `tar`, sorted by
+    /// path.
+    pub entries: Vec<(String, std::ops::Range<usize>)>,
+    /// The files larger than `MAX_FILE_SIZE`, whose contents are left out.
+    pub too_large: Vec<RepoFile>,
+}
+
+impl RepoArchive {
+    /// Decompress and read a gzipped archive of a repo, named `name` in
+    /// errors.
+    fn unpack(name: &str, gzipped: &[u8]) -> eyre::Result<Self> {
+        let tar = crate::inflate::gunzip(gzipped, MAX_UNPACKED_ARCHIVE_SIZE)
+            .wrap_err_with(|| format!("Unable to decompress {}", name))?;
+        let mut entries = Vec::new();
+        let mut too_large = Vec::new();
+        for entry in tarball::entries(&tar).wrap_err_with(|| format!("Unable to read {}", name))? {
+            // Every file is in a directory named after the repo and commit
+            let path = match entry.path.split_once('/') {
+                Some((_, path)) if !path.is_empty() => path.to_string(),
+                _ => continue,
+            };
+            if entry.contents.len() > MAX_FILE_SIZE {
+                too_large.push(RepoFile {
+                    path,
+                    size: entry.contents.len() as u64,
+                });
+            } else {
+                // The contents are a slice of the archive, so only their
+                // position is kept rather than a copy
+                let start = entry.contents.as_ptr() as usize - tar.as_ptr() as usize;
+                entries.push((path, start..start + entry.contents.len()));
+            }
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Self {
+            tar,
+            entries,
+            too_large,
+        })
+    }
+
+    /// The path and contents of each file, sorted by path.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.entries
+            .iter()
+            .map(move |(path, range)| (path.as_str(), &self.tar[range.clone()]))
+    }
+}
+
+/// A file in a repo, as listed by `Client::repo_files`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoFile {
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub repo: EventRepo,
+    /// An ISO 8601 timestamp such as `2021-01-01T12:00:00Z`.
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct EventRepo {
+    /// The full name of the repo, such as `EmbarkStudios/texture-synthesis`.
+    pub name: String,
+}
+
+impl Event {
+    /// When the event happened, in seconds since the Unix epoch.
+    pub fn time(&self) -> Option<u64> {
+        parse_timestamp(&self.created_at)
+    }
+}
+
+/// Parse a UTC timestamp in the `YYYY-MM-DDTHH:MM:SSZ` format used by the
+/// GitHub API into seconds since the Unix epoch.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new(r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})Z$").unwrap();
+    }
+    let captures = RE.captures(timestamp)?;
+    let field = |i: usize| captures[i].parse::<i64>().ok();
+    let (year, month, day) = (field(1)?, field(2)?, field(3)?);
+    let (hour, minute, second) = (field(4)?, field(5)?, field(6)?);
+
+    // Days since the epoch of the civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    u64::try_from(seconds).ok()
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub created_at: String,
+    /// How the author is associated with the repo, such as `MEMBER` or
+    /// `NONE`.
+    pub author_association: String,
+    pull_request: Option<serde_json::Value>,
+}
+
+impl Issue {
+    /// When the issue was opened, in seconds since the Unix epoch.
+    pub fn time(&self) -> Option<u64> {
+        parse_timestamp(&self.created_at)
+    }
+}
+
+/// Changes to the files of a repo, made in a pull request with
+/// `Client::open_pull_request`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileChanges {
+    /// The branch the pull request is to be merged into.
+    pub base: String,
+    /// The new branch the changes are committed to.
+    pub branch: String,
+    /// The title of the pull request, which is also the commit message.
+    pub title: String,
+    pub body: String,
+    /// The new contents of each changed file, by path.
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestHead {
+    pub sha: String,
+    /// The full name of the repo, as `owner/name`.
+    pub repo: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub created_at: String,
+    /// How the author is associated with the repo, such as
+    /// `FIRST_TIME_CONTRIBUTOR`.
+    pub author_association: String,
+    pub merged_at: Option<String>,
+}
+
+impl PullRequest {
+    /// When the pull request was opened, in seconds since the Unix epoch.
+    pub fn time(&self) -> Option<u64> {
+        parse_timestamp(&self.created_at)
+    }
+
+    /// Whether this is the author's first contribution to the repo, or to
+    /// GitHub.
+    pub fn is_first_contribution(&self) -> bool {
+        matches!(
+            self.author_association.as_str(),
+            "FIRST_TIME_CONTRIBUTOR" | "FIRST_TIMER"
+        )
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct IssueComment {
+    pub created_at: String,
+    /// How the commenter is associated with the repo, such as `MEMBER` or
+    /// `NONE`.
+    pub author_association: String,
+}
+
+impl IssueComment {
+    /// When the comment was made, in seconds since the Unix epoch.
+    pub fn time(&self) -> Option<u64> {
+        parse_timestamp(&self.created_at)
+    }
+}
+
+/// Whether an author association from the GitHub API means the user can
+/// maintain the repo.
+pub fn is_maintainer_association(association: &str) -> bool {
+    matches!(association, "OWNER" | "MEMBER" | "COLLABORATOR")
+}
+
+/// The UTC calendar year of a time in seconds since the Unix epoch.
+pub fn year_of(seconds: u64) -> i64 {
+    civil_date(seconds).0
+}
+
+/// Parse a `YYYY-MM-DD` date into seconds since the Unix epoch at the start
+/// of the day in UTC.
+pub fn parse_date(date: &str) -> Option<u64> {
+    parse_timestamp(&format!("{}T00:00:00Z", date))
+}
+
+/// Format a time in seconds since the Unix epoch as a UTC timestamp in the
+/// `YYYY-MM-DDTHH:MM:SSZ` format used by the GitHub API.
+pub fn format_timestamp(seconds: u64) -> String {
+    let (year, month, day) = civil_date(seconds);
+    let time = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// The UTC year, month and day of a time in seconds since the Unix epoch.
+fn civil_date(seconds: u64) -> (i64, i64, i64) {
+    // Civil date from days since the epoch, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// The security features of a repo. Features that are not available to the
+/// repo are omitted by GitHub.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct SecurityAndAnalysis {
+    pub secret_scanning: Option<Feature>,
+    pub secret_scanning_push_protection: Option<Feature>,
+    pub dependabot_security_updates: Option<Feature>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Feature {
+    /// Either `enabled` or `disabled`.
+    pub status: String,
+}
+
+impl Feature {
+    pub fn is_enabled(feature: &Option<Self>) -> bool {
+        feature
+            .as_ref()
+            .is_some_and(|feature| feature.status == "enabled")
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the limit resets, in seconds since the Unix epoch.
+    pub reset: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Repo {
     pub name: String,
     pub archived: bool,
     pub private: bool,
     pub fork: bool,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// When the repo was last pushed to, as a timestamp.
+    #[serde(default, skip_serializing)]
+    pub pushed_at: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub stargazers_count: u64,
+    #[serde(default, skip_serializing)]
+    pub forks_count: u64,
+    /// The main language of the repo, as detected by GitHub.
+    #[serde(default, skip_serializing)]
+    pub language: Option<String>,
 }
 
 impl Repo {
+    /// When the repo was last pushed to, in seconds since the Unix epoch.
+    pub fn pushed_time(&self) -> Option<u64> {
+        parse_timestamp(self.pushed_at.as_deref()?)
+    }
+
     pub fn is_public_active_source_project(&self) -> bool {
         match self.name.as_str() {
             "opensource-template" | ".github" => false,
@@ -113,17 +1929,237 @@ impl Repo {
     }
 }
 
+/// GitHub only shares the requested data, such as the settings of a repo,
+/// with authenticated clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeedsAuth;
+
+impl std::fmt::Display for NeedsAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub credentials are needed to check this")
+    }
+}
+
+impl std::error::Error for NeedsAuth {}
+
+/// Whether GitHub refused a request for an admin only setting, which it does
+/// with 404 or 403 both when the setting is off and when the credentials
+/// can't see it.
+fn is_refused(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN
+}
+
+/// Whether the error was caused by a request needing credentials that were
+/// not given.
+pub fn needs_auth(error: &eyre::Report) -> bool {
+    error
+        .chain()
+        .any(|cause| cause.downcast_ref::<NeedsAuth>().is_some())
+}
+
+/// Why a file could not be downloaded from a repo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadError {
+    /// The repo, branch or file doesn't exist.
+    NotFound { name: String },
+    /// GitHub responded with an unexpected status, such as during an outage.
+    Http { name: String, status: u16 },
+    /// GitHub could not be reached, or the response could not be read.
+    Network { name: String, message: String },
+    /// The file is larger than `limit` bytes, so it was not downloaded.
+    TooLarge { name: String, limit: usize },
+    /// The file is binary rather than text.
+    Binary { name: String },
+}
+
+impl DownloadError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound { .. })
+    }
+
+    /// Whether the file exists but was not downloaded, as it is too large or
+    /// not text.
+    pub fn is_unreadable(&self) -> bool {
+        matches!(self, Self::TooLarge { .. } | Self::Binary { .. })
+    }
+
+    /// Whether the download failed because of GitHub rather than the file,
+    /// so that it may succeed if tried again.
+    pub fn is_infrastructure(&self) -> bool {
+        matches!(self, Self::Http { .. } | Self::Network { .. })
+    }
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound { name } => write!(f, "Unable to download {}: file not found", name),
+            Self::Http { name, status } => write!(
+                f,
+                "Unable to download {}: expected status code 200, got {}",
+                name, status
+            ),
+            Self::Network { name, message } => {
+                write!(f, "Unable to download {}: {}", name, message)
+            }
+            Self::TooLarge { name, limit } => write!(
+                f,
+                "Unable to download {}: the file is larger than {} bytes",
+                name, limit
+            ),
+            Self::Binary { name } => {
+                write!(f, "Unable to download {}: the file is not text", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Whether the error was caused by a file not existing in a repo.
+pub fn is_not_found(error: &eyre::Report) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<DownloadError>())
+        .any(DownloadError::is_not_found)
+}
+
+/// Whether the error was caused by a file that exists but is too large or not
+/// text.
+pub fn is_unreadable(error: &eyre::Report) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<DownloadError>())
+        .any(DownloadError::is_unreadable)
+}
+
+/// Whether the error was caused by GitHub responding with the status.
+fn is_status(error: &eyre::Report, status: reqwest::StatusCode) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|error| error.status() == Some(status))
+}
+
+/// Whether the error was caused by GitHub being unreachable or failing,
+/// rather than by something being missing or wrong in a repo.
+pub fn is_infrastructure_error(error: &eyre::Report) -> bool {
+    error.chain().any(|cause| {
+        if let Some(error) = cause.downcast_ref::<DownloadError>() {
+            return error.is_infrastructure();
+        }
+        // Checks that time out are as likely to be held up by GitHub
+        if cause.is::<tokio::time::error::Elapsed>() {
+            return true;
+        }
+        cause.downcast_ref::<reqwest::Error>().is_some_and(|error| {
+            error.is_connect()
+                || error.is_timeout()
+                || error
+                    .status()
+                    .is_some_and(|status| status.is_server_error())
+        })
+    })
+}
+
+/// Files larger than this many bytes are not downloaded, so that a repo with
+/// an enormous file can't use up the memory of the run. GitHub itself ignores
+/// CODEOWNERS files over 3 MB.
+const MAX_FILE_SIZE: usize = 5 * 1024 * 1024;
+
+/// Archives of repos larger than this many bytes are not downloaded, nor
+/// those that are larger than `MAX_UNPACKED_ARCHIVE_SIZE` once decompressed.
+const MAX_ARCHIVE_SIZE: usize = 100 * 1024 * 1024;
+const MAX_UNPACKED_ARCHIVE_SIZE: usize = 256 * 1024 * 1024;
+
+/// How much of the start of a file is looked at for NUL bytes to tell whether
+/// it is binary, as git does.
+const BINARY_SNIFF_LENGTH: usize = 8000;
+
+/// Download a text file from a repo. Each file is only downloaded once per
+/// run, as many projects share files such as the organisation defaults.
+/// Files larger than `MAX_FILE_SIZE` and binary files are refused.
 pub async fn download_repo_file(
     org: &str,
     repo: &str,
     branch: &str,
     file: &str,
-) -> eyre::Result<String> {
-    let (name, response) = download_file(org, repo, branch, file).await?;
-    response
-        .text()
+) -> Result<String, DownloadError> {
+    lazy_static! {
+        static ref FILES: memo::Memo<String, String, DownloadError> = memo::Memo::new();
+    }
+    let (org, repo, branch, file) = (
+        org.to_string(),
+        repo.to_string(),
+        branch.to_string(),
+        file.to_string(),
+    );
+    let key = format!("{}/{}/{}/{}", org, repo, branch, file);
+    FILES
+        .get_or_fetch(key, async move {
+            let (name, response) = download_file(&org, &repo, &branch, &file).await?;
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string);
+            let body = read_body(&name, response, MAX_FILE_SIZE).await?;
+            decode_text(name, content_type.as_deref(), body)
+        })
         .await
-        .wrap_err(eyre!("Failed to decode {}", name))
+}
+
+/// Read the body of a response, giving up once it is over `limit` bytes
+/// rather than reading all of it.
+async fn read_body(
+    name: &str,
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<Vec<u8>, DownloadError> {
+    let too_large = || DownloadError::TooLarge {
+        name: name.to_string(),
+        limit,
+    };
+    let length = response.content_length().unwrap_or_default();
+    if length > limit as u64 {
+        return Err(too_large());
+    }
+    let mut body = Vec::with_capacity(length as usize);
+    loop {
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|error| DownloadError::Network {
+                name: name.to_string(),
+                message: error.to_string(),
+            })?;
+        match chunk {
+            Some(chunk) if body.len() + chunk.len() > limit => return Err(too_large()),
+            Some(chunk) => body.extend_from_slice(&chunk),
+            None => return Ok(body),
+        }
+    }
+}
+
+/// The text of a downloaded file, which is binary if its content type is a
+/// media type or its start has a NUL byte. Invalid UTF-8 is replaced.
+pub fn decode_text(
+    name: String,
+    content_type: Option<&str>,
+    body: Vec<u8>,
+) -> Result<String, DownloadError> {
+    let media = content_type.is_some_and(|content_type| {
+        ["image/", "audio/", "video/", "font/"]
+            .iter()
+            .any(|prefix| content_type.starts_with(prefix))
+    });
+    if media || body.iter().take(BINARY_SNIFF_LENGTH).any(|&byte| byte == 0) {
+        return Err(DownloadError::Binary { name });
+    }
+    Ok(match String::from_utf8(body) {
+        Ok(text) => text,
+        Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+    })
 }
 
 pub async fn download_repo_json_file<Json: DeserializeOwned>(
@@ -133,10 +2169,8 @@ pub async fn download_repo_json_file<Json: DeserializeOwned>(
     file: &str,
 ) -> eyre::Result<Json> {
     let (name, response) = download_file(org, repo, branch, file).await?;
-    response
-        .json()
-        .await
-        .wrap_err(eyre!("Failed to decode {}", name))
+    let body = read_body(&name, response, MAX_FILE_SIZE).await?;
+    serde_json::from_slice(&body).wrap_err(eyre!("Failed to decode {}", name))
 }
 
 pub async fn download_file(
@@ -144,26 +2178,76 @@ pub async fn download_file(
     repo: &str,
     branch: &str,
     file: &str,
-) -> eyre::Result<(String, reqwest::Response)> {
+) -> Result<(String, reqwest::Response), DownloadError> {
     let path = format!("{}/{}/{}/{}", org, repo, branch, file);
     let name = format!("{}/{}:{}", org, repo, file);
     let url = format!("https://raw.githubusercontent.com/{}", path);
-    let response = reqwest::get(&url)
-        .await
-        .wrap_err(format!("Failed to download {}", name))?;
+    let response = match crate::http::client().get(&url).send_traced().await {
+        Ok(response) => response,
+        Err(error) => {
+            return Err(DownloadError::Network {
+                name,
+                message: error.to_string(),
+            })
+        }
+    };
 
-    // Ensure the file was successfully downloaded
-    if response.status() == 404 {
-        return Err(eyre!("Expected status code 200, got 404"))
-            .wrap_err("File not found in repo")
-            .wrap_err(format!("Unable to download {}", name))?;
+    // Downloads from renamed or transferred repos are redirected
+    if let Some(canonical) = redirected_repo(org, repo, response.url()) {
+        let _ = RENAMED_REPOS
+            .lock()
+            .expect("Renamed repos lock poisoned")
+            .insert(format!("{}/{}", org, repo), canonical);
     }
-    if response.status() != 200 {
-        return Err(eyre!("Expected status code 200, got {}", response.status()))
-            .wrap_err(format!("Unable to download {}", name))?;
+
+    // Ensure the file was successfully downloaded
+    match response.status().as_u16() {
+        200 => Ok((name, response)),
+        404 => Err(DownloadError::NotFound { name }),
+        status => Err(DownloadError::Http { name, status }),
     }
+}
+
+/// Percent-encode a part of a URL, leaving unreserved characters and those in
+/// `keep` as they are.
+fn percent_encode(part: &str, keep: &str) -> String {
+    part.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ if keep.as_bytes().contains(&byte) => char::from(byte).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+lazy_static! {
+    /// The `org/repo` names that downloads were redirected to, by the
+    /// `org/repo` name they were requested from.
+    static ref RENAMED_REPOS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// The `org/repo` name a repo has been renamed or transferred to, if a
+/// download from it has been redirected during this run.
+pub fn renamed_repo(org: &str, repo: &str) -> Option<String> {
+    RENAMED_REPOS
+        .lock()
+        .expect("Renamed repos lock poisoned")
+        .get(&format!("{}/{}", org, repo))
+        .cloned()
+}
 
-    Ok((name, response))
+/// The `org/repo` name of the repo a raw download was redirected to, if it
+/// is not the requested repo. GitHub names are not case sensitive.
+fn redirected_repo(org: &str, repo: &str, url: &reqwest::Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    let (canonical_org, canonical_repo) = (segments.next()?, segments.next()?);
+    if canonical_org.eq_ignore_ascii_case(org) && canonical_repo.eq_ignore_ascii_case(repo) {
+        None
+    } else {
+        Some(format!("{}/{}", canonical_org, canonical_repo))
+    }
 }
 
 fn next_pagination_page(response: &reqwest::Response) -> eyre::Result<Option<String>> {
@@ -193,10 +2277,162 @@ fn parse_next_link_url(content: &str) -> Option<String> {
         .map(|s| s.as_str().to_string())
 }
 
+fn last_page_from_link_header(header: &str) -> Option<u64> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r#"<[^>]*[?&]page=(\d+)[^>]*>; *rel="last""#).unwrap();
+    }
+    RE.captures(header)?[1].parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn repo_settings_need_auth() {
+        let client = Client::new(Auth::Anonymous);
+        let error = client
+            .security_and_analysis("EmbarkStudios", "ash")
+            .await
+            .unwrap_err();
+        assert!(needs_auth(&error));
+        assert!(!is_infrastructure_error(&error));
+
+        let error = client
+            .vulnerability_alerts_enabled("EmbarkStudios", "ash")
+            .await
+            .unwrap_err();
+        assert!(needs_auth(&error));
+        let error = client
+            .requires_signed_commits("EmbarkStudios", "ash")
+            .await
+            .unwrap_err();
+        assert!(needs_auth(&error));
+    }
+
+    #[test]
+    fn percent_encoding() {
+        assert_eq!(
+            percent_encode("docs/Read me #1.md", "/"),
+            "docs/Read%20me%20%231.md"
+        );
+        assert_eq!(percent_encode("release/1.0", ""), "release%2F1.0");
+        assert_eq!(percent_encode("ä", ""), "%C3%A4");
+    }
+
+    #[test]
+    fn refused_admin_settings() {
+        use reqwest::StatusCode;
+
+        assert!(is_refused(StatusCode::NOT_FOUND));
+        assert!(is_refused(StatusCode::FORBIDDEN));
+        assert!(!is_refused(StatusCode::NO_CONTENT));
+        assert!(!is_refused(StatusCode::UNAUTHORIZED));
+        assert!(!is_refused(StatusCode::BAD_GATEWAY));
+    }
+
+    #[test]
+    fn redirected_repos() {
+        let url = |url: &str| reqwest::Url::parse(url).unwrap();
+        assert_eq!(
+            redirected_repo(
+                "EmbarkStudios",
+                "rpmalloc-rs",
+                &url("https://raw.githubusercontent.com/EmbarkStudios/rpmalloc/main/README.md")
+            ),
+            Some("EmbarkStudios/rpmalloc".to_string())
+        );
+        assert_eq!(
+            redirected_repo(
+                "embarkstudios",
+                "Ash",
+                &url("https://raw.githubusercontent.com/EmbarkStudios/ash/main/README.md")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn last_page_links() {
+        let header = r#"<https://api.github.com/repositories/1/contributors?per_page=1&anon=true&page=2>; rel="next", <https://api.github.com/repositories/1/contributors?per_page=1&anon=true&page=57>; rel="last""#;
+        assert_eq!(last_page_from_link_header(header), Some(57));
+        assert_eq!(
+            last_page_from_link_header(r#"<https://api.github.com/x?page=2>; rel="next""#),
+            None
+        );
+    }
+
+    #[test]
+    fn download_error_kinds() {
+        let name = "EmbarkStudios/ash:README.md".to_string();
+        let not_found = eyre::Report::from(DownloadError::NotFound { name: name.clone() })
+            .wrap_err("Unable to determine maintainers");
+        assert!(is_not_found(&not_found));
+        assert!(!is_infrastructure_error(&not_found));
+
+        let outage = eyre::Report::from(DownloadError::Http { name, status: 503 })
+            .wrap_err("Unable to determine maintainers");
+        assert!(!is_not_found(&outage));
+        assert!(is_infrastructure_error(&outage));
+
+        let policy = eyre!("No maintainers were found");
+        assert!(!is_not_found(&policy));
+        assert!(!is_infrastructure_error(&policy));
+
+        let huge = eyre::Report::from(DownloadError::TooLarge {
+            name: "EmbarkStudios/ash:README.md".to_string(),
+            limit: MAX_FILE_SIZE,
+        });
+        assert!(!is_not_found(&huge));
+        assert!(!is_infrastructure_error(&huge));
+        assert!(is_unreadable(&huge));
+        assert!(!is_unreadable(&not_found));
+        assert!(!is_unreadable(&outage));
+    }
+
+    #[test]
+    fn decoding_downloaded_text() {
+        let name = || "EmbarkStudios/ash:.github/CODEOWNERS".to_string();
+        assert_eq!(
+            decode_text(
+                name(),
+                Some("text/plain; charset=utf-8"),
+                b"* @lpil\n".to_vec()
+            ),
+            Ok("* @lpil\n".to_string())
+        );
+        assert_eq!(
+            decode_text(name(), None, b"caf\xe9".to_vec()),
+            Ok("caf\u{fffd}".to_string())
+        );
+        assert_eq!(
+            decode_text(
+                name(),
+                Some("text/plain"),
+                b"\x89PNG\r\n\x1a\n\0\0".to_vec()
+            ),
+            Err(DownloadError::Binary { name: name() })
+        );
+        assert_eq!(
+            decode_text(name(), Some("image/png"), b"picture".to_vec()),
+            Err(DownloadError::Binary { name: name() })
+        );
+
+        // Only the start of a file is looked at
+        let mut long = vec![b'a'; BINARY_SNIFF_LENGTH];
+        long.push(0);
+        assert!(decode_text(name(), None, long).is_ok());
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2000-03-01T00:00:00Z"), Some(951_868_800));
+        assert_eq!(parse_timestamp("2021-06-15T12:34:56Z"), Some(1_623_760_496));
+        assert_eq!(parse_timestamp("1969-12-31T23:59:59Z"), None);
+        assert_eq!(parse_timestamp("2021-06-15"), None);
+    }
+
     #[test]
     fn test_parse_next_link_url() {
         assert_eq!(parse_next_link_url(""), None);
@@ -218,12 +2454,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn years_of_timestamps() {
+        for (timestamp, year) in [
+            ("1970-01-01T00:00:00Z", 1970),
+            ("2020-02-29T12:00:00Z", 2020),
+            ("2022-12-31T23:59:59Z", 2022),
+            ("2023-01-01T00:00:00Z", 2023),
+        ]
+        .iter()
+        {
+            let seconds = parse_timestamp(timestamp).unwrap();
+            assert_eq!(year_of(seconds), *year);
+            assert_eq!(format_timestamp(seconds), *timestamp);
+        }
+    }
+
     fn make_repo() -> Repo {
         Repo {
             name: "name".to_string(),
             archived: false,
             private: false,
             fork: false,
+            topics: Vec::new(),
+            pushed_at: None,
+            stargazers_count: 0,
+            forks_count: 0,
+            language: None,
         }
     }
 
@@ -0,0 +1,156 @@
+use crate::http::Traced;
+use eyre::{eyre, WrapErr};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// Installation tokens are valid for an hour. They are refreshed a little
+/// earlier so that a token never expires while a request is in flight.
+const INSTALLATION_TOKEN_LIFETIME: Duration = Duration::from_secs(55 * 60);
+
+/// Credentials for authenticating as a GitHub App installed in an
+/// organisation. These are exchanged for short lived installation tokens,
+/// which are minted again when they expire.
+pub struct App {
+    app_id: String,
+    key: EncodingKey,
+    organisation: String,
+    installation_id: Option<u64>,
+    token: Mutex<Option<InstallationToken>>,
+}
+
+struct InstallationToken {
+    token: String,
+    refresh_at: Instant,
+}
+
+impl App {
+    pub fn new(
+        app_id: String,
+        private_key_pem: &[u8],
+        organisation: String,
+        installation_id: Option<u64>,
+    ) -> eyre::Result<Self> {
+        let key = EncodingKey::from_rsa_pem(private_key_pem)
+            .wrap_err("Unable to parse GitHub App private key")?;
+        Ok(Self {
+            app_id,
+            key,
+            organisation,
+            installation_id,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Get an installation token for the app, minting a new one if there is
+    /// no token yet or the current one is about to expire.
+    pub async fn installation_token(&self) -> eyre::Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(token) = token.as_ref().filter(|t| t.refresh_at > Instant::now()) {
+            return Ok(token.token.clone());
+        }
+
+        let new = self
+            .mint_installation_token()
+            .await
+            .wrap_err("Unable to authenticate as GitHub App")?;
+        let value = new.token.clone();
+        *token = Some(new);
+        Ok(value)
+    }
+
+    async fn mint_installation_token(&self) -> eyre::Result<InstallationToken> {
+        #[derive(Debug, serde::Deserialize)]
+        pub struct Installation {
+            id: u64,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        pub struct AccessToken {
+            token: String,
+        }
+
+        let jwt = self.jwt()?;
+        let installation_id = match self.installation_id {
+            Some(id) => id,
+            // https://docs.github.com/en/rest/apps/apps#get-an-organization-installation-for-the-authenticated-app
+            None => {
+                let url = format!(
+                    "https://api.github.com/orgs/{}/installation",
+                    self.organisation
+                );
+                let installation: Installation = app_request(reqwest::Method::GET, &url, &jwt)
+                    .send_traced()
+                    .await?
+                    .error_for_status()
+                    .wrap_err("Unable to find the app installation for the organisation")?
+                    .json()
+                    .await?;
+                installation.id
+            }
+        };
+
+        // https://docs.github.com/en/rest/apps/apps#create-an-installation-access-token-for-an-app
+        let url = format!(
+            "https://api.github.com/app/installations/{}/access_tokens",
+            installation_id
+        );
+        let refresh_at = Instant::now() + INSTALLATION_TOKEN_LIFETIME;
+        let access_token: AccessToken = app_request(reqwest::Method::POST, &url, &jwt)
+            .send_traced()
+            .await?
+            .error_for_status()
+            .wrap_err("Unable to create an installation access token")?
+            .json()
+            .await?;
+        Ok(InstallationToken {
+            token: access_token.token,
+            refresh_at,
+        })
+    }
+
+    /// Create a JSON web token identifying the app itself, which is used to
+    /// request installation tokens.
+    fn jwt(&self) -> eyre::Result<String> {
+        #[derive(Debug, serde::Serialize)]
+        struct Claims<'a> {
+            iat: u64,
+            exp: u64,
+            iss: &'a str,
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let claims = Claims {
+            // Backdated to allow for clock drift, as recommended by GitHub
+            iat: now - 60,
+            exp: now + 9 * 60,
+            iss: &self.app_id,
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &self.key)
+            .map_err(|error| eyre!("Unable to sign GitHub App token: {}", error))
+    }
+}
+
+fn app_request(method: reqwest::Method, url: &str, jwt: &str) -> reqwest::RequestBuilder {
+    crate::http::client()
+        .request(method, url)
+        .header("accept", "application/vnd.github.v3+json")
+        .header("user-agent", "embark-oss")
+        .header("authorization", format!("Bearer {}", jwt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_private_key() {
+        let app = App::new(
+            "1".to_string(),
+            b"not a key",
+            "EmbarkStudios".to_string(),
+            None,
+        );
+        assert!(app.is_err());
+    }
+}
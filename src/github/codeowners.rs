@@ -1,6 +1,15 @@
 use eyre::{eyre, WrapErr};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::collections::HashSet;
 
+/// GitHub ignores CODEOWNERS files larger than this many bytes.
+const MAX_SIZE: usize = 3 * 1024 * 1024;
+
+/// Patterns and owners quoted in errors are cut to this many characters, as
+/// the files come from third-party repos and may be anything.
+const MAX_QUOTED_LENGTH: usize = 100;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct CodeOwners {
     /// CODEOWNERS files are ordered so we use a Vec of pairs rather than a
@@ -15,23 +24,71 @@ pub struct CodeOwners {
 pub struct Assignment {
     /// A git compatible glob that specifies which files this assignment applies to.
     file_pattern: String,
-    /// A collection of GitHub usernames or emails for the users that own this
-    /// code section.
-    owners: HashSet<String>,
+    /// The users that own this code section.
+    owners: Owners,
+}
+
+/// The owners of a code section. GitHub accepts both `@username` handles and
+/// the email addresses of users, which are kept apart as only handles can be
+/// checked against organisation membership.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Owners {
+    /// GitHub usernames or teams, without the leading @.
+    pub handles: HashSet<String>,
+    pub emails: HashSet<String>,
+}
+
+impl Owners {
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty() && self.emails.is_empty()
+    }
+
+    fn insert(&mut self, owner: &str) -> eyre::Result<()> {
+        lazy_static! {
+            static ref EMAIL: Regex = Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap();
+            // A username, or a team as `organisation/team`
+            static ref HANDLE: Regex = Regex::new(r"^[A-Za-z0-9_.-]+(/[A-Za-z0-9_.-]+)?$").unwrap();
+        }
+        if let Some(handle) = owner.strip_prefix('@') {
+            if !HANDLE.is_match(handle) {
+                return Err(eyre!(
+                    "Code owner `{}` is not a GitHub username or team",
+                    quote(owner)
+                ));
+            }
+            let _ = self.handles.insert(handle.to_string());
+        } else if EMAIL.is_match(owner) {
+            let _ = self.emails.insert(owner.to_string());
+        } else {
+            return Err(eyre!(
+                "Code owner `{}` is neither an @handle nor an email address",
+                quote(owner)
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl Assignment {
     pub fn from_line(line: &str) -> eyre::Result<Self> {
-        let mut iter = line.split_whitespace().map(String::from);
+        // Comments may follow the owners
+        let mut iter = line
+            .split_whitespace()
+            .take_while(|token| !token.starts_with('#'));
         let file_pattern = iter
             .next()
-            .ok_or_else(|| eyre!("No file pattern for code owners line"))?;
-        let owners = iter
-            .map(validate_name_format)
-            .collect::<eyre::Result<HashSet<String>>>()
-            .wrap_err_with(|| format!("Unable to parse code owners for {}", file_pattern))?;
+            .ok_or_else(|| eyre!("No file pattern for code owners line"))?
+            .to_string();
+        let mut owners = Owners::default();
+        iter.try_for_each(|owner| owners.insert(owner))
+            .wrap_err_with(|| {
+                format!("Unable to parse code owners for {}", quote(&file_pattern))
+            })?;
         if owners.is_empty() {
-            return Err(eyre!("File pattern `{}` has no owners", file_pattern));
+            return Err(eyre!(
+                "File pattern `{}` has no owners",
+                quote(&file_pattern)
+            ));
         }
         Ok(Self {
             file_pattern,
@@ -40,16 +97,17 @@ impl Assignment {
     }
 }
 
-fn validate_name_format(name: String) -> eyre::Result<String> {
-    if name.starts_with('@') {
-        Ok(name.trim_start_matches('@').to_string())
-    } else {
-        Err(eyre!("Code owner `{}` does not start with an @", name))
-    }
-}
-
 impl CodeOwners {
     pub fn new(source: &str) -> eyre::Result<Self> {
+        if source.len() > MAX_SIZE {
+            return Err(eyre!(
+                "The file is {} bytes, but GitHub ignores CODEOWNERS files over {} bytes",
+                source.len(),
+                MAX_SIZE
+            ));
+        }
+        // Editors on Windows may start the file with a byte order mark
+        let source = source.strip_prefix('\u{feff}').unwrap_or(source);
         let assignments = source
             .lines()
             .map(|line| line.trim())
@@ -60,7 +118,7 @@ impl CodeOwners {
         Ok(Self { assignments })
     }
 
-    pub fn primary_maintainers(&self) -> Option<&HashSet<String>> {
+    pub fn primary_maintainers(&self) -> Option<&Owners> {
         self.assignments
             .iter()
             .find(|assignment| assignment.file_pattern == "*")
@@ -68,6 +126,14 @@ impl CodeOwners {
     }
 }
 
+/// Text from the file for an error, cut short if it is long.
+fn quote(text: &str) -> String {
+    match text.char_indices().nth(MAX_QUOTED_LENGTH) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,7 +159,7 @@ mod tests {
             CodeOwners {
                 assignments: vec![Assignment {
                     file_pattern: "*".to_string(),
-                    owners: hashset(&["lpil"])
+                    owners: handles(&["lpil"])
                 }]
             }
         );
@@ -103,7 +169,7 @@ mod tests {
             CodeOwners {
                 assignments: vec![Assignment {
                     file_pattern: "*".to_string(),
-                    owners: hashset(&["lpil", "arirawr"])
+                    owners: handles(&["lpil", "arirawr"])
                 }]
             }
         );
@@ -113,6 +179,24 @@ mod tests {
             "Unable to parse code owners for *",
         );
 
+        assert_eq!(
+            CodeOwners::new("* @lpil lpil@example.com").unwrap(),
+            CodeOwners {
+                assignments: vec![Assignment {
+                    file_pattern: "*".to_string(),
+                    owners: Owners {
+                        handles: hashset(&["lpil"]),
+                        emails: hashset(&["lpil@example.com"]),
+                    }
+                }]
+            }
+        );
+
+        assert_eq!(
+            CodeOwners::new("* lpil@example").unwrap_err().to_string(),
+            "Unable to parse code owners for *",
+        );
+
         assert_eq!(
             CodeOwners::new(
                 "* @lpil @arirawr
@@ -127,25 +211,230 @@ right/ok @celialewis3     @soniasingla    \n"
                 assignments: vec![
                     Assignment {
                         file_pattern: "*".to_string(),
-                        owners: ["lpil", "arirawr"]
-                            .iter()
-                            .cloned()
-                            .map(String::from)
-                            .collect()
+                        owners: Owners {
+                            handles: ["lpil", "arirawr"]
+                                .iter()
+                                .cloned()
+                                .map(String::from)
+                                .collect(),
+                            emails: HashSet::new(),
+                        }
                     },
                     Assignment {
                         file_pattern: "left".to_string(),
-                        owners: hashset(&["XAMPPRocky"])
+                        owners: handles(&["XAMPPRocky"])
                     },
                     Assignment {
                         file_pattern: "right/ok".to_string(),
-                        owners: hashset(&["soniasingla", "celialewis3"])
+                        owners: handles(&["soniasingla", "celialewis3"])
                     }
                 ]
             }
         );
     }
 
+    #[test]
+    fn parsing_unusual_files() {
+        let expected = CodeOwners {
+            assignments: vec![Assignment {
+                file_pattern: "*".to_string(),
+                owners: handles(&["lpil", "EmbarkStudios/opensource"]),
+            }],
+        };
+        assert_eq!(
+            CodeOwners::new("* @lpil @EmbarkStudios/opensource # The whole repo").unwrap(),
+            expected
+        );
+        assert_eq!(
+            CodeOwners::new("\u{feff}* @lpil\r\n*\t@EmbarkStudios/opensource\u{3000}\r\n").unwrap(),
+            CodeOwners {
+                assignments: vec![
+                    Assignment {
+                        file_pattern: "*".to_string(),
+                        owners: handles(&["lpil"]),
+                    },
+                    Assignment {
+                        file_pattern: "*".to_string(),
+                        owners: handles(&["EmbarkStudios/opensource"]),
+                    },
+                ]
+            }
+        );
+        assert!(CodeOwners::new("\\#notes @lpil").is_ok());
+        assert!(CodeOwners::new("* # @lpil").is_err());
+        assert!(CodeOwners::new("* @").is_err());
+        assert!(CodeOwners::new("* @ユーザー").is_err());
+        assert!(CodeOwners::new("* @lpil/").is_err());
+        assert!(CodeOwners::new("🦀 @lpil").is_ok());
+    }
+
+    #[test]
+    fn giant_files() {
+        let line = "/src/**/*.rs @lpil @arirawr lpil@example.com\n";
+        let source = line.repeat(60_000);
+        assert_eq!(CodeOwners::new(&source).unwrap().assignments.len(), 60_000);
+
+        let source = line.repeat(MAX_SIZE / line.len() + 1);
+        assert!(CodeOwners::new(&source)
+            .unwrap_err()
+            .to_string()
+            .contains("GitHub ignores CODEOWNERS files over"));
+
+        // Errors quote only the start of long owners and patterns
+        let owner = "é".repeat(1_000_000);
+        let error = CodeOwners::new(&format!("{} {}", owner, owner)).unwrap_err();
+        let message = format!("{:#}", error);
+        assert!(
+            message.chars().count() < 4 * MAX_QUOTED_LENGTH,
+            "{}",
+            message
+        );
+    }
+
+    /// A small generator of pseudo-random numbers, so that the generated
+    /// files are the same on every run and failures can be reproduced.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            // xorshift64
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.below(items.len())]
+        }
+    }
+
+    const WHITESPACE: &[&str] = &[" ", "  ", "\t", " \t ", "\u{a0}", "\u{2003}", "\u{3000}"];
+
+    #[test]
+    fn arbitrary_files_never_panic() {
+        let fragments = [
+            "*",
+            "**",
+            "/",
+            "docs/",
+            "*.rs",
+            "\\#",
+            "#",
+            "# comment",
+            "@",
+            "@lpil",
+            "@@",
+            "@org/team",
+            "@org/",
+            "lpil@example.com",
+            "@lpil@example.com",
+            "a@b",
+            "é",
+            "🦀",
+            "@ユーザー",
+            "ユーザー@例え.jp",
+            "\u{feff}",
+            "\u{200b}",
+            "\0",
+            "\r",
+            "\n",
+            "\r\n",
+            "\\",
+            "[",
+            "]",
+            "!",
+            "\u{10ffff}",
+        ];
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..5_000 {
+            let mut source = String::new();
+            for _ in 0..rng.below(40) {
+                source.push_str(rng.pick(&fragments));
+                if rng.below(2) == 0 {
+                    source.push_str(rng.pick(WHITESPACE));
+                }
+            }
+            if let Ok(codeowners) = CodeOwners::new(&source) {
+                for assignment in &codeowners.assignments {
+                    assert!(!assignment.owners.is_empty(), "{:?}", source);
+                    assert!(!assignment.file_pattern.starts_with('#'), "{:?}", source);
+                    assert!(
+                        assignment
+                            .owners
+                            .handles
+                            .iter()
+                            .all(|handle| !handle.is_empty()),
+                        "{:?}",
+                        source
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_files_parse_to_their_assignments() {
+        let patterns = ["*", "/docs/", "*.rs", "src/**/mod.rs", "\\#notes", "🦀/"];
+        let owners = [
+            "@lpil",
+            "@arirawr",
+            "@EmbarkStudios/opensource",
+            "@user_emu",
+            "lpil@example.com",
+            "ユーザー@例え.jp",
+        ];
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..1_000 {
+            let mut assignments = Vec::new();
+            let mut source = String::new();
+            for _ in 0..rng.below(8) {
+                // Comments and blank lines between assignments are ignored
+                match rng.below(4) {
+                    0 => source.push_str("# A comment @lpil\n"),
+                    1 => source.push_str(&format!("{}\n", rng.pick(WHITESPACE))),
+                    _ => {}
+                }
+                let file_pattern = rng.pick(&patterns);
+                let mut line = format!("{}{}", rng.pick(WHITESPACE), file_pattern);
+                let mut assigned = Owners::default();
+                for _ in 0..=rng.below(3) {
+                    let owner = rng.pick(&owners);
+                    line.push_str(rng.pick(WHITESPACE));
+                    line.push_str(owner);
+                    assigned.insert(owner).unwrap();
+                }
+                if rng.below(3) == 0 {
+                    line.push_str(" # A trailing comment");
+                }
+                line.push_str(rng.pick(WHITESPACE));
+                source.push_str(&line);
+                source.push_str(if rng.below(2) == 0 { "\n" } else { "\r\n" });
+                assignments.push(Assignment {
+                    file_pattern: file_pattern.to_string(),
+                    owners: assigned,
+                });
+            }
+            assert_eq!(
+                CodeOwners::new(&source).unwrap(),
+                CodeOwners { assignments },
+                "{:?}",
+                source
+            );
+        }
+    }
+
+    fn handles(members: &[&str]) -> Owners {
+        Owners {
+            handles: hashset(members),
+            emails: HashSet::new(),
+        }
+    }
+
     fn hashset(members: &[&str]) -> HashSet<String> {
         members.iter().cloned().map(String::from).collect()
     }
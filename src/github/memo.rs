@@ -0,0 +1,99 @@
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use std::{collections::HashMap, future::Future, hash::Hash, sync::Mutex};
+
+type SharedResult<T, E> = Shared<BoxFuture<'static, Result<T, E>>>;
+
+/// Memoises the results of requests for the duration of a run, so that the
+/// same resource is only fetched once no matter how many projects need it.
+/// Concurrent requests for the same resource share a single in-flight
+/// request. Failures are forgotten once complete so they can be retried.
+pub struct Memo<K, T, E> {
+    entries: Mutex<HashMap<K, SharedResult<T, E>>>,
+}
+
+impl<K, T, E> Memo<K, T, E>
+where
+    K: Eq + Hash + Clone,
+    T: Clone + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_or_fetch<F>(&self, key: K, fetch: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let shared = self
+            .entries
+            .lock()
+            .expect("Memo lock poisoned")
+            .entry(key.clone())
+            .or_insert_with(|| fetch.boxed().shared())
+            .clone();
+
+        match shared.await {
+            Ok(value) => Ok(value),
+            Err(error) => {
+                let _ = self
+                    .entries
+                    .lock()
+                    .expect("Memo lock poisoned")
+                    .remove(&key);
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[tokio::test]
+    async fn fetches_once() {
+        let memo = Memo::<_, _, ()>::new();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let fetch = || {
+            let fetches = fetches.clone();
+            async move {
+                let _ = fetches.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                Ok(1)
+            }
+        };
+
+        // Concurrent and later requests share the first fetch
+        let (a, b) = futures::join!(
+            memo.get_or_fetch("key", fetch()),
+            memo.get_or_fetch("key", fetch())
+        );
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 1));
+        assert_eq!(memo.get_or_fetch("key", fetch()).await.unwrap(), 1);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        assert_eq!(memo.get_or_fetch("other", fetch()).await.unwrap(), 1);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failures_are_retried() {
+        let memo = Memo::<_, i32, _>::new();
+        let error = memo
+            .get_or_fetch("key", async { Err("Oh no".to_string()) })
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Oh no");
+        assert_eq!(memo.get_or_fetch("key", async { Ok(1) }).await.unwrap(), 1);
+    }
+}
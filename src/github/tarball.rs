@@ -0,0 +1,150 @@
+//! Reading of tar archives, as GitHub serves the files of a repo in, so that
+//! a whole repo can be scanned with one download. Only regular files are
+//! read. Directories, links and other entries are left out.
+
+use eyre::eyre;
+
+const BLOCK: usize = 512;
+
+/// A regular file in an archive.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry<'a> {
+    pub path: String,
+    pub contents: &'a [u8],
+}
+
+/// A field of a header, up to its first NUL byte.
+fn field(header: &[u8]) -> &[u8] {
+    let end = header
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(header.len());
+    &header[..end]
+}
+
+/// The size of an entry, in octal or, for large files, in base 256.
+fn size(field: &[u8]) -> eyre::Result<usize> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..]
+            .iter()
+            .fold(usize::from(field[0] & 0x7f), |size, &byte| {
+                (size << 8) | usize::from(byte)
+            }));
+    }
+    let octal = std::str::from_utf8(self::field(field))
+        .map_err(|_| eyre!("The archive has an invalid entry size"))?
+        .trim();
+    if octal.is_empty() {
+        return Ok(0);
+    }
+    usize::from_str_radix(octal, 8).map_err(|_| eyre!("The archive has an invalid entry size"))
+}
+
+/// The path of a pax extended header, whose records look like
+/// `30 path=some/very/long/path\n`.
+fn pax_path(records: &[u8]) -> Option<String> {
+    let mut rest = records;
+    while !rest.is_empty() {
+        let space = rest.iter().position(|&byte| byte == b' ')?;
+        let length: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..length)?;
+        if let Some(path) = record.strip_prefix(b"path=") {
+            let path = path.strip_suffix(b"\n").unwrap_or(path);
+            return Some(String::from_utf8_lossy(path).into_owned());
+        }
+        rest = &rest[length..];
+    }
+    None
+}
+
+/// The regular files of an archive, in the order they are stored.
+pub fn entries(tar: &[u8]) -> eyre::Result<Vec<Entry<'_>>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    // The path given by an extended header for the entry after it
+    let mut long_path = None;
+    while let Some(header) = tar.get(offset..offset + BLOCK) {
+        if header.iter().all(|&byte| byte == 0) {
+            return Ok(entries);
+        }
+        let checksum = size(&header[148..156])?;
+        let sum: usize = header
+            .iter()
+            .enumerate()
+            .map(|(index, &byte)| match index {
+                148..=155 => usize::from(b' '),
+                _ => usize::from(byte),
+            })
+            .sum();
+        if checksum != sum {
+            return Err(eyre!("The archive has a corrupt header at byte {}", offset));
+        }
+        let length = size(&header[124..136])?;
+        let start = offset + BLOCK;
+        let contents = tar
+            .get(start..start + length)
+            .ok_or_else(|| eyre!("The archive ends unexpectedly"))?;
+        offset = start + length.div_ceil(BLOCK) * BLOCK;
+        match header[156] {
+            b'x' => long_path = pax_path(contents),
+            b'L' => long_path = Some(String::from_utf8_lossy(field(contents)).into_owned()),
+            b'0' | b'\0' | b'7' => {
+                let path = long_path.take().unwrap_or_else(|| {
+                    let name = String::from_utf8_lossy(field(&header[..100]));
+                    let prefix = field(&header[345..500]);
+                    if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                        format!("{}/{}", String::from_utf8_lossy(prefix), name)
+                    } else {
+                        name.into_owned()
+                    }
+                });
+                entries.push(Entry { path, contents });
+            }
+            _ => long_path = None,
+        }
+    }
+    Err(eyre!("The archive ends unexpectedly"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_archives() {
+        // Made by Python's `tarfile` in the pax format, like GitHub's archives
+        let tar =
+            crate::inflate::gunzip(include_bytes!("../fixtures/repo.tar.gz"), 1 << 20).unwrap();
+        let entries = entries(&tar).unwrap();
+        let long = format!("EmbarkStudios-ash-0123abc/{}/deep.txt", "nested".repeat(20));
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    path: "EmbarkStudios-ash-0123abc/README.md".to_string(),
+                    contents: b"# ash\n",
+                },
+                Entry {
+                    path: long,
+                    contents: b"deep\n",
+                },
+                Entry {
+                    path: "EmbarkStudios-ash-0123abc/src/lib.rs".to_string(),
+                    contents: &[b'x'; 1000],
+                },
+            ]
+        );
+
+        let end = entries[2].contents.as_ptr() as usize - tar.as_ptr() as usize + 500;
+        assert_eq!(
+            super::entries(&tar[..end]).unwrap_err().to_string(),
+            "The archive ends unexpectedly"
+        );
+        let mut corrupt = tar.clone();
+        corrupt[0] ^= 1;
+        assert_eq!(
+            super::entries(&corrupt).unwrap_err().to_string(),
+            "The archive has a corrupt header at byte 0"
+        );
+    }
+}
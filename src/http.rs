@@ -0,0 +1,132 @@
+//! The HTTP client shared by everything that makes requests, so that proxy
+//! and TLS settings apply everywhere and connections are reused.
+
+use crate::{
+    config::HttpConfig,
+    telemetry::{Span, SpanKind},
+};
+use eyre::{eyre, WrapErr};
+use std::{future::Future, sync::OnceLock};
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// The hosts whose request paths are recorded in traces. The paths of other
+/// hosts, such as Slack webhooks, may be secret.
+const TRACED_PATH_HOSTS: &[&str] = &["api.github.com", "raw.githubusercontent.com"];
+
+/// Build the shared client from the configuration. This must be called
+/// before the first request is made for the configuration to have an effect.
+pub fn init(config: &HttpConfig) -> eyre::Result<()> {
+    let client = build(config)?;
+    CLIENT
+        .set(client)
+        .map_err(|_| eyre!("The HTTP client has already been initialised"))
+}
+
+/// The shared client, which uses the default settings if `init` has not
+/// been called.
+pub fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| build(&HttpConfig::default()).expect("Default HTTP client"))
+}
+
+/// Sending of requests in a span of the current trace, see `telemetry`.
+pub trait Traced {
+    fn send_traced(self) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl Traced for reqwest::RequestBuilder {
+    async fn send_traced(self) -> reqwest::Result<reqwest::Response> {
+        let (client, request) = self.build_split();
+        let request = request?;
+        let url = request.url();
+        let host = url.host_str().unwrap_or_default().to_string();
+        let mut attributes = vec![
+            ("http.request.method", request.method().as_str().into()),
+            ("server.address", host.clone().into()),
+        ];
+        if TRACED_PATH_HOSTS.contains(&host.as_str()) {
+            attributes.push(("url.path", url.path().into()));
+        }
+        let span = Span::start(
+            format!("{} {}", request.method(), host),
+            SpanKind::Client,
+            attributes,
+        );
+        let response = client.execute(request).await;
+        if let Some(mut span) = span {
+            let error = match &response {
+                Ok(response) => {
+                    let status = response.status();
+                    span.set_attribute("http.response.status_code", status.as_u16());
+                    (status.is_client_error() || status.is_server_error())
+                        .then(|| status.to_string())
+                }
+                Err(error) => Some(error.to_string()),
+            };
+            span.end(error);
+        }
+        response
+    }
+}
+
+fn build(config: &HttpConfig) -> eyre::Result<reqwest::Client> {
+    // The HTTPS_PROXY, HTTP_PROXY, and NO_PROXY environment variables are
+    // honoured by default, unless a proxy is configured explicitly.
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = &config.proxy {
+        let no_proxy = config
+            .no_proxy
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+        let proxy = reqwest::Proxy::all(url)
+            .wrap_err_with(|| format!("Invalid proxy URL {}", url))?
+            .no_proxy(no_proxy);
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &config.ca_bundle {
+        let bundle = std::fs::read(path)
+            .wrap_err_with(|| format!("Unable to read CA bundle {}", path.display()))?;
+        for certificate in split_pem_bundle(&bundle) {
+            let certificate = reqwest::Certificate::from_pem(certificate.as_bytes())
+                .wrap_err_with(|| format!("Invalid certificate in {}", path.display()))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    builder.build().wrap_err("Unable to create HTTP client")
+}
+
+/// Split a bundle of PEM encoded certificates into the individual
+/// certificates, as the client only accepts one at a time.
+fn split_pem_bundle(bundle: &[u8]) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    String::from_utf8_lossy(bundle)
+        .split_inclusive(END)
+        .filter(|section| section.contains(END))
+        .map(|section| section.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_bundle() {
+        let bundle = b"# Corporate root
+-----BEGIN CERTIFICATE-----
+AAAA
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+BBBB
+-----END CERTIFICATE-----
+";
+        assert_eq!(
+            split_pem_bundle(bundle),
+            vec![
+                "# Corporate root\n-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----",
+                "-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----",
+            ]
+        );
+        assert!(split_pem_bundle(b"").is_empty());
+    }
+}
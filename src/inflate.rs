@@ -0,0 +1,375 @@
+//! Decompression of gzip data, as GitHub serves repo archives in, following
+//! RFC 1951 (DEFLATE) and RFC 1952 (gzip). Output is limited in size, so that
+//! an archive that expands enormously is refused rather than exhausting memory.
+
+use eyre::{eyre, WrapErr};
+
+/// The longest Huffman code DEFLATE uses.
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order the lengths of the code length code are given in.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Reads the bits of the input, starting with the least significant bit of
+/// each byte.
+struct Bits<'a> {
+    input: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> eyre::Result<u32> {
+        while self.count < count {
+            let byte = *self
+                .input
+                .get(self.position)
+                .ok_or_else(|| eyre!("The compressed data ends unexpectedly"))?;
+            self.position += 1;
+            self.buffer |= u32::from(byte) << self.count;
+            self.count += 8;
+        }
+        let bits = self.buffer & ((1 << count) - 1);
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(bits)
+    }
+
+    /// Skip to the start of the next byte, and return the rest of the input.
+    fn align(&mut self) -> &'a [u8] {
+        self.buffer = 0;
+        self.count = 0;
+        &self.input[self.position..]
+    }
+}
+
+/// A canonical Huffman code, as the number of codes of each length and the
+/// symbols in the order of their codes.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> eyre::Result<Self> {
+        let mut counts = [0; MAX_BITS + 1];
+        for &length in lengths {
+            counts[usize::from(length)] += 1;
+        }
+        counts[0] = 0;
+        // Codes may be incomplete but not over-subscribed
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err(eyre!("The compressed data has an invalid Huffman code"));
+            }
+        }
+        let mut offsets = [0; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; usize::from(offsets[MAX_BITS + 1])];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                let offset = &mut offsets[usize::from(length)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits<'_>) -> eyre::Result<u16> {
+        // The first code of each length follows the last of the one before
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= bits.bits(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(eyre!("The compressed data has an invalid code"))
+    }
+}
+
+/// Decompress raw DEFLATE data, failing if it would be more than `limit`
+/// bytes. Returns the output and the input after the compressed data.
+pub fn inflate(input: &[u8], limit: usize) -> eyre::Result<(Vec<u8>, &[u8])> {
+    let mut output = Vec::new();
+    let mut bits = Bits::new(input);
+    loop {
+        let last = bits.bits(1)? == 1;
+        match bits.bits(2)? {
+            0 => {
+                let rest = bits.align();
+                let header = rest
+                    .get(..4)
+                    .ok_or_else(|| eyre!("The compressed data ends unexpectedly"))?;
+                let length = usize::from(u16::from_le_bytes([header[0], header[1]]));
+                let complement = u16::from_le_bytes([header[2], header[3]]);
+                if length != usize::from(!complement) {
+                    return Err(eyre!("The compressed data has an invalid stored block"));
+                }
+                let data = rest
+                    .get(4..4 + length)
+                    .ok_or_else(|| eyre!("The compressed data ends unexpectedly"))?;
+                if output.len() + length > limit {
+                    return Err(too_large(limit));
+                }
+                output.extend_from_slice(data);
+                bits.position += 4 + length;
+            }
+            1 => {
+                let mut lengths = [0; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literals = Huffman::new(&lengths)?;
+                let distances = Huffman::new(&[5; 30])?;
+                block(&mut bits, &literals, &distances, &mut output, limit)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut bits)?;
+                block(&mut bits, &literals, &distances, &mut output, limit)?;
+            }
+            _ => return Err(eyre!("The compressed data has an invalid block type")),
+        }
+        if last {
+            let rest = bits.align();
+            return Ok((output, rest));
+        }
+    }
+}
+
+fn too_large(limit: usize) -> eyre::Report {
+    eyre!("The data is larger than {} bytes when decompressed", limit)
+}
+
+fn dynamic_codes(bits: &mut Bits<'_>) -> eyre::Result<(Huffman, Huffman)> {
+    let literal_count = bits.bits(5)? as usize + 257;
+    let distance_count = bits.bits(5)? as usize + 1;
+    let code_length_count = bits.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(eyre!("The compressed data has too many codes"));
+    }
+    let mut code_lengths = [0; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = bits.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_lengths.decode(bits)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *index
+                    .checked_sub(1)
+                    .and_then(|previous| lengths.get(previous))
+                    .ok_or_else(|| eyre!("The compressed data repeats a missing length"))?;
+                (previous, 3 + bits.bits(2)? as usize)
+            }
+            17 => (0, 3 + bits.bits(3)? as usize),
+            _ => (0, 11 + bits.bits(7)? as usize),
+        };
+        if index + repeat > lengths.len() {
+            return Err(eyre!("The compressed data has too many code lengths"));
+        }
+        lengths[index..index + repeat].fill(length);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(eyre!("The compressed data has no end of block code"));
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+fn block(
+    bits: &mut Bits<'_>,
+    literals: &Huffman,
+    distances: &Huffman,
+    output: &mut Vec<u8>,
+    limit: usize,
+) -> eyre::Result<()> {
+    loop {
+        let symbol = usize::from(literals.decode(bits)?);
+        if symbol < 256 {
+            if output.len() == limit {
+                return Err(too_large(limit));
+            }
+            output.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+        let index = symbol - 257;
+        let length = *LENGTH_BASES
+            .get(index)
+            .ok_or_else(|| eyre!("The compressed data has an invalid length"))?
+            as usize
+            + bits.bits(u32::from(LENGTH_EXTRA_BITS[index]))? as usize;
+        let index = usize::from(distances.decode(bits)?);
+        let distance = *DISTANCE_BASES
+            .get(index)
+            .ok_or_else(|| eyre!("The compressed data has an invalid distance"))?
+            as usize
+            + bits.bits(u32::from(DISTANCE_EXTRA_BITS[index]))? as usize;
+        if distance > output.len() {
+            return Err(eyre!("The compressed data refers to data before its start"));
+        }
+        if output.len() + length > limit {
+            return Err(too_large(limit));
+        }
+        // The copy may overlap what it writes, repeating the last bytes
+        let start = output.len() - distance;
+        for offset in 0..length {
+            output.push(output[start + offset]);
+        }
+    }
+}
+
+/// The CRC-32 of data, as gzip checks its contents with.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (!(crc & 1)).wrapping_add(1));
+        }
+    }
+    !crc
+}
+
+/// Decompress gzip data, failing if it would be more than `limit` bytes.
+pub fn gunzip(input: &[u8], limit: usize) -> eyre::Result<Vec<u8>> {
+    const HEADER_CRC: u8 = 2;
+    const EXTRA: u8 = 4;
+    const NAME: u8 = 8;
+    const COMMENT: u8 = 16;
+
+    let ends = || eyre!("The gzip data ends unexpectedly");
+    if input.get(..3) != Some(&[0x1f, 0x8b, 8]) {
+        return Err(eyre!("The data is not gzip compressed"));
+    }
+    let flags = *input.get(3).ok_or_else(ends)?;
+    let mut rest = input.get(10..).ok_or_else(ends)?;
+    if flags & EXTRA != 0 {
+        let length = usize::from(u16::from_le_bytes([
+            *rest.first().ok_or_else(ends)?,
+            *rest.get(1).ok_or_else(ends)?,
+        ]));
+        rest = rest.get(2 + length..).ok_or_else(ends)?;
+    }
+    for flag in [NAME, COMMENT] {
+        if flags & flag != 0 {
+            let end = rest.iter().position(|&byte| byte == 0).ok_or_else(ends)?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & HEADER_CRC != 0 {
+        rest = rest.get(2..).ok_or_else(ends)?;
+    }
+
+    let (output, trailer) = inflate(rest, limit).wrap_err("Unable to decompress the gzip data")?;
+    let trailer = trailer.get(..8).ok_or_else(ends)?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32(&output) || size != output.len() as u32 {
+        return Err(eyre!("The gzip data is corrupt"));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `b"hello hello hello hello\n"` compressed by Python's `gzip`, which uses
+    /// a fixed Huffman code for short inputs.
+    const FIXED: &[u8] = &[
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9,
+        0x57, 0xc8, 0x40, 0x27, 0xb9, 0x00, 0x00, 0x88, 0x59, 0x0b, 0x18, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn decompressing_gzip() {
+        assert_eq!(
+            gunzip(FIXED, 1024).unwrap(),
+            b"hello hello hello hello\n".to_vec()
+        );
+        assert_eq!(
+            gunzip(FIXED, 10).unwrap_err().root_cause().to_string(),
+            "The data is larger than 10 bytes when decompressed"
+        );
+        assert!(gunzip(&FIXED[..FIXED.len() - 1], 1024).is_err());
+        let mut corrupt = FIXED.to_vec();
+        corrupt[FIXED.len() - 8] ^= 1;
+        assert_eq!(
+            gunzip(&corrupt, 1024).unwrap_err().to_string(),
+            "The gzip data is corrupt"
+        );
+        assert!(gunzip(b"hello", 1024).is_err());
+    }
+
+    #[test]
+    fn decompressing_stored_and_dynamic_blocks() {
+        // A stored block of "abc"
+        let (output, rest) =
+            inflate(&[0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c', 7], 10).unwrap();
+        assert_eq!(output, b"abc");
+        assert_eq!(rest, &[7]);
+
+        let text: Vec<u8> = (0..2000u32)
+            .flat_map(|n| format!("line {} of the file\n", n % 97).into_bytes())
+            .collect();
+        // Compressed by Python's `gzip` with a dynamic Huffman code
+        let compressed = include_bytes!("fixtures/lines.txt.gz");
+        assert_eq!(gunzip(compressed, text.len()).unwrap(), text);
+        assert!(gunzip(compressed, text.len() - 1).is_err());
+    }
+
+    #[test]
+    fn computing_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
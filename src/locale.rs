@@ -0,0 +1,255 @@
+//! The language of reports and notifications, chosen with `--lang` or the
+//! `EMBARK_OSS_LANG` environment variable, for teams that want them in their
+//! working language. Messages are kept in a bundle for each language in
+//! `src/locales`, and messages missing from a bundle fall back to English.
+//! Bundles are written in the syntax of Fluent, limited to single line
+//! messages whose placeables are `{ $name }` variables or `{ "{" }` literals.
+
+use crate::template::Template;
+use eyre::eyre;
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+/// The environment variable a language is read from if `--lang` isn't given.
+pub const ENV: &str = "EMBARK_OSS_LANG";
+
+static CURRENT: OnceLock<Lang> = OnceLock::new();
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lang {
+    #[default]
+    En,
+    Sv,
+}
+
+impl Lang {
+    pub const ALL: &'static [Self] = &[Self::En, Self::Sv];
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::En => include_str!("locales/en.ftl"),
+            Self::Sv => include_str!("locales/sv.ftl"),
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = eyre::Report;
+
+    /// Parse a language code, ignoring any region and encoding as in
+    /// `sv_SE.UTF-8`.
+    fn from_str(source: &str) -> eyre::Result<Self> {
+        let code = source.split(['_', '-', '.']).next().unwrap_or_default();
+        match code.to_ascii_lowercase().as_str() {
+            "en" => Ok(Self::En),
+            "sv" => Ok(Self::Sv),
+            _ => Err(eyre!(
+                "Unsupported language `{}`, expected en or sv",
+                source
+            )),
+        }
+    }
+}
+
+/// Set the language of the run, from `--lang` if given and otherwise from the
+/// environment. This must be called before the first message is looked up.
+pub fn init(lang: Option<Lang>) -> eyre::Result<()> {
+    let lang = match lang {
+        Some(lang) => lang,
+        None => match std::env::var(ENV) {
+            Ok(value) if !value.is_empty() => value.parse()?,
+            _ => Lang::default(),
+        },
+    };
+    CURRENT
+        .set(lang)
+        .map_err(|_| eyre!("The language has already been set"))
+}
+
+/// The language given with `--lang` among command line arguments, which is
+/// needed before they are parsed so that `--help` is in the language. Invalid
+/// languages are left for the parser to report.
+pub fn lang_from_args(args: impl IntoIterator<Item = String>) -> Option<Lang> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--lang") {
+            Some("") => args.next(),
+            Some(value) => value.strip_prefix('=').map(ToString::to_string),
+            None => continue,
+        };
+        return value.and_then(|value| value.parse().ok());
+    }
+    None
+}
+
+/// The language of the run, English unless `init` chose another.
+pub fn current() -> Lang {
+    CURRENT.get().copied().unwrap_or_default()
+}
+
+/// A message in the language of the run, with its placeholders replaced.
+pub fn text(id: &str, args: &[(&str, &str)]) -> String {
+    text_in(current(), id, args)
+}
+
+/// A message for `--help` in the language of the run. The message is leaked
+/// as the parser of the command line needs it for the rest of the run.
+pub fn help(id: &str) -> &'static str {
+    Box::leak(text(id, &[]).into_boxed_str())
+}
+
+/// A message in a language, falling back to English if the language has no
+/// such message, and to the id if no bundle has it.
+pub fn text_in(lang: Lang, id: &str, args: &[(&str, &str)]) -> String {
+    static BUNDLES: OnceLock<HashMap<Lang, HashMap<String, Template>>> = OnceLock::new();
+    // The bundles are checked by the tests, so one that is invalid anyway
+    // only loses its messages to English
+    let bundles = BUNDLES.get_or_init(|| {
+        Lang::ALL
+            .iter()
+            .map(|&lang| (lang, parse(lang.source()).unwrap_or_default()))
+            .collect()
+    });
+    match bundles[&lang]
+        .get(id)
+        .or_else(|| bundles[&Lang::En].get(id))
+    {
+        Some(message) => message.render(args),
+        None => id.to_string(),
+    }
+}
+
+/// Convert a Fluent message to a template, replacing its `{ $name }`
+/// placeables with `{name}` placeholders.
+fn template(message: &str) -> eyre::Result<Template> {
+    let mut source = String::new();
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let mut placeable = String::new();
+                let (mut closed, mut quoted) = (false, false);
+                for c in chars.by_ref() {
+                    if c == '}' && !quoted {
+                        closed = true;
+                        break;
+                    }
+                    quoted ^= c == '"';
+                    placeable.push(c);
+                }
+                let placeable = placeable.trim();
+                if !closed {
+                    return Err(eyre!("`{{{}` is not closed by `}}`", placeable));
+                } else if let Some(name) = placeable.strip_prefix('$') {
+                    source.push_str(&format!("{{{}}}", name));
+                } else if let Some(literal) = placeable
+                    .strip_prefix('"')
+                    .and_then(|placeable| placeable.strip_suffix('"'))
+                {
+                    source.push_str(&literal.replace('{', "{{").replace('}', "}}"));
+                } else {
+                    return Err(eyre!(
+                        "`{{{}}}` is not a placeable like `{{ $name }}`",
+                        placeable
+                    ));
+                }
+            }
+            '}' => return Err(eyre!("Unmatched `}}`, which is written `{{ \"}}\" }}`")),
+            c => source.push(c),
+        }
+    }
+    Template::parse(&source)
+}
+
+/// Parse a bundle of `id = message` lines. Blank lines and lines starting
+/// with `#` are ignored.
+fn parse(source: &str) -> eyre::Result<HashMap<String, Template>> {
+    let mut messages = HashMap::new();
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (id, message) = line
+            .split_once('=')
+            .ok_or_else(|| eyre!("Line {} is not `id = message`", number + 1))?;
+        let id = id.trim().to_string();
+        let message = template(message.trim())
+            .map_err(|error| error.wrap_err(format!("Invalid message `{}`", id)))?;
+        if messages.insert(id.clone(), message).is_some() {
+            return Err(eyre!("Message `{}` is given more than once", id));
+        }
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_have_every_message() {
+        let english = parse(Lang::En.source()).unwrap();
+        for &lang in Lang::ALL {
+            let bundle = parse(lang.source()).unwrap();
+            let mut missing: Vec<_> = english
+                .keys()
+                .filter(|id| !bundle.contains_key(*id))
+                .collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{:?} is missing {:?}", lang, missing);
+            for (id, message) in &bundle {
+                let placeholders = english[id].placeholders().collect::<Vec<_>>();
+                assert!(message.check(&placeholders).is_ok(), "{:?} {}", lang, id);
+            }
+        }
+    }
+
+    #[test]
+    fn localized_messages() {
+        assert_eq!(
+            text_in(Lang::Sv, "notification-new-project", &[("name", "ash")]),
+            "ash (nytt projekt)"
+        );
+        assert_eq!(
+            text_in(Lang::En, "status-took", &[("duration", "2s")]),
+            "took 2s"
+        );
+        assert_eq!(text_in(Lang::Sv, "no-such-message", &[]), "no-such-message");
+        assert_eq!("sv_SE.UTF-8".parse::<Lang>().unwrap(), Lang::Sv);
+        assert_eq!("EN".parse::<Lang>().unwrap(), Lang::En);
+        assert!("fr".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn parsing_fluent_messages() {
+        let messages =
+            parse("# A comment\n\ngreeting = Hej { $name }, { \"{\" }ok{ \"}\" }").unwrap();
+        assert_eq!(
+            messages["greeting"].render(&[("name", "ash")]),
+            "Hej ash, {ok}"
+        );
+        assert!(parse("greeting = Hej {name}").is_err());
+        assert!(parse("greeting = Hej { $name").is_err());
+        assert!(parse("greeting = Hej }").is_err());
+        assert!(parse("greeting = Hej\ngreeting = Hallå").is_err());
+    }
+
+    #[test]
+    fn reading_the_lang_before_parsing_arguments() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            lang_from_args(args(&["embark-oss", "--lang", "sv", "--help"])),
+            Some(Lang::Sv)
+        );
+        assert_eq!(
+            lang_from_args(args(&["embark-oss", "validate-all", "--lang=sv"])),
+            Some(Lang::Sv)
+        );
+        assert_eq!(lang_from_args(args(&["embark-oss", "--lang", "fr"])), None);
+        assert_eq!(
+            lang_from_args(args(&["embark-oss", "--language", "sv"])),
+            None
+        );
+        assert_eq!(lang_from_args(args(&["embark-oss", "--lang"])), None);
+    }
+}
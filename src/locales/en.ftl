@@ -0,0 +1,65 @@
+# The English messages of reports, notifications and the descriptions of
+# commands in --help, in the syntax of Fluent (https://projectfluent.org) as
+# `id = message` with `{ $name }` placeables. Every message must also be in
+# the other bundles.
+
+notification-immediate = The following Embark open source projects have been found to have maintainership issues.
+notification-digest = This is the regular digest of Embark open source projects that have maintainership issues. Arrows show whether each project has more (↑), fewer (↓), or the same (→) failing checks as in the previous digest.
+notification-escalation = The following Embark open source projects have failed the same check for { $runs } or more consecutive runs and need attention.
+notification-unvalidated = The following Embark open source projects could not be validated because GitHub could not be reached, even when tried again. This is not a problem with the projects, and they will be validated again by the next run.
+notification-celebration = The following Embark open source projects now conform to our guidelines. Thank you to their maintainers!
+notification-new-project = { $name } (new project)
+notification-all-conform = Every project conforms to our guidelines.
+notification-summary-title = Embark open source projects with maintainership issues
+notification-slack-foot = This message was generated by the <https://github.com/EmbarkStudios/opensource/tree/main/tools/embark-oss|embark-oss tool> on GitHub Actions.
+notification-html-foot = This message was generated by the <a href="https://github.com/EmbarkStudios/opensource/tree/main/tools/embark-oss">embark-oss tool</a>.
+notification-expiring-exemptions = The following exemptions from our guidelines expire within { $days } days. Fix the violations they cover before then, rather than renewing the exemptions.
+
+status-could-not-validate = { $name } could not be validated
+status-skipped-needs-auth = { $id }: skipped (needs auth)
+status-skipped-prerequisite = { $id }: skipped: prerequisite { $prerequisite } failed
+status-exempt = { $id }: exempt, { $justification }
+status-known = { $id }: known, in the baseline
+status-took = took { $duration }
+
+run-id = Run { $id }
+run-config = config { $hash }
+run-default-config = default config
+run-rate-limit = { $used } GitHub API requests, { $remaining } of { $limit } remaining
+run-rate-limit-remaining = { $remaining } of { $limit } GitHub API requests remaining
+
+exemptions-expiring = Exemptions that expire soon:
+exemption-expiring = { $check } of { $project } expires on { $expires }, in { $days } days
+exemption-owners = owned by { $owners }
+exemption-no-owner = no owner
+
+cli-dry-run = Print notifications and other changes instead of sending or making them
+cli-lang = The language of reports and notifications [default: $EMBARK_OSS_LANG, or en]
+cli-validate-all = Validate all projects listed in Embark's Open Source website data.json
+cli-validate = Validate one project from Embark's GitHub organisation
+cli-validate-website-pr = Validate the projects a pull request to the website adds or changes in data.json
+cli-review-pr = Review the entries a pull request to the website adds or changes
+cli-inspect = Show what each check looks at for one project and how to fix failures
+cli-preflight = Check whether a repo is ready to be open sourced
+cli-archive = Check that a project is archived and move it to the archived projects
+cli-fix = Fix projects that fail checks which can be corrected automatically
+cli-sync-templates = Open pull requests replacing boilerplate files that drifted from the template
+cli-new-project = Set up a new project, asking for any details not given as flags
+cli-scaffold = Generate guideline compliant files for a new project
+cli-doctor = Diagnose problems with the credentials, network, and config
+cli-slack = Work with Slack notifications
+cli-checks = Describe the checks that are run
+cli-config = Work with the config
+cli-export = Export data about the projects for use in other tools
+cli-scorecard = Score how well all projects follow the guidelines, for reporting
+cli-schema = Print the JSON schema of the validation results
+cli-export-graph = Print a graph of maintainers and the projects they maintain
+cli-export-enrich = Print the website data.json with live GitHub metadata added
+cli-export-metrics = Print the stars, forks and contributors recorded by each run
+cli-export-contributors = Print the external contributors to each project over a time window
+cli-slack-preview = Print the Slack notification that would be sent for a results file
+cli-checks-list = List the checks the config enables and how each is applied
+cli-config-lint = Find mistakes in the config and in exemption files before they are used
+cli-fix-topics = Add required topics to repos and remove forbidden ones
+cli-fix-website-data = Print a diff removing dead and archived projects from the website data and sorting it
+cli-scaffold-codeowners = Generate a CODEOWNERS file, printing it unless --dir is given
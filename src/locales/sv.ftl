@@ -0,0 +1,62 @@
+# The Swedish messages of reports, notifications and --help, see en.ftl.
+
+notification-immediate = Följande öppna källkodsprojekt från Embark har problem med sitt underhåll.
+notification-digest = Det här är den regelbundna sammanfattningen av Embarks öppna källkodsprojekt som har problem med sitt underhåll. Pilarna visar om varje projekt har fler (↑), färre (↓) eller lika många (→) misslyckade kontroller som i förra sammanfattningen.
+notification-escalation = Följande öppna källkodsprojekt från Embark har misslyckats med samma kontroll i { $runs } eller fler körningar i rad och behöver uppmärksamhet.
+notification-unvalidated = Följande öppna källkodsprojekt från Embark kunde inte valideras eftersom GitHub inte gick att nå, inte ens vid nytt försök. Det är inte ett problem med projekten, och de valideras igen vid nästa körning.
+notification-celebration = Följande öppna källkodsprojekt från Embark följer nu våra riktlinjer. Tack till deras underhållare!
+notification-new-project = { $name } (nytt projekt)
+notification-all-conform = Alla projekt följer våra riktlinjer.
+notification-summary-title = Embarks öppna källkodsprojekt med problem i underhållet
+notification-slack-foot = Det här meddelandet skapades av <https://github.com/EmbarkStudios/opensource/tree/main/tools/embark-oss|verktyget embark-oss> på GitHub Actions.
+notification-html-foot = Det här meddelandet skapades av <a href="https://github.com/EmbarkStudios/opensource/tree/main/tools/embark-oss">verktyget embark-oss</a>.
+notification-expiring-exemptions = Följande undantag från våra riktlinjer löper ut inom { $days } dagar. Åtgärda överträdelserna de täcker innan dess, i stället för att förnya undantagen.
+
+status-could-not-validate = { $name } kunde inte valideras
+status-skipped-needs-auth = { $id }: hoppades över (kräver autentisering)
+status-skipped-prerequisite = { $id }: hoppades över: förutsättningen { $prerequisite } misslyckades
+status-exempt = { $id }: undantagen, { $justification }
+status-known = { $id }: känt, finns i baslinjen
+status-took = tog { $duration }
+
+run-id = Körning { $id }
+run-config = konfiguration { $hash }
+run-default-config = standardkonfiguration
+run-rate-limit = { $used } anrop till GitHubs API, { $remaining } av { $limit } kvar
+run-rate-limit-remaining = { $remaining } av { $limit } anrop till GitHubs API kvar
+
+exemptions-expiring = Undantag som snart löper ut:
+exemption-expiring = { $check } i { $project } löper ut { $expires }, om { $days } dagar
+exemption-owners = ansvariga: { $owners }
+exemption-no-owner = ingen ansvarig
+
+cli-dry-run = Skriv ut aviseringar och andra ändringar i stället för att skicka eller göra dem
+cli-lang = Språket i rapporter och aviseringar [standard: $EMBARK_OSS_LANG, annars en]
+cli-validate-all = Validera alla projekt i data.json för Embarks webbplats för öppen källkod
+cli-validate = Validera ett projekt från Embarks GitHub-organisation
+cli-validate-website-pr = Validera projekten som en pull request till webbplatsen lägger till eller ändrar i data.json
+cli-review-pr = Granska posterna som en pull request till webbplatsen lägger till eller ändrar
+cli-inspect = Visa vad varje kontroll tittar på för ett projekt och hur fel åtgärdas
+cli-preflight = Kontrollera om ett repo är redo att bli öppen källkod
+cli-archive = Kontrollera att ett projekt är arkiverat och flytta det till de arkiverade projekten
+cli-fix = Åtgärda projekt som misslyckas med kontroller som kan rättas automatiskt
+cli-sync-templates = Öppna pull requests som ersätter standardfiler som har glidit ifrån mallen
+cli-new-project = Skapa ett nytt projekt och fråga efter uppgifter som inte gavs som flaggor
+cli-scaffold = Skapa filer som följer riktlinjerna för ett nytt projekt
+cli-doctor = Felsök problem med inloggningsuppgifterna, nätverket och konfigurationen
+cli-slack = Arbeta med aviseringar i Slack
+cli-checks = Beskriv kontrollerna som körs
+cli-config = Arbeta med konfigurationen
+cli-export = Exportera data om projekten för användning i andra verktyg
+cli-scorecard = Poängsätt hur väl alla projekt följer riktlinjerna, för rapportering
+cli-schema = Skriv ut JSON-schemat för valideringsresultaten
+cli-export-graph = Skriv ut en graf över underhållare och projekten de underhåller
+cli-export-enrich = Skriv ut webbplatsens data.json med aktuell metadata från GitHub tillagd
+cli-export-metrics = Skriv ut stjärnorna, förgreningarna och bidragsgivarna som varje körning registrerade
+cli-export-contributors = Skriv ut de externa bidragsgivarna till varje projekt under en tidsperiod
+cli-slack-preview = Skriv ut aviseringen i Slack som skulle skickas för en resultatfil
+cli-checks-list = Lista kontrollerna som konfigurationen aktiverar och hur var och en tillämpas
+cli-config-lint = Hitta misstag i konfigurationen och i undantagsfiler innan de används
+cli-fix-topics = Lägg till obligatoriska ämnen i repon och ta bort förbjudna
+cli-fix-website-data = Skriv ut en diff som tar bort döda och arkiverade projekt från webbplatsens data och sorterar den
+cli-scaffold-codeowners = Skapa en CODEOWNERS-fil och skriv ut den om inte --dir anges
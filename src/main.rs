@@ -4,7 +4,7 @@
     clippy::doc_markdown,
     clippy::dbg_macro,
     clippy::todo,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::mem_forget,
     clippy::use_self,
@@ -13,9 +13,7 @@
     clippy::needless_borrow,
     clippy::match_wildcard_for_single_variants,
     clippy::if_let_mutex,
-    clippy::mismatched_target_os,
     clippy::await_holding_lock,
-    clippy::match_on_vec_items,
     clippy::imprecise_flops,
     clippy::suboptimal_flops,
     clippy::lossy_float_literal,
@@ -40,23 +38,294 @@
     unused_results
 )]
 
+mod alerting;
+mod config;
+mod diff;
+mod doctor;
+mod dry_run;
+mod email;
 mod error;
+mod export;
 mod github;
+mod http;
+mod inflate;
+mod locale;
+mod matrix;
 mod policy;
+mod scaffold;
+mod scan;
+mod scorecard;
 mod slack;
+mod telemetry;
+mod template;
+mod upload;
 mod validate;
 
+use std::path::PathBuf;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
 #[structopt(global_settings = &[AppSettings::ColoredHelp, AppSettings::VersionlessSubcommands])]
+struct Options {
+    #[structopt(
+        long,
+        global = true,
+        help = locale::help("cli-dry-run")
+    )]
+    dry_run: bool,
+
+    #[structopt(
+        long,
+        global = true,
+        possible_values = &["en", "sv"],
+        help = locale::help("cli-lang")
+    )]
+    lang: Option<locale::Lang>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug)]
 enum Command {
-    #[structopt(about = "Validate all projects listed in Embark's Open Source website data.json")]
+    #[structopt(about = locale::help("cli-validate-all"))]
     ValidateAll(ValidateAll),
 
-    #[structopt(about = "Validate one project from Embark's GitHub organisation")]
-    Validate { name: String },
+    #[structopt(about = locale::help("cli-validate"))]
+    Validate(Validate),
+
+    #[structopt(
+        about = locale::help("cli-validate-website-pr")
+    )]
+    ValidateWebsitePr(ValidateWebsitePr),
+
+    #[structopt(about = locale::help("cli-review-pr"))]
+    ReviewPr(ReviewPr),
+
+    #[structopt(about = locale::help("cli-inspect"))]
+    Inspect(Inspect),
+
+    #[structopt(about = locale::help("cli-preflight"))]
+    Preflight(Preflight),
+
+    #[structopt(about = locale::help("cli-archive"))]
+    Archive(Archive),
+
+    #[structopt(about = locale::help("cli-fix"))]
+    Fix(Fix),
+
+    #[structopt(
+        about = locale::help("cli-sync-templates")
+    )]
+    SyncTemplates(SyncTemplates),
+
+    #[structopt(about = locale::help("cli-new-project"))]
+    NewProject(NewProject),
+
+    #[structopt(about = locale::help("cli-scaffold"))]
+    Scaffold(Scaffold),
+
+    #[structopt(about = locale::help("cli-doctor"))]
+    Doctor(Doctor),
+
+    #[structopt(about = locale::help("cli-slack"))]
+    Slack(Slack),
+
+    #[structopt(about = locale::help("cli-checks"))]
+    Checks(Checks),
+
+    #[structopt(about = locale::help("cli-config"))]
+    Config(ConfigCommand),
+
+    #[structopt(about = locale::help("cli-export"))]
+    Export(Export),
+
+    #[structopt(about = locale::help("cli-scorecard"))]
+    Scorecard(Scorecard),
+
+    #[structopt(about = locale::help("cli-schema"))]
+    Schema,
+}
+
+#[derive(StructOpt, Debug)]
+struct Scorecard {
+    #[structopt(help = "A JSON results file, as written by --report-file")]
+    results: PathBuf,
+
+    #[structopt(long, default_value = "markdown", possible_values = &["markdown", "json"])]
+    format: scorecard::Format,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Export {
+    #[structopt(about = locale::help("cli-export-graph"))]
+    Graph(ExportGraph),
+
+    #[structopt(about = locale::help("cli-export-enrich"))]
+    Enrich(ExportEnrich),
+
+    #[structopt(about = locale::help("cli-export-metrics"))]
+    Metrics(ExportMetrics),
+
+    #[structopt(about = locale::help("cli-export-contributors"))]
+    Contributors(ExportContributors),
+}
+
+#[derive(StructOpt, Debug)]
+struct ExportContributors {
+    #[structopt(long, help = "The first day of the window, as YYYY-MM-DD")]
+    since: String,
+
+    #[structopt(long, help = "The day after the window, as YYYY-MM-DD [default: now]")]
+    until: Option<String>,
+
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    format: validate::Format,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct ExportMetrics {
+    #[structopt(help = "Where the results are published, as given to --publish-results")]
+    results: validate::Destination,
+
+    #[structopt(long, help = "Only print the metrics of this project")]
+    project: Option<String>,
+
+    #[structopt(long, default_value = "csv", possible_values = &["csv", "json"])]
+    format: export::MetricsFormat,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+}
+
+#[derive(StructOpt, Debug)]
+struct ExportEnrich {
+    #[structopt(
+        long,
+        help = "The organisation whose website data to enrich [default: the first configured]"
+    )]
+    organisation: Option<String>,
+
+    #[structopt(long, help = "Write the JSON to this file instead of printing it")]
+    output: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct ExportGraph {
+    #[structopt(help = "A JSON results file, as written by --report-file")]
+    results: PathBuf,
+
+    #[structopt(long, default_value = "dot", possible_values = &["dot", "graphml"])]
+    format: export::GraphFormat,
+
+    #[structopt(
+        long,
+        help = "Look up the members of teams listed as maintainers, which needs the read:org scope"
+    )]
+    teams: bool,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+}
+
+#[derive(StructOpt, Debug)]
+enum Slack {
+    #[structopt(about = locale::help("cli-slack-preview"))]
+    Preview(SlackPreview),
+}
+
+#[derive(StructOpt, Debug)]
+enum Checks {
+    #[structopt(about = locale::help("cli-checks-list"))]
+    List(ChecksList),
+}
+
+#[derive(StructOpt, Debug)]
+struct ChecksList {
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    format: validate::Format,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+enum ConfigCommand {
+    #[structopt(about = locale::help("cli-config-lint"))]
+    Lint(ConfigLint),
+}
+
+#[derive(StructOpt, Debug)]
+struct ConfigLint {
+    #[structopt(
+        long,
+        help = "The config file to lint [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help = "Exemption files to lint [default: .github/embark-oss.toml, if present]"
+    )]
+    exemptions: Vec<PathBuf>,
+
+    #[structopt(
+        long("skip-webhooks"),
+        help = "Don't check the Slack webhooks, email server and Matrix room of the config"
+    )]
+    skip_webhooks: bool,
+
+    #[structopt(
+        long("slack-webhook-url"),
+        help = "Also check this webhook, as given to validate-all"
+    )]
+    slack_webhook_url: Option<String>,
+}
+
+#[derive(StructOpt, Debug)]
+struct SlackPreview {
+    #[structopt(help = "A JSON results file, as written by --report-file")]
+    results: PathBuf,
+
+    #[structopt(
+        long("slack-webhook-url"),
+        help = "Also send the notification to this webhook, such as one for a test channel"
+    )]
+    slack_webhook_url: Option<String>,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
 }
 
 #[derive(StructOpt, Debug)]
@@ -65,14 +334,475 @@ struct ValidateAll {
     #[structopt(long("slack-webhook-url"))]
     slack_webhook_url: Option<String>,
 
-    #[structopt(long("github-api-token"))]
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long("publish-results"),
+        help = "Upload the JSON results to `gist:<id>`, comparing them with the previous run"
+    )]
+    publish_results: Option<validate::Destination>,
+
+    #[structopt(
+        long("notify-changes-only"),
+        help = "Only notify about projects with failures not present in the previous results"
+    )]
+    notify_changes_only: bool,
+
+    #[structopt(
+        long,
+        help = "Only validate projects with activity since the previous run, reusing other results"
+    )]
+    incremental: bool,
+
+    #[structopt(
+        long("projects-file"),
+        conflicts_with = "publish_results",
+        help = "Only validate the projects named in this file, one per line, or on stdin if `-`"
+    )]
+    projects_file: Option<PathBuf>,
+
+    #[structopt(
+        long("max-failures"),
+        default_value = "0",
+        help = "Only fail the run if more than this many projects fail"
+    )]
+    max_failures: usize,
+
+    #[structopt(
+        long("allow-failing"),
+        use_delimiter = true,
+        help = "Comma separated projects whose failures don't fail the run, though they are reported"
+    )]
+    allow_failing: Vec<String>,
+
+    #[structopt(
+        long,
+        help = "Report failures in this baseline file as known rather than failing the run"
+    )]
+    baseline: Option<PathBuf>,
+
+    #[structopt(
+        long("write-baseline"),
+        help = "Write the current failures to this file, for use with --baseline"
+    )]
+    write_baseline: Option<PathBuf>,
+
+    #[structopt(
+        long("atom-feed"),
+        help = "Add an entry for the failures that changed since the previous run to this Atom feed"
+    )]
+    atom_feed: Option<PathBuf>,
+
+    #[structopt(
+        long("heartbeat-url"),
+        help = "Ping this URL once the run completes, for monitoring that runs keep happening"
+    )]
+    heartbeat_url: Option<String>,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    output: Output,
+}
+
+#[derive(StructOpt, Debug)]
+struct Validate {
+    name: String,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    output: Output,
+}
+
+#[derive(StructOpt, Debug)]
+struct ValidateWebsitePr {
+    #[structopt(help = "The number of the pull request to the website repo")]
+    number: u64,
+
+    #[structopt(
+        long,
+        help = "The organisation whose website the pull request is to [default: the first configured]"
+    )]
+    organisation: Option<String>,
+
+    #[structopt(long, help = "Also comment on the pull request with the failures")]
+    comment: bool,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    output: Output,
+}
+
+#[derive(StructOpt, Debug)]
+struct ReviewPr {
+    #[structopt(help = "The number of the pull request to the website repo")]
+    number: u64,
+
+    #[structopt(
+        long,
+        help = "The organisation whose website the pull request is to [default: the first configured]"
+    )]
+    organisation: Option<String>,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct Inspect {
+    name: String,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct Preflight {
+    name: String,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct Archive {
+    name: String,
+
+    #[structopt(
+        long("open-pr"),
+        help = "Open a pull request moving the project to the archived projects of the website"
+    )]
+    open_pr: bool,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Fix {
+    #[structopt(about = locale::help("cli-fix-topics"))]
+    Topics(FixTopics),
+
+    #[structopt(
+        about = locale::help("cli-fix-website-data")
+    )]
+    WebsiteData(FixWebsiteData),
+}
+
+#[derive(StructOpt, Debug)]
+struct FixWebsiteData {
+    #[structopt(
+        long,
+        help = "The organisation whose website data to fix [default: the first configured]"
+    )]
+    organisation: Option<String>,
+
+    #[structopt(
+        long("open-pr"),
+        help = "Open a pull request to the website repo instead of printing the diff"
+    )]
+    open_pr: bool,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct FixTopics {
+    #[structopt(help = "The project to fix [default: every project]")]
+    name: Option<String>,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct SyncTemplates {
+    #[structopt(help = "The project to sync [default: every project]")]
+    name: Option<String>,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct Doctor {
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(long("slack-webhook-url"))]
+    slack_webhook_url: Option<String>,
+}
+
+#[derive(StructOpt, Debug)]
+struct NewProject {
+    #[structopt(long, help = "The name of the project's repo [default: asked for]")]
+    name: Option<String>,
+
+    #[structopt(
+        long,
+        use_delimiter = true,
+        help = "Comma separated GitHub handles or emails of the maintainers [default: asked for]"
+    )]
+    maintainers: Vec<String>,
+
+    #[structopt(
+        long,
+        use_delimiter = true,
+        help = "Comma separated tags of the project on the website [default: asked for]"
+    )]
+    tags: Option<Vec<String>>,
+
+    #[structopt(
+        long,
+        help = "The organisation of the project [default: the first configured]"
+    )]
+    organisation: Option<String>,
+
+    #[structopt(
+        long,
+        help = "Write the .github directory into this project from the template"
+    )]
+    dir: Option<PathBuf>,
+
+    #[structopt(
+        long("open-pr"),
+        help = "Open a pull request adding the project to the website data"
+    )]
+    open_pr: bool,
+
+    #[structopt(long, help = "Overwrite existing files")]
+    force: bool,
+
+    #[structopt(flatten)]
+    github: GitHubAuth,
+
+    #[structopt(
+        long,
+        help = "The config file to use [default: embark-oss.toml, if present]"
+    )]
+    config: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Scaffold {
+    #[structopt(about = locale::help("cli-scaffold-codeowners"))]
+    Codeowners {
+        #[structopt(
+            long,
+            required = true,
+            use_delimiter = true,
+            help = "Comma separated GitHub handles or emails of the maintainers"
+        )]
+        maintainers: Vec<String>,
+
+        #[structopt(
+            long,
+            help = "Write the files into the .github directory of this project"
+        )]
+        dir: Option<PathBuf>,
+
+        #[structopt(
+            long,
+            help = "Also write the rest of the .github directory from the template"
+        )]
+        all: bool,
+
+        #[structopt(long, help = "Overwrite existing files")]
+        force: bool,
+    },
+}
+
+/// Options for authenticating with the GitHub API, either with a token or as
+/// a GitHub App installed in the organisation.
+#[derive(StructOpt, Debug)]
+struct GitHubAuth {
+    #[structopt(long("github-api-token"), conflicts_with = "github_app_id")]
     github_api_token: Option<String>,
+
+    #[structopt(
+        long("github-app-id"),
+        requires = "github_app_private_key",
+        help = "Authenticate as the GitHub App with this id"
+    )]
+    github_app_id: Option<String>,
+
+    #[structopt(
+        long("github-app-private-key"),
+        requires = "github_app_id",
+        help = "The path of the GitHub App's PEM encoded private key"
+    )]
+    github_app_private_key: Option<PathBuf>,
+
+    #[structopt(
+        long("github-app-installation-id"),
+        requires = "github_app_id",
+        help = "The id of the app's installation [default: looked up for the organisation]"
+    )]
+    github_app_installation_id: Option<u64>,
+}
+
+impl GitHubAuth {
+    fn into_client(self) -> eyre::Result<github::Client> {
+        self.client(config::DEFAULT_ORGANISATION)
+    }
+
+    /// Create a client for an organisation. GitHub App installations are per
+    /// organisation, so a given installation id is only used for the default
+    /// organisation and is otherwise looked up.
+    fn client(&self, organisation: &str) -> eyre::Result<github::Client> {
+        let auth = match (&self.github_api_token, &self.github_app_id) {
+            (Some(token), _) => github::Auth::Token(token.clone()),
+            (None, Some(app_id)) => {
+                let path = self
+                    .github_app_private_key
+                    .as_ref()
+                    .ok_or_else(|| eyre::eyre!("No GitHub App private key given"))?;
+                let key = std::fs::read(path)
+                    .map_err(|error| eyre::eyre!("Unable to read {}: {}", path.display(), error))?;
+                let installation_id = self
+                    .github_app_installation_id
+                    .filter(|_| organisation == config::DEFAULT_ORGANISATION);
+                github::Auth::App(github::App::new(
+                    app_id.clone(),
+                    &key,
+                    organisation.to_string(),
+                    installation_id,
+                )?)
+            }
+            (None, None) => github::Auth::Anonymous,
+        };
+        Ok(github::Client::new(auth))
+    }
+}
+
+/// Options controlling how validation results are printed.
+#[derive(StructOpt, Debug)]
+struct Output {
+    #[structopt(long, default_value = "text", possible_values = &["text", "json"])]
+    format: validate::Format,
+
+    #[structopt(short, long, help = "Print how long each project and check took")]
+    verbose: bool,
+
+    #[structopt(long, help = "Print the N slowest projects and checks")]
+    slowest: Option<usize>,
+
+    #[structopt(long, help = "Also write the JSON results to this file")]
+    report_file: Option<PathBuf>,
+
+    #[structopt(long, help = "Also write the results to this file in SARIF")]
+    sarif_file: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
-    match Command::from_args() {
+    // The language is needed to describe the commands in `--help`, and
+    // `--lang` is parsed again with the rest of the arguments
+    locale::init(locale::lang_from_args(std::env::args()))?;
+    let Options {
+        dry_run,
+        lang,
+        command,
+    } = Options::from_args();
+    debug_assert!(lang.is_none_or(|lang| lang == locale::current()));
+    if dry_run {
+        dry_run::enable();
+    }
+
+    match command {
         Command::ValidateAll(options) => validate::all(options).await,
-        Command::Validate { name } => validate::one(name).await,
+        Command::Validate(options) => validate::one(options).await,
+        Command::ValidateWebsitePr(options) => validate::website_pr(options).await,
+        Command::ReviewPr(options) => validate::review_pr(options).await,
+        Command::Inspect(options) => validate::inspect(options).await,
+        Command::Preflight(options) => validate::preflight(options).await,
+        Command::Archive(options) => validate::archive(options).await,
+        Command::Fix(command) => validate::fix(command).await,
+        Command::SyncTemplates(options) => validate::sync_templates(options).await,
+        Command::NewProject(options) => scaffold::new_project(options).await,
+        Command::Scaffold(command) => scaffold::run(command).await,
+        Command::Doctor(options) => doctor::run(options).await,
+        Command::Slack(Slack::Preview(options)) => validate::preview_slack(options).await,
+        Command::Checks(Checks::List(options)) => validate::list_checks(options),
+        Command::Config(ConfigCommand::Lint(options)) => validate::lint_config(options).await,
+        Command::Export(Export::Graph(options)) => export::graph(options).await,
+        Command::Export(Export::Enrich(options)) => export::enrich(options).await,
+        Command::Export(Export::Metrics(options)) => export::metrics(options).await,
+        Command::Export(Export::Contributors(options)) => export::contributors(options).await,
+        Command::Scorecard(options) => scorecard::run(options),
+        Command::Schema => {
+            print!("{}", validate::SCHEMA);
+            Ok(())
+        }
     }
 }
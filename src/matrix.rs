@@ -0,0 +1,120 @@
+use crate::{config::MatrixConfig, http::Traced};
+use eyre::{eyre, WrapErr};
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The URL of `segments` under the client-server API of the homeserver.
+fn api_url(config: &MatrixConfig, segments: &[&str]) -> eyre::Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(&config.homeserver)
+        .wrap_err_with(|| format!("Invalid Matrix homeserver URL {}", config.homeserver))?;
+    let _ = url
+        .path_segments_mut()
+        .map_err(|_| eyre!("Invalid Matrix homeserver URL {}", config.homeserver))?
+        .pop_if_empty()
+        .extend(["_matrix", "client", "v3"])
+        .extend(segments);
+    Ok(url)
+}
+
+fn access_token(config: &MatrixConfig) -> eyre::Result<String> {
+    std::env::var(&config.access_token_env).map_err(|_| {
+        eyre!(
+            "The {} environment variable is not set",
+            config.access_token_env
+        )
+    })
+}
+
+/// The URL a message is sent to with the client-server API. Each message
+/// needs a unique transaction id so the homeserver can deduplicate retries.
+fn message_url(config: &MatrixConfig, transaction_id: &str) -> eyre::Result<reqwest::Url> {
+    api_url(
+        config,
+        &[
+            "rooms",
+            &config.room_id,
+            "send",
+            "m.room.message",
+            transaction_id,
+        ],
+    )
+}
+
+/// Post a message to the configured room, with a plain text body for
+/// clients that can't render the HTML one.
+pub async fn send(config: &MatrixConfig, text: String, html: String) -> eyre::Result<()> {
+    let payload = json!({
+        "msgtype": "m.text",
+        "body": text,
+        "format": "org.matrix.custom.html",
+        "formatted_body": html,
+    });
+    if crate::dry_run::is_enabled() {
+        crate::dry_run::print_skipped("send Matrix message", &payload);
+        return Ok(());
+    }
+
+    let token = access_token(config)?;
+    let transaction_id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        .to_string();
+
+    crate::http::client()
+        .put(message_url(config, &transaction_id)?)
+        .bearer_auth(token)
+        .json(&payload)
+        .send_traced()
+        .await
+        .wrap_err("Unable to send message to Matrix")?
+        .error_for_status()
+        .wrap_err("Unable to send message to Matrix")
+        .map(|_| ())
+}
+
+/// Check that the access token is valid and that its account has joined the
+/// room, without posting anything.
+pub async fn check(config: &MatrixConfig) -> eyre::Result<()> {
+    #[derive(Debug, serde::Deserialize)]
+    struct JoinedRooms {
+        joined_rooms: Vec<String>,
+    }
+
+    let rooms: JoinedRooms = crate::http::client()
+        .get(api_url(config, &["joined_rooms"])?)
+        .bearer_auth(access_token(config)?)
+        .send_traced()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .wrap_err("Unable to list the joined rooms of the Matrix account")?
+        .json()
+        .await
+        .wrap_err("Unable to parse the joined rooms of the Matrix account")?;
+    if rooms.joined_rooms.contains(&config.room_id) {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "The Matrix account has not joined {}",
+            config.room_id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_url_for_room() {
+        let config = MatrixConfig {
+            homeserver: "https://matrix.example.com/".to_string(),
+            access_token_env: "MATRIX_TOKEN".to_string(),
+            room_id: "!abc:example.com".to_string(),
+        };
+        assert_eq!(
+            message_url(&config, "1").unwrap().as_str(),
+            "https://matrix.example.com/_matrix/client/v3/rooms/!abc:example.com/send/m.room.message/1"
+        );
+    }
+}
@@ -0,0 +1,413 @@
+//! Generation of guideline compliant files for new projects.
+
+use crate::{config::Config, github, validate, NewProject, Scaffold};
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::{collections::HashSet, io::Write, path::Path};
+
+/// Files copied from the open source template repository when scaffolding the
+/// whole `.github` directory. Workflows are not included as they depend on
+/// the language of the project.
+const TEMPLATE_FILES: [&str; 3] = [
+    ".github/ISSUE_TEMPLATE/bug_report.md",
+    ".github/ISSUE_TEMPLATE/feature_request.md",
+    ".github/pull_request_template.md",
+];
+
+pub async fn run(command: Scaffold) -> eyre::Result<()> {
+    match command {
+        Scaffold::Codeowners {
+            maintainers,
+            dir,
+            all,
+            force,
+        } => {
+            let codeowners = codeowners(&maintainers)?;
+            let dir = match dir {
+                Some(dir) => dir,
+                None if all => return Err(eyre!("--all requires --dir to be given")),
+                None => {
+                    print!("{}", codeowners);
+                    return Ok(());
+                }
+            };
+
+            write_github_directory(&dir, codeowners, all, force).await
+        }
+    }
+}
+
+/// Write a CODEOWNERS file into the `.github` directory of a project, and if
+/// `all` is true the rest of the directory from the template.
+async fn write_github_directory(
+    dir: &Path,
+    codeowners: String,
+    all: bool,
+    force: bool,
+) -> eyre::Result<()> {
+    let mut files = vec![(".github/CODEOWNERS".to_string(), codeowners)];
+    if all {
+        for file in TEMPLATE_FILES.iter() {
+            let text =
+                github::download_repo_file("EmbarkStudios", "opensource-template", "main", file)
+                    .await?;
+            files.push((file.to_string(), text));
+        }
+    }
+    for (path, text) in files {
+        write_file(&dir.join(path), &text, force)?;
+    }
+    Ok(())
+}
+
+/// Gather the details of a new project, asking for any not given as flags,
+/// then generate its website data entry and `.github` directory and print the
+/// steps left to register it.
+pub async fn new_project(options: NewProject) -> eyre::Result<()> {
+    let NewProject {
+        name,
+        maintainers,
+        tags,
+        organisation,
+        dir,
+        open_pr,
+        force,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let organisation = config.organisation(organisation.as_deref())?;
+
+    let name = match name {
+        Some(name) => name,
+        None => ask("The name of the repo")?,
+    };
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(eyre!("`{}` is not a valid repo name", name));
+    }
+    let maintainers = if maintainers.is_empty() {
+        list(&ask(
+            "The GitHub handles of the maintainers, comma separated",
+        )?)
+    } else {
+        maintainers
+    };
+    let tags = match tags {
+        Some(tags) => tags,
+        None => list(&ask(
+            "The tags of the project on the website, comma separated",
+        )?),
+    };
+    let codeowners = codeowners(&maintainers)?;
+    let entry = website_entry(&organisation.name, &name, &maintainers, &tags);
+    println!(
+        "The website data entry of the project:\n{}\n",
+        serde_json::to_string_pretty(&entry)?
+    );
+
+    if let Some(dir) = &dir {
+        write_github_directory(dir, codeowners, true, force).await?;
+    }
+    let mut opened = false;
+    if open_pr {
+        let source = organisation
+            .website_data
+            .as_ref()
+            .ok_or_else(|| eyre!("Organisation `{}` has no website data", organisation.name))?;
+        let text = github::download_repo_file(
+            &organisation.name,
+            &source.repo,
+            &source.branch,
+            &source.path,
+        )
+        .await?;
+        let changes = github::FileChanges {
+            base: source.branch.clone(),
+            branch: format!("embark-oss/new-project/{}", name),
+            title: format!("Add {}", name),
+            body: format!(
+                "Adds {} to the website.\n\nOpened by `cargo run new-project`.",
+                name
+            ),
+            files: std::iter::once((
+                source.path.clone(),
+                validate::add_website_entry(&text, &entry)?,
+            ))
+            .collect(),
+        };
+        let client = github.client(&organisation.name)?;
+        if let Some(url) = client
+            .open_pull_request(&organisation.name, &source.repo, &changes)
+            .await?
+        {
+            println!("Opened {}\n", url);
+            opened = true;
+        }
+    }
+
+    println!("To register the project:");
+    let steps = registration_steps(
+        &organisation.name,
+        &name,
+        &maintainers,
+        &tags,
+        dir.is_some(),
+        opened,
+    );
+    for (number, step) in steps.iter().enumerate() {
+        println!("{}. {}", number + 1, step);
+    }
+    Ok(())
+}
+
+/// The entry of a new project in the website data. Maintainers given by
+/// email are left out of its owners, which are GitHub handles.
+pub fn website_entry(
+    organisation: &str,
+    name: &str,
+    maintainers: &[String],
+    tags: &[String],
+) -> Value {
+    let owners: Vec<_> = maintainers
+        .iter()
+        .map(|maintainer| maintainer.trim().trim_start_matches('@'))
+        .filter(|maintainer| !maintainer.contains('@'))
+        .unique()
+        .collect();
+    json!({
+        "name": name,
+        "repo": format!("https://github.com/{}/{}", organisation, name),
+        "tags": tags,
+        "owners": owners,
+    })
+}
+
+/// The steps left to register a new project, leaving out those this command
+/// performed.
+pub fn registration_steps(
+    organisation: &str,
+    name: &str,
+    maintainers: &[String],
+    tags: &[String],
+    scaffolded: bool,
+    opened_pr: bool,
+) -> Vec<String> {
+    let mut steps = vec![format!(
+        "Create the https://github.com/{}/{} repo, keeping it private until it is ready",
+        organisation, name
+    )];
+    steps.push(if scaffolded {
+        "Commit the generated .github directory".to_string()
+    } else {
+        format!(
+            "Add the .github directory with `cargo run scaffold codeowners --maintainers {} --dir PATH --all`",
+            maintainers.join(",")
+        )
+    });
+    steps.push(if opened_pr {
+        "Get the pull request adding the project to the website merged".to_string()
+    } else {
+        "Add the entry above to the website data, or run again with --open-pr".to_string()
+    });
+    steps.push(format!(
+        "Add the required topics with `cargo run fix topics {}`",
+        name
+    ));
+    if tags.iter().any(|tag| tag == "rust") {
+        steps.push(
+            "Add the project to the README of https://github.com/EmbarkStudios/rust-ecosystem"
+                .to_string(),
+        );
+    }
+    steps.push(format!(
+        "Run `cargo run preflight {}` and fix any problems before making the repo public",
+        name
+    ));
+    steps
+}
+
+/// Ask the user a question, returning their answer.
+fn ask(question: &str) -> eyre::Result<String> {
+    eprint!("{}: ", question);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    let _ = std::io::stdin()
+        .read_line(&mut answer)
+        .wrap_err("Unable to read the answer from stdin")?;
+    Ok(answer.trim().to_string())
+}
+
+/// The comma separated values of an answer.
+fn list(answer: &str) -> Vec<String> {
+    answer
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Generate a CODEOWNERS file making the given users the maintainers of the
+/// whole project. The file is parsed again before being returned to ensure
+/// it would pass validation.
+pub fn codeowners(maintainers: &[String]) -> eyre::Result<String> {
+    if maintainers.is_empty() {
+        return Err(eyre!("At least one maintainer is required"));
+    }
+    for maintainer in maintainers {
+        check_maintainer(maintainer)?;
+    }
+
+    let owners = maintainers
+        .iter()
+        .map(|maintainer| maintainer.trim().trim_start_matches('@'))
+        .map(|maintainer| {
+            if maintainer.contains('@') {
+                maintainer.to_string()
+            } else {
+                format!("@{}", maintainer)
+            }
+        })
+        .unique()
+        .join(" ");
+    let text = format!(
+        "# The maintainers of this project, see our maintenance guidelines:
+# https://github.com/EmbarkStudios/opensource/blob/main/content/maintenance-guidelines.md
+* {}
+",
+        owners
+    );
+
+    // Verify the generated file with the same parser used by validation
+    let parsed = github::CodeOwners::new(&text).wrap_err("Generated CODEOWNERS is invalid")?;
+    let parsed = parsed
+        .primary_maintainers()
+        .ok_or_else(|| eyre!("Generated CODEOWNERS has no primary maintainers"))?;
+    let expected: HashSet<_> = owners
+        .split(' ')
+        .map(|owner| owner.trim_start_matches('@'))
+        .collect();
+    let found: HashSet<_> = parsed
+        .handles
+        .iter()
+        .chain(&parsed.emails)
+        .map(String::as_str)
+        .collect();
+    if expected != found {
+        return Err(eyre!(
+            "Generated CODEOWNERS does not list the given maintainers"
+        ));
+    }
+
+    Ok(text)
+}
+
+/// Check that a maintainer is a GitHub handle, with or without the `@`, or
+/// an email address, so that no owner is written as a bare `@`.
+fn check_maintainer(maintainer: &str) -> eyre::Result<()> {
+    lazy_static! {
+        // GitHub usernames are alphanumeric with single hyphens between, of
+        // at most 39 characters, and teams are `org/team`
+        static ref HANDLE: Regex =
+            Regex::new(r"^@?[A-Za-z0-9](-?[A-Za-z0-9]){0,38}(/[A-Za-z0-9_.-]+)?$").unwrap();
+        static ref EMAIL: Regex = Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap();
+    }
+    let maintainer = maintainer.trim();
+    if HANDLE.is_match(maintainer) || EMAIL.is_match(maintainer) {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "`{}` is not a GitHub handle or an email address",
+            maintainer
+        ))
+    }
+}
+
+fn write_file(path: &Path, text: &str, force: bool) -> eyre::Result<()> {
+    if path.exists() && !force {
+        return Err(eyre!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        ));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Unable to create {}", parent.display()))?;
+    }
+    std::fs::write(path, text).wrap_err_with(|| format!("Unable to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_codeowners() {
+        let text = codeowners(&["lpil".to_string(), "@arirawr".to_string()]).unwrap();
+        assert!(text.ends_with("\n* @lpil @arirawr\n"));
+
+        let text = codeowners(&["lpil@example.com".to_string(), "lpil".to_string()]).unwrap();
+        assert!(text.ends_with("\n* lpil@example.com @lpil\n"));
+
+        let text = codeowners(&["EmbarkStudios/rust-ecosystem-team".to_string()]).unwrap();
+        assert!(text.ends_with("\n* @EmbarkStudios/rust-ecosystem-team\n"));
+
+        assert!(codeowners(&[]).is_err());
+        for maintainer in [
+            "not an owner",
+            "",
+            "@",
+            " @ ",
+            "-lpil",
+            "lpil-",
+            "a--b",
+            "@@lpil",
+        ] {
+            let error = codeowners(&[maintainer.to_string()]).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "`{}` is not a GitHub handle or an email address",
+                    maintainer.trim()
+                )
+            );
+        }
+        assert!(codeowners(&["lpil".to_string(), "@".to_string()]).is_err());
+        assert!(codeowners(&["lpil@".to_string()]).is_err());
+    }
+
+    #[test]
+    fn onboarding_projects() {
+        let maintainers = vec!["@lpil".to_string(), "lpil@example.com".to_string()];
+        let tags = vec!["rust".to_string()];
+        assert_eq!(
+            website_entry("EmbarkStudios", "puffin", &maintainers, &tags),
+            json!({
+                "name": "puffin",
+                "repo": "https://github.com/EmbarkStudios/puffin",
+                "tags": ["rust"],
+                "owners": ["lpil"],
+            })
+        );
+        assert_eq!(list(" rust, , gamedev "), vec!["rust", "gamedev"]);
+
+        let steps =
+            registration_steps("EmbarkStudios", "puffin", &maintainers, &tags, false, false);
+        assert_eq!(steps.len(), 6);
+        assert!(steps[1].contains("--maintainers @lpil,lpil@example.com --dir PATH --all"));
+        assert!(steps[4].contains("rust-ecosystem"));
+        let steps = registration_steps("EmbarkStudios", "puffin", &maintainers, &[], true, true);
+        assert_eq!(steps[1], "Commit the generated .github directory");
+        assert_eq!(
+            steps[2],
+            "Get the pull request adding the project to the website merged"
+        );
+        assert_eq!(steps.len(), 5);
+    }
+}
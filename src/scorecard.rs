@@ -0,0 +1,231 @@
+//! A single compliance score for all projects, for reporting on how well the
+//! organisations follow the guidelines over time.
+
+use crate::{
+    config::{self, Config},
+    validate::Report,
+    Scorecard,
+};
+use eyre::eyre;
+use std::{collections::BTreeMap, fmt::Write, str::FromStr};
+
+/// The tier name projects without a tier are reported under.
+const UNTIERED: &str = "untiered";
+
+/// The format a scorecard is printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "markdown" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            _ => Err(eyre!("Unknown scorecard format `{}`", s)),
+        }
+    }
+}
+
+/// How well a group of checks pass, weighted by severity and tier.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct Score {
+    /// The weighted percentage of checks that pass, or `None` if no checks
+    /// were counted.
+    pub score: Option<f64>,
+    /// How many checks were counted.
+    pub checks: usize,
+    /// How many of them failed.
+    pub failures: usize,
+    #[serde(skip)]
+    passed_weight: f64,
+    #[serde(skip)]
+    total_weight: f64,
+}
+
+impl Score {
+    fn add(&mut self, passed: bool, weight: f64) {
+        self.checks += 1;
+        self.total_weight += weight;
+        if passed {
+            self.passed_weight += weight;
+        } else {
+            self.failures += 1;
+        }
+        self.score = if self.total_weight > 0.0 {
+            Some(100.0 * self.passed_weight / self.total_weight)
+        } else {
+            None
+        };
+    }
+}
+
+/// The score of every project together, broken down by category and tier.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct Card {
+    pub overall: Score,
+    pub projects: usize,
+    /// How many projects pass every check.
+    pub conforming: usize,
+    pub categories: BTreeMap<String, Score>,
+    pub tiers: BTreeMap<String, Score>,
+}
+
+impl Card {
+    /// Score the results. Checks that were skipped, or that failed only
+    /// because GitHub could not be reached, are not counted. Exempted checks
+    /// and warnings count as passing.
+    pub fn new(report: &Report, config: &config::Scorecard) -> Self {
+        let mut card = Self::default();
+        for project in &report.projects {
+            card.projects += 1;
+            if project.ok {
+                card.conforming += 1;
+            }
+            let tier = project.tier.as_deref();
+            for check in &project.checks {
+                if check.skipped || check.prerequisite.is_some() || check.infrastructure {
+                    continue;
+                }
+                let weight = config.weight(&check.id, tier);
+                card.overall.add(check.ok, weight);
+                card.categories
+                    .entry(config.category(&check.id).to_string())
+                    .or_default()
+                    .add(check.ok, weight);
+                card.tiers
+                    .entry(tier.unwrap_or(UNTIERED).to_string())
+                    .or_default()
+                    .add(check.ok, weight);
+            }
+        }
+        card
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Open source scorecard\n\n");
+        let _ = writeln!(
+            markdown,
+            "**{}** across {} projects, of which {} pass every check.",
+            percentage(self.overall.score),
+            self.projects,
+            self.conforming
+        );
+        for (heading, scores) in [("Category", &self.categories), ("Tier", &self.tiers)] {
+            let _ = writeln!(
+                markdown,
+                "\n| {} | Score | Failures | Checks |\n| --- | ---: | ---: | ---: |",
+                heading
+            );
+            for (name, score) in scores {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} |",
+                    name,
+                    percentage(score.score),
+                    score.failures,
+                    score.checks
+                );
+            }
+        }
+        markdown
+    }
+}
+
+fn percentage(score: Option<f64>) -> String {
+    match score {
+        Some(score) => format!("{:.1}%", score),
+        None => "n/a".to_string(),
+    }
+}
+
+pub fn run(options: Scorecard) -> eyre::Result<()> {
+    let Scorecard {
+        results,
+        format,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    let report = Report::read(&results)?;
+    let card = Card::new(&report, &config.scorecard);
+    match format {
+        Format::Markdown => print!("{}", card.to_markdown()),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&card)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(id: &str, ok: bool) -> serde_json::Value {
+        json!({
+            "id": id,
+            "ok": ok,
+            "error": (!ok).then_some("Oh no"),
+            "infrastructure": false,
+            "duration_ms": 0,
+        })
+    }
+
+    #[test]
+    fn scoring_projects() {
+        let mut report = json!({
+            "projects": [
+                {
+                    "name": "kajiya",
+                    "ok": false,
+                    "maintainers": [],
+                    "tier": "flagship",
+                    "duration_ms": 0,
+                    "checks": [check("maintainers", true), check("required-files:license", false)],
+                },
+                {
+                    "name": "texture-synthesis",
+                    "ok": false,
+                    "maintainers": [],
+                    "duration_ms": 0,
+                    "checks": [check("maintainers", true), check("msrv", true), check("topics", false)],
+                },
+            ],
+            "partial": false,
+        });
+        report["projects"][1]["checks"][2]["infrastructure"] = json!(true);
+        let report = Report::parse(&report.to_string()).unwrap();
+        let config = Config::parse(
+            "[scorecard]\nseverities = { required-files = 2.0 }\ntier-weights = { flagship = 2.0 }",
+        )
+        .unwrap()
+        .scorecard;
+
+        let card = Card::new(&report, &config);
+        assert_eq!((card.projects, card.conforming), (2, 0));
+        // kajiya passes 2 of 6 and texture-synthesis 2 of 2
+        assert_eq!(card.overall.score, Some(50.0));
+        assert_eq!((card.overall.checks, card.overall.failures), (4, 1));
+        assert_eq!(card.categories["documentation"].score, Some(0.0));
+        assert_eq!(card.categories["ownership"].score, Some(100.0));
+        assert!(!card.categories.contains_key("discoverability"));
+        assert_eq!(card.tiers["flagship"].score, Some(100.0 * 2.0 / 6.0));
+        assert_eq!(card.tiers["untiered"].score, Some(100.0));
+
+        let markdown = card.to_markdown();
+        assert!(markdown.contains("**50.0%** across 2 projects, of which 0 pass every check."));
+        assert!(markdown.contains("| documentation | 0.0% | 1 | 1 |"));
+        assert!(markdown.contains("| flagship | 33.3% | 1 | 2 |"));
+
+        let json = serde_json::to_value(&card).unwrap();
+        assert_eq!(json["overall"]["score"], 50.0);
+        assert_eq!(json["tiers"]["untiered"]["checks"], 2);
+
+        let empty = Report::parse(r#"{ "projects": [], "partial": false }"#).unwrap();
+        let empty = Card::new(&empty, &config);
+        assert!(empty.to_markdown().contains("**n/a** across 0 projects"));
+    }
+}
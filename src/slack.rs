@@ -1,5 +1,7 @@
-use eyre::WrapErr;
+use crate::http::Traced;
+use eyre::{eyre, WrapErr};
 use serde_json::json;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Block {
@@ -8,7 +10,7 @@ pub enum Block {
 }
 
 impl Block {
-    pub fn into_json(self) -> serde_json::Value {
+    pub fn to_json(&self) -> serde_json::Value {
         match self {
             Self::Divider => json!({ "type": "divider" }),
             Self::Text(text) => json!({
@@ -19,21 +21,291 @@ impl Block {
     }
 }
 
-fn blocks_json(blocks: Vec<Block>) -> serde_json::Value {
-    let blocks = blocks.into_iter().map(|b| b.into_json()).collect();
-    json!({
+/// A legacy attachment shown below the blocks of a message, for text that is
+/// copied rather than read, which Slack collapses when it is long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub title: String,
+    /// The text, shown as a code block so that it is copied as is.
+    pub text: String,
+}
+
+impl Attachment {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "title": self.title,
+            "fallback": self.title,
+            "text": format!("```{}```", self.text),
+            "mrkdwn_in": ["text"],
+        })
+    }
+}
+
+/// The JSON payload sent to a webhook for the blocks and attachments.
+pub fn payload(blocks: &[Block], attachments: &[Attachment]) -> serde_json::Value {
+    let blocks = blocks.iter().map(Block::to_json).collect();
+    let mut payload = json!({
         "blocks": serde_json::Value::Array(blocks),
-    })
+    });
+    if !attachments.is_empty() {
+        payload["attachments"] = attachments.iter().map(Attachment::to_json).collect();
+    }
+    payload
+}
+
+/// How many times a message is sent before giving up, when Slack is rate
+/// limiting or failing.
+const ATTEMPTS: u32 = 3;
+
+/// Slack rejects messages with more blocks than this.
+const MAX_BLOCKS: usize = 50;
+
+/// Slack rejects section blocks with more characters of text than this.
+const MAX_TEXT_LENGTH: usize = 3000;
+
+/// Send the blocks and attachments to a webhook, retrying a few times if Slack is rate
+/// limiting or failing. Messages that Slack rejects fail with the error it
+/// gave and what to do about it.
+pub async fn send_webhook(
+    webhook_url: &str,
+    blocks: Vec<Block>,
+    attachments: Vec<Attachment>,
+) -> eyre::Result<()> {
+    let payload = payload(&blocks, &attachments);
+    if crate::dry_run::is_enabled() {
+        crate::dry_run::print_skipped("send Slack webhook", &payload);
+        return Ok(());
+    }
+
+    let mut attempt = 1;
+    loop {
+        let result = crate::http::client()
+            .post(webhook_url)
+            .json(&payload)
+            .send_traced()
+            .await;
+        let (delay, error) = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()?.parse().ok());
+                let body = response.text().await.unwrap_or_default();
+                let error = WebhookError {
+                    status: status.as_u16(),
+                    error: body.trim().to_string(),
+                    problems: block_problems(&blocks),
+                };
+                (retry_delay(status, retry_after, attempt), eyre!(error))
+            }
+            Err(error) => {
+                let delay = if error.is_connect() || error.is_timeout() {
+                    Some(backoff(attempt))
+                } else {
+                    None
+                };
+                (delay, eyre!(error))
+            }
+        };
+        match delay {
+            Some(delay) if attempt < ATTEMPTS => {
+                eprintln!(
+                    "Unable to send webhook to Slack, retrying in {}s: {}",
+                    delay.as_secs(),
+                    error
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            _ => return Err(error).wrap_err("Unable to send webhook to Slack"),
+        }
+    }
+}
+
+/// A message rejected by a Slack webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookError {
+    pub status: u16,
+    /// The error code Slack responded with, such as `invalid_blocks`.
+    pub error: String,
+    /// The blocks of the message that break Slack's limits.
+    pub problems: Vec<String>,
+}
+
+impl WebhookError {
+    /// What to do about the error, for the error codes Slack documents.
+    pub fn advice(&self) -> Option<&'static str> {
+        let advice = match self.error.as_str() {
+            "invalid_blocks" | "invalid_blocks_format" | "invalid_payload" => {
+                "The message is malformed or breaks Slack's limits on blocks"
+            }
+            "channel_not_found" | "channel_is_archived" => {
+                "The webhook's channel no longer exists or is archived, so the webhook must be \
+recreated for a new channel"
+            }
+            "no_service" | "no_service_id" | "no_team" | "team_disabled" | "invalid_token" => {
+                "The webhook has been disabled or removed, so a new webhook URL is needed"
+            }
+            "action_prohibited" | "posting_to_general_channel_denied" => {
+                "The webhook is not allowed to post to its channel, which an admin of the \
+channel can change"
+            }
+            "rate_limited" => "Slack is rate limiting the webhook, so send fewer messages",
+            _ => return None,
+        };
+        Some(advice)
+    }
+}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Slack responded with {}", self.status)?;
+        if !self.error.is_empty() {
+            write!(f, ": {}", self.error)?;
+        }
+        if let Some(advice) = self.advice() {
+            write!(f, "\n{}", advice)?;
+        }
+        for problem in &self.problems {
+            write!(f, "\n- {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+/// The ways the blocks break Slack's limits, which are the usual cause of
+/// `invalid_blocks` errors.
+pub fn block_problems(blocks: &[Block]) -> Vec<String> {
+    let mut problems = Vec::new();
+    if blocks.len() > MAX_BLOCKS {
+        problems.push(format!(
+            "The message has {} blocks, but Slack allows at most {}",
+            blocks.len(),
+            MAX_BLOCKS
+        ));
+    }
+    for (index, block) in blocks.iter().enumerate() {
+        match block {
+            Block::Text(text) if text.is_empty() => {
+                problems.push(format!("Block {} has no text", index + 1));
+            }
+            Block::Text(text) if text.chars().count() > MAX_TEXT_LENGTH => {
+                problems.push(format!(
+                    "Block {} has {} characters of text, but Slack allows at most {}",
+                    index + 1,
+                    text.chars().count(),
+                    MAX_TEXT_LENGTH
+                ));
+            }
+            _ => {}
+        }
+    }
+    problems
+}
+
+/// How long to wait before sending again after Slack responded with the
+/// status, or `None` if sending again won't help. Rate limited messages wait
+/// for as long as Slack asks.
+pub fn retry_delay(
+    status: reqwest::StatusCode,
+    retry_after: Option<u64>,
+    attempt: u32,
+) -> Option<Duration> {
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Some(retry_after.map_or_else(|| backoff(attempt), Duration::from_secs))
+    } else if status.is_server_error() {
+        Some(backoff(attempt))
+    } else {
+        None
+    }
 }
 
-pub async fn send_webhook(webhook_url: &str, blocks: Vec<Block>) -> eyre::Result<()> {
-    reqwest::Client::new()
+/// A delay doubling with each attempt, starting at one second.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1 << (attempt - 1).min(5))
+}
+
+/// Check that a webhook URL is valid without posting a message, by sending
+/// an empty payload which Slack rejects with `no_text` only for valid hooks.
+pub async fn check_webhook(webhook_url: &str) -> eyre::Result<()> {
+    let response = crate::http::client()
         .post(webhook_url)
-        .json(&blocks_json(blocks))
-        .send()
+        .json(&json!({}))
+        .send_traced()
         .await
-        .wrap_err("Unable to send webhook to Slack")?
-        .error_for_status()
-        .wrap_err("Unable to send webhook to Slack")
-        .map(|_| ())
+        .wrap_err("Unable to reach Slack")?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if body.trim() == "no_text" {
+        Ok(())
+    } else {
+        Err(eyre!("Slack responded with {}: {}", status, body.trim()))
+            .wrap_err("The webhook URL is not valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn webhook_errors() {
+        let blocks = vec![
+            Block::Text("Hello".to_string()),
+            Block::Divider,
+            Block::Text(String::new()),
+            Block::Text("a".repeat(3001)),
+        ];
+        let error = WebhookError {
+            status: 400,
+            error: "invalid_blocks".to_string(),
+            problems: block_problems(&blocks),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Slack responded with 400: invalid_blocks\n\
+The message is malformed or breaks Slack's limits on blocks\n\
+- Block 3 has no text\n\
+- Block 4 has 3001 characters of text, but Slack allows at most 3000"
+        );
+
+        let error = WebhookError {
+            status: 404,
+            error: "channel_not_found".to_string(),
+            problems: Vec::new(),
+        };
+        assert!(error.to_string().contains("must be recreated"));
+        let error = WebhookError {
+            status: 400,
+            error: "something_new".to_string(),
+            problems: Vec::new(),
+        };
+        assert_eq!(error.to_string(), "Slack responded with 400: something_new");
+
+        let blocks: Vec<_> = (0..51).map(|_| Block::Divider).collect();
+        assert_eq!(block_problems(&blocks).len(), 1);
+    }
+
+    #[test]
+    fn retry_delays() {
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, Some(30), 1),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, None, 2),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            retry_delay(StatusCode::BAD_GATEWAY, None, 3),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(retry_delay(StatusCode::BAD_REQUEST, Some(30), 1), None);
+        assert_eq!(retry_delay(StatusCode::NOT_FOUND, None, 1), None);
+    }
 }
@@ -0,0 +1,336 @@
+//! OpenTelemetry tracing of validation runs, with a span for each project,
+//! check and HTTP request, exported with OTLP over HTTP so that a tracing
+//! backend shows where a run spends its time and which requests fail.
+
+use crate::config::Telemetry;
+use eyre::{eyre, WrapErr};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{json, Value};
+use std::{
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The endpoint used when the config doesn't give one, as with other
+/// OpenTelemetry exporters.
+const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// How many spans are sent in each export request.
+const MAX_BATCH: usize = 512;
+
+static TRACER: OnceLock<Tracer> = OnceLock::new();
+
+tokio::task_local! {
+    /// The id of the span that spans started within the task are children of.
+    static CURRENT: String;
+}
+
+#[derive(Debug)]
+struct Tracer {
+    endpoint: String,
+    service_name: String,
+    headers: Vec<(String, String)>,
+    /// All spans of a run share one trace.
+    trace_id: String,
+    finished: Mutex<Vec<Value>>,
+}
+
+/// Start recording spans, if tracing is configured. Spans are otherwise
+/// ignored.
+pub fn init(config: Option<&Telemetry>) -> eyre::Result<()> {
+    let config = match config {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let endpoint = match &config.endpoint {
+        Some(endpoint) => endpoint.clone(),
+        None => std::env::var(ENDPOINT_ENV).map_err(|_| {
+            eyre!(
+                "No OTLP endpoint is configured and the {} environment variable is not set",
+                ENDPOINT_ENV
+            )
+        })?,
+    };
+    let headers = match &config.headers_env {
+        Some(name) => parse_headers(
+            &std::env::var(name)
+                .map_err(|_| eyre!("The {} environment variable is not set", name))?,
+        )?,
+        None => Vec::new(),
+    };
+    let tracer = Tracer {
+        endpoint: traces_url(&endpoint),
+        service_name: config.service_name.clone(),
+        headers,
+        trace_id: random_id(16),
+        finished: Mutex::new(Vec::new()),
+    };
+    TRACER
+        .set(tracer)
+        .map_err(|_| eyre!("Tracing has already been initialised"))
+}
+
+/// Send the spans recorded so far to the OTLP endpoint.
+pub async fn export() -> eyre::Result<()> {
+    let tracer = match TRACER.get() {
+        Some(tracer) => tracer,
+        None => return Ok(()),
+    };
+    let spans = std::mem::take(&mut *tracer.finished.lock().expect("Spans lock poisoned"));
+    if spans.is_empty() {
+        return Ok(());
+    }
+    if crate::dry_run::is_enabled() {
+        let export = json!({ "endpoint": tracer.endpoint, "spans": spans.len() });
+        crate::dry_run::print_skipped("export traces", &export);
+        return Ok(());
+    }
+    for batch in spans.chunks(MAX_BATCH) {
+        // The export itself is left out of the trace
+        let mut request = crate::http::client()
+            .post(&tracer.endpoint)
+            .json(&export_request(&tracer.service_name, batch));
+        for (name, value) in &tracer.headers {
+            request = request.header(name, value);
+        }
+        let _ = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .wrap_err_with(|| format!("Unable to export traces to {}", tracer.endpoint))?;
+    }
+    Ok(())
+}
+
+/// Run a future in a span, which is an error if `error` gives a message for
+/// its output.
+pub async fn in_span<F: std::future::Future>(
+    name: impl Into<String>,
+    attributes: Vec<(&'static str, Value)>,
+    future: F,
+    error: impl FnOnce(&F::Output) -> Option<String>,
+) -> F::Output {
+    match Span::start(name, SpanKind::Internal, attributes) {
+        Some(span) => {
+            let output = span.run(future).await;
+            let error = error(&output);
+            span.end(error);
+            output
+        }
+        None => future.await,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    /// A request to another service.
+    Client,
+}
+
+#[derive(Debug)]
+pub struct Span {
+    name: String,
+    kind: SpanKind,
+    id: String,
+    parent: Option<String>,
+    start: SystemTime,
+    attributes: Vec<(&'static str, Value)>,
+}
+
+impl Span {
+    /// Start a span that is a child of the current one, or `None` if tracing
+    /// isn't enabled.
+    pub fn start(
+        name: impl Into<String>,
+        kind: SpanKind,
+        attributes: Vec<(&'static str, Value)>,
+    ) -> Option<Self> {
+        let _ = TRACER.get()?;
+        Some(Self {
+            name: name.into(),
+            kind,
+            id: random_id(8),
+            parent: CURRENT.try_with(Clone::clone).ok(),
+            start: SystemTime::now(),
+            attributes,
+        })
+    }
+
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<Value>) {
+        self.attributes.push((key, value.into()));
+    }
+
+    /// Run a future with this as the current span, so that the spans it
+    /// starts are children of this one.
+    pub async fn run<F: std::future::Future>(&self, future: F) -> F::Output {
+        CURRENT.scope(self.id.clone(), future).await
+    }
+
+    /// End the span, recording it for the next export.
+    pub fn end(self, error: Option<String>) {
+        if let Some(tracer) = TRACER.get() {
+            let span = self.to_json(&tracer.trace_id, SystemTime::now(), error.as_deref());
+            tracer
+                .finished
+                .lock()
+                .expect("Spans lock poisoned")
+                .push(span);
+        }
+    }
+
+    /// The span in the OTLP JSON encoding.
+    // https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+    fn to_json(&self, trace_id: &str, end: SystemTime, error: Option<&str>) -> Value {
+        let status = match error {
+            Some(message) => json!({ "code": 2, "message": message }),
+            None => json!({ "code": 1 }),
+        };
+        json!({
+            "traceId": trace_id,
+            "spanId": self.id,
+            "parentSpanId": self.parent.clone().unwrap_or_default(),
+            "name": self.name,
+            "kind": match self.kind {
+                SpanKind::Internal => 1,
+                SpanKind::Client => 3,
+            },
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(end),
+            "attributes": attributes(&self.attributes),
+            "status": status,
+        })
+    }
+}
+
+fn export_request(service_name: &str, spans: &[Value]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": attributes(&[("service.name", service_name.into())]),
+            },
+            "scopeSpans": [{
+                "scope": { "name": "embark-oss", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn attributes(attributes: &[(&str, Value)]) -> Value {
+    attributes
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::Number(number) if number.is_i64() => json!({ "intValue": number }),
+                Value::Bool(boolean) => json!({ "boolValue": boolean }),
+                Value::String(string) => json!({ "stringValue": string }),
+                other => json!({ "stringValue": other.to_string() }),
+            };
+            json!({ "key": key, "value": value })
+        })
+        .collect()
+}
+
+/// Timestamps are strings, as JSON numbers can't hold every 64 bit integer.
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// Endpoints are the base URL of the collector, as with
+/// `OTEL_EXPORTER_OTLP_ENDPOINT`, or the URL of its traces path.
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+/// Headers given as `name=value` pairs separated by commas, as in
+/// `OTEL_EXPORTER_OTLP_HEADERS`.
+fn parse_headers(headers: &str) -> eyre::Result<Vec<(String, String)>> {
+    headers
+        .split(',')
+        .filter(|header| !header.trim().is_empty())
+        .map(|header| match header.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.trim().to_string())),
+            None => Err(eyre!("Invalid header `{}`, expected `name=value`", header)),
+        })
+        .collect()
+}
+
+/// A random id of `bytes` bytes, in hex.
+fn random_id(bytes: usize) -> String {
+    let mut id = vec![0; bytes];
+    SystemRandom::new()
+        .fill(&mut id)
+        .expect("Unable to generate a random id");
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn encoding_spans() {
+        let span = Span {
+            name: "GET api.github.com".to_string(),
+            kind: SpanKind::Client,
+            id: "00f067aa0ba902b7".to_string(),
+            parent: Some("53995c3f42cd8ad8".to_string()),
+            start: UNIX_EPOCH + Duration::from_secs(1),
+            attributes: vec![
+                ("http.request.method", "GET".into()),
+                ("http.response.status_code", 502.into()),
+            ],
+        };
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let end = UNIX_EPOCH + Duration::from_millis(1500);
+        assert_eq!(
+            span.to_json(trace_id, end, Some("502 Bad Gateway")),
+            json!({
+                "traceId": trace_id,
+                "spanId": "00f067aa0ba902b7",
+                "parentSpanId": "53995c3f42cd8ad8",
+                "name": "GET api.github.com",
+                "kind": 3,
+                "startTimeUnixNano": "1000000000",
+                "endTimeUnixNano": "1500000000",
+                "attributes": [
+                    { "key": "http.request.method", "value": { "stringValue": "GET" } },
+                    { "key": "http.response.status_code", "value": { "intValue": 502 } },
+                ],
+                "status": { "code": 2, "message": "502 Bad Gateway" },
+            })
+        );
+        assert_eq!(random_id(8).len(), 16);
+    }
+
+    #[test]
+    fn exporter_settings() {
+        assert_eq!(
+            traces_url("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("https://otel.example.com/v1/traces"),
+            "https://otel.example.com/v1/traces"
+        );
+        assert_eq!(
+            parse_headers("x-honeycomb-team=abc, x-dataset = oss").unwrap(),
+            vec![
+                ("x-honeycomb-team".to_string(), "abc".to_string()),
+                ("x-dataset".to_string(), "oss".to_string()),
+            ]
+        );
+        assert!(parse_headers("").unwrap().is_empty());
+        assert!(parse_headers("token").is_err());
+    }
+}
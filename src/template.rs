@@ -0,0 +1,379 @@
+//! Templates for the wording of messages, with `{name}` placeholders that are
+//! replaced by values when the message is rendered. `{{` and `}}` are literal
+//! braces. `{% if name %}…{% else %}…{% endif %}` renders a part only if a
+//! value isn't empty, and `{% for line in name %}…{% endfor %}` renders a part
+//! for each line of a value, such as each error of a project. Templates are
+//! parsed when the config is loaded so that mistakes are reported before
+//! anything is sent.
+
+use eyre::eyre;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Placeholder(String),
+    If {
+        name: String,
+        then: Vec<Self>,
+        otherwise: Vec<Self>,
+    },
+    For {
+        item: String,
+        name: String,
+        body: Vec<Self>,
+    },
+}
+
+/// A part of a template as it is written, before tags are matched up.
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Placeholder(String),
+    Tag(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn tokenize(source: &str) -> eyre::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                let _ = chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                let _ = chars.next();
+                text.push('}');
+            }
+            '{' if chars.peek() == Some(&'%') => {
+                let _ = chars.next();
+                let mut tag = String::new();
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    if c == '%' && chars.peek() == Some(&'}') {
+                        let _ = chars.next();
+                        closed = true;
+                        break;
+                    }
+                    tag.push(c);
+                }
+                if !closed {
+                    return Err(eyre!("`{{%{}` is not closed by `%}}`", tag));
+                }
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(Token::Tag(
+                    tag.split_whitespace().map(ToString::to_string).collect(),
+                ));
+            }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(c);
+                }
+                if !closed || !is_name(&name) {
+                    return Err(eyre!("`{{{}` is not a placeholder like `{{name}}`", name));
+                }
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                tokens.push(Token::Placeholder(name));
+            }
+            '}' => return Err(eyre!("Unmatched `}}`, which is written `}}}}`")),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+/// Build the nodes up to the tag in `end` that closes the `opener` tag, which
+/// is returned, or to the end of the template if `end` is empty.
+fn nodes(
+    tokens: &mut std::vec::IntoIter<Token>,
+    opener: &str,
+    end: &[&str],
+) -> eyre::Result<(Vec<Node>, String)> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Placeholder(name) => {
+                nodes.push(Node::Placeholder(name));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        let words: Vec<_> = tag.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            [word] if end.contains(word) => return Ok((nodes, word.to_string())),
+            ["if", name] if is_name(name) => {
+                let (then, ended) = self::nodes(tokens, "if", &["else", "endif"])?;
+                let otherwise = if ended == "else" {
+                    self::nodes(tokens, "if", &["endif"])?.0
+                } else {
+                    Vec::new()
+                };
+                nodes.push(Node::If {
+                    name: name.to_string(),
+                    then,
+                    otherwise,
+                });
+            }
+            ["for", item, "in", name] if is_name(item) && is_name(name) => {
+                let (body, _) = self::nodes(tokens, "for", &["endfor"])?;
+                nodes.push(Node::For {
+                    item: item.to_string(),
+                    name: name.to_string(),
+                    body,
+                });
+            }
+            [word @ ("else" | "endif" | "endfor")] => {
+                return Err(eyre!("`{{% {} %}}` has nothing to close", word))
+            }
+            _ => {
+                return Err(eyre!(
+                    "`{{% {} %}}` is not a tag like `{{% if name %}}` or \
+`{{% for line in name %}}`",
+                    tag.join(" ")
+                ))
+            }
+        }
+    }
+    match end.last() {
+        Some(end) => Err(eyre!(
+            "`{{% {} %}}` is not closed by `{{% {} %}}`",
+            opener,
+            end
+        )),
+        None => Ok((nodes, String::new())),
+    }
+}
+
+/// Collect the names of the values the nodes use, leaving out the items of
+/// the loops they are in.
+fn collect_placeholders<'a>(nodes: &'a [Node], items: &mut Vec<&'a str>, out: &mut Vec<&'a str>) {
+    fn add<'a>(name: &'a str, items: &[&str], out: &mut Vec<&'a str>) {
+        if !items.contains(&name) {
+            out.push(name);
+        }
+    }
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Placeholder(name) => add(name, items, out),
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => {
+                add(name, items, out);
+                collect_placeholders(then, items, out);
+                collect_placeholders(otherwise, items, out);
+            }
+            Node::For { item, name, body } => {
+                add(name, items, out);
+                items.push(item);
+                collect_placeholders(body, items, out);
+                let _ = items.pop();
+            }
+        }
+    }
+}
+
+/// The value of a placeholder. Placeholders without a value are empty, which
+/// `Template::check` prevents.
+fn value<'a>(values: &[(&str, &'a str)], name: &str) -> &'a str {
+    values
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map_or("", |(_, value)| value)
+}
+
+fn render_nodes(nodes: &[Node], values: &[(&str, &str)], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Placeholder(name) => out.push_str(value(values, name)),
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => {
+                if value(values, name).is_empty() {
+                    render_nodes(otherwise, values, out);
+                } else {
+                    render_nodes(then, values, out);
+                }
+            }
+            Node::For { item, name, body } => {
+                for line in value(values, name).lines() {
+                    // The item hides any value of the same name
+                    let values: Vec<_> = std::iter::once((item.as_str(), line))
+                        .chain(values.iter().copied())
+                        .collect();
+                    render_nodes(body, &values, out);
+                }
+            }
+        }
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> eyre::Result<Self> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, _) = nodes(&mut tokens, "", &[])?;
+        Ok(Self { nodes })
+    }
+
+    /// Check that the template only uses the given placeholders.
+    pub fn check(&self, placeholders: &[&str]) -> eyre::Result<()> {
+        match self
+            .placeholders()
+            .find(|name| !placeholders.contains(name))
+        {
+            Some(name) if placeholders.is_empty() => Err(eyre!(
+                "Unknown placeholder `{{{}}}`, none are allowed",
+                name
+            )),
+            Some(name) => Err(eyre!(
+                "Unknown placeholder `{{{}}}`, expected one of {}",
+                name,
+                placeholders
+                    .iter()
+                    .map(|name| format!("`{{{}}}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The names of the values the template uses, in placeholders, conditions
+    /// and loops.
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        let mut placeholders = Vec::new();
+        collect_placeholders(&self.nodes, &mut Vec::new(), &mut placeholders);
+        placeholders.into_iter()
+    }
+
+    /// Render the template, replacing each placeholder with its value.
+    /// Placeholders without a value are left empty, which `check` prevents.
+    pub fn render(&self, values: &[(&str, &str)]) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, values, &mut out);
+        out
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = eyre::Report;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::parse(&source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering_templates() {
+        let template = Template::parse("{emoji} *<{url}|{title}>* {{literal}}").unwrap();
+        assert_eq!(
+            template.render(&[
+                ("emoji", ":tada:"),
+                ("url", "https://ash"),
+                ("title", "ash")
+            ]),
+            ":tada: *<https://ash|ash>* {literal}"
+        );
+        assert!(template.check(&["emoji", "url", "title"]).is_ok());
+        assert_eq!(
+            template.check(&["emoji", "url"]).unwrap_err().to_string(),
+            "Unknown placeholder `{title}`, expected one of `{emoji}`, `{url}`"
+        );
+        assert_eq!(Template::parse("").unwrap().render(&[]), "");
+
+        assert!(Template::parse("{unclosed").is_err());
+        assert!(Template::parse("{}").is_err());
+        assert!(Template::parse("{with spaces}").is_err());
+        assert!(Template::parse("stray }").is_err());
+    }
+
+    #[test]
+    fn conditions_and_loops() {
+        let template = Template::parse(
+            "{title}{% if errors %}:{% for error in errors %}\n- {error} in {title}{% endfor %}\
+{% else %} passes{% endif %}",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&[("title", "ash"), ("errors", "No LICENSE\nNo README")]),
+            "ash:\n- No LICENSE in ash\n- No README in ash"
+        );
+        assert_eq!(
+            template.render(&[("title", "ash"), ("errors", "")]),
+            "ash passes"
+        );
+        assert!(template.check(&["title", "errors"]).is_ok());
+        assert_eq!(
+            template.check(&["title"]).unwrap_err().to_string(),
+            "Unknown placeholder `{errors}`, expected one of `{title}`"
+        );
+        assert_eq!(
+            Template::parse("{% for line in lines %}{line}{% endfor %}{line}")
+                .unwrap()
+                .placeholders()
+                .collect::<Vec<_>>(),
+            vec!["lines", "line"]
+        );
+
+        let error = |source| Template::parse(source).unwrap_err().to_string();
+        assert_eq!(
+            error("{% if errors %}failing"),
+            "`{% if %}` is not closed by `{% endif %}`"
+        );
+        assert_eq!(
+            error("{% for error in errors %}{error}"),
+            "`{% for %}` is not closed by `{% endfor %}`"
+        );
+        assert_eq!(error("{% endif %}"), "`{% endif %}` has nothing to close");
+        assert_eq!(
+            error("{% if errors %}{% endfor %}"),
+            "`{% endfor %}` has nothing to close"
+        );
+        assert_eq!(
+            error("{% while errors %}"),
+            "`{% while errors %}` is not a tag like `{% if name %}` or `{% for line in name %}`"
+        );
+        assert_eq!(
+            error("{% if errors"),
+            "`{% if errors` is not closed by `%}`"
+        );
+    }
+}
@@ -1,108 +1,771 @@
+mod archive;
+mod baseline;
+mod cache;
+mod check_list;
+mod check_runs;
+mod checks;
 mod context;
+mod exemptions;
+mod feed;
+mod fix;
+mod history;
+mod incremental;
+mod inspect;
+mod lint;
+mod notify;
+mod preflight;
 mod project;
+mod provenance;
+mod publish;
+mod registry;
+mod repo_context;
+mod report;
+mod sarif;
+#[cfg(test)]
+mod snapshots;
+mod sync_templates;
 
 #[cfg(test)]
 mod tests;
+mod website_pr;
+
+pub use self::{
+    archive::archive,
+    check_list::list_checks,
+    fix::{add_website_entry, fix},
+    history::{History, Metrics},
+    inspect::inspect,
+    lint::lint_config,
+    notify::preview_slack,
+    preflight::preflight,
+    publish::Destination,
+    report::{Format, Report, SCHEMA},
+    sync_templates::sync_templates,
+    website_pr::{review_pr, website_pr},
+};
 
-use self::{context::*, project::Project};
-use crate::{policy::IGNORED_PROJECTS, slack, ValidateAll};
-use eyre::eyre;
+use self::{
+    baseline::Baseline, context::*, incremental::Scope, notify::Notification, project::Project,
+};
+use crate::{
+    alerting,
+    config::{Config, Schedule},
+    github, locale,
+    policy::IGNORED_PROJECTS,
+    telemetry, Output, Validate, ValidateAll,
+};
+use eyre::{eyre, WrapErr};
+use futures::{stream, StreamExt};
 use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    io::Read,
+    path::Path,
+};
 
 /// Validate all projects listed in the data.json of the Embark Open Source
-/// website.
+/// website, and those of any other organisations in the config.
 pub(crate) async fn all(options: ValidateAll) -> eyre::Result<()> {
+    let config = Config::load(options.config.as_deref())?;
+    crate::http::init(&config.http)?;
+    telemetry::init(config.telemetry.as_ref())?;
+    let result = telemetry::in_span(
+        "validate-all",
+        Vec::new(),
+        run_all(options, config),
+        |result| result.as_ref().err().map(ToString::to_string),
+    )
+    .await;
+    // Traces are most useful for failed runs, so are exported regardless
+    if let Err(error) = telemetry::export().await {
+        eprintln!("{:?}", error);
+    }
+    result
+}
+
+async fn run_all(options: ValidateAll, config: Config) -> eyre::Result<()> {
     let ValidateAll {
         slack_webhook_url,
-        github_api_token,
+        github,
+        publish_results,
+        notify_changes_only,
+        incremental,
+        projects_file,
+        max_failures,
+        allow_failing,
+        baseline,
+        write_baseline,
+        atom_feed,
+        heartbeat_url,
+        config: _,
+        output,
     } = options;
+    let names = match &projects_file {
+        Some(path) => Some(read_project_names(path)?),
+        None => None,
+    };
+    let baseline = match &baseline {
+        Some(path) => Some(Baseline::read(path)?),
+        None => None,
+    };
 
-    // Lookup required contextual information
-    let context = Context::get(github_api_token).await?;
+    // Lookup required contextual information for each organisation
+    let contexts =
+        futures::future::try_join_all(config.organisations().into_iter().map(|organisation| {
+            let client = github.client(&organisation.name);
+            let config = config.clone();
+            async move { Context::get(client?, config, organisation).await }
+        }))
+        .await?;
+
+    // Settings and published results are shared by all organisations, so
+    // they use the first organisation's context
+    let context = &contexts[0];
+    let client = &context.client;
+    print_auth_guidance(client, &context.config);
+    if context.config.check_runs.is_some() && !client.is_app() {
+        return Err(eyre!(
+            "Check runs can only be published when authenticating as a GitHub App with \
+--github-app-id"
+        ));
+    }
+
+    // Fetch the results of the previous run so this run can be compared to it
+    let previous_report = match &publish_results {
+        Some(destination) => destination
+            .download(client)
+            .await
+            .wrap_err("Unable to download previous results")?,
+        None => None,
+    };
+    let mut history = match &publish_results {
+        Some(destination) => destination.download_history(client).await?,
+        None if context.config.notifications.schedule == Schedule::Digest => {
+            return Err(eyre!(
+                "Digest notifications require --publish-results to keep the history of runs"
+            ))
+        }
+        None if context.config.escalation.is_some() => {
+            return Err(eyre!(
+                "Escalation requires --publish-results to keep the history of runs"
+            ))
+        }
+        None => History::default(),
+    };
+
+    // Results of projects that haven't changed are reused if configured
+    let cache = match (&context.config.result_cache, &publish_results) {
+        (Some(config), Some(destination)) => {
+            Some((destination.download_cache(client).await?, config))
+        }
+        (Some(_), None) => {
+            return Err(eyre!(
+                "The result cache requires --publish-results to store the results"
+            ))
+        }
+        (None, _) => None,
+    };
+    let now = history::now();
+    let recorder = provenance::Recorder::start(&context.config, client, now).await;
+
+    // Only validate the projects with activity since the last run if
+    // requested, reusing the previous results of the others
+    let scope = match &publish_results {
+        Some(_) if incremental => {
+            let scope =
+                incremental::scope(&contexts, &history, previous_report.as_ref(), now).await?;
+            if let Scope::Full(reason) = &scope {
+                eprintln!("Validating every project as {}", reason);
+            }
+            scope
+        }
+        None if incremental => {
+            return Err(eyre!(
+                "Incremental validation requires --publish-results to store the results"
+            ))
+        }
+        _ => Scope::Full("incremental validation was not requested"),
+    };
 
     // Download list of maintained projects and then validate each one
-    let futures = context
-        .all_projects()
+    let futures = projects_to_validate(&contexts, names.as_deref())?
         .into_iter()
-        .filter(|project| !IGNORED_PROJECTS.contains(&project.as_str()))
-        .map(Project::new)
-        .map(|project| project.validate(&context));
-    let projects = futures::future::join_all(futures).await;
+        .map(|(context, name)| {
+            let cache = cache.as_ref().map(|(cache, config)| (cache, *config));
+            let unchanged = match &scope {
+                Scope::Changed(changed) if !changed.contains(project::repo_of(&name)) => {
+                    previous_report
+                        .iter()
+                        .flat_map(|report| &report.projects)
+                        .find(|project| project.name == name)
+                        // Projects that could not be validated are tried again
+                        .filter(|project| !project.has_infrastructure_failures())
+                }
+                _ => None,
+            };
+            async move {
+                match unchanged {
+                    Some(project) => {
+                        let stamp = cache
+                            .and_then(|(cache, _)| cache.entries.get(&project.name))
+                            .map(|entry| entry.stamp.clone());
+                        (Project::from_report(project, context), stamp)
+                    }
+                    None => cache::validate(name, context, &context.client, cache, now).await,
+                }
+            }
+        });
+    let (mut validated, mut remaining) = validate_until_interrupted(futures).await;
+
+    // Projects that failed only because GitHub could not be reached are
+    // validated once more, as such errors are often transient
+    if remaining == 0 {
+        let (retry, done): (Vec<_>, Vec<_>) = validated
+            .into_iter()
+            .partition(|(project, _)| project.could_not_validate());
+        validated = done;
+        if !retry.is_empty() {
+            eprintln!(
+                "Validating {} projects again after infrastructure errors",
+                retry.len()
+            );
+            let cache = cache.as_ref().map(|(cache, config)| (cache, *config));
+            let futures = retry.into_iter().map(|(project, _)| {
+                let context = context_of(&contexts, &project.organisation);
+                cache::validate(project.name, context, &context.client, cache, now)
+            });
+            let (retried, interrupted) = validate_until_interrupted(futures).await;
+            validated.extend(retried);
+            remaining = interrupted;
+        }
+    }
+    validated.sort_by(|(a, _), (b, _)| (&a.organisation, &a.name).cmp(&(&b.organisation, &b.name)));
+    let (projects, stamps): (Vec<_>, Vec<_>) = validated.into_iter().unzip();
 
     // Print results
-    projects.iter().for_each(print_status);
+    let mut report = Report::new(&projects);
+    report.partial = remaining > 0;
+    report.run = Some(recorder.finish(client).await);
+    report.expiring_exemptions =
+        exemptions::expiring(&report, now, context.config.expiring_exemptions.within_days);
+    if let Some(baseline) = &baseline {
+        baseline.mark_known(&mut report);
+    }
+    print_results(&projects, &report, &output)?;
 
-    // Collected the projects with issues
-    let problem_projects: Vec<_> = projects
-        .into_iter()
-        .filter(|project| project.has_errors())
+    // Results of an interrupted run are incomplete so they are not published
+    // or notified about
+    if remaining > 0 {
+        return Err(eyre!(
+            "Validation was interrupted with {} projects remaining",
+            remaining
+        ));
+    }
+
+    // The current violations can be accepted as known for future runs
+    if let Some(path) = &write_baseline {
+        Baseline::new(&report).write(path)?;
+    }
+    if let Some(path) = &atom_feed {
+        feed::append(path, &report, now)?;
+    }
+    // Reports that can't be uploaded don't stop the notifications
+    if let Some(upload) = &context.config.result_upload {
+        if let Err(error) = publish::upload_reports(upload, &report).await {
+            eprintln!("Unable to upload the reports: {:?}", error);
+        }
+    }
+
+    // Persist the results for the next run. Results that can't be published
+    // don't stop the notifications, but fail the run once it is done
+    let mut errors = Vec::new();
+    if let Some(destination) = &publish_results {
+        let published = async {
+            destination.upload(client, &report).await?;
+            if cache.is_none() {
+                return Ok(());
+            }
+            let entries = report
+                .projects
+                .iter()
+                .zip(stamps)
+                .filter_map(|(project, stamp)| {
+                    let entry = cache::Entry {
+                        stamp: stamp?,
+                        project: project.clone(),
+                    };
+                    Some((project.name.clone(), entry))
+                })
+                .collect();
+            destination
+                .upload_cache(client, &cache::Cache { entries })
+                .await
+        };
+        if let Err(error) = published.await {
+            eprintln!("Unable to publish the results: {:?}", error);
+            errors.push(error);
+        }
+    }
+
+    // Maintainers see the results of their projects on their own repos. A
+    // repo that can't be published to doesn't stop the others
+    if let Some(config) = &context.config.check_runs {
+        for project in &report.projects {
+            let context = context_of(&contexts, &project.organisation);
+            if let Err(error) = check_runs::publish(config, context, project).await {
+                eprintln!(
+                    "Unable to publish the check run of {}: {:?}",
+                    project.name, error
+                );
+            }
+        }
+    }
+
+    // Collected the projects with issues. Those that only failed because
+    // GitHub could not be reached are not notified about as they may well
+    // conform to our guidelines.
+    let all_ok = report.projects.iter().all(|project| project.ok);
+    let problem_projects: Vec<_> = report
+        .projects
+        .iter()
+        .filter(|project| project.has_policy_failures())
         .collect();
+    let unvalidated = Notification::unvalidated(
+        report
+            .projects
+            .iter()
+            .filter(|project| project.could_not_validate()),
+    )
+    .with_run(report.run.as_ref());
+
+    // Adoption metrics are only worth collecting if the history is kept
+    let mut run = history::Run::new(now, &report);
+    if publish_results.is_some() {
+        run.metrics = collect_metrics(&contexts, &report).await;
+    }
+    let notification = match context.config.notifications.schedule {
+        // Only notify about new failures if requested and there is a
+        // previous run
+        Schedule::Immediate => match &previous_report {
+            Some(previous) if notify_changes_only => {
+                let changed = report.projects_with_new_failures(previous);
+                Some(Notification::immediate(
+                    problem_projects
+                        .into_iter()
+                        .filter(|project| changed.contains(project.name.as_str())),
+                ))
+            }
+            _ => Some(Notification::immediate(problem_projects)),
+        },
+
+        // Failures are only notified about once per digest interval
+        Schedule::Digest => {
+            if history.digest_due(run.time, context.config.notifications.digest_interval()) {
+                let notification = Notification::digest(problem_projects, &history);
+                history.last_digest = Some(run.clone());
+                Some(notification)
+            } else {
+                None
+            }
+        }
+    };
+    let notification = notification.map(|notification| notification.with_run(report.run.as_ref()));
+    let notifying = notification.is_some();
+    history.record(run);
+    if let Scope::Full(_) = scope {
+        history.last_full_validation = Some(now);
+    }
+
+    if let Some(notification) = notification {
+        // Digests are posted in full, before escalated projects are split off.
+        // A digest that can't be posted is still sent to the other destinations
+        if let (notify::Kind::Digest, Some(config)) =
+            (notification.kind, &context.config.digest_post)
+        {
+            if let Err(error) =
+                notify::post_digest(&notification, config, &context.client, now).await
+            {
+                eprintln!("Unable to post the digest to {}: {:?}", config.repo, error);
+            }
+        }
+
+        // Projects that keep failing the same check are escalated instead
+        let (notification, escalation) = match &context.config.escalation {
+            Some(escalation) => {
+                let (notification, escalation) = notification.split_escalated(&history, escalation);
+                (notification, Some(escalation))
+            }
+            None => (notification, None),
+        };
+        let slack_webhook_url = slack_webhook_url.as_deref();
+        if !notification.is_empty() {
+            notify::send(&notification, &contexts, slack_webhook_url).await?;
+        }
+        if let Some(escalation) = escalation.filter(|escalation| !escalation.is_empty()) {
+            notify::send_escalation(&escalation, &contexts, slack_webhook_url).await?;
+        }
+        if !unvalidated.is_empty() {
+            notify::send(&unvalidated, &contexts, slack_webhook_url).await?;
+        }
+    }
+
+    // Exemptions that expire soon are notified about on the same schedule, so
+    // that the violations they cover are fixed before they fail the run
+    if notifying && !report.expiring_exemptions.is_empty() {
+        if let Err(error) =
+            notify::send_expiring_exemptions(&report, &context.config, slack_webhook_url.as_deref())
+                .await
+        {
+            eprintln!("Unable to send the expiring exemptions: {:?}", error);
+        }
+    }
+
+    // Celebrate projects that have started to conform to our guidelines.
+    // Without previous results every project would look new. A celebration
+    // that can't be sent isn't worth failing the run over
+    if let (Some(config), Some(previous)) = (&context.config.celebrations, &previous_report) {
+        let celebration = Notification::celebration(&report, previous);
+        if !celebration.is_empty() {
+            if let Err(error) =
+                notify::send_celebration(&celebration, config, &context.config).await
+            {
+                eprintln!("Unable to send the celebration: {:?}", error);
+            }
+        }
+    }
+
+    // Open incidents for checks that have started failing and resolve those
+    // that pass again. An incident that can't be opened or resolved doesn't
+    // stop the others
+    if let Some(config) = &context.config.alerting {
+        let opened = report.new_alerts(previous_report.as_ref());
+        let resolved = match &previous_report {
+            Some(previous) => report.cleared_failures(previous),
+            None => Vec::new(),
+        };
+        for (project, check) in opened {
+            let error = report.error(project, check).unwrap_or_default();
+            if let Err(error) = alerting::trigger(config, project, check, error).await {
+                eprintln!("{:?}", error);
+                errors.push(error);
+            }
+        }
+        for (project, check) in resolved {
+            if let Err(error) = alerting::resolve(config, project, check).await {
+                eprintln!("{:?}", error);
+                errors.push(error);
+            }
+        }
+    }
+
+    // The history is published after notifying so that a digest which could
+    // not be sent is retried by the next run
+    if let Some(destination) = &publish_results {
+        destination.upload_history(client, &history).await?;
+    }
+
+    // A run whose results could not be published or alerted about hasn't
+    // completed
+    crate::error::combine(errors, |count| {
+        format!("The run ended with {} errors", count)
+    })?;
+
+    // The run has completed whether or not the projects conform, as their
+    // failures have been notified about
+    if let Some(url) = &heartbeat_url {
+        if let Err(error) = alerting::heartbeat(url).await {
+            eprintln!("{:?}", error);
+        }
+    }
 
     // If there is no problem we are done and can return
-    if problem_projects.is_empty() {
+    if all_ok {
         return Ok(());
     }
 
-    // Send a message to slack if a webhook URL has been given
-    if let Some(url) = slack_webhook_url {
-        let blocks = slack_notification_blocks(problem_projects.as_slice());
-        slack::send_webhook(&url, blocks).await?;
+    // Known failures may be tolerated so that the run only fails for new ones
+    let failing = failures_over_budget(&report, max_failures, &allow_failing);
+    if failing.is_empty() {
+        eprintln!("Not all projects conform to our guidelines, but the failures are tolerated");
+        return Ok(());
     }
+    Err(eyre!(
+        "Not all projects conform to our guidelines: {}",
+        failing.join(", ")
+    ))
+}
 
-    Err(eyre!("Not all projects conform to our guidelines"))
+/// The failing projects that fail the run, which is none if at most
+/// `max_failures` projects fail. Projects in `allow_failing`, and those whose
+/// failures are all known from the baseline, never count.
+fn failures_over_budget<'a>(
+    report: &'a Report,
+    max_failures: usize,
+    allow_failing: &[String],
+) -> Vec<&'a str> {
+    let failing: Vec<_> = report
+        .projects
+        .iter()
+        .filter(|project| project.checks.iter().any(|check| !check.ok && !check.known))
+        .map(|project| project.name.as_str())
+        .filter(|name| !allow_failing.iter().any(|allowed| allowed == name))
+        .collect();
+    if failing.len() > max_failures {
+        failing
+    } else {
+        Vec::new()
+    }
 }
 
 /// Validate a single project from the Embark Studios GitHub organisation.
-pub async fn one(project_name: String) -> eyre::Result<()> {
+pub async fn one(options: Validate) -> eyre::Result<()> {
+    let Validate {
+        name,
+        github,
+        config,
+        output,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let (organisation, name) = config.project_organisation(&name);
+    let client = github.client(&organisation.name)?;
+    print_auth_guidance(&client, &config);
+
     // Lookup required contextual information
-    let context = Context::get(None).await?;
+    let context = Context::get(client, config, organisation).await?;
+    let recorder =
+        provenance::Recorder::start(&context.config, &context.client, history::now()).await;
 
     // Validate project
-    let project = Project::new(project_name).validate(&context).await;
-    print_status(&project);
-    if project.has_errors() {
+    let project = Project::new(name).validate(&context).await;
+    let projects = [project];
+    let mut report = Report::new(&projects);
+    report.run = Some(recorder.finish(&context.client).await);
+    report.expiring_exemptions = exemptions::expiring(
+        &report,
+        history::now(),
+        context.config.expiring_exemptions.within_days,
+    );
+    print_results(&projects, &report, &output)?;
+    if projects[0].has_errors() {
         Err(eyre!("The project does not conform to our guidelines"))
     } else {
         Ok(())
     }
 }
 
-fn print_status(project: &Project) {
-    if let Some(errors) = project.errors_to_string(true) {
-        return print!("❌ {}\n{}\n", project.name, errors);
+/// Tell the user which checks will be skipped when no GitHub credentials are
+/// given, rather than leaving them to wonder why the checks pass.
+fn print_auth_guidance(client: &github::Client, config: &Config) {
+    let checks = Project::checks_needing_auth(config);
+    if client.is_authenticated() || checks.is_empty() {
+        return;
+    }
+    eprintln!(
+        "No GitHub credentials were given, so the checks that need them will be skipped: {}. \
+Pass --github-api-token or --github-app-id to run them.",
+        checks.join(", ")
+    );
+}
+
+/// Read the names of projects to validate from a file, or from stdin if the
+/// path is `-`.
+fn read_project_names(path: &Path) -> eyre::Result<Vec<String>> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        let _ = std::io::stdin()
+            .read_to_string(&mut text)
+            .wrap_err("Unable to read project names from stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Unable to read {}", path.display()))?
+    };
+    Ok(parse_project_names(&text))
+}
+
+/// Project names are given one per line, ignoring blank lines and comments
+/// starting with `#`.
+fn parse_project_names(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The projects to validate, with the context to validate each one with.
+/// These are the given names, as `name` or `organisation/name`, or else
+/// every project of every organisation. Names must be unique across
+/// organisations as results and their history are keyed by project name.
+fn projects_to_validate<'a>(
+    contexts: &'a [Context],
+    names: Option<&[String]>,
+) -> eyre::Result<Vec<(&'a Context, String)>> {
+    if let Some(names) = names {
+        return names
+            .iter()
+            .map(|name| {
+                let (organisation, name) = contexts[0].config.project_organisation(name);
+                let context = contexts
+                    .iter()
+                    .find(|context| context.organisation.name == organisation.name)
+                    .ok_or_else(|| {
+                        eyre!("The {} organisation is not configured", organisation.name)
+                    })?;
+                Ok((context, name))
+            })
+            .collect::<eyre::Result<Vec<_>>>()
+            .map(|projects| {
+                projects
+                    .into_iter()
+                    .unique_by(|(_, name)| name.clone())
+                    .collect()
+            });
     }
 
-    if let Ok(maintainers) = &project.maintainers {
-        return println!("✔️ {} ({})", project.name, maintainers.iter().join(", "));
+    let mut organisations = HashMap::new();
+    let mut projects = Vec::new();
+    for context in contexts {
+        let names = context
+            .all_projects()
+            .into_iter()
+            .filter(|project| !IGNORED_PROJECTS.contains(&project.as_str()));
+        for name in names {
+            let organisation = context.organisation.name.as_str();
+            if let Some(other) = organisations.insert(name.clone(), organisation) {
+                return Err(eyre!(
+                    "Project {} is in both the {} and {} organisations, but project names must be unique",
+                    name,
+                    other,
+                    organisation
+                ));
+            }
+            projects.push((context, name));
+        }
     }
+    Ok(projects)
+}
 
-    unreachable!();
+/// Snapshot the adoption metrics of every validated project. Stars and
+/// forks come from the repos already fetched for the context, while counting
+/// contributors takes a request per project.
+async fn collect_metrics(contexts: &[Context], report: &Report) -> BTreeMap<String, Metrics> {
+    stream::iter(&report.projects)
+        .map(|project| async move {
+            let context = context_of(contexts, &project.organisation);
+            let repo = context
+                .embark_github_repos
+                .get(project::repo_of(&project.name))?;
+            let contributors = context
+                .client
+                .contributor_count(&context.organisation.name, &repo.name)
+                .await
+                .ok();
+            let metrics = Metrics {
+                stars: repo.stargazers_count,
+                forks: repo.forks_count,
+                contributors,
+            };
+            Some((project.name.clone(), metrics))
+        })
+        .buffer_unordered(8)
+        .filter_map(futures::future::ready)
+        .collect()
+        .await
 }
 
-fn slack_notification_blocks(projects: &[Project]) -> Vec<slack::Block> {
-    use slack::Block::{Divider, Text};
+/// The context of the organisation with the given name, or of the first
+/// organisation if there is none.
+fn context_of<'a>(contexts: &'a [Context], organisation: &str) -> &'a Context {
+    contexts
+        .iter()
+        .find(|context| context.organisation.name == organisation)
+        .unwrap_or(&contexts[0])
+}
 
-    let head = "The following Embark open source projects have been found to \
-have maintainership issues.";
-    let foot = "This message was generated by the \
-<https://github.com/EmbarkStudios/opensource/tree/main/tools/embark-oss|embark-oss tool> \
-on GitHub Actions.";
+/// How many projects are validated at once. A project may hold every file of
+/// its repo in memory while it is validated.
+const CONCURRENT_PROJECTS: usize = 8;
 
-    let mut blocks = Vec::with_capacity(projects.len() + 4);
+/// Run the validations until they are all complete or the user interrupts
+/// with Ctrl-C, in which case the outstanding validations are cancelled.
+/// Returns the validated projects and the number that were cancelled.
+async fn validate_until_interrupted<T>(
+    futures: impl IntoIterator<Item = impl Future<Output = T>>,
+) -> (Vec<T>, usize) {
+    let futures: Vec<_> = futures.into_iter().collect();
+    let total = futures.len();
+    let mut pending = stream::iter(futures).buffer_unordered(CONCURRENT_PROJECTS);
+    let mut projects = Vec::with_capacity(total);
+    let interrupted = tokio::signal::ctrl_c();
+    tokio::pin!(interrupted);
+    loop {
+        tokio::select! {
+            project = pending.next() => match project {
+                Some(project) => projects.push(project),
+                None => break,
+            },
+            _ = &mut interrupted => {
+                eprintln!("Interrupted, printing the results gathered so far");
+                break;
+            }
+        }
+    }
+    let cancelled = total - projects.len();
+    (projects, cancelled)
+}
 
-    blocks.push(Text(head.to_string()));
-    blocks.push(Divider);
-    blocks.extend(projects.iter().flat_map(slack_project_block));
-    blocks.push(Divider);
-    blocks.push(Text(foot.to_string()));
-    blocks
+/// The results of a run as printed in the terminal by `--format text`.
+fn text_results(projects: &[Project], report: &Report, verbose: bool) -> String {
+    // Projects are grouped under their organisation if there are several
+    let several = projects
+        .iter()
+        .map(|project| &project.organisation)
+        .unique()
+        .count()
+        > 1;
+    let mut text = String::new();
+    let mut organisation = None;
+    for project in projects {
+        if several && organisation != Some(&project.organisation) {
+            text.push_str(&format!("\n{}\n", project.organisation));
+            organisation = Some(&project.organisation);
+        }
+        text.push_str(&report::status(project, verbose));
+        for id in report.known_failures(&project.name) {
+            text.push_str(&format!(
+                "    📌 {}\n",
+                locale::text("status-known", &[("id", id)])
+            ));
+        }
+    }
+    if !report.expiring_exemptions.is_empty() {
+        text.push_str(&format!("\n{}\n", locale::text("exemptions-expiring", &[])));
+        for exemption in &report.expiring_exemptions {
+            text.push_str(&format!("    🛡️ {}\n", exemption.summary()));
+        }
+    }
+    if let Some(run) = &report.run {
+        text.push_str(&format!("\n{}\n", run.summary()));
+    }
+    text
 }
 
-fn slack_project_block(project: &Project) -> Option<slack::Block> {
-    let text = format!(
-        ":red_circle: *<https://github.com/EmbarkStudios/{name}|{name}>*\n```{error}```",
-        name = &project.name,
-        error = project.errors_to_string(false)?,
-    );
-    Some(slack::Block::Text(text))
+fn print_results(projects: &[Project], report: &Report, output: &Output) -> eyre::Result<()> {
+    match output.format {
+        Format::Text => print!("{}", text_results(projects, report, output.verbose)),
+        Format::Json => report::print_json(report)?,
+    }
+    if let Some(path) = &output.report_file {
+        report::write_json(report, path)?;
+    }
+    if let Some(path) = &output.sarif_file {
+        sarif::write(report, path)?;
+    }
+    if let Some(count) = output.slowest {
+        report::print_slowest(projects, count, output.format);
+    }
+    Ok(())
 }
@@ -0,0 +1,114 @@
+//! A checklist for retiring a project, which makes sure that users of an
+//! archived repo aren't misled into thinking it is still maintained.
+
+use super::{context::Context, fix, preflight::Checklist, project::Project};
+use crate::{config::Config, diff, github, Archive};
+use eyre::eyre;
+
+/// Check that a project is archived on GitHub and says so in its README, and
+/// print the change moving it to the archived projects in the website data,
+/// or open a pull request with it.
+pub async fn archive(options: Archive) -> eyre::Result<()> {
+    let Archive {
+        name,
+        open_pr,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let (organisation, name) = config.project_organisation(&name);
+    let client = github.client(&organisation.name)?;
+    let context = Context::get(client, config, organisation).await?;
+    let project = Project::new(name);
+    let organisation = &context.organisation.name;
+
+    let mut checklist = Checklist::default();
+    let repo = context
+        .embark_github_repos
+        .get(project.repo())
+        .ok_or_else(|| eyre!("{} has no repo named {}", organisation, project.repo()))?;
+    checklist.item(
+        "repo archived on GitHub",
+        if repo.archived {
+            Ok(())
+        } else {
+            Err("    Archive the repo in its settings on GitHub".to_string())
+        },
+    );
+    let banner = context
+        .config
+        .maintenance_banner
+        .clone()
+        .unwrap_or_default()
+        .archived
+        .0;
+    let readme = project
+        .download_optional_file(&context, "README.md")
+        .await?;
+    checklist.item(
+        "archived banner in the README",
+        match readme {
+            Some(readme) if banner.is_match(&readme) => Ok(()),
+            Some(_) => Err(format!(
+                "    Add a banner matching `{}` to the top of the README, such as \
+[![Maintenance](https://img.shields.io/badge/maintenance-archived-red.svg)]",
+                banner
+            )),
+            None => Err("    The repo has no README.md".to_string()),
+        },
+    );
+
+    let website_change = match &context.organisation.website_data {
+        Some(source) => {
+            let text = github::download_repo_file(
+                organisation,
+                &source.repo,
+                &source.branch,
+                &source.path,
+            )
+            .await?;
+            fix::archive_website_entry(&text, &project.name)?
+                .map(|archived| (source, text, archived))
+        }
+        None => None,
+    };
+    checklist.item(
+        "moved to the archived projects of the website",
+        match &website_change {
+            None => Ok(()),
+            Some(_) if open_pr => Ok(()),
+            Some(_) => Err("    Apply the diff below, or run again with --open-pr".to_string()),
+        },
+    );
+    checklist.print();
+
+    if let Some((source, text, archived)) = website_change {
+        if open_pr {
+            let changes = github::FileChanges {
+                base: source.branch.clone(),
+                branch: format!("embark-oss/archive/{}", project.repo()),
+                title: format!("Archive {}", project.name),
+                body: format!(
+                    "Moves {} to the archived projects, as its repo is archived.\n\n\
+Opened by `cargo run archive`.",
+                    project.name
+                ),
+                files: std::iter::once((source.path.clone(), archived)).collect(),
+            };
+            let url = context
+                .client
+                .open_pull_request(organisation, &source.repo, &changes)
+                .await?;
+            if let Some(url) = url {
+                println!("\nOpened {}", url);
+            }
+        } else {
+            print!("\n{}", diff::unified(&source.path, &text, &archived));
+        }
+    }
+    match checklist.blockers {
+        0 => Ok(()),
+        _ => Err(eyre!("{} is not fully archived", project.name)),
+    }
+}
@@ -0,0 +1,122 @@
+//! Baselines of known violations, written with `--write-baseline` and given
+//! with `--baseline`, so that a strict new check can be adopted without the
+//! projects that already violate it failing every run.
+
+use super::report::Report;
+use eyre::WrapErr;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Baseline {
+    /// The ids of the failing checks of each project, by project name.
+    pub violations: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Baseline {
+    /// The violations in a report. Failures because GitHub could not be
+    /// reached are not violations, so are left out.
+    pub fn new(report: &Report) -> Self {
+        let mut violations: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for project in &report.projects {
+            for check in &project.checks {
+                if !check.ok && !check.infrastructure {
+                    let _ = violations
+                        .entry(project.name.clone())
+                        .or_default()
+                        .insert(check.id.clone());
+                }
+            }
+        }
+        Self { violations }
+    }
+
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Unable to read {}", path.display()))?;
+        serde_json::from_str(&json).wrap_err_with(|| format!("Unable to parse {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        let json = serde_json::to_string_pretty(self).wrap_err("Unable to serialise baseline")?;
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Unable to write {}", path.display()))
+    }
+
+    /// Mark the failures of the report that are in the baseline as known.
+    pub fn mark_known(&self, report: &mut Report) {
+        for project in &mut report.projects {
+            let known = match self.violations.get(&project.name) {
+                Some(known) => known,
+                None => continue,
+            };
+            for check in &mut project.checks {
+                check.known = !check.ok && !check.infrastructure && known.contains(&check.id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_violations() {
+        let mut report: Report = Report::parse(
+            r#"{
+                "projects": [
+                    {
+                        "name": "ash",
+                        "ok": false,
+                        "maintainers": [],
+                        "duration_ms": 0,
+                        "checks": [
+                            { "id": "msrv", "ok": false, "error": "No MSRV", "infrastructure": false, "duration_ms": 0 },
+                            { "id": "topics", "ok": false, "error": "502", "infrastructure": true, "duration_ms": 0 }
+                        ]
+                    },
+                    {
+                        "name": "puffin",
+                        "ok": false,
+                        "maintainers": [],
+                        "duration_ms": 0,
+                        "checks": [
+                            { "id": "msrv", "ok": false, "error": "No MSRV", "infrastructure": false, "duration_ms": 0 }
+                        ]
+                    }
+                ],
+                "partial": false
+            }"#,
+        )
+        .unwrap();
+        let baseline = Baseline::new(&report);
+        let expected: BTreeMap<_, BTreeSet<_>> = vec![
+            (
+                "ash".to_string(),
+                vec!["msrv".to_string()].into_iter().collect(),
+            ),
+            (
+                "puffin".to_string(),
+                vec!["msrv".to_string()].into_iter().collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(baseline.violations, expected);
+
+        let mut baseline = baseline;
+        let _ = baseline.violations.remove("puffin");
+        baseline.mark_known(&mut report);
+        let known: Vec<_> = report
+            .projects
+            .iter()
+            .flat_map(|project| &project.checks)
+            .map(|check| check.known)
+            .collect();
+        assert_eq!(known, vec![true, false, false]);
+    }
+}
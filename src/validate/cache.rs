@@ -0,0 +1,87 @@
+//! Results of previous runs keyed by the head commit of each project, so
+//! that projects which have not changed don't have to be validated again.
+
+use super::{context::Context, project::Project, report::ProjectReport};
+use crate::{config::ResultCache, github};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cache {
+    pub entries: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    #[serde(flatten)]
+    pub stamp: Stamp,
+    pub project: ProjectReport,
+}
+
+/// The commit a project was validated at and when.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Stamp {
+    pub sha: String,
+    /// In seconds since the Unix epoch.
+    pub time: u64,
+}
+
+impl Cache {
+    /// The cached results for a project if they are for the given commit,
+    /// are not too old, and are for the same checks as would be run now.
+    /// Results with infrastructure errors are never reused.
+    pub fn get(
+        &self,
+        name: &str,
+        sha: &str,
+        now: u64,
+        config: &ResultCache,
+        check_ids: &[String],
+    ) -> Option<&Entry> {
+        let entry = self.entries.get(name)?;
+        let fresh = now < entry.stamp.time + config.max_age().as_secs();
+        let same_checks = entry
+            .project
+            .checks
+            .iter()
+            .map(|check| &check.id)
+            .eq(check_ids);
+        let reliable = !entry.project.has_infrastructure_failures();
+        if entry.stamp.sha == sha && fresh && same_checks && reliable {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+}
+
+/// Validate a project, reusing its cached results if its default branch has
+/// not moved. Returns the stamp to cache the results with, which is `None`
+/// if the head commit could not be found.
+pub async fn validate(
+    name: String,
+    context: &Context,
+    client: &github::Client,
+    cache: Option<(&Cache, &ResultCache)>,
+    now: u64,
+) -> (Project, Option<Stamp>) {
+    let (cache, config) = match cache {
+        Some(cache) => cache,
+        None => return (Project::new(name).validate(context).await, None),
+    };
+    let sha = match client
+        .head_commit_sha(&context.organisation.name, super::project::repo_of(&name))
+        .await
+    {
+        Ok(sha) => sha,
+        Err(_) => return (Project::new(name).validate(context).await, None),
+    };
+    let check_ids = Project::check_ids_for_tier(&context.config, context.tier(&name));
+    if let Some(entry) = cache.get(&name, &sha, now, config, &check_ids) {
+        return (
+            Project::from_report(&entry.project, context),
+            Some(entry.stamp.clone()),
+        );
+    }
+    let project = Project::new(name).validate(context).await;
+    (project, Some(Stamp { sha, time: now }))
+}
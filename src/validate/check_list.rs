@@ -0,0 +1,95 @@
+//! A listing of the checks that `validate` runs with a config, generated from
+//! the same registry the validation uses so that it can't drift from what
+//! is actually checked.
+
+use super::{
+    project::{self, Project},
+    registry::{self, Auth, Severity},
+    report::Format,
+};
+use crate::{config::Config, ChecksList};
+
+/// A check as run with a config.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CheckListing {
+    pub id: String,
+    pub description: &'static str,
+    pub severity: Severity,
+    pub auth: Auth,
+    /// The tiers whose projects skip the check.
+    pub skipped_in_tiers: Vec<String>,
+    /// The tiers whose projects only get warnings from the check.
+    pub warning_in_tiers: Vec<String>,
+    /// The checks that must pass for this one to run.
+    pub prerequisites: Vec<&'static str>,
+}
+
+/// Print the checks the config enables.
+pub fn list_checks(options: ChecksList) -> eyre::Result<()> {
+    let ChecksList { format, config } = options;
+    let config = Config::load(config.as_deref())?;
+    let checks = listings(&config);
+    match format {
+        Format::Text => print!("{}", text(&checks)),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&checks)?),
+    }
+    Ok(())
+}
+
+pub fn listings(config: &Config) -> Vec<CheckListing> {
+    Project::check_ids(config)
+        .into_iter()
+        .map(|id| {
+            let tiers = |in_policy: fn(&crate::config::TierPolicy) -> &Vec<String>| {
+                config
+                    .tiers
+                    .policies
+                    .iter()
+                    .filter(|(_, policy)| in_policy(policy).contains(&id))
+                    .map(|(tier, _)| tier.clone())
+                    .collect()
+            };
+            let check = registry::get(&id);
+            CheckListing {
+                description: check
+                    .map_or("An organisation specific check.", |check| check.description),
+                severity: registry::severity(config, &id),
+                auth: check.map_or(Auth::Anonymous, |check| check.auth(config)),
+                skipped_in_tiers: tiers(|policy| &policy.skip),
+                warning_in_tiers: tiers(|policy| &policy.warn),
+                prerequisites: project::prerequisites(&id),
+                id,
+            }
+        })
+        .collect()
+}
+
+pub fn text(checks: &[CheckListing]) -> String {
+    let mut text = String::new();
+    for check in checks {
+        text.push_str(&format!("{}\n    {}\n", check.id, check.description));
+        let severity = match check.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let auth = match check.auth {
+            Auth::Anonymous => "anonymous",
+            Auth::Token => "token",
+        };
+        let mut details = vec![format!("severity: {}", severity), format!("auth: {}", auth)];
+        if check.skipped_in_tiers.is_empty() && check.warning_in_tiers.is_empty() {
+            details.push("tiers: all".to_string());
+        }
+        if !check.skipped_in_tiers.is_empty() {
+            details.push(format!("skipped in: {}", check.skipped_in_tiers.join(", ")));
+        }
+        if !check.warning_in_tiers.is_empty() {
+            details.push(format!("warning in: {}", check.warning_in_tiers.join(", ")));
+        }
+        if !check.prerequisites.is_empty() {
+            details.push(format!("requires: {}", check.prerequisites.join(", ")));
+        }
+        text.push_str(&format!("    {}\n", details.join(" · ")));
+    }
+    text
+}
@@ -0,0 +1,90 @@
+//! Check runs on the head of each project's default branch summarising its
+//! results, so that maintainers see failures on their own repo rather than
+//! only in the central Slack channel.
+
+use super::{context::Context, project, report::ProjectReport};
+use crate::config::CheckRuns;
+use serde_json::{json, Value};
+
+/// GitHub rejects check run summaries longer than this many bytes.
+const MAX_SUMMARY_LENGTH: usize = 65_535;
+
+/// Publish the results of a project as a check run on the head of its repo's
+/// default branch.
+pub async fn publish(
+    config: &CheckRuns,
+    context: &Context,
+    project: &ProjectReport,
+) -> eyre::Result<()> {
+    let organisation = &context.organisation.name;
+    let repo = project::repo_of(&project.name);
+    let head_sha = context.client.head_commit_sha(organisation, repo).await?;
+    context
+        .client
+        .create_check_run(organisation, repo, &check_run(config, project, &head_sha))
+        .await
+}
+
+/// The check run for a project. Projects that failed only because GitHub
+/// could not be reached have a neutral conclusion, as they may well conform.
+pub fn check_run(config: &CheckRuns, project: &ProjectReport, head_sha: &str) -> Value {
+    // Projects in a directory of the repo each have their own check run
+    let name = match project.name.split_once('#') {
+        Some((_, path)) => format!("{} ({})", config.name, path),
+        None => config.name.clone(),
+    };
+    let failures = project.checks.iter().filter(|check| !check.ok).count();
+    let (conclusion, title) = if project.ok {
+        (
+            "success",
+            "Conforms to the open source guidelines".to_string(),
+        )
+    } else if project.could_not_validate() {
+        ("neutral", "Could not be validated".to_string())
+    } else if failures == 1 {
+        ("failure", "1 check fails".to_string())
+    } else {
+        ("failure", format!("{} checks fail", failures))
+    };
+    json!({
+        "name": name,
+        "head_sha": head_sha,
+        "status": "completed",
+        "conclusion": conclusion,
+        "output": { "title": title, "summary": summary(project) },
+    })
+}
+
+pub(super) fn summary(project: &ProjectReport) -> String {
+    let mut summary = String::new();
+    for check in &project.checks {
+        if let Some(error) = &check.error {
+            summary.push_str(&format!("### ❌ {}\n\n```\n{}\n```\n\n", check.id, error));
+        }
+    }
+    for check in &project.checks {
+        if let Some(warning) = &check.warning {
+            summary.push_str(&format!(
+                "### ⚠️ {} (warning)\n\n```\n{}\n```\n\n",
+                check.id, warning
+            ));
+        }
+        if let Some(justification) = &check.exemption {
+            summary.push_str(&format!("- 🛡️ {} is exempt: {}\n", check.id, justification));
+        }
+    }
+    summary.push_str(&format!(
+        "\nRun `cargo run inspect {}` in [EmbarkStudios/opensource](https://github.com/EmbarkStudios/opensource) \
+to see what each check looks at and how to fix failures.\n",
+        project.name
+    ));
+    if summary.len() > MAX_SUMMARY_LENGTH {
+        let mut end = MAX_SUMMARY_LENGTH - 3;
+        while !summary.is_char_boundary(end) {
+            end -= 1;
+        }
+        summary.truncate(end);
+        summary.push_str("...");
+    }
+    summary
+}
@@ -0,0 +1,37 @@
+//! Checks that are driven by the configuration rather than being hard-coded
+//! into every validation.
+
+pub mod actions_pinning;
+pub mod badges;
+pub mod cargo_metadata;
+pub mod container_images;
+pub mod contribution_agreement;
+pub mod coverage;
+pub mod cpp_license_headers;
+pub mod dependabot;
+pub mod external;
+pub mod first_time_contributors;
+pub mod good_first_issues;
+pub mod install_instructions;
+pub mod internal_references;
+pub mod issue_response;
+pub mod license_year;
+pub mod maintenance_banner;
+pub mod msrv;
+pub mod package_json;
+pub mod readme_links;
+pub mod release_checksums;
+pub mod release_drift;
+pub mod repo_files;
+pub mod required_files;
+pub mod secret_patterns;
+pub mod secret_scanning;
+pub mod signed_releases;
+pub mod template_drift;
+pub mod third_party_licenses;
+pub mod topics;
+pub mod unsafe_code;
+pub mod wasm;
+pub mod website_owners;
+pub mod workflow_audit;
+pub mod workflows;
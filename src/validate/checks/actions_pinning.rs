@@ -0,0 +1,69 @@
+use super::workflows::{self, Workflow};
+use crate::{
+    config::ActionsPinning,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that every action used by the project's workflows is pinned to a
+/// full commit SHA, unless it is allowed not to be by the config.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &ActionsPinning,
+) -> eyre::Result<()> {
+    let unpinned: Vec<_> = workflows::download(project, context)
+        .await?
+        .iter()
+        .flat_map(|workflow| unpinned(workflow, config))
+        .collect();
+    if unpinned.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(unpinned.join("\n")).wrap_err("Actions not pinned to a commit SHA"))
+    }
+}
+
+/// The location and name of each unpinned action in the workflow.
+fn unpinned(workflow: &Workflow, config: &ActionsPinning) -> Vec<String> {
+    workflow
+        .unpinned_actions(&config.allowed)
+        .into_iter()
+        .map(|action| {
+            let version = action.version.unwrap_or("no version");
+            format!(
+                "{}:{}: {}@{}",
+                workflow.path, action.line, action.action, version
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpinned_actions() {
+        let config: ActionsPinning = toml::from_str(r#"allowed = ["actions/*"]"#).unwrap();
+        let workflow = Workflow {
+            path: ".github/workflows/ci.yml".to_string(),
+            text: r#"jobs:
+  build:
+    steps:
+      - uses: actions/checkout@v3
+      - uses: EmbarkStudios/cargo-deny-action@v1
+      - uses: EmbarkStudios/cargo-about-action@8371184bd11e21dcf8ac82ebf8c9c9f74ebf7268
+      - uses: someone/action
+"#
+            .to_string(),
+        };
+        assert_eq!(
+            unpinned(&workflow, &config),
+            vec![
+                ".github/workflows/ci.yml:5: EmbarkStudios/cargo-deny-action@v1",
+                ".github/workflows/ci.yml:7: someone/action@no version",
+            ]
+        );
+    }
+}
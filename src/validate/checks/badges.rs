@@ -0,0 +1,146 @@
+use crate::{
+    config::{Badges, MaintenanceBanner, Pattern},
+    github,
+    validate::{checks::maintenance_banner::Status, context::Context, project::Project},
+};
+use eyre::{eyre, WrapErr};
+use regex::Regex;
+
+/// The standard badges of READMEs, in the format of the `badges.toml` of the
+/// opensource repo.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BadgeStandard {
+    #[serde(rename = "badge", default)]
+    pub badges: Vec<Badge>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Badge {
+    pub name: String,
+    /// A regular expression that READMEs with the badge match.
+    pub pattern: Pattern,
+    /// The markdown of the badge, to copy into READMEs.
+    pub markdown: String,
+    /// Whether every README must have the badge.
+    #[serde(default)]
+    pub required: bool,
+    /// The maintenance status the badge is the banner of, if any.
+    pub status: Option<Status>,
+}
+
+impl BadgeStandard {
+    pub fn parse(text: &str) -> eyre::Result<Self> {
+        toml::from_str(text).wrap_err("Unable to parse the badge definitions")
+    }
+
+    /// Download the badge definitions. They are only downloaded once per run.
+    pub async fn get(config: &Badges) -> eyre::Result<Self> {
+        let (owner, repo) = config
+            .repo
+            .split_once('/')
+            .ok_or_else(|| eyre!("The badges repo `{}` is not `owner/name`", config.repo))?;
+        let text = github::download_repo_file(owner, repo, &config.branch, &config.path).await?;
+        Self::parse(&text)
+    }
+
+    /// The required badges a README doesn't have.
+    pub fn missing<'a>(&'a self, readme: &str) -> Vec<&'a Badge> {
+        self.badges
+            .iter()
+            .filter(|badge| badge.required && !badge.pattern.0.is_match(readme))
+            .collect()
+    }
+
+    /// The maintenance banner settings with the banners of these badges in
+    /// place of those of `config`. Statuses without a badge keep their banner
+    /// from `config`.
+    pub fn maintenance_banner(
+        &self,
+        config: &MaintenanceBanner,
+    ) -> eyre::Result<MaintenanceBanner> {
+        let banner = |status: Status, default: &Pattern| -> eyre::Result<Pattern> {
+            let patterns: Vec<_> = self
+                .badges
+                .iter()
+                .filter(|badge| badge.status == Some(status))
+                .map(|badge| format!("(?:{})", badge.pattern.0.as_str()))
+                .collect();
+            if patterns.is_empty() {
+                return Ok(default.clone());
+            }
+            Ok(Pattern(Regex::new(&patterns.join("|"))?))
+        };
+        Ok(MaintenanceBanner {
+            active: banner(Status::Active, &config.active)?,
+            maintenance: banner(Status::Maintenance, &config.maintenance)?,
+            archived: banner(Status::Archived, &config.archived)?,
+            inactive_days: config.inactive_days,
+        })
+    }
+}
+
+/// Check that the README has the required badges of the badge definitions,
+/// which are downloaded when the check runs so that it keeps up with changes
+/// to the standard. Projects without a README are left to the
+/// `required-files` checks.
+pub async fn check(project: &Project, context: &Context, config: &Badges) -> eyre::Result<()> {
+    let readme = match project.download_optional_file(context, "README.md").await? {
+        Some(readme) => readme,
+        None => return Ok(()),
+    };
+    let standard = BadgeStandard::get(config).await?;
+    let missing = standard.missing(&readme);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let badges: Vec<_> = missing
+        .iter()
+        .map(|badge| format!("{}: {}", badge.name, badge.markdown))
+        .collect();
+    Err(eyre!(badges.join("\n")).wrap_err("The README is missing standard badges"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_badges() {
+        let standard = BadgeStandard::parse(include_str!("../../../badges.toml")).unwrap();
+        let names = |badges: Vec<&Badge>| -> Vec<String> {
+            badges.iter().map(|badge| badge.name.clone()).collect()
+        };
+        assert_eq!(
+            names(standard.missing("# Ash\n\nNo badges here")),
+            vec!["Embark", "Discord"]
+        );
+        let readme = standard
+            .badges
+            .iter()
+            .filter(|badge| badge.required)
+            .map(|badge| badge.markdown.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(standard.missing(&readme).is_empty());
+
+        let config = MaintenanceBanner {
+            active: Pattern(Regex::new("unused").unwrap()),
+            ..MaintenanceBanner::default()
+        };
+        let banner = standard.maintenance_banner(&config).unwrap();
+        assert!(banner.active.0.is_match(
+            "https://img.shields.io/badge/maintenance-actively--developed-brightgreen.svg"
+        ));
+        assert!(banner
+            .archived
+            .0
+            .is_match("https://img.shields.io/badge/maintenance-as--is-yellow.svg"));
+
+        let standard = BadgeStandard::parse("").unwrap();
+        assert!(standard.missing("").is_empty());
+        let banner = standard.maintenance_banner(&config).unwrap();
+        assert_eq!(banner.active.0.as_str(), "unused");
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{
+    config::{CargoMetadata, Glob},
+    validate::{context::Context, project::Project},
+};
+use eyre::{eyre, WrapErr};
+use std::convert::TryFrom;
+use toml::Value;
+
+/// Check that every publishable crate of a Rust project has the metadata
+/// crates.io shows, such as a license and description. For workspaces each
+/// member is checked, not only the root crate. Projects without a Cargo.toml
+/// pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &CargoMetadata,
+) -> eyre::Result<()> {
+    let root = match project
+        .download_optional_file(context, "Cargo.toml")
+        .await?
+    {
+        Some(text) => parse(&text, "Cargo.toml")?,
+        None => return Ok(()),
+    };
+    let workspace_package = root
+        .get("workspace")
+        .and_then(|workspace| workspace.get("package"));
+
+    let mut problems = Vec::new();
+    let mut manifests = Vec::new();
+    if root.get("package").is_some() {
+        manifests.push((String::new(), root.clone()));
+    }
+    for dir in members(project, context, &root).await? {
+        let path = format!("{}/Cargo.toml", dir);
+        match project.download_optional_file(context, &path).await? {
+            Some(text) => manifests.push((dir, parse(&text, &path)?)),
+            None => problems.push(format!("{}: workspace member not found", path)),
+        }
+    }
+
+    for (dir, manifest) in &manifests {
+        if !is_publishable(manifest) {
+            continue;
+        }
+        let path = manifest_path(dir);
+        for field in missing_fields(manifest, workspace_package, &config.fields) {
+            problems.push(format!("{}: missing {}", path, field));
+        }
+        if config.fields.iter().any(|field| field == "readme") {
+            if let Some(readme) = readme_path(dir, manifest, workspace_package) {
+                if project
+                    .download_optional_file(context, &readme)
+                    .await?
+                    .is_none()
+                {
+                    problems.push(format!("{}: readme {} not found", path, readme));
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("\n")).wrap_err("Incomplete Cargo metadata"))
+    }
+}
+
+fn parse(text: &str, path: &str) -> eyre::Result<Value> {
+    toml::from_str(text).wrap_err_with(|| format!("Unable to parse {}", path))
+}
+
+fn manifest_path(dir: &str) -> String {
+    if dir.is_empty() {
+        "Cargo.toml".to_string()
+    } else {
+        format!("{}/Cargo.toml", dir)
+    }
+}
+
+/// The directories of the workspace members listed in the root manifest.
+/// Members given as globs are matched against the directories of every
+/// Cargo.toml in the repo.
+async fn members(project: &Project, context: &Context, root: &Value) -> eyre::Result<Vec<String>> {
+    let list = |key: &str| -> Vec<String> {
+        root.get("workspace")
+            .and_then(|workspace| workspace.get(key))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|member| member.trim_end_matches('/').to_string())
+            .filter(|member| !member.is_empty() && member != ".")
+            .collect()
+    };
+    let (members, exclude) = (list("members"), list("exclude"));
+    if !members.iter().any(|member| member.contains('*')) {
+        return Ok(members);
+    }
+
+    let (_, files) = project.repo_files(context).await?;
+    let dirs: Vec<_> = files
+        .iter()
+        .filter_map(|file| file.path.strip_suffix("/Cargo.toml"))
+        .collect();
+    Ok(expand(&members, &exclude, &dirs))
+}
+
+fn expand(members: &[String], exclude: &[String], dirs: &[&str]) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for member in members {
+        if !member.contains('*') {
+            expanded.push(member.clone());
+            continue;
+        }
+        let glob = match Glob::try_from(member.clone()) {
+            Ok(glob) => glob,
+            Err(_) => continue,
+        };
+        expanded.extend(
+            dirs.iter()
+                .filter(|dir| glob.matches(dir) && !exclude.iter().any(|e| e == *dir))
+                .map(|dir| dir.to_string()),
+        );
+    }
+    expanded
+}
+
+/// Crates with `publish = false`, or an empty list of registries, are never
+/// published so don't need the metadata.
+fn is_publishable(manifest: &Value) -> bool {
+    match manifest
+        .get("package")
+        .and_then(|package| package.get("publish"))
+    {
+        Some(Value::Boolean(publish)) => *publish,
+        Some(Value::Array(registries)) => !registries.is_empty(),
+        _ => true,
+    }
+}
+
+/// The fields the package doesn't set, either directly or by inheriting
+/// them from the workspace with `field.workspace = true`. A license file
+/// counts as a license.
+fn missing_fields<'a>(
+    manifest: &Value,
+    workspace_package: Option<&Value>,
+    fields: &'a [String],
+) -> Vec<&'a str> {
+    fields
+        .iter()
+        .map(String::as_str)
+        .filter(|field| {
+            let has = |field: &str| field_value(manifest, workspace_package, field).is_some();
+            match *field {
+                "license" => !has("license") && !has("license-file"),
+                // Cargo uses a README.md next to the manifest by default
+                "readme" => false,
+                field => !has(field),
+            }
+        })
+        .collect()
+}
+
+fn field_value<'a>(
+    manifest: &'a Value,
+    workspace_package: Option<&'a Value>,
+    field: &str,
+) -> Option<&'a Value> {
+    let value = manifest.get("package")?.get(field)?;
+    let inherited = value
+        .get("workspace")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if inherited {
+        workspace_package?.get(field)
+    } else {
+        Some(value)
+    }
+}
+
+/// The path of the README crates.io would show for the package, relative to
+/// the root of the repo, or `None` if the package opts out with
+/// `readme = false`.
+fn readme_path(dir: &str, manifest: &Value, workspace_package: Option<&Value>) -> Option<String> {
+    let readme = match field_value(manifest, workspace_package, "readme") {
+        Some(Value::String(readme)) => readme.trim_start_matches("./").to_string(),
+        Some(Value::Boolean(false)) => return None,
+        _ => "README.md".to_string(),
+    };
+    Some(if dir.is_empty() {
+        readme
+    } else {
+        format!("{}/{}", dir, readme)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<String> {
+        CargoMetadata::default().fields
+    }
+
+    #[test]
+    fn missing_and_inherited_fields() {
+        let root: Value = toml::from_str(
+            r#"
+[workspace]
+members = ["crates/*"]
+[workspace.package]
+license = "MIT OR Apache-2.0"
+"#,
+        )
+        .unwrap();
+        let workspace_package = root.get("workspace").and_then(|w| w.get("package"));
+        let member: Value = toml::from_str(
+            r#"
+[package]
+name = "ash-window"
+license.workspace = true
+description.workspace = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            missing_fields(&member, workspace_package, &fields()),
+            vec!["description"]
+        );
+
+        let member: Value = toml::from_str(
+            "[package]\nname = \"ash\"\nlicense-file = \"LICENSE\"\ndescription = \"Vulkan\"",
+        )
+        .unwrap();
+        assert!(missing_fields(&member, None, &fields()).is_empty());
+    }
+
+    #[test]
+    fn publishable_crates() {
+        let manifest = |text: &str| toml::from_str::<Value>(text).unwrap();
+        assert!(is_publishable(&manifest("[package]\nname = \"ash\"")));
+        assert!(!is_publishable(&manifest("[package]\npublish = false")));
+        assert!(!is_publishable(&manifest("[package]\npublish = []")));
+        assert!(is_publishable(&manifest(
+            "[package]\npublish = [\"crates-io\"]"
+        )));
+    }
+
+    #[test]
+    fn readme_paths() {
+        let manifest = |text: &str| toml::from_str::<Value>(text).unwrap();
+        assert_eq!(
+            readme_path("crates/ash", &manifest("[package]\nname = \"ash\""), None),
+            Some("crates/ash/README.md".to_string())
+        );
+        assert_eq!(
+            readme_path(
+                "",
+                &manifest("[package]\nreadme = \"./docs/README.md\""),
+                None
+            ),
+            Some("docs/README.md".to_string())
+        );
+        assert_eq!(
+            readme_path("", &manifest("[package]\nreadme = false"), None),
+            None
+        );
+    }
+
+    #[test]
+    fn expanding_members() {
+        let members = vec!["crates/*".to_string(), "tools/xtask".to_string()];
+        let exclude = vec!["crates/sandbox".to_string()];
+        let dirs = ["crates/ash", "crates/sandbox", "examples/triangle"];
+        assert_eq!(
+            expand(&members, &exclude, &dirs),
+            vec!["crates/ash", "tools/xtask"]
+        );
+    }
+}
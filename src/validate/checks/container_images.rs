@@ -0,0 +1,160 @@
+use super::workflows::{self, Workflow};
+use crate::{
+    config::ContainerImages,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Check that a project with a Dockerfile has a workflow that publishes its
+/// image, and that the images its README tells people to pull or run are in
+/// one of the allowed registry namespaces. Projects without a Dockerfile
+/// pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &ContainerImages,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let (_, files) = project.repo_files(context).await?;
+    if !files.iter().any(|file| is_dockerfile(&file.path)) {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    let workflows = workflows::download(project, context).await?;
+    if !workflows.iter().any(publishes_image) {
+        problems.push("No workflow publishes the container image".to_string());
+    }
+    let readme = project
+        .download_optional_file(context, "README.md")
+        .await?
+        .unwrap_or_default();
+    let images = readme_images(&readme);
+    if images.is_empty() {
+        problems.push("The README does not show how to pull the container image".to_string());
+    }
+    let disallowed = images
+        .iter()
+        .filter(|image| !policy.allows(image))
+        .join(", ");
+    if !disallowed.is_empty() {
+        problems.push(format!(
+            "The README references images outside of {}: {}",
+            policy.namespaces.join(", "),
+            disallowed
+        ));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("; ")))
+    }
+}
+
+fn is_dockerfile(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".dockerfile")
+}
+
+/// Whether a workflow pushes an image to a registry.
+fn publishes_image(workflow: &Workflow) -> bool {
+    workflow.lines().any(|(_, code)| {
+        code.contains("docker/build-push-action")
+            || ["docker push ", "podman push ", "buildah push "]
+                .iter()
+                .any(|command| code.contains(command))
+    })
+}
+
+/// The images pulled or run by commands in a README, with the registry
+/// included, such as `ghcr.io/embarkstudios/tool`. Images without a
+/// registry are on Docker Hub.
+fn readme_images(readme: &str) -> Vec<String> {
+    lazy_static! {
+        static ref COMMAND: Regex = Regex::new(r"\b(?:docker|podman) (?:pull|run)\b").unwrap();
+        static ref IMAGE: Regex = Regex::new(
+            r"^[a-z0-9][a-z0-9._-]*(?::[0-9]+)?(?:/[a-z0-9._-]+)+(?::[\w.-]+|@sha256:[0-9a-f]+)?$"
+        )
+        .unwrap();
+    }
+    readme
+        .lines()
+        .filter_map(|line| line.get(COMMAND.find(line)?.end()..))
+        .flat_map(str::split_whitespace)
+        .map(|token| token.trim_matches(['`', '"', '\'']))
+        .filter(|token| IMAGE.is_match(token))
+        .map(without_version)
+        .unique()
+        .collect()
+}
+
+/// An image without its tag or digest, and with the registry included.
+fn without_version(image: &str) -> String {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = match image.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => name,
+        _ => image,
+    };
+    let registry = name.split('/').next().unwrap_or(name);
+    if registry.contains(['.', ':']) || registry == "localhost" {
+        name.to_string()
+    } else {
+        format!("docker.io/{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finding_dockerfiles() {
+        assert!(is_dockerfile("Dockerfile"));
+        assert!(is_dockerfile("docker/Dockerfile.release"));
+        assert!(is_dockerfile("ci/builder.dockerfile"));
+        assert!(!is_dockerfile("docs/Dockerfiles.md"));
+    }
+
+    #[test]
+    fn publishing_workflows() {
+        let workflow = |text: &str| Workflow {
+            path: ".github/workflows/release.yml".to_string(),
+            text: text.to_string(),
+        };
+        assert!(publishes_image(&workflow(
+            "steps:\n  - uses: docker/build-push-action@v4\n    with:\n      push: true\n"
+        )));
+        assert!(publishes_image(&workflow(
+            "      - run: docker push ghcr.io/embarkstudios/tool\n"
+        )));
+        assert!(!publishes_image(&workflow(
+            "      - run: docker build .\n      # docker push ghcr.io/embarkstudios/tool\n"
+        )));
+    }
+
+    #[test]
+    fn images_in_readmes() {
+        let readme = "\
+```sh
+docker pull ghcr.io/embarkstudios/tool:1.2.0
+docker run --rm -v $(pwd):/work -p 8080:80 embarkstudios/server@sha256:abcd1234
+podman run -it quay.io/someone/else:latest --help
+```
+Build it with `docker build .`
+";
+        assert_eq!(
+            readme_images(readme),
+            vec![
+                "ghcr.io/embarkstudios/tool",
+                "docker.io/embarkstudios/server",
+                "quay.io/someone/else",
+            ]
+        );
+    }
+}
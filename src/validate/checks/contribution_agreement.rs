@@ -0,0 +1,81 @@
+use super::workflows;
+use crate::{
+    config::{ContributionAgreement, ContributionAgreementPolicy},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// The configuration file of CLA assistant, <https://cla-assistant.io>.
+const CLA_ASSISTANT_FILE: &str = ".clabot";
+
+/// Check that the project enforces the contribution agreement the policy
+/// declares for it. The DCO must be a required status check of the default
+/// branch, and a CLA must be configured for CLA assistant, either the app or
+/// its GitHub Action.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &ContributionAgreementPolicy,
+) -> eyre::Result<()> {
+    match policy.agreement(&project.name) {
+        ContributionAgreement::None => Ok(()),
+        ContributionAgreement::Dco => {
+            let required = context
+                .client
+                .required_status_checks(&context.organisation.name, project.repo())
+                .await?;
+            if required.iter().any(|check| is_dco_check(check)) {
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "The DCO status check is not required on the default branch"
+                ))
+            }
+        }
+        ContributionAgreement::Cla => {
+            if project
+                .download_optional_file(context, CLA_ASSISTANT_FILE)
+                .await?
+                .is_some()
+            {
+                return Ok(());
+            }
+            let uses_action = workflows::download(project, context)
+                .await?
+                .iter()
+                .flat_map(|workflow| workflow.action_uses())
+                .any(|action| is_cla_action(action.action));
+            if uses_action {
+                Ok(())
+            } else {
+                Err(eyre!(
+                    "No {} file or CLA assistant workflow found",
+                    CLA_ASSISTANT_FILE
+                ))
+            }
+        }
+    }
+}
+
+fn is_dco_check(name: &str) -> bool {
+    name.eq_ignore_ascii_case("dco")
+}
+
+fn is_cla_action(action: &str) -> bool {
+    let action = action.to_ascii_lowercase();
+    action == "contributor-assistant/github-action" || action == "cla-assistant/github-action"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agreement_detection() {
+        assert!(is_dco_check("DCO"));
+        assert!(!is_dco_check("CI"));
+        assert!(is_cla_action("contributor-assistant/github-action"));
+        assert!(is_cla_action("cla-assistant/github-action"));
+        assert!(!is_cla_action("actions/checkout"));
+    }
+}
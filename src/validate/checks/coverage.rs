@@ -0,0 +1,82 @@
+use super::workflows::{self, Workflow};
+use crate::{
+    config::Coverage,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Codecov configuration files, in the locations Codecov looks for them.
+const CODECOV_FILES: [&str; 4] = [
+    "codecov.yml",
+    ".codecov.yml",
+    ".github/codecov.yml",
+    ".github/.codecov.yml",
+];
+
+/// Text in a workflow that means it measures or uploads coverage.
+const COVERAGE_TOOLS: [&str; 6] = [
+    "codecov/codecov-action",
+    "coverallsapp/github-action",
+    "cargo-llvm-cov",
+    "cargo llvm-cov",
+    "cargo-tarpaulin",
+    "grcov",
+];
+
+/// Check that Rust projects the config applies to report test coverage,
+/// either with a Codecov config file or a workflow step.
+pub async fn check(project: &Project, context: &Context, config: &Coverage) -> eyre::Result<()> {
+    if !config.applies_to(&project.name, context.website_tags(&project.name))
+        || project.uses_language("Rust") == Some(false)
+    {
+        return Ok(());
+    }
+    for path in CODECOV_FILES.iter() {
+        if project
+            .download_optional_file(context, path)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+    }
+    if workflows::download(project, context)
+        .await?
+        .iter()
+        .any(measures_coverage)
+    {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "No codecov.yml or workflow step reporting test coverage found"
+        ))
+    }
+}
+
+fn measures_coverage(workflow: &Workflow) -> bool {
+    workflow
+        .lines()
+        .any(|(_, code)| COVERAGE_TOOLS.iter().any(|tool| code.contains(tool)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_steps() {
+        let workflow = |text: &str| Workflow {
+            path: ".github/workflows/ci.yml".to_string(),
+            text: text.to_string(),
+        };
+        assert!(measures_coverage(&workflow(
+            "steps:\n  - run: cargo llvm-cov --lcov --output-path lcov.info\n"
+        )));
+        assert!(measures_coverage(&workflow(
+            "steps:\n  - uses: codecov/codecov-action@v3\n"
+        )));
+        assert!(!measures_coverage(&workflow(
+            "steps:\n  # - uses: codecov/codecov-action@v3\n  - run: cargo test\n"
+        )));
+    }
+}
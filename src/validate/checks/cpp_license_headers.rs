@@ -0,0 +1,101 @@
+use crate::{
+    config::CppLicenseHeaders,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// The extensions of C and C++ source and header files.
+const EXTENSIONS: [&str; 8] = [".c", ".cc", ".cpp", ".cxx", ".h", ".hh", ".hpp", ".hxx"];
+
+/// How many lines at the start of a file the license header must be within.
+const HEADER_LINES: usize = 20;
+
+/// Check that a sample of a C++ project's source files start with the license
+/// header, and that the project has a .clang-format so contributions match its
+/// style. Projects without C or C++ files, or that GitHub detects no C++ in,
+/// pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &CppLicenseHeaders,
+) -> eyre::Result<()> {
+    if project.uses_language("C++") == Some(false) {
+        return Ok(());
+    }
+    let (_, files) = project.repo_files(context).await?;
+    let sources: Vec<_> = files
+        .iter()
+        .map(|file| file.path.as_str())
+        .filter(|path| is_source(path, config))
+        .collect();
+    if sources.is_empty() {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    if config.clang_format && !files.iter().any(|file| file.path == ".clang-format") {
+        problems.push("No .clang-format found".to_string());
+    }
+    for path in sample(&sources, config.sample) {
+        if let Some(text) = project.download_optional_file(context, path).await? {
+            if !has_header(&text, config) {
+                problems.push(format!("{}: no license header", path));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("\n")).wrap_err("C++ files don't follow our conventions"))
+    }
+}
+
+fn is_source(path: &str, config: &CppLicenseHeaders) -> bool {
+    EXTENSIONS.iter().any(|extension| path.ends_with(extension))
+        && !config.exclude.iter().any(|glob| glob.matches(path))
+}
+
+/// Up to `count` files spread evenly over the list, so that the sample isn't
+/// all from one directory.
+fn sample<'a>(files: &[&'a str], count: usize) -> Vec<&'a str> {
+    let step = (files.len() / count.max(1)).max(1);
+    files.iter().step_by(step).take(count).copied().collect()
+}
+
+fn has_header(text: &str, config: &CppLicenseHeaders) -> bool {
+    text.lines()
+        .take(HEADER_LINES)
+        .any(|line| config.header.0.is_match(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling_sources() {
+        let config = CppLicenseHeaders::default();
+        assert!(is_source("src/physx.cpp", &config));
+        assert!(is_source("include/physx.h", &config));
+        assert!(!is_source("third_party/zlib/zlib.h", &config));
+        assert!(!is_source("src/lib.rs", &config));
+
+        let files = ["a.cpp", "b.cpp", "c.cpp", "d.cpp", "e.cpp", "f.cpp"];
+        assert_eq!(sample(&files, 3), vec!["a.cpp", "c.cpp", "e.cpp"]);
+        assert_eq!(sample(&files[..2], 3), vec!["a.cpp", "b.cpp"]);
+    }
+
+    #[test]
+    fn license_headers() {
+        let config = CppLicenseHeaders::default();
+        assert!(has_header(
+            "// Copyright (c) 2023 Embark Studios\n#pragma once\n",
+            &config
+        ));
+        assert!(has_header(
+            "/*\n * SPDX-License-Identifier: MIT OR Apache-2.0\n */\n",
+            &config
+        ));
+        assert!(!has_header("#pragma once\nint main() {}\n", &config));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    config::Dependabot,
+    github::Feature,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that Dependabot alerts, and security updates if the config requires
+/// them, are enabled for the project's repo.
+pub async fn check(project: &Project, context: &Context, config: &Dependabot) -> eyre::Result<()> {
+    let client = &context.client;
+    let alerts = client
+        .vulnerability_alerts_enabled(&context.organisation.name, project.repo())
+        .await?;
+    let security_updates = if config.security_updates {
+        let settings = client
+            .security_and_analysis(&context.organisation.name, project.repo())
+            .await?;
+        Feature::is_enabled(&settings.dependabot_security_updates)
+    } else {
+        true
+    };
+    verdict(alerts, security_updates)
+}
+
+fn verdict(alerts: bool, security_updates: bool) -> eyre::Result<()> {
+    match (alerts, security_updates) {
+        (true, true) => Ok(()),
+        (false, true) => Err(eyre!("Dependabot alerts are disabled")),
+        (true, false) => Err(eyre!("Dependabot security updates are disabled")),
+        (false, false) => Err(eyre!("Dependabot alerts and security updates are disabled")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_features_are_reported() {
+        assert!(verdict(true, true).is_ok());
+        let message =
+            |alerts, security_updates| verdict(alerts, security_updates).unwrap_err().to_string();
+        assert_eq!(message(false, true), "Dependabot alerts are disabled");
+        assert_eq!(
+            message(true, false),
+            "Dependabot security updates are disabled"
+        );
+        assert_eq!(
+            message(false, false),
+            "Dependabot alerts and security updates are disabled"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Checks implemented by external programs, so that organisation specific
+//! policies can be added without changing this tool.
+//!
+//! The program is given a JSON description of the project on stdin and must
+//! print a JSON result such as `{ "ok": false, "errors": ["Oh no"] }` to
+//! stdout.
+
+use crate::{
+    config::ExternalCheck,
+    github,
+    validate::{
+        context::{Context, OpenSourceWebsiteDataProject},
+        project::Project,
+    },
+};
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use std::{collections::HashSet, process::Stdio};
+use tokio::io::AsyncWriteExt;
+
+/// The information about a project given to external checks.
+#[derive(Debug, serde::Serialize)]
+pub(super) struct Input<'a> {
+    organisation: &'a str,
+    project: &'a str,
+    maintainers: Option<&'a HashSet<String>>,
+    repo: Option<&'a github::Repo>,
+    website_data: Option<&'a OpenSourceWebsiteDataProject>,
+}
+
+impl<'a> Input<'a> {
+    pub(super) fn new(
+        project: &'a Project,
+        context: &'a Context,
+        maintainers: Option<&'a HashSet<String>>,
+    ) -> Self {
+        Self {
+            organisation: &context.organisation.name,
+            project: &project.name,
+            maintainers,
+            repo: context.embark_github_repos.get(project.repo()),
+            website_data: context
+                .opensource_website_projects
+                .iter()
+                .find(|proj| proj.name == project.name),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Output {
+    ok: bool,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+impl Output {
+    /// Convert the output into the result of the check with the given name.
+    pub(super) fn into_result(self, check_name: &str) -> eyre::Result<()> {
+        if self.ok {
+            Ok(())
+        } else if self.errors.is_empty() {
+            Err(eyre!("{} failed", check_name))
+        } else {
+            Err(eyre!(self.errors.iter().join("\n"))).wrap_err(format!("{} failed", check_name))
+        }
+    }
+}
+
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    maintainers: Option<&HashSet<String>>,
+    external: &ExternalCheck,
+) -> eyre::Result<()> {
+    let input = Input::new(project, context, maintainers);
+    run(external, &serde_json::to_vec(&input)?)
+        .await
+        .wrap_err_with(|| format!("Unable to run external check {}", external.id))?
+        .into_result(&format!("External check {}", external.id))
+}
+
+async fn run(external: &ExternalCheck, input: &[u8]) -> eyre::Result<Output> {
+    let (program, args) = external
+        .command
+        .split_first()
+        .ok_or_else(|| eyre!("No command given"))?;
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .wrap_err_with(|| format!("Unable to start `{}`", program))?;
+
+    // Programs that don't need the input may exit without reading it
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    match stdin.write_all(input).await {
+        Err(error) if error.kind() != std::io::ErrorKind::BrokenPipe => return Err(error.into()),
+        _ => drop(stdin),
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "`{}` exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout).wrap_err("Unable to parse output as a JSON result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn external(script: &str) -> ExternalCheck {
+        ExternalCheck {
+            id: "test".to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn run_external_command() {
+        // The input is given on stdin
+        let output = run(
+            &external(r#"grep -q '"project"' && echo '{"ok": true}'"#),
+            br#"{"project": "name"}"#,
+        )
+        .await
+        .unwrap();
+        assert!(output.ok);
+
+        let output = run(
+            &external(r#"echo '{"ok": false, "errors": ["Oh no"]}'"#),
+            b"{}",
+        )
+        .await
+        .unwrap();
+        assert!(!output.ok);
+        assert_eq!(output.errors, vec!["Oh no".to_string()]);
+
+        // Failing commands and invalid output are errors
+        assert!(run(&external("exit 1"), b"{}").await.is_err());
+        assert!(run(&external("echo ok"), b"{}").await.is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use super::issue_response::first_response;
+use crate::{
+    config::FirstTimeContributors,
+    github::PullRequest,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The most recent pull requests looked at, as the comments and reviews of
+/// each one must be requested separately.
+const MAX_PULL_REQUESTS: usize = 30;
+
+/// Check that recent pull requests from first-time contributors have been
+/// merged or responded to by a maintainer, so that new contributors aren't
+/// ignored. Pull requests get the configured number of days to be responded
+/// to before they fail the check.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &FirstTimeContributors,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let client = &context.client;
+    let organisation = &context.organisation.name;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let since = now.saturating_sub(policy.window().as_secs());
+    let before = now.saturating_sub(policy.max_wait().as_secs());
+
+    let mut ignored = Vec::new();
+    for pull_request in client
+        .recent_pull_requests(organisation, project.repo(), MAX_PULL_REQUESTS)
+        .await?
+    {
+        if !needs_response(&pull_request, since, before) {
+            continue;
+        }
+        let number = pull_request.number;
+        let mut responses = client
+            .issue_comments(organisation, project.repo(), number)
+            .await?;
+        responses.extend(
+            client
+                .pull_request_reviews(organisation, project.repo(), number)
+                .await?,
+        );
+        if first_response(&responses).is_none() {
+            ignored.push(number);
+        }
+    }
+
+    if ignored.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Pull requests from first-time contributors have had no response from a maintainer \
+for more than {} days: {}",
+            policy.max_wait_days,
+            ignored
+                .iter()
+                .map(|number| format!("#{}", number))
+                .join(", ")
+        ))
+    }
+}
+
+/// Whether an unmerged pull request from a first-time contributor was
+/// opened between `since` and `before`, and so must have been responded to.
+fn needs_response(pull_request: &PullRequest, since: u64, before: u64) -> bool {
+    pull_request.is_first_contribution()
+        && pull_request.merged_at.is_none()
+        && pull_request
+            .time()
+            .is_some_and(|opened| opened >= since && opened < before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pull_requests_needing_responses() {
+        let pull_request = |association: &str, created_at: &str, merged: bool| PullRequest {
+            number: 1,
+            created_at: created_at.to_string(),
+            author_association: association.to_string(),
+            merged_at: merged.then(|| "2023-01-20T00:00:00Z".to_string()),
+        };
+        // 2023-01-01 to 2023-01-25
+        let (since, before) = (1_672_531_200, 1_674_604_800);
+        assert!(needs_response(
+            &pull_request("FIRST_TIME_CONTRIBUTOR", "2023-01-10T00:00:00Z", false),
+            since,
+            before
+        ));
+        assert!(!needs_response(
+            &pull_request("FIRST_TIME_CONTRIBUTOR", "2023-01-10T00:00:00Z", true),
+            since,
+            before
+        ));
+        assert!(!needs_response(
+            &pull_request("FIRST_TIMER", "2023-01-30T00:00:00Z", false),
+            since,
+            before
+        ));
+        assert!(!needs_response(
+            &pull_request("FIRST_TIMER", "2022-12-30T00:00:00Z", false),
+            since,
+            before
+        ));
+        assert!(!needs_response(
+            &pull_request("CONTRIBUTOR", "2023-01-10T00:00:00Z", false),
+            since,
+            before
+        ));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    config::GoodFirstIssues,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that the project has open issues labelled as suitable for new
+/// contributors. Projects the policy doesn't apply to always pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &GoodFirstIssues,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let count = context
+        .client
+        .open_issues_with_label(&context.organisation.name, project.repo(), &policy.label)
+        .await?;
+    if count >= policy.minimum {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} open issues labelled \"{}\", at least {} required",
+            count,
+            policy.label,
+            policy.minimum
+        ))
+    }
+}
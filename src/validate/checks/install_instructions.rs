@@ -0,0 +1,128 @@
+use crate::{
+    config::InstallInstructions,
+    validate::{context::Context, project::Project},
+};
+use eyre::{eyre, WrapErr};
+
+/// Commands that install a tool, any of which in the README documents how to
+/// install it.
+const INSTALL_COMMANDS: [&str; 4] = [
+    "cargo install",
+    "cargo binstall",
+    "brew install",
+    "scoop install",
+];
+
+/// Check that command line tools can be installed by end users, either with
+/// `cargo binstall` through `[package.metadata.binstall]` in Cargo.toml or by
+/// following install instructions in the README. Projects that aren't tagged
+/// as tools, or that don't build a binary when no tags are configured, pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &InstallInstructions,
+) -> eyre::Result<()> {
+    let manifest = match project
+        .download_optional_file(context, "Cargo.toml")
+        .await?
+    {
+        Some(text) => Some(toml::from_str(&text).wrap_err("Unable to parse Cargo.toml")?),
+        None => None,
+    };
+    let is_tool = if policy.tags.is_empty() {
+        match &manifest {
+            Some(manifest) => {
+                has_binary(manifest)
+                    || (manifest.get("package").is_some()
+                        && project
+                            .download_optional_file(context, "src/main.rs")
+                            .await?
+                            .is_some())
+            }
+            None => false,
+        }
+    } else {
+        let tags = context.website_tags(&project.name);
+        policy.tags.iter().any(|tag| tags.contains(tag))
+    };
+    if !is_tool || manifest.as_ref().is_some_and(has_binstall_metadata) {
+        return Ok(());
+    }
+
+    let readme = project
+        .download_optional_file(context, "README.md")
+        .await?
+        .unwrap_or_default();
+    if documents_install(&readme) {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "No install instructions in the README and no [package.metadata.binstall] in Cargo.toml"
+        ))
+    }
+}
+
+/// Whether a manifest declares a `[[bin]]` target.
+fn has_binary(manifest: &toml::Value) -> bool {
+    manifest
+        .get("bin")
+        .and_then(toml::Value::as_array)
+        .is_some_and(|bins| !bins.is_empty())
+}
+
+fn has_binstall_metadata(manifest: &toml::Value) -> bool {
+    manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("binstall"))
+        .is_some()
+}
+
+fn documents_install(readme: &str) -> bool {
+    let readme = readme.to_ascii_lowercase();
+    INSTALL_COMMANDS
+        .iter()
+        .any(|command| readme.contains(command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binaries_and_binstall_metadata() {
+        let manifest = |text: &str| -> toml::Value { toml::from_str(text).unwrap() };
+        let tool = manifest(
+            r#"
+[package]
+name = "cargo-deny"
+
+[[bin]]
+name = "cargo-deny"
+path = "src/cargo-deny/main.rs"
+
+[package.metadata.binstall]
+pkg-url = "{ repo }/releases/download/{ version }/{ name }-{ version }-{ target }.tar.gz"
+"#,
+        );
+        assert!(has_binary(&tool));
+        assert!(has_binstall_metadata(&tool));
+
+        let library = manifest("[package]\nname = \"ash\"\n[lib]\nname = \"ash\"");
+        assert!(!has_binary(&library));
+        assert!(!has_binstall_metadata(&library));
+    }
+
+    #[test]
+    fn install_instructions() {
+        assert!(documents_install(
+            "## Installation\n\n```sh\ncargo install --locked cargo-deny\n```"
+        ));
+        assert!(documents_install(
+            "Install with `Brew Install embark/tap/tool`"
+        ));
+        assert!(!documents_install(
+            "Add `ash = \"0.37\"` to your Cargo.toml"
+        ));
+    }
+}
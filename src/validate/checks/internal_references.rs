@@ -0,0 +1,77 @@
+use super::workflows;
+use crate::{
+    config::InternalReferences,
+    scan,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// Check that the project's README, workflows, and config files don't
+/// mention internal host names, ticket URLs, or code names, which are easily
+/// left behind when a repo is open sourced.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &InternalReferences,
+) -> eyre::Result<()> {
+    let mut found = Vec::new();
+    for path in &config.files {
+        if let Some(text) = project.download_optional_file(context, path).await? {
+            found.extend(findings(path, &text, config));
+        }
+    }
+    for workflow in workflows::download(project, context).await? {
+        found.extend(findings(&workflow.path, &workflow.text, config));
+    }
+
+    if found.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(found.iter().join("\n")).wrap_err("Internal references found in files"))
+    }
+}
+
+/// Every internal reference the config lists that is in the text of a file.
+pub fn findings(path: &str, text: &str, config: &InternalReferences) -> Vec<scan::Finding> {
+    let mut found = scan::hostnames(path, text, &config.hostnames);
+    found.extend(scan::patterns(path, text, &config.patterns));
+    found.extend(scan::code_names(path, text, &config.code_names));
+    found.sort_by_key(|finding| finding.line);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn references_in_order() {
+        let config = Config::parse(
+            r#"
+[internal-references]
+hostnames = ["corp.embark.net"]
+patterns = ['jira\.embark\.net']
+code-names = ["Falcon"]
+"#,
+        )
+        .unwrap()
+        .internal_references
+        .unwrap();
+        let text =
+            "# Falcon\nSee https://jira.embark.net/browse/TECH-1\nhttps://ci.corp.embark.net\n";
+        let found: Vec<_> = findings("README.md", text, &config)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                "README.md:1: internal code name Falcon",
+                "README.md:2: internal reference matching `jira\\.embark\\.net`",
+                "README.md:3: internal host name corp.embark.net",
+            ]
+        );
+    }
+}
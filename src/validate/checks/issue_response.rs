@@ -0,0 +1,109 @@
+use crate::{
+    config::IssueResponse,
+    github::{self, IssueComment},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The most recent issues looked at, as the comments of each one must be
+/// requested separately.
+const MAX_ISSUES: usize = 30;
+
+/// Check that the median time for a maintainer to first respond to recent
+/// issues opened by people outside the project is within the config's SLA.
+/// Issues that are still waiting for a response count as having waited
+/// until now.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &IssueResponse,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let organisation = &context.organisation.name;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let since = now.saturating_sub(policy.window().as_secs());
+
+    let mut waits = Vec::new();
+    for issue in context
+        .client
+        .recent_issues(organisation, project.repo(), MAX_ISSUES)
+        .await?
+    {
+        let opened = match issue.time() {
+            Some(opened) if opened >= since => opened,
+            _ => continue,
+        };
+        if github::is_maintainer_association(&issue.author_association) {
+            continue;
+        }
+        let comments = context
+            .client
+            .issue_comments(organisation, project.repo(), issue.number)
+            .await?;
+        let responded = first_response(&comments).unwrap_or(now);
+        waits.push(responded.saturating_sub(opened));
+    }
+
+    if waits.len() < policy.minimum_issues {
+        return Ok(());
+    }
+    let median = Duration::from_secs(median(&mut waits));
+    if median <= policy.max_median() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Median first response to the last {} issues took {} hours, more than {} hours",
+            waits.len(),
+            median.as_secs() / 3600,
+            policy.max_median_hours
+        ))
+    }
+}
+
+/// When a maintainer first commented on an issue.
+pub fn first_response(comments: &[IssueComment]) -> Option<u64> {
+    comments
+        .iter()
+        .filter(|comment| github::is_maintainer_association(&comment.author_association))
+        .filter_map(IssueComment::time)
+        .min()
+}
+
+fn median(values: &mut [u64]) -> u64 {
+    values.sort_unstable();
+    match values.len() {
+        0 => 0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2,
+        len => values[len / 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintainer_responses() {
+        let comment = |created_at: &str, author_association: &str| IssueComment {
+            created_at: created_at.to_string(),
+            author_association: author_association.to_string(),
+        };
+        let comments = vec![
+            comment("2021-03-01T10:00:00Z", "NONE"),
+            comment("2021-03-02T10:00:00Z", "MEMBER"),
+            comment("2021-03-03T10:00:00Z", "OWNER"),
+        ];
+        assert_eq!(first_response(&comments), Some(1_614_679_200));
+        assert_eq!(first_response(&comments[..1]), None);
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&mut [5, 1, 3]), 3);
+        assert_eq!(median(&mut [4, 1, 3, 2]), 2);
+        assert_eq!(median(&mut []), 0);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    config::LicenseYear,
+    github,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Check that the copyright years of a project's license files include the
+/// current year if the repo has been pushed to this year. Files without
+/// copyright years, and repos that haven't changed this year, pass.
+pub async fn check(project: &Project, context: &Context, config: &LicenseYear) -> eyre::Result<()> {
+    let year = github::year_of(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
+    let pushed = context
+        .embark_github_repos
+        .get(project.repo())
+        .and_then(github::Repo::pushed_time)
+        .map(github::year_of);
+    if pushed != Some(year) {
+        return Ok(());
+    }
+
+    let mut stale = Vec::new();
+    for path in &config.files {
+        if let Some(text) = project.download_optional_file(context, path).await? {
+            if let Some(latest) = latest_copyright_year(&text) {
+                if latest < year {
+                    stale.push(format!("{} ({})", path, latest));
+                }
+            }
+        }
+    }
+    if stale.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Copyright years do not include {}, the repo was changed this year: {}",
+            year,
+            stale.join(", ")
+        ))
+    }
+}
+
+/// The latest year on any copyright line, such as 2022 for
+/// "Copyright (c) 2019-2022 Embark Studios".
+fn latest_copyright_year(text: &str) -> Option<i64> {
+    lazy_static! {
+        static ref YEAR: Regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+    }
+    text.lines()
+        .filter(|line| line.to_ascii_lowercase().contains("copyright"))
+        .flat_map(|line| YEAR.find_iter(line))
+        .filter_map(|year| year.as_str().parse().ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copyright_years() {
+        assert_eq!(
+            latest_copyright_year("MIT License\n\nCopyright (c) 2019-2022 Embark Studios\n"),
+            Some(2022)
+        );
+        assert_eq!(
+            latest_copyright_year("Copyright 2020, 2021 Embark Studios AB"),
+            Some(2021)
+        );
+        assert_eq!(
+            latest_copyright_year("Apache License\nVersion 2.0, January 2004\n   Copyright [yyyy] [name of copyright owner]"),
+            None
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    config::MaintenanceBanner,
+    validate::{checks::badges::BadgeStandard, context::Context, project::Project},
+};
+use eyre::eyre;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The maintenance status a README's banner claims.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Active,
+    Maintenance,
+    Archived,
+}
+
+/// Check that the README has a maintenance status banner, and that it agrees
+/// with the state of the repo, as a stale banner misleads users about whether
+/// the project is looked after. The banners of the badge definitions are used
+/// if `[badges]` is configured. Projects without a README are left to the
+/// `required-files` checks.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &MaintenanceBanner,
+) -> eyre::Result<()> {
+    let repo = match context.embark_github_repos.get(project.repo()) {
+        Some(repo) => repo,
+        None => return Ok(()),
+    };
+    let readme = match project.download_optional_file(context, "README.md").await? {
+        Some(readme) => readme,
+        None => return Ok(()),
+    };
+    let config = match &context.config.badges {
+        Some(badges) => BadgeStandard::get(badges)
+            .await?
+            .maintenance_banner(config)?,
+        None => config.clone(),
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let inactive_days = repo
+        .pushed_time()
+        .map(|pushed| now.saturating_sub(pushed) / (24 * 60 * 60));
+    consistent(&readme, repo.archived, inactive_days, &config).map_err(|error| eyre!(error))
+}
+
+/// The statuses whose banners are in the README.
+pub fn statuses(readme: &str, config: &MaintenanceBanner) -> Vec<Status> {
+    [
+        (Status::Active, &config.active),
+        (Status::Maintenance, &config.maintenance),
+        (Status::Archived, &config.archived),
+    ]
+    .iter()
+    .filter(|(_, pattern)| pattern.0.is_match(readme))
+    .map(|(status, _)| *status)
+    .collect()
+}
+
+/// Whether the banner of a README agrees with whether the repo is archived
+/// and how many days ago it was last pushed to.
+pub fn consistent(
+    readme: &str,
+    archived: bool,
+    inactive_days: Option<u64>,
+    config: &MaintenanceBanner,
+) -> Result<(), String> {
+    let status = match statuses(readme, config).as_slice() {
+        [] => return Err("The README has no maintenance status banner".to_string()),
+        [status] => *status,
+        _ => return Err("The README has conflicting maintenance status banners".to_string()),
+    };
+    match (status, archived) {
+        (Status::Archived, false) => {
+            Err("The README says the project is archived, but the repo is not".to_string())
+        }
+        (Status::Active | Status::Maintenance, true) => {
+            Err("The repo is archived, but the README doesn't say so".to_string())
+        }
+        (Status::Active, false) => match inactive_days {
+            Some(days) if days > config.inactive_days => Err(format!(
+                "The README says the project is actively maintained, but the repo hasn't been \
+pushed to in {} days",
+                days
+            )),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consistent_banners() {
+        let config = MaintenanceBanner::default();
+        let active =
+            "[![](https://img.shields.io/badge/maintenance-actively--developed-brightgreen.svg)]";
+        let maintenance =
+            "[![](https://img.shields.io/badge/maintenance-passively--maintained-yellowgreen.svg)]";
+        let archived = "[![](https://img.shields.io/badge/maintenance-archived-red.svg)]";
+        assert_eq!(statuses(active, &config), vec![Status::Active]);
+        assert_eq!(statuses(maintenance, &config), vec![Status::Maintenance]);
+        assert_eq!(statuses(archived, &config), vec![Status::Archived]);
+
+        assert!(consistent(active, false, Some(30), &config).is_ok());
+        assert!(consistent(active, false, Some(400), &config).is_err());
+        assert!(consistent(active, true, Some(30), &config).is_err());
+        assert!(consistent(maintenance, false, Some(400), &config).is_ok());
+        assert!(consistent(maintenance, true, Some(400), &config).is_err());
+        assert!(consistent(archived, true, None, &config).is_ok());
+        assert!(consistent(archived, false, None, &config).is_err());
+        assert!(consistent("# Ash", false, None, &config).is_err());
+        assert!(consistent(&format!("{}{}", active, archived), true, None, &config).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use super::workflows::{self, Workflow};
+use crate::validate::{context::Context, project::Project};
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Check that Rust projects declare their minimum supported Rust version,
+/// with `rust-version` in Cargo.toml or in the README, and that any CI job
+/// testing the MSRV uses the declared version. Projects without a
+/// Cargo.toml pass.
+pub async fn check(project: &Project, context: &Context) -> eyre::Result<()> {
+    let manifest = match project
+        .download_optional_file(context, "Cargo.toml")
+        .await?
+    {
+        Some(text) => text,
+        None => return Ok(()),
+    };
+    let declared = match cargo_rust_version(&manifest)? {
+        Some(version) => version,
+        None => match project.download_optional_file(context, "README.md").await? {
+            Some(readme) => readme_msrv(&readme).ok_or_else(|| {
+                eyre!(
+                    "No MSRV declared, set `rust-version` in Cargo.toml or state it in the README"
+                )
+            })?,
+            None => return Err(eyre!("No MSRV declared, set `rust-version` in Cargo.toml")),
+        },
+    };
+
+    let tested = workflows::download(project, context)
+        .await?
+        .iter()
+        .flat_map(ci_msrv_versions)
+        .unique()
+        .collect_vec();
+    if tested.is_empty()
+        || tested
+            .iter()
+            .any(|version| same_version(version, &declared))
+    {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "The declared MSRV is {} but CI tests the MSRV with {}",
+            declared,
+            tested.join(", ")
+        ))
+    }
+}
+
+/// The `rust-version` of the package, or of the workspace for workspaces
+/// and packages that inherit it.
+fn cargo_rust_version(manifest: &str) -> eyre::Result<Option<String>> {
+    let manifest: toml::Value = toml::from_str(manifest).wrap_err("Unable to parse Cargo.toml")?;
+    let version = |table: Option<&toml::Value>| {
+        table?
+            .get("rust-version")?
+            .as_str()
+            .map(ToString::to_string)
+    };
+    Ok(version(manifest.get("package"))
+        .or_else(|| version(manifest.get("workspace").and_then(|w| w.get("package")))))
+}
+
+/// An MSRV stated in a README, such as "MSRV: 1.60" or "Minimum Supported
+/// Rust Version is 1.60.0", or in a shields.io MSRV badge.
+fn readme_msrv(readme: &str) -> Option<String> {
+    lazy_static! {
+        static ref MSRV: Regex = Regex::new(
+            r"(?i)(?:\bmsrv\b|minimum supported rust version|rustc[ -])[^0-9]{0,60}(1\.\d+(?:\.\d+)?)"
+        )
+        .unwrap();
+    }
+    Some(MSRV.captures(readme)?[1].to_string())
+}
+
+/// The Rust versions installed by a workflow that tests the MSRV, found by
+/// the workflow mentioning the MSRV.
+fn ci_msrv_versions(workflow: &Workflow) -> Vec<String> {
+    lazy_static! {
+        static ref TOOLCHAIN: Regex = Regex::new(
+            r#"(?:toolchain\s*:\s*["']?|rust-toolchain@|toolchain install |cargo \+)(1\.\d+(?:\.\d+)?)"#
+        )
+        .unwrap();
+    }
+    if !workflow.text.to_ascii_lowercase().contains("msrv") {
+        return Vec::new();
+    }
+    workflow
+        .lines()
+        .filter_map(|(_, code)| Some(TOOLCHAIN.captures(code)?[1].to_string()))
+        .collect()
+}
+
+/// Whether two versions are the same, treating a missing patch version as 0.
+fn same_version(a: &str, b: &str) -> bool {
+    let parts = |version: &str| {
+        let mut parts: Vec<_> = version.split('.').map(str::to_string).collect();
+        parts.resize(3, "0".to_string());
+        parts
+    };
+    parts(a) == parts(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declared_versions() {
+        let version = |text: &str| cargo_rust_version(text).unwrap();
+        assert_eq!(
+            version("[package]\nname = \"ash\"\nrust-version = \"1.60\""),
+            Some("1.60".to_string())
+        );
+        assert_eq!(
+            version("[workspace]\nmembers = []\n[workspace.package]\nrust-version = \"1.65.0\""),
+            Some("1.65.0".to_string())
+        );
+        assert_eq!(version("[package]\nname = \"ash\""), None);
+
+        assert_eq!(
+            readme_msrv("## Minimum Supported Rust Version\n\nThis crate requires Rust 1.59."),
+            Some("1.59".to_string())
+        );
+        assert_eq!(
+            readme_msrv("![MSRV](https://img.shields.io/badge/rustc-1.60+-ab6000.svg)"),
+            Some("1.60".to_string())
+        );
+        assert_eq!(readme_msrv("# Ash\nVulkan bindings for Rust 2021"), None);
+    }
+
+    #[test]
+    fn ci_versions() {
+        let workflow = |text: &str| Workflow {
+            path: ".github/workflows/ci.yml".to_string(),
+            text: text.to_string(),
+        };
+        assert_eq!(
+            ci_msrv_versions(&workflow(
+                "jobs:\n  msrv:\n    steps:\n      - uses: dtolnay/rust-toolchain@1.60\n      - run: cargo +1.60.0 check\n"
+            )),
+            vec!["1.60", "1.60.0"]
+        );
+        assert!(ci_msrv_versions(&workflow(
+            "jobs:\n  test:\n    steps:\n      - uses: dtolnay/rust-toolchain@1.70\n"
+        ))
+        .is_empty());
+        assert!(same_version("1.60", "1.60.0"));
+        assert!(!same_version("1.60", "1.61"));
+    }
+}
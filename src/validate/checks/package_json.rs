@@ -0,0 +1,123 @@
+use crate::{
+    config::PackageJson,
+    validate::{context::Context, project::Project},
+};
+use eyre::{eyre, WrapErr};
+use serde_json::Value;
+
+/// The lockfiles of the package managers our web projects use.
+pub const LOCKFILES: [&str; 4] = [
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+];
+
+/// Check that the package.json of a JavaScript or TypeScript project has the
+/// metadata npm shows, such as a license and repository, and that its
+/// dependencies are locked. Projects without a package.json, or that GitHub
+/// detects neither language in, pass.
+pub async fn check(project: &Project, context: &Context, config: &PackageJson) -> eyre::Result<()> {
+    if project.uses_language("JavaScript") == Some(false)
+        && project.uses_language("TypeScript") == Some(false)
+    {
+        return Ok(());
+    }
+    let manifest: Value = match project
+        .download_optional_file(context, "package.json")
+        .await?
+    {
+        Some(text) => serde_json::from_str(&text).wrap_err("Unable to parse package.json")?,
+        None => return Ok(()),
+    };
+
+    let mut has_lockfile = false;
+    for path in LOCKFILES.iter() {
+        if project.has_file(context, path).await? {
+            has_lockfile = true;
+            break;
+        }
+    }
+
+    let problems = problems(&manifest, &config.fields, has_lockfile || !config.lockfile);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("\n")).wrap_err("Incomplete package.json"))
+    }
+}
+
+/// The problems with a package.json. Private packages are not published, so
+/// only need a lockfile.
+fn problems(manifest: &Value, fields: &[String], has_lockfile: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    let private = manifest
+        .get("private")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if !private {
+        for field in fields {
+            if !has_field(manifest, field) {
+                problems.push(format!("package.json: missing {}", field));
+            }
+        }
+    }
+    if !has_lockfile {
+        problems.push(format!(
+            "No lockfile found, commit one of {}",
+            LOCKFILES.join(", ")
+        ));
+    }
+    problems
+}
+
+/// Whether a field is set to something other than an empty value. The
+/// `repository` may be given as a string or as an object with a `url`.
+fn has_field(manifest: &Value, field: &str) -> bool {
+    match manifest.get(field) {
+        Some(Value::String(value)) => !value.trim().is_empty(),
+        Some(Value::Object(object)) if field == "repository" => object
+            .get("url")
+            .and_then(Value::as_str)
+            .is_some_and(|url| !url.trim().is_empty()),
+        Some(Value::Null) | None => false,
+        Some(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<String> {
+        PackageJson::default().fields
+    }
+
+    #[test]
+    fn package_json_problems() {
+        let manifest = serde_json::json!({
+            "name": "@embarkstudios/web-ui",
+            "license": "MIT OR Apache-2.0",
+            "repository": { "type": "git", "url": "" },
+        });
+        assert_eq!(
+            problems(&manifest, &fields(), false),
+            vec![
+                "package.json: missing repository",
+                "No lockfile found, commit one of package-lock.json, yarn.lock, pnpm-lock.yaml, \
+bun.lockb",
+            ]
+        );
+
+        let manifest = serde_json::json!({
+            "name": "web-ui",
+            "license": "MIT",
+            "repository": "github:EmbarkStudios/web-ui",
+        });
+        assert!(problems(&manifest, &fields(), true).is_empty());
+
+        let private = serde_json::json!({ "name": "website", "private": true });
+        assert!(problems(&private, &fields(), true).is_empty());
+        assert_eq!(problems(&private, &fields(), false).len(), 1);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    config::ReadmeLinks,
+    github::memo::Memo,
+    http::Traced,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{convert::Infallible, sync::OnceLock, time::Duration};
+use tokio::sync::Semaphore;
+
+/// Limits how many links are requested at once across every project, as
+/// projects are validated concurrently.
+static REQUESTS: OnceLock<Semaphore> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LinkStatus {
+    Alive,
+    Dead(String),
+    /// The link could not be checked, such as because the request timed out
+    /// or the site rejects automated requests. It is not reported.
+    Unknown,
+}
+
+/// Check that the links in the project's README resolve, so broken links
+/// don't greet people finding the project.
+pub async fn check(project: &Project, context: &Context, config: &ReadmeLinks) -> eyre::Result<()> {
+    let readme = match project.download_optional_file(context, "README.md").await? {
+        Some(readme) => readme,
+        // Whether there must be a README is up to the required files
+        None => return Ok(()),
+    };
+
+    let links = links(&readme)
+        .into_iter()
+        .filter(|link| !config.ignore.iter().any(|glob| glob.matches(link)));
+    let statuses = futures::future::join_all(links.map(|link| async move {
+        let status = link_status(&link, config).await;
+        (link, status)
+    }))
+    .await;
+    let dead = statuses
+        .into_iter()
+        .filter_map(|(link, status)| match status {
+            LinkStatus::Dead(reason) => Some(format!("{} ({})", link, reason)),
+            LinkStatus::Alive | LinkStatus::Unknown => None,
+        })
+        .join("\n");
+
+    if dead.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(dead).wrap_err("Dead links in README.md"))
+    }
+}
+
+/// The distinct absolute links in a markdown document, in the order they
+/// first appear. Relative links are not included.
+fn links(markdown: &str) -> Vec<String> {
+    lazy_static! {
+        static ref LINK: Regex = Regex::new(r#"https?://[^\s()<>\[\]"'`]+"#).unwrap();
+    }
+    LINK.find_iter(markdown)
+        .map(|link| {
+            link.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '*'])
+                .to_string()
+        })
+        .unique()
+        .collect()
+}
+
+/// Request a link, once per run no matter how many READMEs contain it.
+async fn link_status(link: &str, config: &ReadmeLinks) -> LinkStatus {
+    lazy_static! {
+        static ref STATUSES: Memo<String, LinkStatus, Infallible> = Memo::new();
+    }
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    let concurrency = config.concurrency;
+    let link = link.to_string();
+    let status = STATUSES
+        .get_or_fetch(link.clone(), async move {
+            let requests = REQUESTS.get_or_init(|| Semaphore::new(concurrency));
+            let _permit = requests.acquire().await.expect("Semaphore closed");
+            let response = crate::http::client()
+                .get(&link)
+                .header("user-agent", "embark-oss")
+                .timeout(timeout)
+                .send_traced()
+                .await;
+            Ok(match response {
+                Ok(response) => classify(response.status().as_u16()),
+                Err(error) if error.is_connect() => LinkStatus::Dead("unreachable".to_string()),
+                Err(_) => LinkStatus::Unknown,
+            })
+        })
+        .await;
+    match status {
+        Ok(status) => status,
+        Err(never) => match never {},
+    }
+}
+
+/// Only responses that mean the page is gone count as dead. Others such as
+/// 403 or 429 are often sites turning away automated requests.
+fn classify(status: u16) -> LinkStatus {
+    match status {
+        200..=399 => LinkStatus::Alive,
+        404 | 410 => LinkStatus::Dead(format!("HTTP {}", status)),
+        _ => LinkStatus::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracting_links() {
+        let readme = r#"# Ash
+[![Crates.io](https://img.shields.io/crates/v/ash.svg)](https://crates.io/crates/ash)
+See <https://docs.rs/ash>, or the [guide](docs/guide.md).
+<img src="https://embark.dev/logo.png" width="100">
+[Vulkan]: https://www.vulkan.org/.
+Again: https://docs.rs/ash
+"#;
+        assert_eq!(
+            links(readme),
+            vec![
+                "https://img.shields.io/crates/v/ash.svg",
+                "https://crates.io/crates/ash",
+                "https://docs.rs/ash",
+                "https://embark.dev/logo.png",
+                "https://www.vulkan.org/",
+            ]
+        );
+    }
+
+    #[test]
+    fn classifying_responses() {
+        assert_eq!(classify(200), LinkStatus::Alive);
+        assert_eq!(classify(301), LinkStatus::Alive);
+        assert_eq!(classify(404), LinkStatus::Dead("HTTP 404".to_string()));
+        assert_eq!(classify(403), LinkStatus::Unknown);
+        assert_eq!(classify(503), LinkStatus::Unknown);
+    }
+}
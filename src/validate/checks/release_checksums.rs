@@ -0,0 +1,172 @@
+use crate::{
+    config::ReleaseChecksums,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use std::collections::HashSet;
+
+const CHECKSUM_SUFFIXES: [&str; 4] = [".sha256", ".sha256sum", ".sha512", ".sha512sum"];
+const SIGNATURE_SUFFIXES: [&str; 4] = [".sig", ".asc", ".minisig", ".pem"];
+
+/// Check that every binary asset of the latest release has a checksum, and a
+/// signature if the config says so. Checksums can be separate `.sha256`
+/// assets or lines of a combined list such as `SHA256SUMS`, and a signed list
+/// signs every asset in it. Projects that don't release binaries pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &ReleaseChecksums,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let release = match project.latest_release(context).await? {
+        Some(release) if !release.assets.is_empty() => release,
+        _ => return Ok(()),
+    };
+
+    let mut listed = HashSet::new();
+    for asset in &release.assets {
+        if is_checksum_list(&asset.name) {
+            let text = asset.download_text().await?;
+            listed.extend(checksum_list_names(&text));
+        }
+    }
+    let names: Vec<_> = release
+        .assets
+        .iter()
+        .map(|asset| asset.name.as_str())
+        .collect();
+    let problems = problems(&names, &listed, policy.require_signatures);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Release {}: {}",
+            release.tag_name,
+            problems.join("; ")
+        ))
+    }
+}
+
+fn strip_any<'a>(name: &'a str, suffixes: &[&str]) -> Option<&'a str> {
+    suffixes.iter().find_map(|suffix| name.strip_suffix(suffix))
+}
+
+/// Whether an asset is a list of checksums for several assets, such as
+/// `SHA256SUMS` or `checksums.txt`.
+fn is_checksum_list(name: &str) -> bool {
+    let name = name.to_lowercase();
+    strip_any(&name, &CHECKSUM_SUFFIXES).is_none()
+        && strip_any(&name, &SIGNATURE_SUFFIXES).is_none()
+        && (name.contains("checksums")
+            || name.contains("sha256sums")
+            || name.contains("sha512sums"))
+}
+
+/// The file names in a list of checksums in the format of `sha256sum`, where
+/// binary mode names start with `*`.
+fn checksum_list_names(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|name| name.trim_start_matches('*').to_string())
+}
+
+/// Describe the binary assets without checksums or signatures, given the
+/// names of every asset and the names listed in checksum lists.
+fn problems(names: &[&str], listed: &HashSet<String>, require_signatures: bool) -> Vec<String> {
+    let checksummed: HashSet<_> = names
+        .iter()
+        .filter_map(|name| strip_any(name, &CHECKSUM_SUFFIXES))
+        .collect();
+    let signed: HashSet<_> = names
+        .iter()
+        .filter_map(|name| strip_any(name, &SIGNATURE_SUFFIXES))
+        .collect();
+    let lists: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| is_checksum_list(name))
+        .collect();
+    let binaries: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| {
+            strip_any(name, &CHECKSUM_SUFFIXES).is_none()
+                && strip_any(name, &SIGNATURE_SUFFIXES).is_none()
+                && !is_checksum_list(name)
+        })
+        .collect();
+
+    let mut problems = Vec::new();
+    let without_checksums: Vec<_> = binaries
+        .iter()
+        .copied()
+        .filter(|name| !checksummed.contains(name) && !listed.contains(*name))
+        .collect();
+    if !without_checksums.is_empty() {
+        problems.push(format!("no checksums for {}", without_checksums.join(", ")));
+    }
+    if require_signatures {
+        let lists_signed = !lists.is_empty() && lists.iter().all(|list| signed.contains(list));
+        let without_signatures: Vec<_> = binaries
+            .iter()
+            .copied()
+            .filter(|name| !(signed.contains(name) || lists_signed && listed.contains(*name)))
+            .collect();
+        if !without_signatures.is_empty() {
+            problems.push(format!(
+                "no signatures for {}",
+                without_signatures.join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_lists() {
+        assert!(is_checksum_list("SHA256SUMS"));
+        assert!(is_checksum_list("tool_1.0.0_checksums.txt"));
+        assert!(!is_checksum_list("SHA256SUMS.sig"));
+        assert!(!is_checksum_list("tool.tar.gz.sha256"));
+        let names: Vec<_> = checksum_list_names(
+            "e3b0c442  tool-x86_64-linux.tar.gz\nd7a8fbb3 *tool-x86_64-windows.zip\n\n",
+        )
+        .collect();
+        assert_eq!(
+            names,
+            vec!["tool-x86_64-linux.tar.gz", "tool-x86_64-windows.zip"]
+        );
+    }
+
+    #[test]
+    fn assets_without_checksums_or_signatures() {
+        let names = [
+            "tool-linux.tar.gz",
+            "tool-linux.tar.gz.sha256",
+            "tool-macos.tar.gz",
+            "tool-windows.zip",
+            "SHA256SUMS",
+        ];
+        let listed: HashSet<_> = vec!["tool-windows.zip".to_string()].into_iter().collect();
+        assert_eq!(
+            problems(&names, &listed, false),
+            vec!["no checksums for tool-macos.tar.gz"]
+        );
+        assert_eq!(
+            problems(&names, &listed, true),
+            vec![
+                "no checksums for tool-macos.tar.gz",
+                "no signatures for tool-linux.tar.gz, tool-macos.tar.gz, tool-windows.zip",
+            ]
+        );
+
+        let names = ["tool-windows.zip", "SHA256SUMS", "SHA256SUMS.sig"];
+        assert!(problems(&names, &listed, true).is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{
+    config::ReleaseDrift,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that the default branch is not too many commits ahead of the latest
+/// release, so that fixes reach users. Projects that have never been
+/// released, or that the policy doesn't apply to, pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &ReleaseDrift,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let client = &context.client;
+    let organisation = &context.organisation.name;
+    let tag = match project.latest_release(context).await? {
+        Some(release) => release.tag_name,
+        None => return Ok(()),
+    };
+    let ahead = client
+        .commits_ahead_of(organisation, project.repo(), &tag)
+        .await?;
+    if ahead <= policy.max_commits {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Default branch is {} commits ahead of the latest release {}, more than {}",
+            ahead,
+            tag,
+            policy.max_commits
+        ))
+    }
+}
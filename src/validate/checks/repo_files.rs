@@ -0,0 +1,62 @@
+//! The text files of an archive of a repo, for checks that scan the whole repo
+//! rather than particular files.
+
+use crate::{github::RepoArchive, scan};
+use std::borrow::Cow;
+
+#[derive(Debug, Default)]
+pub struct TextFiles<'a> {
+    /// The path and text of each file, borrowed from the archive unless it
+    /// isn't valid UTF-8. Binary files are left out.
+    pub files: Vec<(&'a str, Cow<'a, str>)>,
+    /// The paths of files that were too large to download.
+    pub skipped: Vec<String>,
+}
+
+/// The text files of an archive, leaving out those larger than
+/// `max_file_size`. Scanning them is CPU bound, so it is done outside of the
+/// async runtime.
+pub fn text_files(archive: &RepoArchive, max_file_size: u64) -> TextFiles<'_> {
+    let mut text_files = TextFiles::default();
+    for (path, bytes) in archive.files() {
+        if bytes.len() as u64 > max_file_size {
+            text_files.skipped.push(path.to_string());
+        } else if scan::is_text(bytes) {
+            text_files
+                .files
+                .push((path, String::from_utf8_lossy(bytes)));
+        }
+    }
+    text_files
+        .skipped
+        .extend(archive.too_large.iter().map(|file| file.path.clone()));
+    text_files.skipped.sort();
+    text_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::RepoFile;
+
+    #[test]
+    fn text_files_of_archives() {
+        let mut tar = b"# ash\n\x89PNG\0\0".to_vec();
+        tar.extend([b'x'; 100]);
+        let archive = RepoArchive {
+            tar,
+            entries: vec![
+                ("README.md".to_string(), 0..6),
+                ("logo.png".to_string(), 6..12),
+                ("src/lib.rs".to_string(), 12..112),
+            ],
+            too_large: vec![RepoFile {
+                path: "assets/model.bin".to_string(),
+                size: 100 << 20,
+            }],
+        };
+        let files = text_files(&archive, 50);
+        assert_eq!(files.files, vec![("README.md", Cow::Borrowed("# ash\n"))]);
+        assert_eq!(files.skipped, vec!["assets/model.bin", "src/lib.rs"]);
+    }
+}
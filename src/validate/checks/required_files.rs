@@ -0,0 +1,53 @@
+use crate::{
+    config::{RequiredFile, RequiredFiles},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// Check that the project has one of the sets of files listed by the
+/// requirement, with contents that match any patterns given for them.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    requirement: &RequiredFiles,
+) -> eyre::Result<()> {
+    let uses_language = |language: &String| project.uses_language(language) != Some(false);
+    if !requirement.languages.is_empty() && !requirement.languages.iter().any(uses_language) {
+        return Ok(());
+    }
+    let mut problems = Vec::new();
+    for files in requirement.all_alternatives() {
+        match check_alternative(project, context, files).await? {
+            Ok(()) => return Ok(()),
+            Err(problem) => problems.push(problem),
+        }
+    }
+    Err(eyre!(problems.join(", or "))
+        .wrap_err(format!("Missing required {} files", requirement.id)))
+}
+
+/// Returns a description of the first problem found with the set of files,
+/// or an error if the files could not be downloaded.
+async fn check_alternative(
+    project: &Project,
+    context: &Context,
+    files: &[RequiredFile],
+) -> eyre::Result<Result<(), String>> {
+    for file in files {
+        let text = match project.download_optional_file(context, file.path()).await? {
+            Some(text) => text,
+            None => return Ok(Err(format!("{} not found", file.path()))),
+        };
+        let unmatched = file
+            .patterns()
+            .iter()
+            .filter(|pattern| !pattern.0.is_match(&text))
+            .map(|pattern| format!("`{}`", pattern.0))
+            .join(", ");
+        if !unmatched.is_empty() {
+            return Ok(Err(format!("{} does not match {}", file.path(), unmatched)));
+        }
+    }
+    Ok(Ok(()))
+}
@@ -0,0 +1,69 @@
+use crate::{
+    config::SecretScanning,
+    github::{Feature, SecurityAndAnalysis},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// Check that secret scanning, and push protection if the config requires
+/// it, are enabled for the project's repo.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &SecretScanning,
+) -> eyre::Result<()> {
+    let settings = context
+        .client
+        .security_and_analysis(&context.organisation.name, project.repo())
+        .await?;
+    let disabled = disabled(&settings, config);
+    if disabled.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Disabled for the repo: {}",
+            disabled.iter().join(", ")
+        ))
+    }
+}
+
+/// The names of the required features that are not enabled.
+fn disabled(settings: &SecurityAndAnalysis, config: &SecretScanning) -> Vec<&'static str> {
+    let mut disabled = Vec::new();
+    if !Feature::is_enabled(&settings.secret_scanning) {
+        disabled.push("secret scanning");
+    }
+    if config.push_protection && !Feature::is_enabled(&settings.secret_scanning_push_protection) {
+        disabled.push("push protection");
+    }
+    disabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_features() {
+        let settings: SecurityAndAnalysis = serde_json::from_str(
+            r#"{
+                "secret_scanning": { "status": "enabled" },
+                "secret_scanning_push_protection": { "status": "disabled" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            disabled(&settings, &SecretScanning::default()),
+            vec!["push protection"]
+        );
+        let config = SecretScanning {
+            push_protection: false,
+        };
+        assert!(disabled(&settings, &config).is_empty());
+        assert_eq!(
+            disabled(&SecurityAndAnalysis::default(), &config),
+            vec!["secret scanning"]
+        );
+    }
+}
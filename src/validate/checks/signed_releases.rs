@@ -0,0 +1,82 @@
+use crate::{
+    config::SignedReleases,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that the latest release of the project is a signed tag, and that
+/// the default branch requires signed commits if the config says so.
+/// Projects the policy doesn't apply to always pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &SignedReleases,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let client = &context.client;
+    let organisation = &context.organisation.name;
+
+    let latest_tag = match project.latest_release(context).await? {
+        Some(release) => {
+            let verified = client
+                .is_tag_verified(organisation, project.repo(), &release.tag_name)
+                .await?;
+            Some((release.tag_name, verified))
+        }
+        None => None,
+    };
+    let signed_commits = if policy.require_signed_commits {
+        Some(
+            client
+                .requires_signed_commits(organisation, project.repo())
+                .await?,
+        )
+    } else {
+        None
+    };
+    verdict(latest_tag, signed_commits)
+}
+
+/// The verdict on the tag of the latest release and whether it is verified,
+/// if there is a release, and on whether the default branch requires signed
+/// commits, if the policy requires them.
+fn verdict(latest_tag: Option<(String, bool)>, signed_commits: Option<bool>) -> eyre::Result<()> {
+    let mut problems = Vec::new();
+    if let Some((tag, false)) = latest_tag {
+        problems.push(format!("Latest release {} is not a signed tag", tag));
+    }
+    if signed_commits == Some(false) {
+        problems.push("Default branch does not require signed commits".to_string());
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsigned_releases_and_branches_are_reported() {
+        let tag = |verified| Some(("v1.0.0".to_string(), verified));
+        assert!(verdict(None, None).is_ok());
+        assert!(verdict(tag(true), Some(true)).is_ok());
+        assert_eq!(
+            verdict(tag(false), None).unwrap_err().to_string(),
+            "Latest release v1.0.0 is not a signed tag"
+        );
+        assert_eq!(
+            verdict(None, Some(false)).unwrap_err().to_string(),
+            "Default branch does not require signed commits"
+        );
+        assert_eq!(
+            verdict(tag(false), Some(false)).unwrap_err().to_string(),
+            "Latest release v1.0.0 is not a signed tag; Default branch does not require signed commits"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    config::TemplateDrift,
+    diff, github,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// How many lines of the diff of each drifted file are shown in the report.
+const SUMMARY_LINES: usize = 10;
+
+/// A boilerplate file of a project that has drifted from the template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftedFile {
+    pub path: String,
+    /// The contents of the file in the template.
+    pub template: String,
+    /// The contents of the file in the project.
+    pub text: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Check that the boilerplate files a project has, such as its issue
+/// templates and code of conduct, have not drifted from those of the template
+/// repo, so that updates to the template reach every project. Files the
+/// project doesn't have are left to the `required-files` checks.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    config: &TemplateDrift,
+) -> eyre::Result<()> {
+    let drifted = drifted_files(project, context, config).await?;
+    if drifted.is_empty() {
+        return Ok(());
+    }
+    let files: Vec<_> = drifted
+        .iter()
+        .map(|file| {
+            format!(
+                "{}: {} lines added, {} removed\n{}",
+                file.path,
+                file.added,
+                file.removed,
+                summary(&file.path, &file.template, &file.text)
+            )
+        })
+        .collect();
+    Err(eyre!(files.join("\n")).wrap_err(format!(
+        "Files have drifted from the {} template",
+        config.repo
+    )))
+}
+
+/// The boilerplate files of a project that have drifted from the template.
+pub async fn drifted_files(
+    project: &Project,
+    context: &Context,
+    config: &TemplateDrift,
+) -> eyre::Result<Vec<DriftedFile>> {
+    let (owner, repo) = config
+        .repo
+        .split_once('/')
+        .ok_or_else(|| eyre!("The template repo `{}` is not `owner/name`", config.repo))?;
+    if owner.eq_ignore_ascii_case(&context.organisation.name) && project.repo() == repo {
+        return Ok(Vec::new());
+    }
+
+    let mut drifted = Vec::new();
+    for path in &config.files {
+        let text = match project.download_optional_file(context, path).await? {
+            Some(text) => text,
+            None => continue,
+        };
+        let template = github::download_repo_file(owner, repo, &config.branch, path).await?;
+        let (added, removed) = drift(&template, &text);
+        if added + removed > config.max_changed_lines {
+            drifted.push(DriftedFile {
+                path: path.clone(),
+                template,
+                text,
+                added,
+                removed,
+            });
+        }
+    }
+    Ok(drifted)
+}
+
+/// How many lines of a file are added and removed compared with the
+/// template. Changes to whitespace and blank lines are not material, so are
+/// ignored.
+pub fn drift(template: &str, text: &str) -> (usize, usize) {
+    diff::line_changes(&normalise(template), &normalise(text))
+}
+
+/// The start of the diff from the template to the file, ignoring whitespace
+/// as `drift` does, indented to go under the file in the report.
+fn summary(path: &str, template: &str, text: &str) -> String {
+    let diff = diff::unified(path, &normalise(template), &normalise(text));
+    // The file names are already given
+    let lines: Vec<_> = diff.lines().skip(2).collect();
+    let mut summary = lines
+        .iter()
+        .take(SUMMARY_LINES)
+        .map(|line| format!("    {}", line))
+        .join("\n");
+    if lines.len() > SUMMARY_LINES {
+        summary.push_str(&format!(
+            "\n    ... {} more lines of the diff",
+            lines.len() - SUMMARY_LINES
+        ));
+    }
+    summary
+}
+
+/// A file without changes to whitespace and blank lines, which are not
+/// material.
+fn normalise(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().join(" "))
+        .filter(|line| !line.is_empty())
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift_from_templates() {
+        let template = "## Description\n\nDescribe the bug.\n\n## Steps\n";
+        assert_eq!(drift(template, template), (0, 0));
+        assert_eq!(
+            drift(
+                template,
+                "## Description\r\nDescribe the  bug.\n\n\n## Steps  \n"
+            ),
+            (0, 0)
+        );
+        assert_eq!(
+            drift(
+                template,
+                "## Description\n\nWhat went wrong?\n\n## Steps\n## Logs\n"
+            ),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn summaries_of_drift() {
+        let template = "## Description\n\nDescribe the bug.\n\n## Steps\n";
+        assert_eq!(
+            summary(
+                "bug_report.md",
+                template,
+                "## Description\nWhat went wrong?\n## Steps\n"
+            ),
+            "    @@ -1,3 +1,3 @@\n     ## Description\n    -Describe the bug.\n    +What went \
+wrong?\n     ## Steps"
+        );
+        let long: String = (0..20).map(|i| format!("Step {}\n", i)).collect();
+        assert!(summary("bug_report.md", template, &long)
+            .ends_with("\n    ... 14 more lines of the diff"));
+    }
+}
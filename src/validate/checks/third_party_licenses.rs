@@ -0,0 +1,65 @@
+use crate::{
+    config::{Glob, ThirdPartyLicenses},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+
+/// Check that a project whose latest release has binary assets distributes
+/// the licenses of its dependencies, either as a release asset or as a file
+/// in the repo such as the `about.toml` that `cargo about` generates them
+/// from. Projects that don't release binaries pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    policy: &ThirdPartyLicenses,
+) -> eyre::Result<()> {
+    if !policy.applies_to(context.website_tags(&project.name)) {
+        return Ok(());
+    }
+    let release = match project.latest_release(context).await? {
+        Some(release) if !release.assets.is_empty() => release,
+        _ => return Ok(()),
+    };
+    if release
+        .assets
+        .iter()
+        .any(|asset| matches_any(&policy.assets, &asset.name))
+    {
+        return Ok(());
+    }
+
+    let (_, files) = project.repo_files(context).await?;
+    if files
+        .iter()
+        .any(|file| matches_any(&policy.files, &file.path))
+    {
+        return Ok(());
+    }
+    Err(eyre!(
+        "Release {} has binary assets but no third-party license file, in its assets or the repo",
+        release.tag_name
+    ))
+}
+
+/// Whether a file name matches any of the globs, ignoring case.
+fn matches_any(globs: &[Glob], name: &str) -> bool {
+    let name = name.to_lowercase();
+    globs.iter().any(|glob| glob.matches(&name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_license_files() {
+        let policy = ThirdPartyLicenses::default();
+        assert!(matches_any(&policy.assets, "THIRD-PARTY-LICENSES.html"));
+        assert!(matches_any(&policy.assets, "licenses.html"));
+        assert!(!matches_any(&policy.assets, "tool-x86_64-linux.tar.gz"));
+        assert!(matches_any(&policy.files, "about.toml"));
+        assert!(matches_any(&policy.files, "cli/about.toml"));
+        assert!(matches_any(&policy.files, "docs/third_party_licenses.md"));
+        assert!(!matches_any(&policy.files, "LICENSE-MIT"));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    config::TopicsPolicy,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+/// Check that the project's repo has the required topics and none of the
+/// forbidden ones.
+pub fn check(project: &Project, context: &Context, policy: &TopicsPolicy) -> eyre::Result<()> {
+    let repo = context
+        .embark_github_repos
+        .get(project.repo())
+        .ok_or_else(|| eyre!("Repo not found in the EmbarkStudios organisation"))?;
+    let tags = context.website_tags(&project.name);
+
+    let missing = required(policy, tags)
+        .filter(|topic| !repo.topics.contains(topic))
+        .join(", ");
+    let forbidden = repo
+        .topics
+        .iter()
+        .filter(|topic| policy.forbidden.contains(topic))
+        .join(", ");
+    match (missing.is_empty(), forbidden.is_empty()) {
+        (true, true) => Ok(()),
+        (false, true) => Err(eyre!("Missing required topics: {}", missing)),
+        (true, false) => Err(eyre!("Has forbidden topics: {}", forbidden)),
+        (false, false) => Err(eyre!(
+            "Missing required topics: {}; has forbidden topics: {}",
+            missing,
+            forbidden
+        )),
+    }
+}
+
+/// The topics a repo should have to pass the check, keeping any others it
+/// already has.
+pub fn fixed_topics(
+    current: &[String],
+    policy: &TopicsPolicy,
+    tags: &HashSet<String>,
+) -> Vec<String> {
+    current
+        .iter()
+        .filter(|topic| !policy.forbidden.contains(topic))
+        .chain(required(policy, tags))
+        .unique()
+        .cloned()
+        .collect()
+}
+
+fn required<'a>(
+    policy: &'a TopicsPolicy,
+    tags: &'a HashSet<String>,
+) -> impl Iterator<Item = &'a String> + Clone {
+    policy
+        .required
+        .iter()
+        .chain(
+            policy
+                .required_by_tag
+                .iter()
+                .filter(move |(tag, _)| tags.contains(*tag))
+                .flat_map(|(_, topics)| topics),
+        )
+        .unique()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixing_topics() {
+        let policy: TopicsPolicy = toml::from_str(
+            r#"
+required = ["embark"]
+required-by-tag = { rust = ["rust"] }
+forbidden = ["wip"]
+"#,
+        )
+        .unwrap();
+        let current = vec!["wip".to_string(), "gamedev".to_string()];
+        let no_tags = HashSet::new();
+        assert_eq!(
+            fixed_topics(&current, &policy, &no_tags),
+            vec!["gamedev", "embark"]
+        );
+        let rust = vec!["rust".to_string()].into_iter().collect();
+        assert_eq!(
+            fixed_topics(&current, &policy, &rust),
+            vec!["gamedev", "embark", "rust"]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    config::UnsafeCode,
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// The crate roots that may forbid unsafe code with an attribute.
+const CRATE_ROOTS: [&str; 2] = ["src/lib.rs", "src/main.rs"];
+
+/// Check that projects the policy applies to forbid unsafe code, with a
+/// crate attribute or the lints table of Cargo.toml, or document their
+/// policy for it in a README section. Other projects pass.
+pub async fn check(project: &Project, context: &Context, policy: &UnsafeCode) -> eyre::Result<()> {
+    if !policy.applies_to(&project.name, context.website_tags(&project.name))
+        || project.uses_language("Rust") == Some(false)
+    {
+        return Ok(());
+    }
+    if let Some(manifest) = project
+        .download_optional_file(context, "Cargo.toml")
+        .await?
+    {
+        if lints_forbid_unsafe(&manifest) {
+            return Ok(());
+        }
+    }
+    for path in CRATE_ROOTS.iter() {
+        if let Some(code) = project.download_optional_file(context, path).await? {
+            if attribute_forbids_unsafe(&code) {
+                return Ok(());
+            }
+        }
+    }
+    if let Some(readme) = project.download_optional_file(context, "README.md").await? {
+        if documents_safety(&readme) {
+            return Ok(());
+        }
+    }
+    Err(eyre!(
+        "Unsafe code is neither forbidden with #![forbid(unsafe_code)] or Cargo.toml lints, nor \
+         documented in a README safety section"
+    ))
+}
+
+fn attribute_forbids_unsafe(code: &str) -> bool {
+    lazy_static! {
+        static ref ATTRIBUTE: Regex =
+            Regex::new(r"(?m)^\s*#!\[\s*(forbid|deny)\s*\([^)]*\bunsafe_code\b").unwrap();
+    }
+    ATTRIBUTE.is_match(code)
+}
+
+/// Whether the `[lints.rust]` table of the manifest, or the workspace's
+/// table if the package inherits it, forbids or denies unsafe code.
+fn lints_forbid_unsafe(manifest: &str) -> bool {
+    let manifest: toml::Value = match toml::from_str(manifest) {
+        Ok(manifest) => manifest,
+        Err(_) => return false,
+    };
+    let forbids = |lints: Option<&toml::Value>| {
+        let level = lints
+            .and_then(|lints| lints.get("rust"))
+            .and_then(|rust| rust.get("unsafe_code"));
+        let level = level.and_then(|level| level.as_str().or_else(|| level.get("level")?.as_str()));
+        matches!(level, Some("forbid" | "deny"))
+    };
+    forbids(manifest.get("lints"))
+        || forbids(
+            manifest
+                .get("workspace")
+                .and_then(|workspace| workspace.get("lints")),
+        )
+}
+
+/// Whether the README has a section about safety or unsafe code.
+fn documents_safety(readme: &str) -> bool {
+    lazy_static! {
+        static ref HEADING: Regex = Regex::new(r"(?im)^#+ .*\b(safety|unsafe)\b").unwrap();
+    }
+    HEADING.is_match(readme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forbidding_unsafe_code() {
+        assert!(attribute_forbids_unsafe(
+            "//! Docs\n#![forbid(unsafe_code)]\n"
+        ));
+        assert!(attribute_forbids_unsafe(
+            "#![deny(missing_docs, unsafe_code)]\n"
+        ));
+        assert!(!attribute_forbids_unsafe("#![allow(unsafe_code)]\n"));
+        assert!(!attribute_forbids_unsafe("// #![forbid(unsafe_code)]\n"));
+
+        assert!(lints_forbid_unsafe(
+            "[lints.rust]\nunsafe_code = \"forbid\""
+        ));
+        assert!(lints_forbid_unsafe(
+            "[workspace.lints.rust]\nunsafe_code = { level = \"deny\", priority = 1 }"
+        ));
+        assert!(!lints_forbid_unsafe("[lints.rust]\nunsafe_code = \"warn\""));
+        assert!(!lints_forbid_unsafe("[package]\nname = \"ash\""));
+    }
+
+    #[test]
+    fn safety_sections() {
+        assert!(documents_safety(
+            "# Ash\n\n## Safety\n\nAll calls are unsafe."
+        ));
+        assert!(documents_safety("### Use of `unsafe` code"));
+        assert!(!documents_safety("# Ash\nA safe wrapper"));
+    }
+}
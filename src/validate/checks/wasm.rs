@@ -0,0 +1,212 @@
+//! Checks implemented as WebAssembly modules, which are run in a sandbox with
+//! no access to the host so they can be distributed and run safely.
+//!
+//! A module receives and returns the same JSON as an external check, and
+//! must export:
+//!
+//! - `memory`: its linear memory.
+//! - `alloc(len: i32) -> i32`: allocate `len` bytes for the input, returning
+//!   a pointer to them.
+//! - `check(ptr: i32, len: i32) -> i64`: run the check on the input JSON,
+//!   returning the pointer to its output JSON in the high 32 bits and the
+//!   length of the output in the low 32 bits.
+
+use super::external::{Input, Output};
+use crate::{
+    config::WasmCheck,
+    validate::{context::Context, project::Project},
+};
+use eyre::{eyre, WrapErr};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use wasmi::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// The most memory a module may grow to.
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// The modules compiled so far in the run by path, or why they could not be,
+/// so that each is compiled once rather than for every project.
+static MODULES: OnceLock<tokio::sync::Mutex<HashMap<PathBuf, Result<Module, String>>>> =
+    OnceLock::new();
+
+/// The engine every module is compiled for, which meters fuel.
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = wasmi::Config::default();
+        let _ = config.consume_fuel(true);
+        Engine::new(&config)
+    })
+}
+
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    maintainers: Option<&HashSet<String>>,
+    wasm: &WasmCheck,
+) -> eyre::Result<()> {
+    let input = serde_json::to_vec(&Input::new(project, context, maintainers))?;
+    let module = module(&wasm.module).await?;
+
+    // Running the module is CPU bound so keep it off the async runtime
+    let fuel = wasm.fuel;
+    let output = tokio::task::spawn_blocking(move || run(&module, &input, fuel))
+        .await?
+        .wrap_err_with(|| format!("Unable to run WebAssembly check {}", wasm.id))?;
+    output.into_result(&format!("WebAssembly check {}", wasm.id))
+}
+
+/// The compiled module at `path`, compiling it if this is its first use in
+/// the run.
+async fn module(path: &Path) -> eyre::Result<Module> {
+    let mut modules = MODULES.get_or_init(Default::default).lock().await;
+    if let Some(module) = modules.get(path) {
+        return module.clone().map_err(|error| eyre!(error));
+    }
+    let module = match tokio::fs::read(path).await {
+        Ok(bytes) => tokio::task::spawn_blocking(move || compile(&bytes))
+            .await?
+            .map_err(|error| format!("{:#}", error)),
+        Err(error) => Err(format!("Unable to read {}: {}", path.display(), error)),
+    };
+    let _ = modules.insert(path.to_path_buf(), module.clone());
+    module.map_err(|error| eyre!(error))
+}
+
+fn compile(module: &[u8]) -> eyre::Result<Module> {
+    Module::new(engine(), module).map_err(|error| eyre!("Invalid module: {}", error))
+}
+
+fn run(module: &Module, input: &[u8], fuel: u64) -> eyre::Result<Output> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .build();
+    let mut store = Store::new(engine(), limits);
+    store.limiter(|limits: &mut StoreLimits| limits);
+    store.set_fuel(fuel).map_err(wasm_error)?;
+
+    // No host functions are provided, so the module can only compute
+    let instance = Linker::<StoreLimits>::new(engine())
+        .instantiate_and_start(&mut store, module)
+        .map_err(wasm_error)?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| eyre!("Module does not export `memory`"))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(wasm_error)?;
+    let check = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "check")
+        .map_err(wasm_error)?;
+
+    let len = i32::try_from(input.len()).wrap_err("Input is too large")?;
+    let ptr = alloc.call(&mut store, len).map_err(wasm_error)?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input)
+        .map_err(wasm_error)?;
+
+    let packed = check.call(&mut store, (ptr, len)).map_err(wasm_error)? as u64;
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+
+    // The length is up to the module, so it is checked against its memory
+    // before anything is allocated for the output
+    let in_memory = ptr
+        .checked_add(len)
+        .is_some_and(|end| end <= memory.data_size(&store));
+    if !in_memory || len > MAX_MEMORY_BYTES {
+        return Err(eyre!(
+            "The output of {} bytes at {} is outside of the module's memory",
+            len,
+            ptr
+        ));
+    }
+    let mut output = vec![0; len];
+    memory.read(&store, ptr, &mut output).map_err(wasm_error)?;
+    serde_json::from_slice(&output).wrap_err("Unable to parse output as a JSON result")
+}
+
+fn wasm_error(error: impl std::fmt::Display) -> eyre::Report {
+    eyre!("{}", error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module that returns a fixed output after checking it was given the
+    /// expected input.
+    const MODULE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{\"ok\": false, \"errors\": [\"Oh no\"]}")
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "check") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.ne (i32.load8_u (local.get $ptr)) (i32.const 123))
+      (then unreachable))
+    (i64.const 34)))
+"#;
+
+    fn run_source(source: &str, input: &[u8]) -> eyre::Result<Output> {
+        run(&compile(source.as_bytes())?, input, 1_000_000)
+    }
+
+    #[test]
+    fn run_module() {
+        let output = run_source(MODULE, b"{}").unwrap();
+        assert!(output.into_result("test").is_err());
+
+        // Traps are errors
+        assert!(run_source(MODULE, b"[]").is_err());
+
+        // Invalid modules are errors
+        assert!(compile(b"(module").is_err());
+
+        // A module can be run any number of times once compiled
+        let module = compile(MODULE.as_bytes()).unwrap();
+        for _ in 0..3 {
+            assert!(run(&module, b"{}", 1_000_000).is_ok());
+        }
+    }
+
+    #[test]
+    fn infinite_loops_run_out_of_fuel() {
+        let module = MODULE.replace("(i64.const 34))", "(loop (br 0)) (i64.const 34))");
+        assert!(run_source(&module, b"{}").is_err());
+    }
+
+    #[test]
+    fn outputs_outside_of_memory_are_rejected() {
+        // 4 GiB from the start of the memory, and 2 bytes from its last address
+        for packed in ["4294967295", "-4294967294"] {
+            let module = MODULE.replace("(i64.const 34))", &format!("(i64.const {}))", packed));
+            let error = run_source(&module, b"{}").unwrap_err();
+            assert!(error
+                .to_string()
+                .ends_with("is outside of the module's memory"));
+        }
+    }
+
+    #[tokio::test]
+    async fn modules_are_compiled_once() {
+        let path = std::env::temp_dir().join(format!("wasm-check-{}.wat", std::process::id()));
+        std::fs::write(&path, MODULE).unwrap();
+        let first = module(&path).await.unwrap();
+
+        // Changing the file has no effect as the compiled module is kept
+        std::fs::write(&path, "(module").unwrap();
+        let second = module(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(run(&second, b"{}", 1_000_000).is_ok());
+        drop(first);
+
+        let missing = module(Path::new("/nonexistent/check.wasm")).await;
+        assert!(missing
+            .unwrap_err()
+            .to_string()
+            .starts_with("Unable to read"));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::validate::{context::Context, project::Project};
+use eyre::eyre;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+/// Check that the owners and team the opensource website data gives for the
+/// project agree with the maintainers in its CODEOWNERS. Projects without
+/// owners in the website data, or whose maintainers are unknown, pass.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    maintainers: Option<&HashSet<String>>,
+) -> eyre::Result<()> {
+    let website = match context
+        .opensource_website_projects
+        .iter()
+        .find(|website| website.name == project.name)
+    {
+        Some(website) => website,
+        None => return Ok(()),
+    };
+    let maintainers = match maintainers {
+        Some(maintainers) => maintainers,
+        None => return Ok(()),
+    };
+
+    let mut problems = Vec::new();
+    if !website.owners.is_empty() {
+        problems.extend(owners_drift(&website.owners, maintainers));
+    }
+    if let Some(team) = &website.team {
+        let members = context
+            .client
+            .team_members(&context.organisation.name, team)
+            .await?;
+        let outside = not_in(maintainers.iter(), &members);
+        if !outside.is_empty() {
+            problems.push(format!(
+                "CODEOWNERS maintainers not in the website's team {}: {}",
+                team,
+                outside.join(", ")
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("; ")))
+    }
+}
+
+/// Describe the differences between the website's owners and the
+/// maintainers, comparing handles case insensitively like GitHub does.
+fn owners_drift(owners: &[String], maintainers: &HashSet<String>) -> Vec<String> {
+    let owners: Vec<_> = owners
+        .iter()
+        .map(|owner| owner.trim_start_matches('@').to_string())
+        .collect();
+    let mut problems = Vec::new();
+    let not_maintainers = not_in(owners.iter(), maintainers);
+    if !not_maintainers.is_empty() {
+        problems.push(format!(
+            "Website owners not in CODEOWNERS: {}",
+            not_maintainers.join(", ")
+        ));
+    }
+    let not_owners = not_in(maintainers.iter(), &owners);
+    if !not_owners.is_empty() {
+        problems.push(format!(
+            "CODEOWNERS maintainers not website owners: {}",
+            not_owners.join(", ")
+        ));
+    }
+    problems
+}
+
+/// The handles that aren't in `set`, sorted.
+fn not_in<'a>(
+    handles: impl Iterator<Item = &'a String>,
+    set: impl IntoIterator<Item = &'a String> + Clone,
+) -> Vec<&'a str> {
+    handles
+        .filter(|handle| {
+            !set.clone()
+                .into_iter()
+                .any(|other| other.eq_ignore_ascii_case(handle))
+        })
+        .map(String::as_str)
+        .sorted()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift() {
+        let maintainers: HashSet<_> = vec!["lpil".to_string(), "arirawr".to_string()]
+            .into_iter()
+            .collect();
+        let owners = |owners: &[&str]| owners.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+        assert!(owners_drift(&owners(&["@Lpil", "arirawr"]), &maintainers).is_empty());
+        assert_eq!(
+            owners_drift(&owners(&["lpil", "someone"]), &maintainers),
+            vec![
+                "Website owners not in CODEOWNERS: someone",
+                "CODEOWNERS maintainers not website owners: arirawr",
+            ]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use super::workflows::{self, Workflow};
+use crate::{
+    config::{ActionsPinning, WorkflowAudit},
+    validate::{context::Context, project::Project},
+};
+use eyre::eyre;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Check the project's workflows for patterns that let untrusted code run
+/// with access to secrets or write permissions. Unpinned actions are left to
+/// the `actions-pinning` check if it is enabled, so they aren't reported
+/// twice.
+pub async fn check(
+    project: &Project,
+    context: &Context,
+    _config: &WorkflowAudit,
+) -> eyre::Result<()> {
+    let pinning = match &context.config.actions_pinning {
+        Some(_) => None,
+        None => Some(ActionsPinning::default()),
+    };
+    let problems: Vec<_> = workflows::download(project, context)
+        .await?
+        .iter()
+        .flat_map(|workflow| audit(workflow, pinning.as_ref()))
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(problems.join("\n")).wrap_err("Insecure GitHub Actions workflows"))
+    }
+}
+
+/// Describe each problem with the workflow, prefixed with its location.
+/// Unpinned actions are only problems if `pinning` is given.
+fn audit(workflow: &Workflow, pinning: Option<&ActionsPinning>) -> Vec<String> {
+    lazy_static! {
+        static ref PULL_REQUEST_TARGET: Regex = Regex::new(r"\bpull_request_target\b").unwrap();
+        static ref PULL_REQUEST_CODE: Regex =
+            Regex::new(r"github\.event\.pull_request\.head\.(sha|ref)|github\.head_ref").unwrap();
+        static ref WRITE_ALL: Regex =
+            Regex::new(r#"^\s*permissions\s*:\s*["']?write-all\b"#).unwrap();
+    }
+    let mut problems = Vec::new();
+    let mut problem = |line: usize, description: String| {
+        problems.push(format!("{}:{}: {}", workflow.path, line, description));
+    };
+
+    let pull_request_target = workflow
+        .lines()
+        .any(|(_, code)| PULL_REQUEST_TARGET.is_match(code));
+    for (line, code) in workflow.lines() {
+        if pull_request_target && PULL_REQUEST_CODE.is_match(code) {
+            problem(
+                line,
+                "checks out pull request code in a pull_request_target workflow".to_string(),
+            );
+        }
+        if WRITE_ALL.is_match(code) {
+            problem(line, "grants write-all permissions".to_string());
+        }
+    }
+    let unpinned = pinning.map_or_else(Vec::new, |pinning| {
+        workflow.unpinned_actions(&pinning.allowed)
+    });
+    for action in unpinned {
+        problem(
+            action.line,
+            format!(
+                "third-party action {} is not pinned to a commit SHA",
+                action.action
+            ),
+        );
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dangerous_patterns() {
+        let workflow = Workflow {
+            path: ".github/workflows/pr.yml".to_string(),
+            text: r#"on:
+  pull_request_target:
+permissions: write-all
+jobs:
+  build:
+    steps:
+      - uses: actions/checkout@v3
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+      - uses: someone/action@v1
+"#
+            .to_string(),
+        };
+        assert_eq!(audit(&workflow, None).len(), 2);
+        assert_eq!(
+            audit(&workflow, Some(&ActionsPinning::default())),
+            vec![
+                ".github/workflows/pr.yml:3: grants write-all permissions",
+                ".github/workflows/pr.yml:9: checks out pull request code in a \
+pull_request_target workflow",
+                ".github/workflows/pr.yml:10: third-party action someone/action is not \
+pinned to a commit SHA",
+            ]
+        );
+    }
+
+    #[test]
+    fn safe_workflow() {
+        let workflow = Workflow {
+            path: ".github/workflows/ci.yml".to_string(),
+            text: r#"on: [push, pull_request]
+permissions:
+  contents: read
+jobs:
+  build:
+    steps:
+      - uses: actions/checkout@v3
+        with:
+          ref: ${{ github.head_ref }}
+"#
+            .to_string(),
+        };
+        assert!(audit(&workflow, Some(&ActionsPinning::default())).is_empty());
+    }
+}
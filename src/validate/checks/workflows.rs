@@ -0,0 +1,143 @@
+//! GitHub Actions workflows of a project, shared by the checks that audit
+//! them. Workflows are scanned line by line rather than parsed so that
+//! problems can be reported with the line they are on.
+
+use crate::{
+    config::Glob,
+    validate::{context::Context, project::Project},
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+const WORKFLOWS_DIR: &str = ".github/workflows";
+
+#[derive(Debug)]
+pub struct Workflow {
+    pub path: String,
+    pub text: String,
+}
+
+/// A step or job that uses an action, such as `actions/checkout@v3`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ActionUse<'a> {
+    /// The line number, starting from 1.
+    pub line: usize,
+    /// The action without the version, such as `actions/checkout`.
+    pub action: &'a str,
+    pub version: Option<&'a str>,
+}
+
+impl ActionUse<'_> {
+    /// Whether the action is pinned to a full commit SHA, which unlike a tag
+    /// or branch can't be moved to point at different code.
+    pub fn is_pinned(&self) -> bool {
+        self.version.is_some_and(|version| {
+            version.len() == 40 && version.chars().all(|c| c.is_ascii_hexdigit())
+        })
+    }
+}
+
+/// Download the workflows on the default branch of the project.
+pub async fn download(project: &Project, context: &Context) -> eyre::Result<Vec<Workflow>> {
+    let paths = context
+        .client
+        .directory_files(&context.organisation.name, project.repo(), WORKFLOWS_DIR)
+        .await?;
+    let mut workflows = Vec::new();
+    for path in paths {
+        if !(path.ends_with(".yml") || path.ends_with(".yaml")) {
+            continue;
+        }
+        let text = project.download_file(context, &path).await?;
+        workflows.push(Workflow { path, text });
+    }
+    Ok(workflows)
+}
+
+impl Workflow {
+    /// The lines of the workflow without comments, numbered from 1.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.text.lines().enumerate().map(|(i, line)| {
+            let code = match line.find('#') {
+                Some(0) => "",
+                Some(i) if line[..i].ends_with(char::is_whitespace) => &line[..i],
+                _ => line,
+            };
+            (i + 1, code)
+        })
+    }
+
+    /// Actions used by the workflow that are not pinned to a commit SHA, nor
+    /// allowed not to be by a glob in `allowed`.
+    pub fn unpinned_actions(&self, allowed: &[Glob]) -> Vec<ActionUse<'_>> {
+        self.action_uses()
+            .into_iter()
+            .filter(|action| !action.is_pinned())
+            .filter(|action| !allowed.iter().any(|glob| glob.matches(action.action)))
+            .collect()
+    }
+
+    /// Actions used by the workflow, excluding local actions and Docker
+    /// images.
+    pub fn action_uses(&self) -> Vec<ActionUse<'_>> {
+        lazy_static! {
+            static ref USES: Regex =
+                Regex::new(r#"^\s*(?:-\s*)?uses\s*:\s*["']?([^"'\s@]+)(?:@([^"'\s]+))?"#).unwrap();
+        }
+        self.lines()
+            .filter_map(|(line, code)| {
+                let captures = USES.captures(code)?;
+                let action = captures.get(1)?.as_str();
+                if action.starts_with("./") || action.starts_with("docker://") {
+                    return None;
+                }
+                Some(ActionUse {
+                    line,
+                    action,
+                    version: captures.get(2).map(|version| version.as_str()),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_uses() {
+        let workflow = Workflow {
+            path: ".github/workflows/ci.yml".to_string(),
+            text: r#"
+jobs:
+  test:
+    steps:
+      - uses: actions/checkout@v3
+      - uses: "EmbarkStudios/cargo-deny-action@8371184bd11e21dcf8ac82ebf8c9c9f74ebf7268"
+      - uses: ./local-action
+      # - uses: some/commented-out@v1
+      - uses: docker://alpine:3
+      - name: Build
+        uses: some/action # no version
+"#
+            .to_string(),
+        };
+        let uses = workflow.action_uses();
+        assert_eq!(
+            uses.iter()
+                .map(|u| (u.line, u.action, u.version, u.is_pinned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (5, "actions/checkout", Some("v3"), false),
+                (
+                    6,
+                    "EmbarkStudios/cargo-deny-action",
+                    Some("8371184bd11e21dcf8ac82ebf8c9c9f74ebf7268"),
+                    true
+                ),
+                (11, "some/action", None, false),
+            ]
+        );
+    }
+}
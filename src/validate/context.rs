@@ -1,4 +1,7 @@
-use crate::github;
+use crate::{
+    config::{Config, Organisation},
+    github,
+};
 use eyre::WrapErr;
 use std::collections::{HashMap, HashSet};
 
@@ -9,6 +12,11 @@ use std::collections::{HashMap, HashSet};
 /// limits.
 #[derive(Debug)]
 pub struct Context {
+    pub config: Config,
+    /// The organisation whose projects are validated with this context.
+    pub organisation: Organisation,
+    /// The client for checks that use the GitHub API.
+    pub client: github::Client,
     pub embark_github_organisation_members: HashSet<String>,
     pub embark_github_repos: HashMap<String, github::Repo>,
     pub rust_ecosystem_readme: String,
@@ -16,22 +24,27 @@ pub struct Context {
 }
 
 impl Context {
-    pub async fn get(github_api_token: Option<String>) -> eyre::Result<Self> {
-        let client = github::Client::new(github_api_token);
-
+    pub async fn get(
+        client: github::Client,
+        config: Config,
+        organisation: Organisation,
+    ) -> eyre::Result<Self> {
         let (
             opensource_website_projects,
             embark_github_organisation_members,
             embark_github_repos,
             rust_ecosystem_readme,
         ) = futures::join!(
-            download_projects_list(),
-            client.public_organisation_members("EmbarkStudios"),
-            client.organisation_repos("EmbarkStudios"),
+            download_projects_list(&organisation),
+            client.public_organisation_members(&organisation.name),
+            client.organisation_repos(&organisation.name),
             github::download_repo_file("EmbarkStudios", "rust-ecosystem", "main", "README.md")
         );
 
         Ok(Self {
+            config,
+            organisation,
+            client,
             embark_github_organisation_members: embark_github_organisation_members?,
             opensource_website_projects: opensource_website_projects?,
             rust_ecosystem_readme: rust_ecosystem_readme?,
@@ -39,35 +52,76 @@ impl Context {
         })
     }
 
-    /// Get the names of all projects found on GitHub and in the opensource
-    /// website data.json. We lookup from both as a project may accidentally be
-    /// in one but not the other.
+    /// The tags of a project in the opensource website data.json, which are
+    /// empty if the project is not in it.
+    pub fn website_tags(&self, name: &str) -> &HashSet<String> {
+        lazy_static::lazy_static! {
+            static ref NO_TAGS: HashSet<String> = HashSet::new();
+        }
+        self.opensource_website_projects
+            .iter()
+            .find(|project| project.name == name)
+            .map_or(&NO_TAGS, |project| &project.tags)
+    }
+
+    /// The tier of a project, from the opensource website data.json or the
+    /// config.
+    pub fn tier(&self, name: &str) -> Option<&str> {
+        let website_tier = self
+            .opensource_website_projects
+            .iter()
+            .find(|project| project.name == name)
+            .and_then(|project| project.tier.as_deref());
+        self.config.tiers.of(name, website_tier)
+    }
+
+    /// The client to download the files of a repo with through the API, if the
+    /// repo is private. Only authenticated clients can see private repos, and
+    /// their files can't be downloaded from raw.githubusercontent.com.
+    pub fn private_repo_client(&self, repo: &str) -> Option<&github::Client> {
+        self.embark_github_repos
+            .get(repo)
+            .filter(|repo| repo.private)
+            .map(|_| &self.client)
+    }
+
+    /// Get the names of all projects found on GitHub, in the opensource
+    /// website data.json, and listed for the organisation in the config. We
+    /// lookup from both GitHub and the website as a project may accidentally
+    /// be in one but not the other.
     pub fn all_projects(&self) -> HashSet<String> {
-        let website_projects = self.opensource_website_projects.iter().map(|proj| {
-            proj.repo
-                .as_ref()
-                .and_then(|repo_url| repo_url.rfind('/').map(|i| &repo_url[i + 1..]))
-                .unwrap_or(&proj.name)
-                .to_owned()
-        });
+        let website_projects = self
+            .opensource_website_projects
+            .iter()
+            .map(OpenSourceWebsiteDataProject::project_name);
         let github_projects = self
             .embark_github_repos
             .values()
             .filter(|project| project.is_public_active_source_project())
             .map(|project| project.name.to_string());
-        website_projects.chain(github_projects).collect()
+        let listed_projects = self.organisation.repos.iter().cloned();
+        website_projects
+            .chain(github_projects)
+            .chain(listed_projects)
+            .collect()
     }
 }
 
-async fn download_projects_list() -> eyre::Result<Vec<OpenSourceWebsiteDataProject>> {
+async fn download_projects_list(
+    organisation: &Organisation,
+) -> eyre::Result<Vec<OpenSourceWebsiteDataProject>> {
+    let source = match &organisation.website_data {
+        Some(source) => source,
+        None => return Ok(Vec::new()),
+    };
     let data = github::download_repo_json_file::<OpenSourceWebsiteData>(
-        "EmbarkStudios",
-        "opensource-website",
-        "main",
-        "static/data/data.json",
+        &organisation.name,
+        &source.repo,
+        &source.branch,
+        &source.path,
     )
     .await
-    .wrap_err("Unable to get list of open source Embark projects")?;
+    .wrap_err_with(|| format!("Unable to get list of {} projects", organisation.name))?;
     Ok(data.projects)
 }
 
@@ -76,10 +130,41 @@ pub struct OpenSourceWebsiteData {
     pub projects: Vec<OpenSourceWebsiteDataProject>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct OpenSourceWebsiteDataProject {
     pub name: String,
     pub repo: Option<String>,
     #[serde(default)]
     pub tags: HashSet<String>,
+    /// The GitHub handles of the people the website says own the project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// The slug of the team in the organisation the website says owns the
+    /// project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// The tier of the project, such as `flagship` or `experimental`, which
+    /// decides the checks it is held to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+}
+
+impl OpenSourceWebsiteDataProject {
+    /// The name the project is validated as, which is that of its repo.
+    pub fn project_name(&self) -> String {
+        let repo_url = match &self.repo {
+            Some(repo_url) => repo_url,
+            None => return self.name.clone(),
+        };
+        // Projects in a directory of a repo are given as `url#path`
+        let (url, path) = match repo_url.split_once('#') {
+            Some((url, path)) => (url, Some(path)),
+            None => (repo_url.as_str(), None),
+        };
+        let repo = url.rfind('/').map_or(self.name.as_str(), |i| &url[i + 1..]);
+        match path {
+            Some(path) => format!("{}#{}", repo, path),
+            None => repo.to_owned(),
+        }
+    }
 }
@@ -0,0 +1,212 @@
+//! Exemptions from checks that a repo declares for itself, in
+//! `.github/embark-oss.toml`, so that deliberate exceptions to the guidelines
+//! are recorded next to the code along with why they were made.
+
+use super::{
+    context::Context,
+    project::Project,
+    report::{ExpiringExemption, Report},
+};
+use crate::config::ExemptionPolicy;
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+
+/// Where a repo declares its exemptions.
+pub const PATH: &str = ".github/embark-oss.toml";
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default, rename = "exemption")]
+    exemptions: Vec<Exemption>,
+}
+
+/// An accepted failure of one check.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Exemption {
+    /// The id of the exempted check, such as `msrv` or `required-files:license`.
+    pub check: String,
+    /// Why the project doesn't follow the guideline. Exemptions without one
+    /// are not honoured.
+    #[serde(default)]
+    pub justification: String,
+    /// The day the exemption stops applying, as YYYY-MM-DD.
+    pub expires: Option<String>,
+    /// Who is responsible for fixing the violation before the exemption
+    /// expires, such as a GitHub username. The project's maintainers are
+    /// otherwise.
+    pub owner: Option<String>,
+}
+
+impl Exemption {
+    fn is_justified(&self) -> bool {
+        !self.justification.trim().is_empty()
+    }
+
+    /// Whether the exemption applies at the given time, in seconds since the
+    /// Unix epoch. Exemptions with an invalid expiry date don't apply.
+    pub fn is_active(&self, now: u64) -> bool {
+        self.is_justified()
+            && match &self.expires {
+                Some(expires) => crate::github::parse_date(expires).is_some_and(|day| now < day),
+                None => true,
+            }
+    }
+}
+
+/// Download and parse the exemptions of a project, which has none if it has
+/// no exemptions file.
+pub async fn download(project: &Project, context: &Context) -> eyre::Result<Vec<Exemption>> {
+    match project.download_optional_file(context, PATH).await? {
+        Some(text) => parse(&text),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub fn parse(text: &str) -> eyre::Result<Vec<Exemption>> {
+    let file: File = toml::from_str(text).wrap_err_with(|| format!("Unable to parse {}", PATH))?;
+    Ok(file.exemptions)
+}
+
+/// The exemptions of the report's projects that cover a failing check and
+/// expire within `within_days` days of `now`, in seconds since the Unix epoch,
+/// soonest first.
+pub fn expiring(report: &Report, now: u64, within_days: u64) -> Vec<ExpiringExemption> {
+    const DAY: u64 = 24 * 60 * 60;
+    let mut expiring: Vec<_> = report
+        .projects
+        .iter()
+        .flat_map(|project| project.checks.iter().map(move |check| (project, check)))
+        .filter_map(|(project, check)| {
+            let justification = check.exemption.as_ref()?;
+            let expires = check.exemption_expires.as_ref()?;
+            let day = crate::github::parse_date(expires)?;
+            // Part of a day left counts as a whole day
+            let days_left = day.checked_sub(now)?.div_ceil(DAY);
+            if days_left > within_days {
+                return None;
+            }
+            let owners = match &check.exemption_owner {
+                Some(owner) => vec![owner.clone()],
+                None => project.maintainers.iter().sorted().cloned().collect(),
+            };
+            Some(ExpiringExemption {
+                organisation: project.organisation.clone(),
+                project: project.name.clone(),
+                check: check.id.clone(),
+                justification: justification.clone(),
+                expires: expires.clone(),
+                days_left,
+                owners,
+            })
+        })
+        .collect();
+    expiring.sort_by(|a, b| {
+        (&a.expires, &a.organisation, &a.project, &a.check).cmp(&(
+            &b.expires,
+            &b.organisation,
+            &b.project,
+            &b.check,
+        ))
+    });
+    expiring
+}
+
+/// Check that every exemption has a justification and a valid expiry date,
+/// and is from a check the policy allows exempting.
+pub fn check(exemptions: &[Exemption], policy: &ExemptionPolicy) -> eyre::Result<()> {
+    let mut problems = Vec::new();
+    for exemption in exemptions {
+        if !policy.allows(&exemption.check) {
+            problems.push(format!(
+                "The exemption from {} is not allowed, as the check can't be exempted",
+                exemption.check
+            ));
+        }
+        if !exemption.is_justified() {
+            problems.push(format!(
+                "The exemption from {} has no justification",
+                exemption.check
+            ));
+        }
+        if let Some(expires) = &exemption.expires {
+            if crate::github::parse_date(expires).is_none() {
+                problems.push(format!(
+                    "The exemption from {} expires on `{}`, which is not a YYYY-MM-DD date",
+                    exemption.check, expires
+                ));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!("{} is invalid:\n{}", PATH, problems.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_and_checking_exemptions() {
+        let exemptions = parse(
+            r#"
+[[exemption]]
+check = "msrv"
+justification = "Tracks the latest stable Rust"
+
+[[exemption]]
+check = "required-files:license"
+expires = "2024-6-1"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            exemptions[0],
+            Exemption {
+                check: "msrv".to_string(),
+                justification: "Tracks the latest stable Rust".to_string(),
+                expires: None,
+                owner: None,
+            }
+        );
+        assert_eq!(
+            check(&exemptions, &ExemptionPolicy::default())
+                .unwrap_err()
+                .to_string(),
+            ".github/embark-oss.toml is invalid:\n\
+The exemption from required-files:license has no justification\n\
+The exemption from required-files:license expires on `2024-6-1`, which is not a YYYY-MM-DD date"
+        );
+        assert!(check(&exemptions[..1], &ExemptionPolicy::default()).is_ok());
+        let policy = ExemptionPolicy {
+            exemptable: Some(vec!["required-files".to_string()]),
+        };
+        assert_eq!(
+            check(&exemptions[..1], &policy).unwrap_err().to_string(),
+            ".github/embark-oss.toml is invalid:\n\
+The exemption from msrv is not allowed, as the check can't be exempted"
+        );
+        assert!(parse("[[exemption]]\ncheck = \"msrv\"\nreason = \"\"").is_err());
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn exemptions_expire() {
+        let exemption = |justification: &str, expires: Option<&str>| Exemption {
+            check: "msrv".to_string(),
+            justification: justification.to_string(),
+            expires: expires.map(ToString::to_string),
+            owner: None,
+        };
+        let now = crate::github::parse_date("2024-06-01").unwrap();
+        assert!(exemption("Because", None).is_active(now));
+        assert!(exemption("Because", Some("2024-06-02")).is_active(now));
+        assert!(!exemption("Because", Some("2024-06-01")).is_active(now));
+        assert!(!exemption(" ", None).is_active(now));
+        assert!(!exemption("Because", Some("soon")).is_active(now));
+    }
+}
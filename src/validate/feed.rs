@@ -0,0 +1,203 @@
+//! An Atom feed of the changes in compliance between runs, appended to by
+//! `validate-all --atom-feed`, so that anyone can subscribe to them without
+//! Slack access. Each entry also records the failures of its run, which the
+//! next run compares with, so the feed needs no other state.
+
+use super::report::{Report, RunMetadata};
+use crate::{export::xml_escape, github};
+use eyre::WrapErr;
+use std::{collections::BTreeSet, path::Path};
+
+/// How many entries are kept, with the oldest dropped first.
+const MAX_ENTRIES: usize = 100;
+
+/// The namespace of the elements recording the failures of each run.
+const NAMESPACE: &str = "https://github.com/EmbarkStudios/opensource#embark-oss";
+
+/// The organisation and name of a project, and the id of a failing check.
+type Failure = (String, String, String);
+
+/// Add an entry for the changes since the previous run to the feed at the
+/// path, creating it if it doesn't exist.
+pub fn append(path: &Path, report: &Report, now: u64) -> eyre::Result<()> {
+    let feed = match std::fs::read_to_string(path) {
+        Ok(feed) => Some(feed),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => {
+            return Err(error).wrap_err_with(|| format!("Unable to read {}", path.display()))
+        }
+    };
+    if let Some(feed) = update(feed.as_deref(), report, now) {
+        std::fs::write(path, feed)
+            .wrap_err_with(|| format!("Unable to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// The feed with an entry for the run added, or `None` if nothing changed
+/// since the latest entry.
+pub fn update(feed: Option<&str>, report: &Report, now: u64) -> Option<String> {
+    let entries = feed.map(entries).unwrap_or_default();
+    let mut failures = failures(report);
+    let (new, fixed) = match entries.first() {
+        Some(latest) => {
+            let validated: BTreeSet<_> = report
+                .projects
+                .iter()
+                .map(|project| (project.organisation.as_str(), project.name.as_str()))
+                .collect();
+            // Projects this run didn't validate keep their previous failures
+            let (previous, unvalidated): (BTreeSet<_>, BTreeSet<_>) = recorded_failures(latest)
+                .into_iter()
+                .partition(|(organisation, name, _)| {
+                    validated.contains(&(organisation.as_str(), name.as_str()))
+                });
+            let new: Vec<_> = failures.difference(&previous).cloned().collect();
+            let fixed: Vec<_> = previous.difference(&failures).cloned().collect();
+            if new.is_empty() && fixed.is_empty() {
+                return None;
+            }
+            failures.extend(unvalidated);
+            (new, fixed)
+        }
+        None => (failures.iter().cloned().collect(), Vec::new()),
+    };
+
+    let updated = github::format_timestamp(now);
+    let mut feed = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:oss="{}">
+  <title>Embark open source compliance</title>
+  <id>urn:embark-oss:feed</id>
+  <updated>{}</updated>
+  <author><name>embark-oss</name></author>
+"#,
+        NAMESPACE, updated
+    );
+    feed.push_str(&entry(
+        &new,
+        &fixed,
+        &failures,
+        report.run.as_ref(),
+        &updated,
+        now,
+    ));
+    for entry in entries.iter().take(MAX_ENTRIES - 1) {
+        feed.push_str("  ");
+        feed.push_str(entry);
+        feed.push('\n');
+    }
+    feed.push_str("</feed>\n");
+    Some(feed)
+}
+
+fn entry(
+    new: &[Failure],
+    fixed: &[Failure],
+    failures: &BTreeSet<Failure>,
+    run: Option<&RunMetadata>,
+    updated: &str,
+    now: u64,
+) -> String {
+    let title = match (new.len(), fixed.len()) {
+        (0, 0) => "No failures".to_string(),
+        (new, 0) => format!("{} new failures", new),
+        (0, fixed) => format!("{} failures fixed", fixed),
+        (new, fixed) => format!("{} new failures, {} fixed", new, fixed),
+    };
+    let mut content = String::new();
+    for (heading, failures) in [("New failures", new), ("Fixed", fixed)].iter() {
+        if failures.is_empty() {
+            continue;
+        }
+        content.push_str(&format!("<h2>{}</h2>\n<ul>\n", heading));
+        for (organisation, name, check) in failures.iter() {
+            content.push_str(&format!(
+                "<li><a href=\"https://github.com/{0}/{1}\">{0}/{1}</a>: {2}</li>\n",
+                xml_escape(organisation),
+                xml_escape(name),
+                xml_escape(check)
+            ));
+        }
+        content.push_str("</ul>\n");
+    }
+    if let Some(run) = run {
+        content.push_str(&format!("<p>{}</p>\n", xml_escape(&run.summary())));
+    }
+
+    let mut entry = format!(
+        "  <entry>\n    <id>urn:embark-oss:run:{}</id>\n    <title>{}</title>\n    \
+<updated>{}</updated>\n    <content type=\"html\">{}</content>\n",
+        now,
+        title,
+        updated,
+        xml_escape(&content)
+    );
+    for (organisation, name, check) in failures {
+        entry.push_str(&format!(
+            "    <oss:failure organisation=\"{}\" project=\"{}\" check=\"{}\"/>\n",
+            xml_escape(organisation),
+            xml_escape(name),
+            xml_escape(check)
+        ));
+    }
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+/// The failing checks of the report, leaving out those that failed because
+/// GitHub could not be reached.
+fn failures(report: &Report) -> BTreeSet<Failure> {
+    report
+        .projects
+        .iter()
+        .flat_map(|project| {
+            project
+                .checks
+                .iter()
+                .filter(|check| !check.ok && !check.infrastructure)
+                .map(move |check| {
+                    (
+                        project.organisation.clone(),
+                        project.name.clone(),
+                        check.id.clone(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The entries of a feed, newest first.
+fn entries(feed: &str) -> Vec<&str> {
+    lazy_static::lazy_static! {
+        static ref ENTRY: regex::Regex = regex::Regex::new(r"(?s)<entry>.*?</entry>").unwrap();
+    }
+    ENTRY.find_iter(feed).map(|entry| entry.as_str()).collect()
+}
+
+/// The failures recorded in an entry.
+fn recorded_failures(entry: &str) -> BTreeSet<Failure> {
+    lazy_static::lazy_static! {
+        static ref FAILURE: regex::Regex = regex::Regex::new(
+            r#"<oss:failure organisation="([^"]*)" project="([^"]*)" check="([^"]*)"/>"#
+        )
+        .unwrap();
+    }
+    FAILURE
+        .captures_iter(entry)
+        .map(|captures| {
+            (
+                xml_unescape(&captures[1]),
+                xml_unescape(&captures[2]),
+                xml_unescape(&captures[3]),
+            )
+        })
+        .collect()
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,559 @@
+//! Automatic fixes for projects that fail checks which can be corrected
+//! through the GitHub API.
+
+use super::{
+    checks,
+    context::{Context, OpenSourceWebsiteDataProject},
+    IGNORED_PROJECTS,
+};
+use crate::{config::Config, diff, github, Fix, FixTopics, FixWebsiteData};
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use std::{borrow::Cow, collections::HashMap, ops::Range};
+
+/// The branch pull requests correcting the website data are opened from. An
+/// open pull request from it is updated by later corrections.
+const WEBSITE_DATA_BRANCH: &str = "embark-oss/website-data";
+
+pub async fn fix(command: Fix) -> eyre::Result<()> {
+    match command {
+        Fix::Topics(options) => topics(options).await,
+        Fix::WebsiteData(options) => website_data(options).await,
+    }
+}
+
+/// Add the required topics to repos and remove the forbidden ones.
+async fn topics(options: FixTopics) -> eyre::Result<()> {
+    let FixTopics {
+        name,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    if config.topics.is_none() {
+        return Err(eyre!("The config has no topics policy"));
+    }
+
+    // Either the one project or every project of every organisation
+    let targets = match name {
+        Some(name) => {
+            let (organisation, name) = config.project_organisation(&name);
+            vec![(organisation, Some(name))]
+        }
+        None => config
+            .organisations()
+            .into_iter()
+            .map(|organisation| (organisation, None))
+            .collect(),
+    };
+    for (organisation, name) in targets {
+        let client = github.client(&organisation.name)?;
+        let context = Context::get(client, config.clone(), organisation).await?;
+        fix_topics(&context, name).await?;
+    }
+    Ok(())
+}
+
+async fn fix_topics(context: &Context, name: Option<String>) -> eyre::Result<()> {
+    let policy = context
+        .config
+        .topics
+        .as_ref()
+        .ok_or_else(|| eyre!("The config has no topics policy"))?;
+    let names = match name {
+        Some(name) => vec![name],
+        None => context
+            .all_projects()
+            .into_iter()
+            .filter(|project| !IGNORED_PROJECTS.contains(&project.as_str()))
+            .sorted()
+            .collect(),
+    };
+    for name in names {
+        let repo = match context.embark_github_repos.get(&name) {
+            Some(repo) => repo,
+            None => {
+                eprintln!("Skipping {} as it is not in the organisation", name);
+                continue;
+            }
+        };
+        let topics =
+            checks::topics::fixed_topics(&repo.topics, policy, context.website_tags(&name));
+        if topics == repo.topics {
+            continue;
+        }
+        context
+            .client
+            .set_repo_topics(&context.organisation.name, &name, &topics)
+            .await?;
+        println!("Set the topics of {} to {}", name, topics.join(", "));
+    }
+    Ok(())
+}
+
+/// Print a diff correcting the website data of an organisation, or open a
+/// pull request to the website repo with the corrections.
+async fn website_data(options: FixWebsiteData) -> eyre::Result<()> {
+    let FixWebsiteData {
+        organisation,
+        open_pr,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let organisation = config.organisation(organisation.as_deref())?;
+    let source = organisation
+        .website_data
+        .clone()
+        .ok_or_else(|| eyre!("Organisation `{}` has no website data", organisation.name))?;
+    let client = github.client(&organisation.name)?;
+    let context = Context::get(client, config, organisation).await?;
+    let text = github::download_repo_file(
+        &context.organisation.name,
+        &source.repo,
+        &source.branch,
+        &source.path,
+    )
+    .await?;
+
+    let missing = missing_repos(&text, &context).await?;
+    let (corrected, corrections) = correct_website_data(&text, &context, &missing)?;
+    if corrections.is_empty() {
+        eprintln!("The website data needs no corrections");
+        return Ok(());
+    }
+    for correction in &corrections {
+        eprintln!("{}", correction);
+    }
+    if !open_pr {
+        print!("{}", diff::unified(&source.path, &text, &corrected));
+        return Ok(());
+    }
+    let body = corrections
+        .iter()
+        .map(|correction| format!("- {}\n", correction))
+        .join("")
+        + "\nOpened by `cargo run fix website-data`.";
+    let changes = github::FileChanges {
+        base: source.branch,
+        branch: WEBSITE_DATA_BRANCH.to_string(),
+        title: "Correct the website data".to_string(),
+        body,
+        files: std::iter::once((source.path, corrected)).collect(),
+    };
+    let url = context
+        .client
+        .open_pull_request(&context.organisation.name, &source.repo, &changes)
+        .await?;
+    if let Some(url) = url {
+        println!("Opened {}", url);
+    }
+    Ok(())
+}
+
+/// The repos of entries that are missing from the repos of the organisation,
+/// by their lowercase name, with the `org/repo` name GitHub redirects them to
+/// if they were renamed or transferred, or `None` if they were deleted.
+pub type MissingRepos = HashMap<String, Option<String>>;
+
+/// Look up the repos of the entries of the website data that are missing from
+/// the repos of the organisation, as a renamed repo is only listed under its
+/// new name.
+pub async fn missing_repos(text: &str, context: &Context) -> eyre::Result<MissingRepos> {
+    let mut missing = MissingRepos::new();
+    for span in entry_spans(text)? {
+        let entry: OpenSourceWebsiteDataProject = serde_json::from_str(&text[span])
+            .wrap_err("Unable to parse an entry of the website data")?;
+        let name = match repo_name(&entry, context) {
+            Some((name, _)) if find_repo(name, context).is_none() => name.to_lowercase(),
+            _ => continue,
+        };
+        if missing.contains_key(&name) {
+            continue;
+        }
+        let full_name = context
+            .client
+            .repo_full_name(&context.organisation.name, &name)
+            .await
+            .wrap_err_with(|| format!("Unable to look up the repo {}", name))?;
+        let _ = missing.insert(name, full_name);
+    }
+    Ok(missing)
+}
+
+/// The website data with the entries of projects whose repos were archived
+/// moved to the `archived` array, like `archive_website_entry`, those whose
+/// repos were deleted removed, those whose repos were renamed pointed at
+/// their new name, and the entries sorted by name, along with a description
+/// of each correction. Only the entries are changed, so the rest of the file
+/// keeps its formatting and the diff stays small. Entries whose repos are
+/// missing from the organisation are only corrected once they have been
+/// looked up with `missing_repos`.
+pub fn correct_website_data(
+    text: &str,
+    context: &Context,
+    missing: &MissingRepos,
+) -> eyre::Result<(String, Vec<String>)> {
+    let mut archived_text = text.to_string();
+    let mut archived = Vec::new();
+    for span in entry_spans(text)? {
+        let entry: OpenSourceWebsiteDataProject = serde_json::from_str(&text[span])
+            .wrap_err("Unable to parse an entry of the website data")?;
+        if let Some(RepoProblem::Archived(repo)) = repo_problem(&entry, context, missing) {
+            if let Some(moved) = archive_website_entry(&archived_text, &entry.name)? {
+                archived_text = moved;
+                archived.push(format!(
+                    "Moved {} to the archived projects, as its repo {} is archived",
+                    entry.name, repo
+                ));
+            }
+        }
+    }
+    let (corrected, corrections) = remove_deleted_entries(&archived_text, context, missing)?;
+    Ok((corrected, archived.into_iter().chain(corrections).collect()))
+}
+
+/// The website data with the entries of projects whose repos were deleted
+/// removed, those whose repos were renamed corrected, and the entries sorted
+/// by name, see `correct_website_data`.
+fn remove_deleted_entries(
+    text: &str,
+    context: &Context,
+    missing: &MissingRepos,
+) -> eyre::Result<(String, Vec<String>)> {
+    let spans = entry_spans(text)?;
+    let mut corrections = Vec::new();
+    let mut entries = Vec::with_capacity(spans.len());
+    for span in &spans {
+        let entry: OpenSourceWebsiteDataProject = serde_json::from_str(&text[span.clone()])
+            .wrap_err("Unable to parse an entry of the website data")?;
+        let entry_text = &text[span.clone()];
+        match repo_problem(&entry, context, missing) {
+            Some(RepoProblem::Deleted(repo)) => corrections.push(format!(
+                "Removed {}, as its repo {} doesn't exist",
+                entry.name, repo
+            )),
+            Some(RepoProblem::Renamed { repo, url }) => {
+                corrections.push(format!(
+                    "Changed the repo of {} to {}, as its repo {} was renamed",
+                    entry.name, url, repo
+                ));
+                // The URL is replaced as it is written, quotes included, so
+                // that other fields starting with it are left alone
+                let old = serde_json::to_string(entry.repo.as_deref().unwrap_or_default())?;
+                let new = serde_json::to_string(&url)?;
+                let corrected = entry_text.replacen(&old, &new, 1);
+                entries.push((entry.name.to_lowercase(), Cow::Owned(corrected)));
+            }
+            _ => entries.push((entry.name.to_lowercase(), Cow::Borrowed(entry_text))),
+        }
+    }
+    if !entries.windows(2).all(|pair| pair[0].0 <= pair[1].0) {
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        corrections.push("Sorted the entries by name".to_string());
+    }
+    if corrections.is_empty() {
+        return Ok((text.to_string(), corrections));
+    }
+
+    // The entries are separated the same way they were before
+    let separator = match spans.get(1) {
+        Some(second) => &text[spans[0].end..second.start],
+        None => ",\n",
+    };
+    let corrected = format!(
+        "{}{}{}",
+        &text[..spans[0].start],
+        entries.iter().map(|(_, entry)| entry).join(separator),
+        &text[spans[spans.len() - 1].end..]
+    );
+    Ok((corrected, corrections))
+}
+
+/// The website data with a new entry added among the others, which are
+/// assumed to be sorted by name. Only the new entry is added, so the rest of
+/// the file keeps its formatting.
+pub fn add_website_entry(text: &str, entry: &serde_json::Value) -> eyre::Result<String> {
+    let name = entry["name"]
+        .as_str()
+        .ok_or_else(|| eyre!("The new entry has no name"))?;
+    let spans = entry_spans(text)?;
+    let (first, last) = match (spans.first(), spans.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(eyre!("The website data has no entries to add to")),
+    };
+    let mut names = Vec::with_capacity(spans.len());
+    for span in &spans {
+        let existing: OpenSourceWebsiteDataProject = serde_json::from_str(&text[span.clone()])
+            .wrap_err("Unable to parse an entry of the website data")?;
+        if existing.name.eq_ignore_ascii_case(name) {
+            return Err(eyre!(
+                "The website data already has an entry named {}",
+                name
+            ));
+        }
+        names.push(existing.name.to_lowercase());
+    }
+
+    // The entry is indented and separated like the first one
+    let line_start = text[..first.start].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &text[line_start..first.start];
+    let separator = match spans.get(1) {
+        Some(second) => text[first.end..second.start].to_string(),
+        None => format!(",\n{}", indent),
+    };
+    let rendered = serde_json::to_string_pretty(entry)?
+        .lines()
+        .join(&format!("\n{}", indent));
+    let name = name.to_lowercase();
+    Ok(match names.iter().position(|existing| *existing > name) {
+        Some(index) => {
+            let start = spans[index].start;
+            format!(
+                "{}{}{}{}",
+                &text[..start],
+                rendered,
+                separator,
+                &text[start..]
+            )
+        }
+        None => format!(
+            "{}{}{}{}",
+            &text[..last.end],
+            separator,
+            rendered,
+            &text[last.end..]
+        ),
+    })
+}
+
+/// The website data with the entry of a project moved from the `projects`
+/// array to the `archived` one, which is added after `projects` if there is
+/// none, or `None` if the project has no entry in `projects`.
+pub fn archive_website_entry(text: &str, name: &str) -> eyre::Result<Option<String>> {
+    let projects = entry_array(text, "projects")?
+        .ok_or_else(|| eyre!("The website data has no `projects` array"))?;
+    let spans = &projects.entries;
+    let mut index = None;
+    for (i, span) in spans.iter().enumerate() {
+        let entry: OpenSourceWebsiteDataProject = serde_json::from_str(&text[span.clone()])
+            .wrap_err("Unable to parse an entry of the website data")?;
+        if entry.project_name() == name || entry.name.eq_ignore_ascii_case(name) {
+            index = Some(i);
+            break;
+        }
+    }
+    let index = match index {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let span = spans[index].clone();
+    let entry = &text[span.clone()];
+    // The entry is removed along with the separator before or after it
+    let removed = if index + 1 < spans.len() {
+        span.start..spans[index + 1].start
+    } else if index > 0 {
+        spans[index - 1].end..span.end
+    } else {
+        span.clone()
+    };
+
+    // The entry is indented and separated like the entries of `projects`
+    let line_start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let indent = match &text[line_start..span.start] {
+        indent if indent.trim().is_empty() => indent,
+        _ => "",
+    };
+    let key_line_start = text[..projects.contents.start]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let key_indent: String = text[key_line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let separator = match spans.get(1) {
+        Some(second) => text[spans[0].end..second.start].to_string(),
+        None => format!(",\n{}", indent),
+    };
+    let (at, inserted) = match entry_array(text, "archived")? {
+        Some(archived) => match archived.entries.last() {
+            Some(last) => (last.end, format!("{}{}", separator, entry)),
+            None if archived.contents.is_empty() => (
+                archived.contents.start,
+                format!("\n{}{}\n{}", indent, entry, key_indent),
+            ),
+            None => (archived.contents.start, format!("\n{}{}", indent, entry)),
+        },
+        None => (
+            projects.contents.end + 1,
+            format!(
+                ",\n{0}\"archived\": [\n{1}{2}\n{0}]",
+                key_indent, indent, entry
+            ),
+        ),
+    };
+    Ok(Some(if at >= removed.end {
+        format!(
+            "{}{}{}{}",
+            &text[..removed.start],
+            &text[removed.end..at],
+            inserted,
+            &text[at..]
+        )
+    } else {
+        format!(
+            "{}{}{}{}",
+            &text[..at],
+            inserted,
+            &text[at..removed.start],
+            &text[removed.end..]
+        )
+    }))
+}
+
+/// Why the repo of an entry needs correcting, with the name of its repo.
+enum RepoProblem {
+    Deleted(String),
+    Archived(String),
+    /// The repo was renamed or transferred, and is now at `url`.
+    Renamed {
+        repo: String,
+        url: String,
+    },
+}
+
+/// Why the repo of an entry needs correcting. Entries without a repo, whose
+/// repo is outside the organisation, or whose repo is missing but wasn't
+/// looked up in `missing`, are left alone.
+fn repo_problem(
+    entry: &OpenSourceWebsiteDataProject,
+    context: &Context,
+    missing: &MissingRepos,
+) -> Option<RepoProblem> {
+    let (name, rest) = repo_name(entry, context)?;
+    match find_repo(name, context) {
+        Some(repo) if repo.archived => Some(RepoProblem::Archived(name.to_string())),
+        Some(_) => None,
+        None => match missing.get(&name.to_lowercase())? {
+            None => Some(RepoProblem::Deleted(name.to_string())),
+            Some(full_name) => Some(RepoProblem::Renamed {
+                repo: name.to_string(),
+                url: format!("https://github.com/{}{}", full_name, rest),
+            }),
+        },
+    }
+}
+
+/// The name of the repo of an entry in the organisation, and the rest of its
+/// URL, such as a `#` fragment.
+fn repo_name<'a>(
+    entry: &'a OpenSourceWebsiteDataProject,
+    context: &Context,
+) -> Option<(&'a str, &'a str)> {
+    let prefix = format!("https://github.com/{}/", context.organisation.name);
+    let path = entry.repo.as_deref()?.strip_prefix(&prefix)?;
+    let end = path.find('#').unwrap_or(path.len());
+    let name = path[..end].trim_end_matches('/');
+    Some((name, &path[name.len()..]))
+}
+
+/// The repo of the organisation with the given name. GitHub ignores the case
+/// of repo names in URLs.
+fn find_repo<'a>(name: &str, context: &'a Context) -> Option<&'a github::Repo> {
+    context
+        .embark_github_repos
+        .values()
+        .find(|repo| repo.name.eq_ignore_ascii_case(name))
+}
+
+/// The byte ranges of the entries of the `projects` array in the text of the
+/// website data.
+fn entry_spans(text: &str) -> eyre::Result<Vec<Range<usize>>> {
+    let projects = entry_array(text, "projects")?
+        .ok_or_else(|| eyre!("The website data has no `projects` array"))?;
+    Ok(projects.entries)
+}
+
+/// Where an array of entries is in the text of the website data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EntryArray {
+    /// The byte range of the text between the brackets.
+    contents: Range<usize>,
+    /// The byte ranges of the entries.
+    entries: Vec<Range<usize>>,
+}
+
+/// The array of entries with the given key in the top level object of the
+/// website data, or `None` if there is none.
+fn entry_array(text: &str, array_key: &str) -> eyre::Result<Option<EntryArray>> {
+    let data: serde_json::Value =
+        serde_json::from_str(text).wrap_err("Unable to parse the website data")?;
+    let count = match &data[array_key] {
+        serde_json::Value::Null => return Ok(None),
+        value => value
+            .as_array()
+            .ok_or_else(|| eyre!("`{}` in the website data is not an array", array_key))?
+            .len(),
+    };
+
+    let mut spans = Vec::with_capacity(count);
+    let mut depth = 0;
+    let (mut in_string, mut escaped) = (false, false);
+    // The last string in the top level object, which is the key of the array
+    // that follows it
+    let mut string_start = 0;
+    let mut key = "";
+    let mut array_start = None;
+    let mut entry_start = None;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if depth == 1 {
+                    key = &text[string_start + 1..i];
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                string_start = i;
+            }
+            '{' | '[' => {
+                if depth == 1 && c == '[' && key == array_key {
+                    array_start = Some(i + 1);
+                } else if depth == 2 && c == '{' && array_start.is_some() {
+                    entry_start = Some(i);
+                }
+                depth += 1;
+            }
+            '}' | ']' => {
+                depth -= 1;
+                match array_start {
+                    Some(_) if depth == 2 && c == '}' => {
+                        spans.extend(entry_start.take().map(|start| start..i + 1));
+                    }
+                    Some(start) if depth == 1 => {
+                        if spans.len() != count {
+                            break;
+                        }
+                        return Ok(Some(EntryArray {
+                            contents: start..i,
+                            entries: spans,
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(eyre!("Unable to find the entries of the website data"))
+}
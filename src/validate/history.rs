@@ -0,0 +1,159 @@
+//! A record of the failures found by each run, so that notifications can
+//! take into account how failures change over time.
+
+use super::report::Report;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How many runs are kept, the oldest being dropped first.
+const MAX_RUNS: usize = 200;
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct History {
+    pub runs: Vec<Run>,
+    /// The run whose failures were sent in the most recent digest.
+    #[serde(default)]
+    pub last_digest: Option<Run>,
+    /// When every project was last validated, in seconds since the Unix
+    /// epoch, as incremental runs only validate some.
+    #[serde(default)]
+    pub last_full_validation: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Run {
+    /// When the run happened, in seconds since the Unix epoch.
+    pub time: u64,
+    /// The ids of the failing checks of each project with failures.
+    pub failures: BTreeMap<String, Vec<String>>,
+    /// Adoption metrics of each project at the time of the run.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, Metrics>,
+}
+
+/// How widely a project is used, snapshotted by each run to give trends
+/// over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Metrics {
+    pub stars: u64,
+    pub forks: u64,
+    /// Missing if the contributors could not be counted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contributors: Option<u64>,
+}
+
+impl Run {
+    pub fn new(time: u64, report: &Report) -> Self {
+        let failures = report
+            .projects
+            .iter()
+            .filter(|project| !project.ok)
+            .map(|project| {
+                let checks = project
+                    .checks
+                    .iter()
+                    .filter(|check| !check.ok)
+                    .map(|check| check.id.clone())
+                    .collect();
+                (project.name.clone(), checks)
+            })
+            .collect();
+        Self {
+            time,
+            failures,
+            metrics: BTreeMap::new(),
+        }
+    }
+}
+
+/// How the failures of a project have changed since the last digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// The project had no failures at the last digest.
+    New,
+    /// More checks are failing than at the last digest.
+    Worse,
+    /// Fewer checks are failing than at the last digest.
+    Better,
+    Same,
+}
+
+impl Trend {
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Self::New => "🆕",
+            Self::Worse => "↑",
+            Self::Better => "↓",
+            Self::Same => "→",
+        }
+    }
+}
+
+impl History {
+    pub fn record(&mut self, run: Run) {
+        self.runs.push(run);
+        if self.runs.len() > MAX_RUNS {
+            let _ = self.runs.drain(..self.runs.len() - MAX_RUNS);
+        }
+    }
+
+    /// The metrics of each project recorded by each run, oldest first.
+    pub fn metrics(&self) -> BTreeMap<&str, Vec<(u64, Metrics)>> {
+        let mut series: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for run in &self.runs {
+            for (project, metrics) in &run.metrics {
+                series
+                    .entry(project.as_str())
+                    .or_default()
+                    .push((run.time, *metrics));
+            }
+        }
+        series
+    }
+
+    /// How many of the most recent runs the check has failed for the project
+    /// in a row.
+    pub fn consecutive_failures(&self, project: &str, check: &str) -> usize {
+        self.runs
+            .iter()
+            .rev()
+            .take_while(|run| {
+                run.failures
+                    .get(project)
+                    .is_some_and(|checks| checks.iter().any(|id| id == check))
+            })
+            .count()
+    }
+
+    /// Whether a digest should be sent at `now`, given the interval between
+    /// digests.
+    pub fn digest_due(&self, now: u64, interval: Duration) -> bool {
+        match &self.last_digest {
+            Some(last) => now >= last.time + interval.as_secs(),
+            None => true,
+        }
+    }
+
+    /// How a project with the given number of failing checks has changed
+    /// since the last digest, if one has been sent.
+    pub fn trend(&self, project: &str, failing_checks: usize) -> Option<Trend> {
+        let last = self.last_digest.as_ref()?;
+        let trend = match last.failures.get(project).map(Vec::len) {
+            None => Trend::New,
+            Some(was) if failing_checks > was => Trend::Worse,
+            Some(was) if failing_checks < was => Trend::Better,
+            Some(_) => Trend::Same,
+        };
+        Some(trend)
+    }
+}
+
+/// The current time in seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,124 @@
+//! Incremental validation, in which only the projects with activity since
+//! the last run are validated again.
+
+use super::{context::Context, history::History, report::Report};
+use crate::github;
+use std::{collections::HashSet, time::Duration};
+
+/// How often every project is validated even when incremental.
+const FULL_VALIDATION_INTERVAL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The most events GitHub returns, after which older events are missing.
+const MAX_EVENTS: usize = 300;
+
+/// Events that may change the outcome of the checks of a project.
+const CHANGE_EVENTS: [&str; 7] = [
+    "PushEvent",
+    "ReleaseEvent",
+    "CreateEvent",
+    "DeleteEvent",
+    "PublicEvent",
+    "MemberEvent",
+    "RepositoryEvent",
+];
+
+/// Which projects are validated in a run.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// Every project, for the given reason.
+    Full(&'static str),
+    /// Only the projects with these names, the others reusing their previous
+    /// results.
+    Changed(HashSet<String>),
+}
+
+/// Find the projects of the organisations with activity since the last run,
+/// falling back to validating every project when that can't be determined.
+pub async fn scope(
+    contexts: &[Context],
+    history: &History,
+    previous: Option<&Report>,
+    now: u64,
+) -> eyre::Result<Scope> {
+    if previous.is_none() {
+        return Ok(Scope::Full("there are no previous results"));
+    }
+    let since = match history.runs.last() {
+        Some(run) => run.time,
+        None => return Ok(Scope::Full("there is no history of previous runs")),
+    };
+    match history.last_full_validation {
+        Some(time) if now < time + FULL_VALIDATION_INTERVAL.as_secs() => (),
+        _ => return Ok(Scope::Full("a full validation is due")),
+    }
+
+    let mut changed = HashSet::new();
+    for context in contexts {
+        let events = context
+            .client
+            .organisation_events(&context.organisation.name)
+            .await?;
+        match changed_since(&events, since) {
+            Scope::Changed(names) => changed.extend(names),
+            full @ Scope::Full(_) => return Ok(full),
+        }
+    }
+    Ok(Scope::Changed(changed))
+}
+
+fn changed_since(events: &[github::Event], since: u64) -> Scope {
+    // Events may be missing if GitHub doesn't return any from before the
+    // last run
+    let oldest = events.iter().filter_map(github::Event::time).min();
+    if events.len() >= MAX_EVENTS && oldest.is_none_or(|oldest| oldest > since) {
+        return Scope::Full("there have been too many events since the last run");
+    }
+
+    let changed = events
+        .iter()
+        .filter(|event| CHANGE_EVENTS.contains(&event.kind.as_str()))
+        .filter(|event| event.time().is_none_or(|time| time >= since))
+        .map(|event| {
+            let name = &event.repo.name;
+            name.rsplit('/').next().unwrap_or(name).to_string()
+        })
+        .collect();
+    Scope::Changed(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_event(kind: &str, repo: &str, created_at: &str) -> github::Event {
+        github::Event {
+            kind: kind.to_string(),
+            repo: github::EventRepo {
+                name: format!("EmbarkStudios/{}", repo),
+            },
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn projects_changed_since_the_last_run() {
+        // 2021-01-01T00:00:00Z
+        let since = 1_609_459_200;
+        let events = [
+            make_event("PushEvent", "a", "2021-01-02T00:00:00Z"),
+            make_event("WatchEvent", "b", "2021-01-02T00:00:00Z"),
+            make_event("ReleaseEvent", "c", "2020-12-31T00:00:00Z"),
+        ];
+        let expected = vec!["a".to_string()].into_iter().collect();
+        assert_eq!(changed_since(&events, since), Scope::Changed(expected));
+    }
+
+    #[test]
+    fn too_many_events_needs_full_validation() {
+        let events = vec![make_event("PushEvent", "a", "2021-01-02T00:00:00Z"); MAX_EVENTS];
+        assert!(matches!(
+            changed_since(&events, 1_609_459_200),
+            Scope::Full(_)
+        ));
+    }
+}
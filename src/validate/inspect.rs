@@ -0,0 +1,653 @@
+//! A detailed report of a single project, showing what each check looked at
+//! and how to fix the checks that fail.
+
+use super::{context::Context, project::Project};
+use crate::{config::Config, github, Inspect};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// How many lines of a file are shown as evidence.
+const SNIPPET_LINES: usize = 10;
+
+pub async fn inspect(options: Inspect) -> eyre::Result<()> {
+    let Inspect {
+        name,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let (organisation, name) = config.project_organisation(&name);
+    let client = github.client(&organisation.name)?;
+    let context = Context::get(client, config, organisation).await?;
+
+    let project = Project::new(name).validate(&context).await;
+
+    println!("{}", project.name);
+    match context.embark_github_repos.get(project.repo()) {
+        Some(repo) => println!(
+            "    GitHub: archived {}, private {}, fork {}",
+            repo.archived, repo.private, repo.fork
+        ),
+        None => println!(
+            "    GitHub: not found in the {} organisation",
+            context.organisation.name
+        ),
+    }
+
+    for check in &project.checks {
+        let icon = if check.result.is_ok() {
+            "✔️"
+        } else {
+            "❌"
+        };
+        println!("\n{} {}", icon, check.id);
+        if let Err(error) = &check.result {
+            print!("{}", crate::error::cause_string(error.as_ref(), true));
+        }
+        if let Some(prerequisite) = &check.prerequisite {
+            println!("  ⏭️ Skipped: prerequisite {} failed", prerequisite);
+        }
+        if let Some(exemption) = &check.exemption {
+            println!(
+                "  🛡️ Exempt, this check would fail:\n    {}",
+                exemption.justification
+            );
+        }
+        if let Some(warning) = &check.warning {
+            println!(
+                "  ⚠️ Warning, this check does not fail yet:\n    {}",
+                warning
+            );
+        }
+        let evidence = evidence(&project, &context, &check.id).await;
+        if !evidence.is_empty() {
+            println!("  Evidence:");
+            for line in evidence {
+                println!("    {}", line);
+            }
+        }
+        if check.result.is_err() || check.warning.is_some() {
+            println!("  To fix:\n    {}", remediation(&check.id));
+        }
+    }
+
+    if project.has_errors() {
+        Err(eyre!("The project does not conform to our guidelines"))
+    } else {
+        Ok(())
+    }
+}
+
+/// What a check looked at, gathered again after it has run. Downloads are
+/// cached for the run so this doesn't repeat requests.
+async fn evidence(project: &Project, context: &Context, check: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let (kind, id) = check.split_once(':').unwrap_or((check, ""));
+    match kind {
+        "maintainers" => {
+            match project.download_file(context, ".github/CODEOWNERS").await {
+                Ok(text) => {
+                    lines.push(".github/CODEOWNERS:".to_string());
+                    lines.extend(snippet(&text));
+                }
+                Err(_) => lines.push(".github/CODEOWNERS not found".to_string()),
+            }
+            if let Some(maintainers) = &project.maintainers {
+                lines.push(format!(
+                    "Maintainers: {}",
+                    maintainers.iter().sorted().join(", ")
+                ));
+            }
+        }
+
+        "website-data-inclusion" | "rust-ecosystem-registration" => {
+            let entry = context
+                .opensource_website_projects
+                .iter()
+                .find(|website_project| website_project.name == project.name);
+            match entry {
+                Some(entry) => {
+                    let json = serde_json::to_string(entry).unwrap_or_default();
+                    lines.push(format!("data.json entry: {}", json));
+                }
+                None => lines.push(format!("No entry named {} in data.json", project.name)),
+            }
+            if kind == "rust-ecosystem-registration" {
+                let mentioned = context.rust_ecosystem_readme.contains(&project.name);
+                lines.push(format!("Mentioned in rust-ecosystem README: {}", mentioned));
+            }
+        }
+
+        "website-owners" => {
+            if let Some(website) = context
+                .opensource_website_projects
+                .iter()
+                .find(|website| website.name == project.name)
+            {
+                lines.push(format!("Website owners: {}", website.owners.join(", ")));
+                if let Some(team) = &website.team {
+                    lines.push(format!("Website team: {}", team));
+                }
+            }
+            lines.push(format!(
+                "CODEOWNERS maintainers: {}",
+                project.maintainers.iter().flatten().sorted().join(", ")
+            ));
+        }
+
+        "topics" => {
+            if let Some(repo) = context.embark_github_repos.get(project.repo()) {
+                lines.push(format!("Topics: {}", repo.topics.join(", ")));
+            }
+        }
+
+        "workflow-audit" | "actions-pinning" | "coverage" => {
+            let paths = context
+                .client
+                .directory_files(
+                    &context.organisation.name,
+                    project.repo(),
+                    ".github/workflows",
+                )
+                .await
+                .unwrap_or_default();
+            lines.push(format!("Workflows: {}", paths.join(", ")));
+        }
+
+        "secret-scanning" => {
+            if let Ok(settings) = context
+                .client
+                .security_and_analysis(&context.organisation.name, project.repo())
+                .await
+            {
+                let status = |feature: &Option<github::Feature>| {
+                    feature
+                        .as_ref()
+                        .map_or("unavailable", |f| f.status.as_str())
+                        .to_string()
+                };
+                lines.push(format!(
+                    "Secret scanning: {}",
+                    status(&settings.secret_scanning)
+                ));
+                lines.push(format!(
+                    "Push protection: {}",
+                    status(&settings.secret_scanning_push_protection)
+                ));
+            }
+        }
+
+        "dependabot" => {
+            let client = &context.client;
+            if let Ok(enabled) = client
+                .vulnerability_alerts_enabled(&context.organisation.name, project.repo())
+                .await
+            {
+                lines.push(format!("Dependabot alerts enabled: {}", enabled));
+            }
+            if let Ok(settings) = client
+                .security_and_analysis(&context.organisation.name, project.repo())
+                .await
+            {
+                lines.push(format!(
+                    "Dependabot security updates enabled: {}",
+                    github::Feature::is_enabled(&settings.dependabot_security_updates)
+                ));
+            }
+        }
+
+        "signed-releases" => {
+            let client = &context.client;
+            if let Ok(tag) = client
+                .latest_release_tag(&context.organisation.name, project.repo())
+                .await
+            {
+                match tag {
+                    Some(tag) => lines.push(format!("Latest release: {}", tag)),
+                    None => lines.push("No releases".to_string()),
+                }
+            }
+            if let Ok(required) = client
+                .requires_signed_commits(&context.organisation.name, project.repo())
+                .await
+            {
+                lines.push(format!("Signed commits required: {}", required));
+            }
+        }
+
+        "contribution-agreement" => {
+            if let Some(policy) = &context.config.contribution_agreement {
+                lines.push(format!(
+                    "Declared agreement: {:?}",
+                    policy.agreement(&project.name)
+                ));
+            }
+            if let Ok(required) = context
+                .client
+                .required_status_checks(&context.organisation.name, project.repo())
+                .await
+            {
+                lines.push(format!("Required status checks: {}", required.join(", ")));
+            }
+        }
+
+        "required-files" => {
+            let requirement = context
+                .config
+                .required_files
+                .iter()
+                .find(|requirement| requirement.id == id);
+            let files = requirement
+                .into_iter()
+                .flat_map(|requirement| requirement.all_alternatives())
+                .flatten()
+                .map(|file| file.path())
+                .unique();
+            for path in files {
+                let status = match project.download_optional_file(context, path).await {
+                    Ok(Some(_)) => "found",
+                    Ok(None) => "not found",
+                    Err(_) => "could not be downloaded",
+                };
+                lines.push(format!("{}: {}", path, status));
+            }
+        }
+
+        "secret-patterns" => {
+            if let Some(config) = &context.config.secret_patterns {
+                lines.push(format!(
+                    "Files larger than {} bytes are not scanned",
+                    config.max_file_size
+                ));
+                if !config.ignore.is_empty() {
+                    lines.push(format!("{} ignore globs configured", config.ignore.len()));
+                }
+            }
+        }
+
+        "internal-references" => {
+            if let Some(config) = &context.config.internal_references {
+                for path in &config.files {
+                    if let Ok(Some(_)) = project.download_optional_file(context, path).await {
+                        lines.push(format!("Scanned {}", path));
+                    }
+                }
+            }
+            let paths = context
+                .client
+                .directory_files(
+                    &context.organisation.name,
+                    project.repo(),
+                    ".github/workflows",
+                )
+                .await
+                .unwrap_or_default();
+            lines.push(format!("Scanned workflows: {}", paths.join(", ")));
+        }
+
+        "readme-links" => match project.download_optional_file(context, "README.md").await {
+            Ok(Some(_)) => lines.push("Links of README.md requested".to_string()),
+            Ok(None) => lines.push("README.md not found, so no links were checked".to_string()),
+            Err(_) => lines.push("README.md could not be downloaded".to_string()),
+        },
+
+        "good-first-issues" => {
+            if let Some(policy) = &context.config.good_first_issues {
+                lines.push(format!(
+                    "Applies to project: {}",
+                    policy.applies_to(context.website_tags(&project.name))
+                ));
+                if let Ok(count) = context
+                    .client
+                    .open_issues_with_label(
+                        &context.organisation.name,
+                        project.repo(),
+                        &policy.label,
+                    )
+                    .await
+                {
+                    lines.push(format!(
+                        "Open issues labelled \"{}\": {}",
+                        policy.label, count
+                    ));
+                }
+            }
+        }
+
+        "issue-response" => {
+            if let Some(policy) = &context.config.issue_response {
+                lines.push(format!(
+                    "Applies to project: {}",
+                    policy.applies_to(context.website_tags(&project.name))
+                ));
+                lines.push(format!(
+                    "SLA: median first response within {} hours over the last {} days",
+                    policy.max_median_hours, policy.window_days
+                ));
+            }
+        }
+
+        "release-drift" => {
+            let client = &context.client;
+            let organisation = &context.organisation.name;
+            match client
+                .latest_release_tag(organisation, project.repo())
+                .await
+            {
+                Ok(Some(tag)) => {
+                    lines.push(format!("Latest release: {}", tag));
+                    if let Ok(ahead) = client
+                        .commits_ahead_of(organisation, project.repo(), &tag)
+                        .await
+                    {
+                        lines.push(format!("Commits since the release: {}", ahead));
+                    }
+                }
+                Ok(None) => lines.push("No releases".to_string()),
+                Err(_) => (),
+            }
+        }
+
+        "cargo-metadata" => match project.download_optional_file(context, "Cargo.toml").await {
+            Ok(Some(text)) => {
+                lines.push("Cargo.toml:".to_string());
+                lines.extend(snippet(&text));
+            }
+            Ok(None) => lines.push("Cargo.toml not found".to_string()),
+            Err(_) => lines.push("Cargo.toml could not be downloaded".to_string()),
+        },
+
+        "package-json" => match project
+            .download_optional_file(context, "package.json")
+            .await
+        {
+            Ok(Some(text)) => {
+                lines.push("package.json:".to_string());
+                lines.extend(snippet(&text));
+            }
+            Ok(None) => lines.push("package.json not found".to_string()),
+            Err(_) => lines.push("package.json could not be downloaded".to_string()),
+        },
+
+        "msrv" => {
+            if let Ok(Some(text)) = project.download_optional_file(context, "Cargo.toml").await {
+                let declared = text
+                    .lines()
+                    .find(|line| line.trim_start().starts_with("rust-version"));
+                lines.push(format!(
+                    "Cargo.toml: {}",
+                    declared.map_or("no rust-version", str::trim)
+                ));
+            }
+        }
+
+        "unsafe-code" => {
+            if let Some(policy) = &context.config.unsafe_code {
+                lines.push(format!(
+                    "Applies to project: {}",
+                    policy.applies_to(&project.name, context.website_tags(&project.name))
+                ));
+            }
+            for path in ["Cargo.toml", "src/lib.rs", "src/main.rs", "README.md"].iter() {
+                let status = match project.download_optional_file(context, path).await {
+                    Ok(Some(_)) => "found",
+                    Ok(None) => "not found",
+                    Err(_) => "could not be downloaded",
+                };
+                lines.push(format!("{}: {}", path, status));
+            }
+        }
+
+        "license-year" => {
+            if let Some(repo) = context.embark_github_repos.get(project.repo()) {
+                lines.push(format!(
+                    "Last pushed: {}",
+                    repo.pushed_at.as_deref().unwrap_or("unknown")
+                ));
+            }
+            if let Some(config) = &context.config.license_year {
+                for path in &config.files {
+                    if let Ok(Some(text)) = project.download_optional_file(context, path).await {
+                        let copyright = text
+                            .lines()
+                            .find(|line| line.to_ascii_lowercase().contains("copyright"));
+                        lines.push(format!(
+                            "{}: {}",
+                            path,
+                            copyright.map_or("no copyright line", str::trim)
+                        ));
+                    }
+                }
+            }
+        }
+
+        "third-party-licenses" | "release-checksums" => {
+            if let Ok(Some(release)) = context
+                .client
+                .latest_release(&context.organisation.name, project.repo())
+                .await
+            {
+                let assets: Vec<_> = release
+                    .assets
+                    .iter()
+                    .map(|asset| asset.name.as_str())
+                    .collect();
+                lines.push(format!(
+                    "Release {} assets: {}",
+                    release.tag_name,
+                    if assets.is_empty() {
+                        "none".to_string()
+                    } else {
+                        assets.join(", ")
+                    }
+                ));
+            }
+        }
+
+        "external" => {
+            if let Some(external) = context
+                .config
+                .external_checks
+                .iter()
+                .find(|external| external.id == id)
+            {
+                lines.push(format!("Command: {}", external.command.join(" ")));
+            }
+        }
+
+        "wasm" => {
+            if let Some(wasm) = context.config.wasm_checks.iter().find(|wasm| wasm.id == id) {
+                lines.push(format!("Module: {}", wasm.module.display()));
+            }
+        }
+
+        _ => (),
+    }
+    lines
+}
+
+fn snippet(text: &str) -> Vec<String> {
+    let mut lines: Vec<_> = text
+        .lines()
+        .take(SNIPPET_LINES)
+        .map(|line| format!("| {}", line))
+        .collect();
+    let remaining = text.lines().count().saturating_sub(SNIPPET_LINES);
+    if remaining > 0 {
+        lines.push(format!("| ... {} more lines", remaining));
+    }
+    lines
+}
+
+/// How to fix a failing check.
+fn remediation(check: &str) -> &'static str {
+    match check.split_once(':').map_or(check, |(kind, _)| kind) {
+        "maintainers" => {
+            "Add a .github/CODEOWNERS file with a `* @handle` line listing the maintainers, who \
+must be public members of EmbarkStudios. `scaffold codeowners` can generate one."
+        }
+        "website-data-inclusion" => {
+            "Add the project to static/data/data.json in EmbarkStudios/opensource-website."
+        }
+        "rust-ecosystem-registration" => {
+            "Add the project to the README of EmbarkStudios/rust-ecosystem."
+        }
+        "website-owners" => {
+            "Update the owners and team of the project in data.json in \
+EmbarkStudios/opensource-website, or its CODEOWNERS file, so that they agree."
+        }
+        "topics" => {
+            "Run `fix topics` with the project's name, or edit the topics in the repo's settings."
+        }
+        "workflow-audit" => {
+            "Pin third-party actions to a full commit SHA, check out only the base branch in \
+pull_request_target workflows, and grant only the permissions jobs need."
+        }
+        "actions-pinning" => {
+            "Replace the version of each listed action with the full SHA of the commit it points \
+to, keeping the version in a comment, or allow the action in `[actions-pinning]`."
+        }
+        "secret-scanning" => {
+            "Enable secret scanning and push protection under Code security and analysis in the \
+repo's settings."
+        }
+        "dependabot" => {
+            "Enable Dependabot alerts and security updates under Code security and analysis in \
+the repo's settings."
+        }
+        "signed-releases" => {
+            "Create release tags with `git tag --sign` using a key added to GitHub, and require \
+signed commits in the default branch's protection rule if the policy asks for it."
+        }
+        "contribution-agreement" => {
+            "For the DCO, install the DCO app and make its check required in the default \
+branch's protection rule. For a CLA, set up CLA assistant with a .clabot file or its GitHub \
+Action."
+        }
+        "coverage" => {
+            "Add a codecov.yml, or a workflow step that measures coverage with a tool such as \
+cargo-llvm-cov and uploads it."
+        }
+        "secret-patterns" => {
+            "Revoke the secrets, as they may already have been copied, then remove them from the \
+repo. Fake keys such as those of test fixtures can be ignored with the config's `ignore` globs."
+        }
+        "internal-references" => {
+            "Remove or replace the internal references, such as by linking to public issues or \
+describing the project without its internal code name."
+        }
+        "readme-links" => {
+            "Fix or remove the dead links in the README. Links to sites that reject automated \
+requests can be ignored with the config's `ignore` globs."
+        }
+        "good-first-issues" => {
+            "Open or label issues that are small and well described enough for a new contributor \
+to pick up, with mentoring instructions if possible."
+        }
+        "issue-response" => {
+            "Triage the unanswered issues, and consider adding maintainers or asking for help in \
+the open source channel if the project has more issues than its maintainers can handle."
+        }
+        "release-drift" => {
+            "Publish a new release with the changes made since the latest one, as described in \
+the maintenance guidelines."
+        }
+        "cargo-metadata" => {
+            "Set the missing fields in the [package] section of each crate's Cargo.toml, or in \
+[workspace.package] with `field.workspace = true` in the members. Crates that are not published \
+can set `publish = false`."
+        }
+        "msrv" => {
+            "Set `rust-version` in Cargo.toml to the oldest Rust version the project supports, and \
+make any CI job testing the MSRV install that version."
+        }
+        "unsafe-code" => {
+            "Add #![forbid(unsafe_code)] to the crate root or `unsafe_code = \"forbid\"` to the \
+[lints.rust] table of Cargo.toml. Projects that need unsafe code should explain how it is kept \
+sound in a Safety section of the README."
+        }
+        "license-year" => {
+            "Extend the copyright years of the license files to include the current year, such as \
+`Copyright (c) 2019-2023`."
+        }
+        "third-party-licenses" => {
+            "Generate the licenses of dependencies with `cargo about generate` in the release \
+workflow and upload them as a release asset, or commit the `about.toml` it is configured by."
+        }
+        "release-checksums" => {
+            "Upload a checksum for every release asset, such as a `SHA256SUMS` file generated with \
+`sha256sum`, and sign it if signatures are required."
+        }
+        "container-images" => {
+            "Publish the image from a workflow, such as with `docker/build-push-action`, to one of \
+the allowed registry namespaces and show how to pull it from there in the README."
+        }
+        "install-instructions" => {
+            "Add `[package.metadata.binstall]` to Cargo.toml so the tool can be installed with \
+`cargo binstall`, or document how to install it in the README, such as with `cargo install` or \
+`brew install`."
+        }
+        "first-time-contributors" => {
+            "Thank the first-time contributors and review, merge or explain why their pull \
+requests can't be accepted."
+        }
+        "package-json" => {
+            "Set the missing fields in package.json, or mark the package `\"private\": true` if it \
+isn't published, and commit the lockfile of the package manager."
+        }
+        "cpp-license-headers" => {
+            "Add the license header to the start of every C and C++ source file, and a \
+.clang-format describing the project's style to the root of the repo."
+        }
+        "template-drift" => {
+            "Update the boilerplate files to match those of the template repo, keeping only the \
+changes the project needs, or raise the allowed `max-changed-lines`."
+        }
+        "maintenance-banner" => {
+            "Put the one maintenance status badge that matches the project's state at the top of \
+the README, such as `maintenance-archived` once the repo is archived, or `passively--maintained` \
+once it is no longer actively developed."
+        }
+        "badges" => {
+            "Add the missing badges to the top of the README, copying the markdown given for each \
+from the error or from badges.toml in EmbarkStudios/opensource."
+        }
+        "repo-files" => {
+            "Check that the repo exists and GitHub can be reached. Repos with more files than the \
+GitHub API lists at once need their largest generated or vendored directories removed."
+        }
+        "repo-name" => {
+            "Update the project's `repo` in data.json, and any mentions of it in the config, to the \
+new name of the repository."
+        }
+        "tier" => {
+            "Set the project's `tier` in data.json to one of the tiers in the config's \
+`tiers.policies`."
+        }
+        "exemptions" => {
+            "Give every exemption in .github/embark-oss.toml a `justification`, and write \
+`expires` dates as YYYY-MM-DD."
+        }
+        "required-files" => {
+            "Add the files that were not found, or the files of one of the alternatives, to one of \
+the project's branches."
+        }
+        _ => "See the documentation of this organisation specific check.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_files_are_truncated() {
+        let text = (1..=12).map(|n| n.to_string()).join("\n");
+        let lines = snippet(&text);
+        assert_eq!(lines.len(), SNIPPET_LINES + 1);
+        assert_eq!(lines[0], "| 1");
+        assert_eq!(lines[SNIPPET_LINES], "| ... 2 more lines");
+        assert_eq!(snippet("a\nb"), vec!["| a", "| b"]);
+    }
+}
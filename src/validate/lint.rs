@@ -0,0 +1,280 @@
+//! Linting of the config and of the exemption files of repos, so that
+//! mistakes are caught before the nightly run uses them rather than showing
+//! up as confusing results.
+
+use super::{exemptions, history, project::Project, registry};
+use crate::{
+    config::{self, Config},
+    slack, ConfigLint,
+};
+use eyre::{eyre, WrapErr};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// A mistake that changes what is checked or notified.
+    Error(String),
+    /// Something that has no effect, or could not be checked here.
+    Warning(String),
+}
+
+pub async fn lint_config(options: ConfigLint) -> eyre::Result<()> {
+    let ConfigLint {
+        config,
+        exemptions,
+        skip_webhooks,
+        slack_webhook_url,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+
+    // A repo's own exemptions are linted when run from its checkout
+    let exemptions = if exemptions.is_empty() && Path::new(exemptions::PATH).exists() {
+        vec![exemptions::PATH.into()]
+    } else {
+        exemptions
+    };
+    let mut findings = lint(&config);
+    for path in &exemptions {
+        let text = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Unable to read {}", path.display()))?;
+        findings.extend(
+            lint_exemptions(&config, &text, history::now())
+                .into_iter()
+                .map(|finding| finding.map(|message| format!("{}: {}", path.display(), message))),
+        );
+    }
+    if !skip_webhooks {
+        findings.extend(lint_webhooks(&config, slack_webhook_url.as_deref()).await);
+        findings.extend(lint_destinations(&config).await);
+    }
+
+    let mut errors = 0;
+    for finding in &findings {
+        match finding {
+            Finding::Error(message) => {
+                errors += 1;
+                println!("❌ {}", message);
+            }
+            Finding::Warning(message) => println!("⚠️ {}", message),
+        }
+    }
+    match errors {
+        0 => {
+            if findings.is_empty() {
+                println!("✔️ No problems found");
+            }
+            Ok(())
+        }
+        errors => Err(eyre!("Found {} problems with the config", errors)),
+    }
+}
+
+impl Finding {
+    fn map(self, f: impl FnOnce(String) -> String) -> Self {
+        match self {
+            Self::Error(message) => Self::Error(f(message)),
+            Self::Warning(message) => Self::Warning(f(message)),
+        }
+    }
+}
+
+/// The problems with the check ids the config refers to.
+pub fn lint(config: &Config) -> Vec<Finding> {
+    let enabled = Project::check_ids(config);
+    let mut findings = Vec::new();
+    for (tier, policy) in &config.tiers.policies {
+        for (field, ids) in [("skip", &policy.skip), ("warn", &policy.warn)] {
+            for id in ids {
+                let field = format!("tiers.policies.{}.{}", tier, field);
+                findings.extend(lint_check_id(&field, id, |check| check == id, &enabled));
+            }
+        }
+    }
+    let scorecard = &config.scorecard;
+    let categories = scorecard.categories.iter().flat_map(|(category, ids)| {
+        ids.iter()
+            .map(move |id| (format!("scorecard.categories.{}", category), id))
+    });
+    let severities = scorecard
+        .severities
+        .keys()
+        .map(|id| ("scorecard.severities".to_string(), id));
+    let exemptable = config
+        .exemptions
+        .exemptable
+        .iter()
+        .flatten()
+        .map(|id| ("exemptions.exemptable".to_string(), id));
+    for (field, id) in categories.chain(severities).chain(exemptable) {
+        let findings_of_id = lint_check_id(
+            &field,
+            id,
+            |check| config::matches_check(id, check),
+            &enabled,
+        );
+        // The scorecard and the exemption policy describe every built-in
+        // check whether or not it is enabled, so only unknown checks are a
+        // problem
+        findings.extend(
+            findings_of_id
+                .into_iter()
+                .filter(|finding| matches!(finding, Finding::Error(_))),
+        );
+    }
+    findings
+}
+
+fn lint_check_id(
+    field: &str,
+    id: &str,
+    matches: impl Fn(&str) -> bool,
+    enabled: &[String],
+) -> Option<Finding> {
+    if enabled.iter().any(|check| matches(check)) {
+        None
+    } else if !id.contains(':') && registry::get(id).is_some() {
+        Some(Finding::Warning(format!(
+            "`{}` names `{}`, which the config doesn't enable",
+            field, id
+        )))
+    } else {
+        Some(Finding::Error(format!(
+            "`{}` names `{}`, which is not a check",
+            field, id
+        )))
+    }
+}
+
+/// The problems with the exemptions of a repo, given at `now` in seconds
+/// since the Unix epoch.
+pub fn lint_exemptions(config: &Config, text: &str, now: u64) -> Vec<Finding> {
+    let exemptions = match exemptions::parse(text) {
+        Ok(exemptions) => exemptions,
+        Err(error) => return vec![Finding::Error(format!("{:#}", error))],
+    };
+    let enabled = Project::check_ids(config);
+    let mut findings: Vec<_> = match exemptions::check(&exemptions, &config.exemptions) {
+        Ok(()) => Vec::new(),
+        Err(error) => error
+            .to_string()
+            .lines()
+            // The first line only says that the file is invalid
+            .skip(1)
+            .map(|line| Finding::Error(line.to_string()))
+            .collect(),
+    };
+    for exemption in &exemptions {
+        let id = &exemption.check;
+        if !enabled.contains(id) && (id.contains(':') || registry::get(id).is_none()) {
+            findings.push(Finding::Error(format!(
+                "The exemption from {} names a check that doesn't exist",
+                id
+            )));
+        }
+        let expired = exemption
+            .expires
+            .as_deref()
+            .and_then(crate::github::parse_date)
+            .is_some_and(|day| day <= now);
+        if expired {
+            findings.push(Finding::Error(format!(
+                "The exemption from {} expired on {}",
+                id,
+                exemption.expires.as_deref().unwrap_or_default()
+            )));
+        }
+    }
+    findings
+}
+
+/// Check every Slack webhook the config names, and the `--slack-webhook-url`
+/// one if given, without posting anything.
+async fn lint_webhooks(config: &Config, slack_webhook_url: Option<&str>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if let Some(url) = slack_webhook_url {
+        if let Err(error) = slack::check_webhook(url).await {
+            findings.push(Finding::Error(format!(
+                "The --slack-webhook-url webhook can't be used: {:#}",
+                error
+            )));
+        }
+    }
+    let names = config
+        .slack_routes
+        .iter()
+        .map(|route| &route.webhook_url_env)
+        .chain(
+            config
+                .escalation
+                .iter()
+                .filter_map(|escalation| escalation.webhook_url_env.as_ref()),
+        )
+        .chain(
+            config
+                .celebrations
+                .iter()
+                .map(|celebrations| &celebrations.webhook_url_env),
+        )
+        .chain(config.expiring_exemptions.webhook_url_env.as_ref());
+    for name in names {
+        let url = match std::env::var(name) {
+            Ok(url) => url,
+            Err(_) => {
+                findings.push(Finding::Warning(format!(
+                    "The {} environment variable is not set, so its Slack webhook was not checked",
+                    name
+                )));
+                continue;
+            }
+        };
+        if let Err(error) = slack::check_webhook(&url).await {
+            findings.push(Finding::Error(format!(
+                "The Slack webhook in {} can't be used: {:#}",
+                name, error
+            )));
+        }
+    }
+    findings
+}
+
+/// Check the email server and Matrix room the config sends to, without
+/// sending anything. Alerting services can't be checked without opening an
+/// incident, so only their keys being set is.
+async fn lint_destinations(config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if let Some(email) = &config.email {
+        if let Err(error) = crate::email::check(email).await {
+            findings.push(Finding::Error(format!(
+                "The email server can't be used: {:#}",
+                error
+            )));
+        }
+    }
+    if let Some(matrix) = &config.matrix {
+        if let Err(error) = crate::matrix::check(matrix).await {
+            findings.push(Finding::Error(format!(
+                "The Matrix room can't be used: {:#}",
+                error
+            )));
+        }
+    }
+    if let Some(alerting) = &config.alerting {
+        let (service, name) = match alerting {
+            config::Alerting::Pagerduty { routing_key_env } => ("PagerDuty", routing_key_env),
+            config::Alerting::Opsgenie { api_key_env, .. } => ("Opsgenie", api_key_env),
+        };
+        let message = match std::env::var(name) {
+            Ok(_) => format!(
+                "The {} key in {} was not checked, as that would open an incident",
+                service, name
+            ),
+            Err(_) => format!(
+                "The {} environment variable is not set, so {} can't be alerted",
+                name, service
+            ),
+        };
+        findings.push(Finding::Warning(message));
+    }
+    findings
+}
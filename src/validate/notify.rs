@@ -0,0 +1,802 @@
+//! Notifications about projects that do not conform to our guidelines.
+
+use super::{
+    context::Context,
+    history::{History, Trend},
+    report::{ProjectReport, Report, RunMetadata},
+};
+use crate::{
+    config::{
+        AttachmentFormat, Celebrations, Config, DigestPost, Escalation, PostKind, SlackRoute,
+        SlackTemplates,
+    },
+    email, error, github, locale, matrix, slack, SlackPreview,
+};
+use eyre::eyre;
+use serde_json::json;
+use std::borrow::Cow;
+
+/// Slack cuts off attachments with more characters than this.
+const MAX_ATTACHMENT_LENGTH: usize = 7_000;
+
+/// The projects to notify about.
+#[derive(Debug, Clone)]
+pub struct Notification<'a> {
+    pub kind: Kind<'a>,
+    pub notices: Vec<Notice<'a>>,
+    /// The run the projects were validated in, shown at the foot.
+    pub run: Option<&'a RunMetadata>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind<'a> {
+    /// The failures of a single run.
+    Immediate,
+    /// The current failures, sent periodically.
+    Digest,
+    /// Projects that have been failing the same check for at least `runs`
+    /// consecutive runs, with Slack text to mention people such as `<!here>`.
+    Escalation {
+        runs: usize,
+        mention: Option<&'a str>,
+    },
+    /// Projects that could not be validated because of infrastructure
+    /// errors, even when validated again at the end of the run.
+    Unvalidated,
+    /// Projects that have started to conform to our guidelines, or that
+    /// passed their first validation.
+    Celebration,
+}
+
+impl Kind<'_> {
+    /// The id of the kind of notification, for machine-readable summaries.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Immediate => "immediate",
+            Self::Digest => "digest",
+            Self::Escalation { .. } => "escalation",
+            Self::Unvalidated => "unvalidated",
+            Self::Celebration => "celebration",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Notice<'a> {
+    pub project: &'a ProjectReport,
+    /// How the project has changed since the last digest, only given in
+    /// digests.
+    pub trend: Option<Trend>,
+    /// Whether this was the first run to validate the project, only set in
+    /// celebrations.
+    pub first_validation: bool,
+}
+
+impl<'a> Notification<'a> {
+    pub fn immediate(projects: impl IntoIterator<Item = &'a ProjectReport>) -> Self {
+        Self {
+            kind: Kind::Immediate,
+            notices: projects
+                .into_iter()
+                .map(|project| Notice {
+                    project,
+                    trend: None,
+                    first_validation: false,
+                })
+                .collect(),
+            run: None,
+        }
+    }
+
+    pub fn unvalidated(projects: impl IntoIterator<Item = &'a ProjectReport>) -> Self {
+        Self {
+            kind: Kind::Unvalidated,
+            ..Self::immediate(projects)
+        }
+    }
+
+    /// A digest of the projects that are currently failing, with how they
+    /// have changed since the previous digest.
+    pub fn digest(
+        projects: impl IntoIterator<Item = &'a ProjectReport>,
+        history: &History,
+    ) -> Self {
+        Self {
+            kind: Kind::Digest,
+            notices: projects
+                .into_iter()
+                .map(|project| {
+                    let failing_checks = project.checks.iter().filter(|check| !check.ok).count();
+                    Notice {
+                        project,
+                        trend: history.trend(&project.name, failing_checks),
+                        first_validation: false,
+                    }
+                })
+                .collect(),
+            run: None,
+        }
+    }
+
+    /// The projects that pass but had policy failures in the `previous`
+    /// report, or were not in it at all. Projects that previously only
+    /// failed because GitHub could not be reached don't count.
+    pub fn celebration(report: &'a Report, previous: &Report) -> Self {
+        Self {
+            kind: Kind::Celebration,
+            notices: report
+                .projects
+                .iter()
+                .filter(|project| project.ok)
+                .filter_map(|project| {
+                    let before = previous.projects.iter().find(|before| {
+                        before.name == project.name && before.organisation == project.organisation
+                    });
+                    match before {
+                        Some(before) if !before.has_policy_failures() => None,
+                        before => Some(Notice {
+                            project,
+                            trend: None,
+                            first_validation: before.is_none(),
+                        }),
+                    }
+                })
+                .collect(),
+            run: report.run.as_ref(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notices.is_empty()
+    }
+
+    /// Move the projects that have failed the same check for at least `runs`
+    /// consecutive runs into an escalation, so they are not notified about
+    /// twice.
+    pub fn split_escalated(self, history: &History, escalation: &'a Escalation) -> (Self, Self) {
+        let runs = escalation.after_runs;
+        let (escalated, notices) = self.notices.into_iter().partition(|notice| {
+            notice
+                .project
+                .checks
+                .iter()
+                .filter(|check| !check.ok)
+                .any(|check| history.consecutive_failures(&notice.project.name, &check.id) >= runs)
+        });
+        let escalation = Self {
+            kind: Kind::Escalation {
+                runs,
+                mention: escalation.mention.as_deref(),
+            },
+            notices: escalated,
+            run: self.run,
+        };
+        (
+            Self {
+                kind: self.kind,
+                notices,
+                run: self.run,
+            },
+            escalation,
+        )
+    }
+
+    fn head(&self) -> String {
+        match self.kind {
+            Kind::Immediate => locale::text("notification-immediate", &[]),
+            Kind::Digest => locale::text("notification-digest", &[]),
+            Kind::Escalation { runs, .. } => {
+                locale::text("notification-escalation", &[("runs", &runs.to_string())])
+            }
+            Kind::Unvalidated => locale::text("notification-unvalidated", &[]),
+            Kind::Celebration => locale::text("notification-celebration", &[]),
+        }
+    }
+
+    /// Show the run the projects were validated in at the foot.
+    pub fn with_run(self, run: Option<&'a RunMetadata>) -> Self {
+        Self { run, ..self }
+    }
+
+    fn with_notices(&self, notices: Vec<Notice<'a>>) -> Self {
+        Self {
+            kind: self.kind,
+            notices,
+            run: self.run,
+        }
+    }
+}
+
+impl<'a> Notification<'a> {
+    /// The notices grouped by organisation, with the organisation's name as
+    /// a heading if the notification covers more than one.
+    fn sections(&self) -> Vec<(Option<&str>, Vec<&Notice<'a>>)> {
+        let mut sections: Vec<(&str, Vec<_>)> = Vec::new();
+        for notice in &self.notices {
+            let organisation = notice.project.organisation.as_str();
+            match sections.iter_mut().find(|(name, _)| *name == organisation) {
+                Some((_, notices)) => notices.push(notice),
+                None => sections.push((organisation, vec![notice])),
+            }
+        }
+        let several = sections.len() > 1;
+        sections
+            .into_iter()
+            .map(|(name, notices)| (Some(name).filter(|_| several), notices))
+            .collect()
+    }
+}
+
+impl Notice<'_> {
+    /// The project name prefixed with its trend, if any.
+    fn title(&self) -> String {
+        match self.trend {
+            Some(trend) => format!("{} {}", trend.arrow(), self.project.name),
+            None if self.first_validation => {
+                locale::text("notification-new-project", &[("name", &self.project.name)])
+            }
+            None => self.project.name.clone(),
+        }
+    }
+}
+
+/// Send the notification to every configured destination. The contexts are
+/// those of the organisations the projects are in, with the settings taken
+/// from the first. A destination that can't be sent to doesn't stop the
+/// others, the errors of all of them are returned together at the end.
+pub async fn send(
+    notification: &Notification<'_>,
+    contexts: &[Context],
+    slack_webhook_url: Option<&str>,
+) -> eyre::Result<()> {
+    let context = &contexts[0];
+    let mut errors = Vec::new();
+
+    // Send messages to slack, routing projects to their team's channel
+    // where configured and otherwise to the webhook URL if given
+    let routing = route_slack(notification, contexts);
+    for (route, notification) in &routing.routed {
+        if notification.is_empty() {
+            continue;
+        }
+        let result = match std::env::var(&route.webhook_url_env) {
+            Ok(url) => send_slack(&url, notification, &context.config).await,
+            Err(_) => Err(eyre!(
+                "The {} environment variable is not set",
+                route.webhook_url_env
+            )),
+        };
+        if let Err(error) = result {
+            errors.push(error.wrap_err(format!(
+                "Unable to send to the Slack route of {}",
+                route.webhook_url_env
+            )));
+        }
+    }
+    if let Some(url) = slack_webhook_url {
+        if !routing.unrouted.is_empty() {
+            if let Err(error) = send_slack(url, &routing.unrouted, &context.config).await {
+                errors.push(error.wrap_err("Unable to send to the Slack webhook"));
+            }
+        }
+    }
+
+    // Send an email if an SMTP server has been configured
+    if let Some(config) = &context.config.email {
+        let title = locale::text("notification-summary-title", &[]);
+        if let Err(error) = email::send(config, &title, html_summary(notification)).await {
+            errors.push(error.wrap_err("Unable to send the email"));
+        }
+    }
+
+    // Post to a Matrix room if one has been configured
+    if let Some(config) = &context.config.matrix {
+        let text = text_summary(notification);
+        if let Err(error) = matrix::send(config, text, html_summary(notification)).await {
+            errors.push(error.wrap_err("Unable to post to the Matrix room"));
+        }
+    }
+
+    error::combine(errors, |count| {
+        format!("Unable to send the notification to {} destinations", count)
+    })
+}
+
+/// Send an escalation to the escalation webhook if one is configured, and
+/// otherwise to the usual destinations.
+pub async fn send_escalation(
+    notification: &Notification<'_>,
+    contexts: &[Context],
+    slack_webhook_url: Option<&str>,
+) -> eyre::Result<()> {
+    let escalation = contexts[0].config.escalation.as_ref();
+    let env = match escalation.and_then(|escalation| escalation.webhook_url_env.as_ref()) {
+        Some(env) => env,
+        None => return send(notification, contexts, slack_webhook_url).await,
+    };
+    let url =
+        std::env::var(env).map_err(|_| eyre!("The {} environment variable is not set", env))?;
+    send_slack(&url, notification, &contexts[0].config).await
+}
+
+/// Send a celebration to the configured celebrations channel.
+pub async fn send_celebration(
+    notification: &Notification<'_>,
+    celebrations: &Celebrations,
+    config: &Config,
+) -> eyre::Result<()> {
+    let env = &celebrations.webhook_url_env;
+    let url =
+        std::env::var(env).map_err(|_| eyre!("The {} environment variable is not set", env))?;
+    send_slack(&url, notification, config).await
+}
+
+/// Send the exemptions of the report that expire soon to the configured
+/// webhook, or otherwise to the `--slack-webhook-url` webhook if given. A
+/// configured webhook whose environment variable isn't set is warned about
+/// and the `--slack-webhook-url` webhook used instead.
+pub async fn send_expiring_exemptions(
+    report: &Report,
+    config: &Config,
+    slack_webhook_url: Option<&str>,
+) -> eyre::Result<()> {
+    let configured = config
+        .expiring_exemptions
+        .webhook_url_env
+        .as_ref()
+        .and_then(|env| {
+            let url = std::env::var(env).ok();
+            if url.is_none() {
+                eprintln!(
+                    "The {} environment variable is not set, expiring exemptions go to the default webhook",
+                    env
+                );
+            }
+            url
+        });
+    let url = match configured.as_deref().or(slack_webhook_url) {
+        Some(url) => url.to_string(),
+        None => return Ok(()),
+    };
+    let blocks = expiring_exemptions_blocks(report, config);
+    slack::send_webhook(&url, blocks, Vec::new()).await
+}
+
+/// Post a digest in the repo of the config, as a discussion or an issue.
+/// Digests without projects are posted too, so that the record has no gaps.
+pub async fn post_digest(
+    notification: &Notification<'_>,
+    config: &DigestPost,
+    client: &github::Client,
+    now: u64,
+) -> eyre::Result<()> {
+    let (owner, repo) = config
+        .repo
+        .split_once('/')
+        .ok_or_else(|| eyre!("The digest repo `{}` is not `owner/name`", config.repo))?;
+    let date = &github::format_timestamp(now)[..10];
+    let title = format!(
+        "{} ({})",
+        locale::text("notification-summary-title", &[]),
+        date
+    );
+    let body = markdown_summary(notification);
+    let url = match config.post_as {
+        PostKind::Discussion => {
+            client
+                .create_discussion(owner, repo, &config.category, &title, &body)
+                .await?
+        }
+        PostKind::Issue => {
+            let issue = json!({ "title": title, "body": body, "labels": config.labels });
+            client.create_issue(owner, repo, &issue).await?
+        }
+    };
+    if let Some(url) = url {
+        println!("Posted the digest at {}", url);
+    }
+    Ok(())
+}
+
+/// Send the notification to a Slack webhook, with the attachments of the
+/// config.
+async fn send_slack(
+    url: &str,
+    notification: &Notification<'_>,
+    config: &Config,
+) -> eyre::Result<()> {
+    let blocks = slack_notification_blocks(notification, &config.slack_templates);
+    slack::send_webhook(url, blocks, slack_attachments(notification, config)).await
+}
+
+/// Projects assigned to the Slack webhooks they should be notified to.
+#[derive(Debug)]
+pub struct SlackRouting<'a> {
+    /// The projects for each configured route, in the order of the config.
+    pub routed: Vec<(&'a SlackRoute, Notification<'a>)>,
+    /// Projects matching no route, for the `--slack-webhook-url` webhook.
+    pub unrouted: Notification<'a>,
+}
+
+/// Assign projects to the routes they match, using the project's tags in the
+/// website data of its organisation. A project may match more than one route.
+pub fn route_slack<'a>(
+    notification: &Notification<'a>,
+    contexts: &'a [Context],
+) -> SlackRouting<'a> {
+    let mut routed: Vec<_> = contexts[0]
+        .config
+        .slack_routes
+        .iter()
+        .map(|route| (route, Vec::new()))
+        .collect();
+    let mut unrouted = Vec::new();
+    for &notice in &notification.notices {
+        let tags = contexts
+            .iter()
+            .find(|context| context.organisation.name == notice.project.organisation)
+            .unwrap_or(&contexts[0])
+            .website_tags(&notice.project.name);
+        let mut matched = false;
+        for (route, notices) in &mut routed {
+            if route.matches(&notice.project.name, tags) {
+                notices.push(notice);
+                matched = true;
+            }
+        }
+        if !matched {
+            unrouted.push(notice);
+        }
+    }
+    SlackRouting {
+        routed: routed
+            .into_iter()
+            .map(|(route, notices)| (route, notification.with_notices(notices)))
+            .collect(),
+        unrouted: notification.with_notices(unrouted),
+    }
+}
+
+pub fn slack_notification_blocks(
+    notification: &Notification<'_>,
+    templates: &SlackTemplates,
+) -> Vec<slack::Block> {
+    use slack::Block::{Divider, Text};
+
+    let mut blocks = Vec::with_capacity(notification.notices.len() + 4);
+
+    let count = notification.notices.len().to_string();
+    let (head, runs) = match notification.kind {
+        Kind::Immediate => (&templates.immediate, 0),
+        Kind::Digest => (&templates.digest, 0),
+        Kind::Escalation { runs, .. } => (&templates.escalation, runs),
+        Kind::Unvalidated => (&templates.unvalidated, 0),
+        Kind::Celebration => (&templates.celebration, 0),
+    };
+    let runs = runs.to_string();
+    let values = [("count", count.as_str()), ("runs", runs.as_str())];
+    let head = match head {
+        Some(head) => head.render(&values),
+        None => notification.head(),
+    };
+    let head = match notification.kind {
+        Kind::Escalation {
+            mention: Some(mention),
+            ..
+        } => format!("{} {}", mention, head),
+        _ => head,
+    };
+    blocks.push(Text(head));
+    blocks.push(Divider);
+    let emoji = match notification.kind {
+        Kind::Unvalidated => ":warning:",
+        Kind::Celebration => ":tada:",
+        _ => ":red_circle:",
+    };
+    for (organisation, notices) in notification.sections() {
+        if let Some(organisation) = organisation {
+            blocks.push(Text(
+                templates
+                    .organisation
+                    .render(&[("organisation", organisation)]),
+            ));
+        }
+        blocks.extend(
+            notices
+                .into_iter()
+                .flat_map(|notice| slack_project_block(notice, emoji, templates)),
+        );
+    }
+    blocks.push(Divider);
+    let foot = templates.foot.render(&values);
+    blocks.push(Text(match notification.run {
+        Some(run) => format!("{}\n{}", foot, run.summary()),
+        None => foot,
+    }));
+    blocks
+}
+
+/// The Slack blocks of a notification about the exemptions of a report that
+/// expire soon, with the owner and justification of each.
+pub fn expiring_exemptions_blocks(report: &Report, config: &Config) -> Vec<slack::Block> {
+    use slack::Block::{Divider, Text};
+
+    let days = config.expiring_exemptions.within_days.to_string();
+    let mut blocks = vec![
+        Text(locale::text(
+            "notification-expiring-exemptions",
+            &[("days", &days)],
+        )),
+        Divider,
+    ];
+    for exemption in &report.expiring_exemptions {
+        blocks.push(Text(format!(
+            ":shield: *<https://github.com/{}/{}|{}>* {}",
+            exemption.organisation,
+            exemption.project,
+            exemption.project,
+            exemption.summary()
+        )));
+    }
+    blocks.push(Divider);
+    let count = report.expiring_exemptions.len().to_string();
+    let foot = config
+        .slack_templates
+        .foot
+        .render(&[("count", &count), ("runs", "0")]);
+    blocks.push(Text(match &report.run {
+        Some(run) => format!("{}\n{}", foot, run.summary()),
+        None => foot,
+    }));
+    blocks
+}
+
+fn slack_project_block(
+    notice: &Notice<'_>,
+    emoji: &str,
+    templates: &SlackTemplates,
+) -> Option<slack::Block> {
+    let project = notice.project;
+    let url = format!(
+        "https://github.com/{}/{}",
+        project.organisation, project.name
+    );
+    let title = notice.title();
+    // Celebrated projects have no errors to show
+    let (template, errors) = match project.errors_to_string() {
+        Some(errors) => (&templates.project, errors),
+        None if project.ok => (&templates.celebrated_project, String::new()),
+        None => return None,
+    };
+    Some(slack::Block::Text(template.render(&[
+        ("emoji", emoji),
+        ("url", &url),
+        ("title", &title),
+        ("name", &project.name),
+        ("organisation", &project.organisation),
+        ("errors", &errors),
+    ])))
+}
+
+/// The attachments of a Slack notification, which are a machine-readable
+/// summary of its projects if one is configured.
+pub fn slack_attachments(
+    notification: &Notification<'_>,
+    config: &Config,
+) -> Vec<slack::Attachment> {
+    let format = match &config.slack_attachment {
+        Some(attachment) => attachment.format,
+        None => return Vec::new(),
+    };
+    let (extension, mut text) = match format {
+        AttachmentFormat::Json => ("json", json_summary(notification).to_string()),
+        AttachmentFormat::Csv => ("csv", csv_summary(notification)),
+    };
+    // Slack cuts off long attachments, which would leave them unparseable
+    if text.chars().count() > MAX_ATTACHMENT_LENGTH {
+        text = format!(
+            "The summary of {} projects is too long to attach. \
+Write the results with --report-file instead.",
+            notification.notices.len()
+        );
+    }
+    vec![slack::Attachment {
+        title: format!("summary.{}", extension),
+        text,
+    }]
+}
+
+/// A compact JSON summary of the projects of a notification and their
+/// failing checks.
+pub fn json_summary(notification: &Notification<'_>) -> serde_json::Value {
+    let projects: Vec<_> = notification
+        .notices
+        .iter()
+        .map(|notice| {
+            let project = notice.project;
+            json!({
+                "organisation": project.organisation,
+                "name": project.name,
+                "failing": failing_checks(project),
+                "could_not_validate": project.could_not_validate(),
+            })
+        })
+        .collect();
+    let mut summary = json!({
+        "kind": notification.kind.id(),
+        "projects": projects,
+    });
+    if let Some(run) = notification.run {
+        summary["run"] = json!(run);
+    }
+    summary
+}
+
+/// A CSV summary of the projects of a notification, with their failing
+/// checks separated by `;`.
+pub fn csv_summary(notification: &Notification<'_>) -> String {
+    let mut csv = "organisation,name,failing,could_not_validate\n".to_string();
+    for notice in &notification.notices {
+        let project = notice.project;
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&project.organisation),
+            csv_field(&project.name),
+            csv_field(&failing_checks(project).join(";")),
+            project.could_not_validate()
+        ));
+    }
+    csv
+}
+
+/// A CSV field, quoted as RFC 4180 requires if it contains a comma, a quote
+/// or a line break, with its quotes doubled.
+fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn failing_checks(project: &ProjectReport) -> Vec<&str> {
+    project
+        .checks
+        .iter()
+        .filter(|check| !check.ok)
+        .map(|check| check.id.as_str())
+        .collect()
+}
+
+/// Render an HTML summary of the projects for email and Matrix notifications.
+pub fn html_summary(notification: &Notification<'_>) -> String {
+    let mut html = format!("<p>{}</p>\n", escape_html(&notification.head()));
+    for (organisation, notices) in notification.sections() {
+        if let Some(organisation) = organisation {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_html(organisation)));
+        }
+        for notice in notices {
+            html.push_str(&format!(
+                "<h3><a href=\"https://github.com/{organisation}/{name}\">{title}</a></h3>\n<pre>{errors}</pre>\n",
+                organisation = escape_html(&notice.project.organisation),
+                name = escape_html(&notice.project.name),
+                title = escape_html(&notice.title()),
+                errors = escape_html(&notice.project.errors_to_string().unwrap_or_default()),
+            ));
+        }
+    }
+    html.push_str(&format!(
+        "<p>{}</p>\n",
+        locale::text("notification-html-foot", &[])
+    ));
+    if let Some(run) = notification.run {
+        html.push_str(&format!(
+            "<p><small>{}</small></p>\n",
+            escape_html(&run.summary())
+        ));
+    }
+    html
+}
+
+/// Render a Markdown summary of the projects, for posts on GitHub.
+pub fn markdown_summary(notification: &Notification<'_>) -> String {
+    let mut markdown = format!("{}\n\n", notification.head());
+    if notification.is_empty() {
+        markdown.push_str(&format!(
+            "{}\n\n",
+            locale::text("notification-all-conform", &[])
+        ));
+    }
+    for (organisation, notices) in notification.sections() {
+        if let Some(organisation) = organisation {
+            markdown.push_str(&format!("## {}\n\n", organisation));
+        }
+        for notice in notices {
+            markdown.push_str(&format!(
+                "### [{title}](https://github.com/{organisation}/{name})\n\n```\n{errors}```\n\n",
+                title = notice.title(),
+                organisation = notice.project.organisation,
+                name = notice.project.name,
+                errors = notice.project.errors_to_string().unwrap_or_default(),
+            ));
+        }
+    }
+    markdown.push_str(&locale::text("notification-html-foot", &[]));
+    markdown.push('\n');
+    if let Some(run) = notification.run {
+        markdown.push_str(&format!("\n<sub>{}</sub>\n", run.summary()));
+    }
+    markdown
+}
+
+/// Render a plain text summary of the projects, for clients that can't
+/// display HTML.
+pub fn text_summary(notification: &Notification<'_>) -> String {
+    let mut text = format!("{}\n\n", notification.head());
+    for (organisation, notices) in notification.sections() {
+        if let Some(organisation) = organisation {
+            text.push_str(&format!("{}\n\n", organisation));
+        }
+        for notice in notices {
+            text.push_str(&format!(
+                "{}\n{}\n",
+                notice.title(),
+                notice.project.errors_to_string().unwrap_or_default(),
+            ));
+        }
+    }
+    if let Some(run) = notification.run {
+        text.push_str(&format!("\n{}\n", run.summary()));
+    }
+    text
+}
+
+/// Render an HTML section of the exemptions of a report that expire soon,
+/// which is empty if none do.
+pub fn html_expiring_exemptions(report: &Report) -> String {
+    if report.expiring_exemptions.is_empty() {
+        return String::new();
+    }
+    let mut html = format!(
+        "<h2>{}</h2>\n<ul>\n",
+        escape_html(&locale::text("exemptions-expiring", &[]))
+    );
+    for exemption in &report.expiring_exemptions {
+        html.push_str(&format!("<li>{}</li>\n", escape_html(&exemption.summary())));
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Print the Slack payload that would be sent for a results file, and
+/// optionally send it to a test webhook.
+pub async fn preview_slack(options: SlackPreview) -> eyre::Result<()> {
+    let SlackPreview {
+        results,
+        slack_webhook_url,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    let report = Report::read(&results)?;
+    let notification =
+        Notification::immediate(report.projects.iter().filter(|project| !project.ok))
+            .with_run(report.run.as_ref());
+    let blocks = slack_notification_blocks(&notification, &config.slack_templates);
+    let attachments = slack_attachments(&notification, &config);
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&slack::payload(&blocks, &attachments))?
+    );
+    if let Some(url) = slack_webhook_url {
+        slack::send_webhook(&url, blocks, attachments).await?;
+    }
+    Ok(())
+}
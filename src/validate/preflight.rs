@@ -0,0 +1,268 @@
+//! A go/no-go checklist for a repo that is about to be open sourced, which
+//! extends the usual checks with scans for things that must not be published.
+
+use super::{
+    checks::{internal_references, repo_files, secret_patterns},
+    context::Context,
+    project::Project,
+};
+use crate::{
+    config::{Config, InternalReferences, SecretPatterns},
+    scan, Preflight,
+};
+use eyre::{eyre, WrapErr};
+use std::collections::HashSet;
+
+/// Checks that can only pass once a project has been published, so don't
+/// block publishing it.
+const AFTER_PUBLICATION: [&str; 3] = [
+    "website-data-inclusion",
+    "rust-ecosystem-registration",
+    "website-owners",
+];
+
+pub async fn preflight(options: Preflight) -> eyre::Result<()> {
+    let Preflight {
+        name,
+        github,
+        config,
+    } = options;
+    let mut config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    // Secrets are always looked for, by the scan below rather than the check
+    // so that the files are only downloaded once. Internal references are
+    // looked for in every file rather than only those the check reads.
+    let secret_patterns = config.secret_patterns.take().unwrap_or_default();
+    let internal_references = config.internal_references.take();
+    let (organisation, name) = config.project_organisation(&name);
+    let client = github.client(&organisation.name)?;
+    super::print_auth_guidance(&client, &config);
+    let context = Context::get(client, config, organisation).await?;
+
+    let project = Project::new(name).validate(&context).await;
+    let mut checklist = Checklist::default();
+    for check in &project.checks {
+        let result = check.result.as_ref().map(|_| ()).map_err(|error| {
+            crate::error::cause_string(error.as_ref(), true)
+                .trim_end()
+                .to_string()
+        });
+        if check.skipped {
+            checklist.skipped(&check.id);
+        } else if AFTER_PUBLICATION.contains(&check.id.as_str()) {
+            checklist.after_publication(&check.id, result);
+        } else {
+            checklist.item(&check.id, result);
+        }
+    }
+
+    let mut skipped = Vec::new();
+    match scan_repo(
+        &context,
+        &project,
+        &secret_patterns,
+        internal_references.as_ref(),
+    )
+    .await
+    {
+        Ok(scan) => {
+            checklist.item("no secrets in files", findings(&scan.secrets));
+            checklist.item(
+                "no internal references in files",
+                findings(&scan.internal_references),
+            );
+            skipped = scan.skipped;
+        }
+        Err(error) => checklist.item(
+            "files scanned",
+            Err(crate::error::cause_string(error.as_ref(), true)
+                .trim_end()
+                .to_string()),
+        ),
+    }
+
+    checklist.print();
+    if !skipped.is_empty() {
+        println!(
+            "\nFiles larger than {} bytes were not scanned, check them by hand:",
+            context.config.preflight.max_file_size
+        );
+        for path in &skipped {
+            println!("    {}", path);
+        }
+    }
+    match checklist.blockers {
+        0 => {
+            println!("\nGO: {} is ready to be open sourced", project.name);
+            Ok(())
+        }
+        blockers => {
+            println!(
+                "\nNO-GO: {} items must be fixed before {} is open sourced",
+                blockers, project.name
+            );
+            Err(eyre!("{} is not ready to be open sourced", project.name))
+        }
+    }
+}
+
+/// The results of scanning every file on the default branch of a repo, and
+/// every other branch and tag for secrets.
+#[derive(Debug, Default)]
+struct Scan {
+    secrets: Vec<scan::Finding>,
+    internal_references: Vec<scan::Finding>,
+    /// Files that were too large to scan.
+    skipped: Vec<String>,
+}
+
+async fn scan_repo(
+    context: &Context,
+    project: &Project,
+    secret_patterns: &SecretPatterns,
+    references: Option<&InternalReferences>,
+) -> eyre::Result<Scan> {
+    let settings = context.config.preflight.clone();
+    let max_file_size = settings.max_file_size;
+    let archive = project.repo_archive(context).await?;
+    let patterns = secret_patterns.clone();
+    let references = references.cloned();
+
+    // Scanning every file is CPU bound so keep it off the async runtime
+    let mut scan = tokio::task::spawn_blocking(move || {
+        let files = repo_files::text_files(&archive, settings.max_file_size);
+        let internal_references = files
+            .files
+            .iter()
+            .flat_map(|(path, text)| {
+                let mut found = scan::hostnames(path, text, &settings.internal_hostnames);
+                if let Some(references) = &references {
+                    found.extend(internal_references::findings(path, text, references));
+                }
+                found
+            })
+            .collect();
+        Scan {
+            secrets: secret_patterns::findings(&files, &patterns),
+            internal_references,
+            skipped: files.skipped,
+        }
+    })
+    .await?;
+
+    // Secrets stay published in the other branches and tags, which may be
+    // behind the default branch or point at commits it has left behind
+    let organisation = &context.organisation.name;
+    let repo = project.repo();
+    let (default_branch, _) = project.repo_files(context).await?;
+    let mut scanned = HashSet::new();
+    for (name, sha) in context.client.refs(organisation, repo).await? {
+        if name == default_branch || !scanned.insert(sha.clone()) {
+            continue;
+        }
+        let archive = context
+            .client
+            .repo_archive(organisation, repo, &sha)
+            .await
+            .wrap_err_with(|| format!("Unable to download the files of {}", name))?;
+        let patterns = secret_patterns.clone();
+        let (secrets, skipped) = tokio::task::spawn_blocking(move || {
+            let files = repo_files::text_files(&archive, max_file_size);
+            (secret_patterns::findings(&files, &patterns), files.skipped)
+        })
+        .await?;
+        for mut finding in secrets {
+            if !scan.secrets.contains(&finding) {
+                finding.path = format!("{}:{}", name, finding.path);
+                scan.secrets.push(finding);
+            }
+        }
+        scan.skipped
+            .extend(skipped.into_iter().map(|path| format!("{}:{}", name, path)));
+    }
+    Ok(scan)
+}
+
+fn findings(findings: &[scan::Finding]) -> Result<(), String> {
+    if findings.is_empty() {
+        return Ok(());
+    }
+    Err(findings
+        .iter()
+        .map(|finding| format!("    {}", finding))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// The items that decide whether a repo can be open sourced, each either
+/// passing or failing with an indented description.
+#[derive(Debug, Default)]
+pub(super) struct Checklist {
+    lines: Vec<String>,
+    pub(super) blockers: usize,
+}
+
+impl Checklist {
+    pub(super) fn item(&mut self, name: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.lines.push(format!("✔️ {}", name)),
+            Err(error) => {
+                self.blockers += 1;
+                self.lines.push(format!("❌ {}\n{}", name, error));
+            }
+        }
+    }
+
+    fn after_publication(&mut self, name: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.lines.push(format!("✔️ {}", name)),
+            Err(_) => self
+                .lines
+                .push(format!("⏳ {} (passes once published)", name)),
+        }
+    }
+
+    /// Checks that need credentials can't tell whether the repo is ready, so
+    /// are left to be checked by hand rather than blocking.
+    fn skipped(&mut self, name: &str) {
+        self.lines
+            .push(format!("⏭️ {} (skipped, needs auth)", name));
+    }
+
+    pub(super) fn print(&self) {
+        for line in &self.lines {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_blockers() {
+        let mut checklist = Checklist::default();
+        checklist.item("maintainers", Ok(()));
+        checklist.after_publication("website-data-inclusion", Err("missing".to_string()));
+        checklist.skipped("dependabot");
+        assert_eq!(checklist.blockers, 0);
+
+        let finding = scan::Finding {
+            path: ".env".to_string(),
+            line: 2,
+            kind: "AWS access key".to_string(),
+        };
+        checklist.item("no secrets in files", findings(&[finding]));
+        assert_eq!(checklist.blockers, 1);
+        assert_eq!(
+            checklist.lines,
+            vec![
+                "✔️ maintainers",
+                "⏳ website-data-inclusion (passes once published)",
+                "⏭️ dependabot (skipped, needs auth)",
+                "❌ no secrets in files\n    .env:2: AWS access key",
+            ]
+        );
+    }
+}
@@ -1,63 +1,901 @@
-use super::context::Context;
-use crate::github;
+use super::{
+    checks,
+    context::Context,
+    exemptions,
+    registry::{self, Severity},
+    repo_context::RepoContext,
+    report::ProjectReport,
+};
+use crate::{
+    config::{Config, TierPolicy},
+    github,
+    telemetry::{self, Span, SpanKind},
+};
 use eyre::{eyre, WrapErr};
-use futures::TryFutureExt;
+use futures::FutureExt;
 use itertools::Itertools;
-use std::{collections::HashSet, ops::Not};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    future::Future,
+    ops::Not,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug)]
 pub struct Project {
     pub name: String,
-    /// Projects must have a maintainer at Embark
-    pub maintainers: eyre::Result<HashSet<String>>,
-    // Rust based projects must be included in the rust-ecosystem README.
-    rust_ecosystem_registration: eyre::Result<()>,
-    // Projects must be included in the opensource website data.json
-    website_data_inclusion: eyre::Result<()>,
+    /// The GitHub organisation the project's repo is in.
+    pub organisation: String,
+    /// Projects must have a maintainer at Embark. This is `None` until the
+    /// maintainers have been successfully looked up.
+    pub maintainers: Option<HashSet<String>>,
+    /// The main languages of the project's code, as detected by GitHub. This
+    /// is `None` if they could not be looked up.
+    pub languages: Option<HashSet<String>>,
+    /// The tier of the project, from the website data.json or the config.
+    pub tier: Option<String>,
+    /// The resources of the repo shared by the checks while it is validated.
+    repo_context: RepoContext,
+    /// The outcome of each check, in the order they were run.
+    pub checks: Vec<CheckResult>,
+    /// How long it took to validate the project as a whole.
+    pub duration: Duration,
+}
+
+/// The outcome of running a single check against a project.
+#[derive(Debug)]
+pub struct CheckResult {
+    /// A short stable identifier for the check, used in reports.
+    pub id: String,
+    pub result: eyre::Result<()>,
+    /// Why a warning-only check would have failed. The check itself passes.
+    pub warning: Option<String>,
+    /// Whether the check was skipped as it needs GitHub credentials that were
+    /// not given. The check itself passes.
+    pub skipped: bool,
+    /// The exemption the project declares in its exemptions file for the
+    /// check, which would have failed. The check itself passes.
+    pub exemption: Option<exemptions::Exemption>,
+    /// The id of the prerequisite that failed, so the check was skipped
+    /// rather than failing for the same reason. The check itself passes.
+    pub prerequisite: Option<String>,
+    pub duration: Duration,
+}
+
+impl CheckResult {
+    /// Run a check, catching panics so that a broken check fails rather than
+    /// taking down the validation of every project.
+    pub(super) fn run(id: impl Into<String>, check: impl FnOnce() -> eyre::Result<()>) -> Self {
+        let id = id.into();
+        let span = check_span(&id);
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(check)).unwrap_or_else(panicked);
+        if let Some(span) = span {
+            span.end(result.as_ref().err().map(ToString::to_string));
+        }
+        Self::new(id, result, start.elapsed())
+    }
+
+    /// Run a check that makes requests, catching panics and giving up on it
+    /// after the configured timeout so that one slow check can't stall the
+    /// validation of the project.
+    pub(super) async fn run_async(
+        context: &Context,
+        id: impl Into<String>,
+        check: impl Future<Output = eyre::Result<()>>,
+    ) -> Self {
+        let id = id.into();
+        if !context.client.is_authenticated() && registry::needs_auth(&context.config, &id) {
+            return Self::skipped(id);
+        }
+        let timeout = context.config.checks.timeout();
+        let check = tokio::time::timeout(timeout, AssertUnwindSafe(check).catch_unwind());
+        let span = check_span(&id);
+        let (result, duration) = match &span {
+            Some(span) => timed(span.run(check)).await,
+            None => timed(check).await,
+        };
+        let result = match result {
+            Ok(result) => result.unwrap_or_else(panicked),
+            Err(elapsed) => Err(eyre::Report::new(elapsed).wrap_err(format!(
+                "The check did not finish within {} seconds",
+                timeout.as_secs()
+            ))),
+        };
+        if let Some(span) = span {
+            span.end(result.as_ref().err().map(ToString::to_string));
+        }
+        Self::new(id, result, duration)
+    }
+
+    /// Checks that fail because they need credentials are skipped, as they
+    /// can't tell whether the project conforms.
+    fn new(id: String, result: eyre::Result<()>, duration: Duration) -> Self {
+        let skipped = result.as_ref().err().is_some_and(github::needs_auth);
+        Self {
+            id,
+            result: if skipped { Ok(()) } else { result },
+            warning: None,
+            skipped,
+            exemption: None,
+            prerequisite: None,
+            duration,
+        }
+    }
+
+    /// A check skipped without being run as it needs credentials.
+    fn skipped(id: String) -> Self {
+        Self::new(id, Err(github::NeedsAuth.into()), Duration::default())
+    }
+
+    /// Accept the failure of a check that the project is exempt from.
+    /// Infrastructure errors are kept, as they say nothing about the project.
+    pub fn exempt(self, exemption: &exemptions::Exemption) -> Self {
+        match self.result {
+            Err(error) if !github::is_infrastructure_error(&error) => Self {
+                result: Ok(()),
+                exemption: Some(exemption.clone()),
+                ..self
+            },
+            result => Self { result, ..self },
+        }
+    }
+
+    /// Turn the failure of a check into a warning, so that a new check can
+    /// be introduced without failing projects straight away. Infrastructure
+    /// errors are kept, as they say nothing about the project.
+    pub fn into_warning(self) -> Self {
+        match self.result {
+            Err(error) if !github::is_infrastructure_error(&error) => Self {
+                result: Ok(()),
+                warning: Some(
+                    crate::error::cause_string(error.as_ref(), false)
+                        .trim_end()
+                        .to_string(),
+                ),
+                ..self
+            },
+            result => Self { result, ..self },
+        }
+    }
 }
 
 impl Project {
     pub fn new(name: String) -> Self {
         Self {
             name,
-            maintainers: not_yet_checked(),
-            website_data_inclusion: not_yet_checked(),
-            rust_ecosystem_registration: not_yet_checked(),
+            organisation: crate::config::DEFAULT_ORGANISATION.to_string(),
+            maintainers: None,
+            languages: None,
+            tier: None,
+            repo_context: RepoContext::default(),
+            checks: Vec::new(),
+            duration: Duration::default(),
         }
     }
 
     pub async fn validate(self, context: &Context) -> Self {
-        let maintainers = self.lookup_project_maintainers(context).await;
-        let rust_ecosystem_registration = self.check_rust_ecosystem_registration(context);
-        let website_data_inclusion = self.check_website_data_inclusion(context);
+        let attributes = vec![
+            ("embark.project", self.name.clone().into()),
+            ("embark.organisation", self.organisation.clone().into()),
+        ];
+        telemetry::in_span(
+            "validate project",
+            attributes,
+            self.run_checks(context),
+            |project| {
+                let failing = project
+                    .checks
+                    .iter()
+                    .filter(|check| check.result.is_err())
+                    .map(|check| check.id.as_str())
+                    .join(", ");
+                (!failing.is_empty()).then(|| format!("Failing checks: {}", failing))
+            },
+        )
+        .await
+    }
+
+    async fn run_checks(mut self, context: &Context) -> Self {
+        let start = Instant::now();
+
+        // Language specific checks only apply to projects in that language.
+        // If the languages can't be looked up the checks apply as usual.
+        self.languages = context
+            .client
+            .languages(&context.organisation.name, self.repo())
+            .await
+            .ok()
+            .map(|bytes| main_languages(&bytes));
+        self.tier = context.tier(&self.name).map(ToString::to_string);
+        let tier_policy = self
+            .tier
+            .as_deref()
+            .and_then(|tier| context.config.tiers.policies.get(tier));
+        let mut checks = Checks::new(tier_policy);
+
+        // Projects must have a maintainer at Embark. Anonymous clients only
+        // see the public members of the organisation, so only the CODEOWNERS
+        // file is checked without credentials
+        let anonymous = !context.client.is_authenticated()
+            && registry::needs_auth(&context.config, "maintainers");
+        let maintainers = timed(self.lookup_project_maintainers(context, !anonymous)).await;
+        let (maintainers, check) = maintainers_check(maintainers, anonymous);
+        checks.push(check);
+
+        // The checks that look at every file of the repo share one listing
+        if Self::check_ids_for_tier(&context.config, self.tier.as_deref())
+            .iter()
+            .any(|id| id == "repo-files")
+        {
+            checks
+                .run_async(context, "repo-files", async {
+                    let _ = self.repo_files(context).await?;
+                    Ok(())
+                })
+                .await;
+        }
+
+        // Projects must be included in the opensource website data.json
+        checks.run("website-data-inclusion", || {
+            self.check_website_data_inclusion(context)
+        });
+
+        // Rust based projects must be included in the rust-ecosystem README.
+        checks.run("rust-ecosystem-registration", || {
+            self.check_rust_ecosystem_registration(context)
+        });
+
+        // The website's owners must agree with who actually maintains it
+        checks
+            .run_async(
+                context,
+                "website-owners",
+                checks::website_owners::check(&self, context, maintainers.as_ref()),
+            )
+            .await;
+
+        // Repos must have the topics required by the config
+        if let Some(policy) = &context.config.topics {
+            checks.run("topics", || checks::topics::check(&self, context, policy));
+        }
+
+        // Workflows must not let untrusted code run with privileges
+        if let Some(config) = &context.config.workflow_audit {
+            checks
+                .run_async(
+                    context,
+                    "workflow-audit",
+                    checks::workflow_audit::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Actions must be pinned to commits so a moved tag can't change them
+        if let Some(config) = &context.config.actions_pinning {
+            checks
+                .run_async(
+                    context,
+                    "actions-pinning",
+                    checks::actions_pinning::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Repos must be scanned for accidentally committed secrets
+        if let Some(config) = &context.config.secret_scanning {
+            checks
+                .run_async(
+                    context,
+                    "secret-scanning",
+                    checks::secret_scanning::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Repos must be alerted about vulnerable dependencies
+        if let Some(config) = &context.config.dependabot {
+            checks
+                .run_async(
+                    context,
+                    "dependabot",
+                    checks::dependabot::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Releases must be signed so users can verify where they came from
+        if let Some(policy) = &context.config.signed_releases {
+            checks
+                .run_async(
+                    context,
+                    "signed-releases",
+                    checks::signed_releases::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Contributions must be made under the project's agreement
+        if let Some(policy) = &context.config.contribution_agreement {
+            checks
+                .run_async(
+                    context,
+                    "contribution-agreement",
+                    checks::contribution_agreement::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Rust projects must report their test coverage
+        if let Some(config) = &context.config.coverage {
+            checks
+                .run_async(
+                    context,
+                    "coverage",
+                    checks::coverage::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Files must not contain credentials
+        if let Some(config) = &context.config.secret_patterns {
+            checks
+                .run_async(
+                    context,
+                    "secret-patterns",
+                    checks::secret_patterns::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Files must not mention things internal to the organisation
+        if let Some(config) = &context.config.internal_references {
+            checks
+                .run_async(
+                    context,
+                    "internal-references",
+                    checks::internal_references::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // READMEs must not have dead links
+        if let Some(config) = &context.config.readme_links {
+            checks
+                .run_async(
+                    context,
+                    "readme-links",
+                    checks::readme_links::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Projects must have issues for new contributors to pick up
+        if let Some(policy) = &context.config.good_first_issues {
+            checks
+                .run_async(
+                    context,
+                    "good-first-issues",
+                    checks::good_first_issues::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Maintainers must respond to issues in good time
+        if let Some(policy) = &context.config.issue_response {
+            checks
+                .run_async(
+                    context,
+                    "issue-response",
+                    checks::issue_response::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Projects must be released regularly
+        if let Some(policy) = &context.config.release_drift {
+            checks
+                .run_async(
+                    context,
+                    "release-drift",
+                    checks::release_drift::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Crates must have the metadata shown on crates.io
+        if let Some(config) = &context.config.cargo_metadata {
+            checks
+                .run_async(
+                    context,
+                    "cargo-metadata",
+                    checks::cargo_metadata::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Rust projects must declare the oldest Rust version they support
+        if context.config.msrv.is_some() {
+            checks
+                .run_async(context, "msrv", checks::msrv::check(&self, context))
+                .await;
+        }
+
+        // Some projects must forbid unsafe code or document its use
+        if let Some(policy) = &context.config.unsafe_code {
+            checks
+                .run_async(
+                    context,
+                    "unsafe-code",
+                    checks::unsafe_code::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Copyright years must be kept up to date
+        if let Some(config) = &context.config.license_year {
+            checks
+                .run_async(
+                    context,
+                    "license-year",
+                    checks::license_year::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Projects that release binaries must distribute third-party licenses
+        if let Some(policy) = &context.config.third_party_licenses {
+            checks
+                .run_async(
+                    context,
+                    "third-party-licenses",
+                    checks::third_party_licenses::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Release assets must have checksums
+        if let Some(policy) = &context.config.release_checksums {
+            checks
+                .run_async(
+                    context,
+                    "release-checksums",
+                    checks::release_checksums::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Container images must be published to the allowed registries
+        if let Some(policy) = &context.config.container_images {
+            checks
+                .run_async(
+                    context,
+                    "container-images",
+                    checks::container_images::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Command line tools must be installable by end users
+        if let Some(policy) = &context.config.install_instructions {
+            checks
+                .run_async(
+                    context,
+                    "install-instructions",
+                    checks::install_instructions::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // Pull requests from first-time contributors must not be ignored
+        if let Some(policy) = &context.config.first_time_contributors {
+            checks
+                .run_async(
+                    context,
+                    "first-time-contributors",
+                    checks::first_time_contributors::check(&self, context, policy),
+                )
+                .await;
+        }
+
+        // JavaScript and TypeScript projects must have complete npm metadata
+        if let Some(config) = &context.config.package_json {
+            checks
+                .run_async(
+                    context,
+                    "package-json",
+                    checks::package_json::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // C++ projects must have license headers and a formatting style
+        if let Some(config) = &context.config.cpp_license_headers {
+            checks
+                .run_async(
+                    context,
+                    "cpp-license-headers",
+                    checks::cpp_license_headers::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Boilerplate files must keep up with the template
+        if let Some(config) = &context.config.template_drift {
+            checks
+                .run_async(
+                    context,
+                    "template-drift",
+                    checks::template_drift::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // READMEs must say whether the project is still maintained
+        if let Some(config) = &context.config.maintenance_banner {
+            checks
+                .run_async(
+                    context,
+                    "maintenance-banner",
+                    checks::maintenance_banner::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // READMEs must have the standard badges
+        if let Some(config) = &context.config.badges {
+            checks
+                .run_async(
+                    context,
+                    "badges",
+                    checks::badges::check(&self, context, config),
+                )
+                .await;
+        }
+
+        // Projects must contain the files required by the config
+        for requirement in &context.config.required_files {
+            checks
+                .run_async(
+                    context,
+                    format!("required-files:{}", requirement.id),
+                    checks::required_files::check(&self, context, requirement),
+                )
+                .await;
+        }
+
+        // Organisation specific checks implemented by external programs
+        for external in &context.config.external_checks {
+            checks
+                .run_async(
+                    context,
+                    format!("external:{}", external.id),
+                    checks::external::check(&self, context, maintainers.as_ref(), external),
+                )
+                .await;
+        }
+        for wasm in &context.config.wasm_checks {
+            checks
+                .run_async(
+                    context,
+                    format!("wasm:{}", wasm.id),
+                    checks::wasm::check(&self, context, maintainers.as_ref(), wasm),
+                )
+                .await;
+        }
+
+        // Checks are skipped when data they depend on could not be looked up
+        let mut checks = apply_prerequisites(checks.results);
+
+        // Projects must be listed under the current name of their repo. This
+        // is checked last as renames are noticed when downloads are redirected
+        checks.push(CheckResult::run("repo-name", || {
+            self.check_repo_name(context)
+        }));
+
+        // Checks whose failures are only reported are turned into warnings
+        let mut checks: Vec<_> = checks
+            .into_iter()
+            .map(
+                |check| match registry::severity(&context.config, &check.id) {
+                    Severity::Warning => check.into_warning(),
+                    Severity::Error => check,
+                },
+            )
+            .collect();
+
+        // Projects are held to the standards of their tier
+        if !context.config.tiers.policies.is_empty() {
+            let start = Instant::now();
+            let (policy, result) = match self.tier.as_deref() {
+                Some(tier) => match context.config.tiers.policies.get(tier) {
+                    Some(policy) => (Some(policy), Ok(())),
+                    None => (
+                        None,
+                        Err(eyre!("The tier `{}` in data.json is not configured", tier)),
+                    ),
+                },
+                None => (None, Ok(())),
+            };
+            if let Some(policy) = policy {
+                checks = apply_tier_policy(checks, policy);
+            }
+            checks.push(CheckResult::new(
+                "tier".to_string(),
+                result,
+                start.elapsed(),
+            ));
+        }
+
+        // Repos may exempt themselves from checks, with a justification
+        let (exemptions, exemptions_duration) = timed(exemptions::download(&self, context)).await;
+        let (exemptions, exemptions_result) = match exemptions {
+            Ok(exemptions) => {
+                let result = exemptions::check(&exemptions, &context.config.exemptions);
+                (exemptions, result)
+            }
+            Err(error) => (Vec::new(), Err(error)),
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        let mut checks: Vec<_> = checks
+            .into_iter()
+            .map(|check| {
+                let policy = &context.config.exemptions;
+                match exemptions.iter().find(|exemption| {
+                    exemption.check == check.id
+                        && exemption.is_active(now)
+                        && policy.allows(&exemption.check)
+                }) {
+                    Some(exemption) => check.exempt(exemption),
+                    None => check,
+                }
+            })
+            .collect();
+        checks.push(CheckResult {
+            id: "exemptions".to_string(),
+            result: exemptions_result,
+            warning: None,
+            skipped: false,
+            exemption: None,
+            prerequisite: None,
+            duration: exemptions_duration,
+        });
 
         Self {
             name: self.name,
+            organisation: context.organisation.name.clone(),
             maintainers,
-            website_data_inclusion,
-            rust_ecosystem_registration,
+            languages: self.languages,
+            tier: self.tier,
+            repo_context: RepoContext::default(),
+            checks,
+            duration: start.elapsed(),
+        }
+    }
+
+    /// Rebuild a project from its report, such as one cached from an earlier
+    /// run. The checks that only look at the context are run again as they
+    /// are cheap and the context changes independently of the project.
+    pub fn from_report(report: &ProjectReport, context: &Context) -> Self {
+        let maintainers_ok = report
+            .checks
+            .iter()
+            .any(|check| check.id == "maintainers" && check.ok);
+        let mut project = Self {
+            name: report.name.clone(),
+            organisation: report.organisation.clone(),
+            maintainers: if maintainers_ok {
+                Some(report.maintainers.iter().cloned().collect())
+            } else {
+                None
+            },
+            languages: None,
+            tier: context.tier(&report.name).map(ToString::to_string),
+            repo_context: RepoContext::default(),
+            checks: Vec::with_capacity(report.checks.len()),
+            duration: Duration::default(),
+        };
+        for check in &report.checks {
+            let result = match check.id.as_str() {
+                "website-data-inclusion" => project.check_website_data_inclusion(context),
+                "rust-ecosystem-registration" => project.check_rust_ecosystem_registration(context),
+                _ => match &check.error {
+                    Some(error) => Err(eyre!(error.clone())),
+                    None => Ok(()),
+                },
+            };
+            project.checks.push(CheckResult {
+                id: check.id.clone(),
+                result,
+                warning: check.warning.clone(),
+                skipped: check.skipped,
+                exemption: check
+                    .exemption
+                    .as_ref()
+                    .map(|justification| exemptions::Exemption {
+                        check: check.id.clone(),
+                        justification: justification.clone(),
+                        expires: check.exemption_expires.clone(),
+                        owner: check.exemption_owner.clone(),
+                    }),
+                prerequisite: check.prerequisite.clone(),
+                duration: Duration::default(),
+            });
+        }
+        project
+    }
+
+    /// The ids of the checks the config enables that need GitHub credentials,
+    /// which are skipped without being run when there are none.
+    pub fn checks_needing_auth(config: &Config) -> Vec<String> {
+        Self::check_ids(config)
+            .into_iter()
+            .filter(|id| registry::needs_auth(config, id))
+            .collect()
+    }
+
+    /// The ids of the checks `validate` runs for a project of `tier`, which
+    /// leaves out those the tier skips.
+    pub fn check_ids_for_tier(config: &Config, tier: Option<&str>) -> Vec<String> {
+        let skip = tier
+            .and_then(|tier| config.tiers.policies.get(tier))
+            .map_or(&[][..], |policy| &policy.skip);
+        let mut ids: Vec<_> = Self::check_ids(config)
+            .into_iter()
+            .filter(|id| !skip.contains(id))
+            .collect();
+        if !ids.iter().any(|id| depends_on(id, "repo-files")) {
+            ids.retain(|id| id != "repo-files");
         }
+        ids
+    }
+
+    /// The ids of the checks `validate` runs with the config, in the order of
+    /// the registry.
+    pub fn check_ids(config: &Config) -> Vec<String> {
+        let mut ids: Vec<String> = registry::CHECKS
+            .iter()
+            .filter(|check| check.id != "repo-files")
+            .flat_map(|check| check.ids(config))
+            .collect();
+        // The repo's files are only listed when a check needs them
+        if ids.iter().any(|id| depends_on(id, "repo-files")) {
+            ids.insert(1, "repo-files".to_string());
+        }
+        ids
     }
 
     pub fn has_errors(&self) -> bool {
         !self.errors().is_empty()
     }
 
+    /// Whether every failed check failed because of an infrastructure error,
+    /// such as GitHub being unreachable, so the project could not be
+    /// validated.
+    pub fn could_not_validate(&self) -> bool {
+        let errors = self.errors();
+        !errors.is_empty() && errors.into_iter().all(github::is_infrastructure_error)
+    }
+
+    /// The ids of the checks that were skipped as they need credentials.
+    pub fn skipped(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|check| check.skipped)
+            .map(|check| check.id.as_str())
+            .collect()
+    }
+
+    /// The ids of the checks that were skipped as a prerequisite failed, with
+    /// the id of the prerequisite.
+    pub fn prerequisites_failed(&self) -> Vec<(&str, &str)> {
+        self.checks
+            .iter()
+            .filter_map(|check| Some((check.id.as_str(), check.prerequisite.as_deref()?)))
+            .collect()
+    }
+
+    /// The name of the project's repo. Projects that live in a directory of a
+    /// larger repo are named `repo#path`.
+    pub fn repo(&self) -> &str {
+        repo_of(&self.name)
+    }
+
+    /// The directory of the repo the project lives in, or `None` if it is the
+    /// whole repo.
+    pub fn path(&self) -> Option<&str> {
+        self.name
+            .split_once('#')
+            .map(|(_, path)| path.trim_matches('/'))
+            .filter(|path| !path.is_empty())
+    }
+
+    /// The default branch of the project's repo and every file on it, with
+    /// paths relative to the project's directory. Files outside of it are
+    /// left out.
+    pub async fn repo_files(
+        &self,
+        context: &Context,
+    ) -> eyre::Result<(String, Vec<github::RepoFile>)> {
+        let (branch, files) = self
+            .repo_context
+            .listing(&context.client, &context.organisation.name, self.repo())
+            .await?;
+        let files = match self.path() {
+            Some(dir) => {
+                let prefix = format!("{}/", dir);
+                files
+                    .into_iter()
+                    .filter_map(|file| {
+                        Some(github::RepoFile {
+                            path: file.path.strip_prefix(&prefix)?.to_string(),
+                            size: file.size,
+                        })
+                    })
+                    .collect()
+            }
+            None => files,
+        };
+        Ok((branch, files))
+    }
+
+    /// Every file on the default branch of the project's repo, including
+    /// those outside of the project's directory.
+    pub async fn repo_archive(
+        &self,
+        context: &Context,
+    ) -> eyre::Result<std::sync::Arc<github::RepoArchive>> {
+        self.repo_context
+            .archive(&context.client, &context.organisation.name, self.repo())
+            .await
+    }
+
+    /// The latest release of the project's repo, if it has been released.
+    pub async fn latest_release(&self, context: &Context) -> eyre::Result<Option<github::Release>> {
+        self.repo_context
+            .latest_release(&context.client, &context.organisation.name, self.repo())
+            .await
+    }
+
+    /// Whether a main language of the project is `language`, such as `Rust`
+    /// or `C++`, or `None` if the languages are not known.
+    pub fn uses_language(&self, language: &str) -> Option<bool> {
+        self.languages.as_ref().map(|languages| {
+            languages
+                .iter()
+                .any(|used| used.eq_ignore_ascii_case(language))
+        })
+    }
+
+    /// The justifications of the failing checks the project is exempt from,
+    /// with the id of each check.
+    pub fn exemptions(&self) -> Vec<(&str, &str)> {
+        self.checks
+            .iter()
+            .filter_map(|check| {
+                let exemption = check.exemption.as_ref()?;
+                Some((check.id.as_str(), exemption.justification.as_str()))
+            })
+            .collect()
+    }
+
+    /// The warnings of warning-only checks, with the id of each check.
+    pub fn warnings(&self) -> Vec<(&str, &str)> {
+        self.checks
+            .iter()
+            .filter_map(|check| Some((check.id.as_str(), check.warning.as_deref()?)))
+            .collect()
+    }
+
     pub fn errors(&self) -> Vec<&eyre::Report> {
-        let Self {
-            name: _,
-            maintainers,
-            website_data_inclusion,
-            rust_ecosystem_registration,
-        } = self;
-        vec![
-            maintainers.as_ref().err(),
-            website_data_inclusion.as_ref().err(),
-            rust_ecosystem_registration.as_ref().err(),
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
+        self.checks
+            .iter()
+            .filter_map(|check| check.result.as_ref().err())
+            .collect()
     }
 
     pub fn errors_to_string(&self, indent: bool) -> Option<String> {
@@ -76,25 +914,35 @@ impl Project {
     pub async fn lookup_project_maintainers(
         &self,
         context: &Context,
+        check_membership: bool,
     ) -> eyre::Result<HashSet<String>> {
-        // Download CODEOWNERS from one of the accepted branches
-        let get = |branch| {
-            github::download_repo_file("EmbarkStudios", &self.name, branch, ".github/CODEOWNERS")
-        };
-        let text = get("main")
-            .or_else(|_| get("master"))
+        let text = self
+            .download_file(context, ".github/CODEOWNERS")
             .await
             .wrap_err("Unable to determine maintainers")?;
 
         // Determine if there is at least 1 primary maintainer listed for each project
-        let maintainers = github::CodeOwners::new(&text)
+        let github::Owners { handles, emails } = github::CodeOwners::new(&text)
             .wrap_err("Unable to determine maintainers")?
             .primary_maintainers()
             .cloned()
             .ok_or_else(|| eyre!("No maintainers were found for * the CODEOWNERS file"))?;
 
-        // Ensure all maintainers are in the EmbarkStudios organisation
-        let mut maintainers_not_in_embark = maintainers
+        // Email owners can't be checked for organisation membership so they
+        // are only accepted if the policy allows them
+        if !emails.is_empty() && !context.config.codeowners.allow_email_owners {
+            return Err(eyre!(
+                "Maintainers listed by email rather than GitHub handle: {}",
+                emails.iter().sorted().join(", "),
+            ));
+        }
+
+        if !check_membership {
+            return Ok(handles.into_iter().chain(emails).collect());
+        }
+
+        // Ensure all maintainers are in the project's organisation
+        let mut maintainers_not_in_embark = handles
             .difference(&context.embark_github_organisation_members)
             .filter(|user_name| {
                 // filter out non-embark users that are explicitly allowed to be maintained
@@ -106,12 +954,103 @@ impl Project {
             .peekable();
         if maintainers_not_in_embark.peek().is_some() {
             return Err(eyre!(
-                "Maintainers not public EmbarkStudios members: {}",
+                "Maintainers not public {} members: {}",
+                context.organisation.name,
                 maintainers_not_in_embark.join(", "),
             ));
         }
 
-        Ok(maintainers)
+        Ok(handles.into_iter().chain(emails).collect())
+    }
+
+    /// Download a file from the first of the project's branches in the
+    /// config that has it. If the project does not have the file and org
+    /// defaults are enabled then the organisation's default from its
+    /// `.github` repo is used instead.
+    pub async fn download_file(&self, context: &Context, path: &str) -> eyre::Result<String> {
+        let organisation = &context.organisation.name;
+        let mut not_found = None;
+        // The files of a sub-project are looked for in its directory, then in
+        // the root of the repo if the whole repo shares them, as monorepos
+        // often share a license or .github
+        let paths = match self.path() {
+            Some(dir) if is_shared_by_repo(path) => vec![format!("{}/{}", dir, path), path.into()],
+            Some(dir) => vec![format!("{}/{}", dir, path)],
+            None => vec![path.to_string()],
+        };
+        for path in paths {
+            for branch in context.config.branches.of(self.repo()) {
+                match self
+                    .repo_context
+                    .download(
+                        context.private_repo_client(self.repo()),
+                        organisation,
+                        self.repo(),
+                        branch,
+                        &path,
+                    )
+                    .await
+                {
+                    Ok(text) => return Ok(text),
+                    Err(error) if error.is_not_found() => {
+                        // The error for the sub-project's own path is kept
+                        let _ = not_found.get_or_insert(error);
+                    }
+                    // GitHub failing is not a reason to look elsewhere
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        }
+        // Config parsing ensures there is at least one branch
+        let error = not_found.expect("no branches to download from");
+
+        let org_defaults = &context.config.org_defaults;
+        if !org_defaults.enabled {
+            return Err(error.into());
+        }
+        match self
+            .repo_context
+            .download(
+                context.private_repo_client(".github"),
+                organisation,
+                ".github",
+                &org_defaults.branch,
+                path,
+            )
+            .await
+        {
+            Ok(text) => Ok(text),
+            // The project's own error is more relevant than the org default's
+            Err(default_error) if default_error.is_not_found() => Err(error.into()),
+            Err(default_error) => Err(default_error.into()),
+        }
+    }
+
+    /// Download a file like `download_file`, returning `None` if neither the
+    /// project nor the org defaults have it. Other errors, such as GitHub
+    /// being unreachable, are still returned.
+    pub async fn download_optional_file(
+        &self,
+        context: &Context,
+        path: &str,
+    ) -> eyre::Result<Option<String>> {
+        match self.download_file(context, path).await {
+            Ok(text) => Ok(Some(text)),
+            Err(error) if github::is_not_found(&error) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Whether the project or the org defaults have a file, like
+    /// `download_optional_file`. Files that are too large or binary to
+    /// download, such as `bun.lockb`, are there too.
+    pub async fn has_file(&self, context: &Context, path: &str) -> eyre::Result<bool> {
+        match self.download_file(context, path).await {
+            Ok(_) => Ok(true),
+            Err(error) if github::is_unreadable(&error) => Ok(true),
+            Err(error) if github::is_not_found(&error) => Ok(false),
+            Err(error) => Err(error),
+        }
     }
 
     pub fn check_rust_ecosystem_registration(&self, context: &Context) -> eyre::Result<()> {
@@ -130,6 +1069,17 @@ impl Project {
         }
     }
 
+    pub fn check_repo_name(&self, context: &Context) -> eyre::Result<()> {
+        match github::renamed_repo(&context.organisation.name, self.repo()) {
+            Some(canonical) => Err(eyre!(
+                "The repository was renamed to {}, update data.json and the config to use the new \
+name",
+                canonical
+            )),
+            None => Ok(()),
+        }
+    }
+
     pub fn check_website_data_inclusion(&self, context: &Context) -> eyre::Result<()> {
         if context
             .opensource_website_projects
@@ -145,6 +1095,209 @@ impl Project {
     }
 }
 
-fn not_yet_checked<T>() -> eyre::Result<T> {
-    Err(eyre!("This property has not yet been validated"))
+/// The name of the repo of the project with the given name, which is the
+/// part before any `#path` of a project in a directory of a repo.
+pub fn repo_of(name: &str) -> &str {
+    name.split_once('#').map_or(name, |(repo, _)| repo)
+}
+
+/// Whether a file in the root of a repo applies to every project in it, such
+/// as a license, CODEOWNERS, or the files in `.github`. Other files, such as a
+/// README or Cargo.toml, are the root project's own.
+pub(super) fn is_shared_by_repo(path: &str) -> bool {
+    path.starts_with("LICENSE")
+        || path.starts_with(".github/")
+        || ["CODEOWNERS", "docs/CODEOWNERS"].contains(&path)
+}
+
+/// The share of a project's code a language must make up to be one of its
+/// main languages, so that the odd build script doesn't count.
+const MAIN_LANGUAGE_SHARE: f64 = 0.1;
+
+/// The main languages of a project, given the number of bytes of code in
+/// each language.
+pub(super) fn main_languages(bytes: &HashMap<String, u64>) -> HashSet<String> {
+    let total: u64 = bytes.values().sum();
+    bytes
+        .iter()
+        .filter(|(_, bytes)| **bytes as f64 >= total as f64 * MAIN_LANGUAGE_SHARE)
+        .map(|(language, _)| language.clone())
+        .collect()
+}
+
+/// The checks other checks depend on, with the ids of the checks that depend
+/// on each.
+const PREREQUISITES: [(&str, &[&str]); 2] = [
+    ("maintainers", &["website-owners"]),
+    (
+        "repo-files",
+        &[
+            "cargo-metadata",
+            "container-images",
+            "cpp-license-headers",
+            "third-party-licenses",
+        ],
+    ),
+];
+
+/// The maintainers of a project and the result of the `maintainers` check,
+/// from looking them up. If the organisation membership of the maintainers
+/// was not compared as the client is `anonymous`, the check is skipped once
+/// the CODEOWNERS file has been read, but a missing or invalid file fails it.
+pub(super) fn maintainers_check(
+    (maintainers, duration): (eyre::Result<HashSet<String>>, Duration),
+    anonymous: bool,
+) -> (Option<HashSet<String>>, CheckResult) {
+    match maintainers {
+        Ok(maintainers) if anonymous => (
+            Some(maintainers),
+            CheckResult::skipped("maintainers".to_string()),
+        ),
+        Ok(maintainers) => (
+            Some(maintainers),
+            CheckResult::new("maintainers".to_string(), Ok(()), duration),
+        ),
+        Err(error) => (
+            None,
+            CheckResult {
+                id: "maintainers".to_string(),
+                result: Err(error),
+                warning: None,
+                skipped: false,
+                exemption: None,
+                prerequisite: None,
+                duration,
+            },
+        ),
+    }
+}
+
+/// The checks that must pass for a check to run.
+pub(super) fn prerequisites(check: &str) -> Vec<&'static str> {
+    PREREQUISITES
+        .iter()
+        .filter(|(_, dependents)| dependents.contains(&check))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+/// Whether a check depends on the `prerequisite` check.
+fn depends_on(check: &str, prerequisite: &str) -> bool {
+    PREREQUISITES
+        .iter()
+        .any(|(id, dependents)| *id == prerequisite && dependents.contains(&check))
+}
+
+/// Skip the checks whose prerequisites failed, as they would only fail for
+/// the same reason or pass without having looked at anything.
+pub(super) fn apply_prerequisites(checks: Vec<CheckResult>) -> Vec<CheckResult> {
+    let failed: Vec<&str> = PREREQUISITES
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| {
+            checks
+                .iter()
+                .any(|check| check.id == *id && check.result.is_err())
+        })
+        .collect();
+    checks
+        .into_iter()
+        .map(
+            |check| match failed.iter().find(|id| depends_on(&check.id, id)) {
+                Some(id) => CheckResult {
+                    result: Ok(()),
+                    prerequisite: Some(id.to_string()),
+                    ..check
+                },
+                None => check,
+            },
+        )
+        .collect()
+}
+
+/// The results of a project's checks as they are run. Checks that the tier
+/// of the project skips are not run at all, as their results would be left
+/// out anyway.
+pub(super) struct Checks<'a> {
+    skip: &'a [String],
+    pub(super) results: Vec<CheckResult>,
+}
+
+impl<'a> Checks<'a> {
+    pub(super) fn new(policy: Option<&'a TierPolicy>) -> Self {
+        Self {
+            skip: policy.map_or(&[], |policy| &policy.skip),
+            results: Vec::new(),
+        }
+    }
+
+    pub(super) fn skips(&self, id: &str) -> bool {
+        self.skip.iter().any(|skip| skip == id)
+    }
+
+    pub(super) fn push(&mut self, check: CheckResult) {
+        if !self.skips(&check.id) {
+            self.results.push(check);
+        }
+    }
+
+    pub(super) fn run(&mut self, id: &str, check: impl FnOnce() -> eyre::Result<()>) {
+        if !self.skips(id) {
+            self.results.push(CheckResult::run(id, check));
+        }
+    }
+
+    pub(super) async fn run_async(
+        &mut self,
+        context: &Context,
+        id: impl Into<String>,
+        check: impl Future<Output = eyre::Result<()>>,
+    ) {
+        let id = id.into();
+        if !self.skips(&id) {
+            let result = CheckResult::run_async(context, id, check).await;
+            self.results.push(result);
+        }
+    }
+}
+
+/// Leave out the checks a tier skips and turn the failures of those it only
+/// warns about into warnings.
+pub(super) fn apply_tier_policy(checks: Vec<CheckResult>, policy: &TierPolicy) -> Vec<CheckResult> {
+    checks
+        .into_iter()
+        .filter(|check| !policy.skip.contains(&check.id))
+        .map(|check| {
+            if policy.warn.contains(&check.id) {
+                check.into_warning()
+            } else {
+                check
+            }
+        })
+        .collect()
+}
+
+/// The error of a check that panicked, with the panic message if it has one.
+fn panicked(payload: Box<dyn Any + Send>) -> eyre::Result<()> {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    Err(eyre!("The check panicked: {}", message))
+}
+
+fn check_span(id: &str) -> Option<Span> {
+    Span::start(
+        format!("check {}", id),
+        SpanKind::Internal,
+        vec![("embark.check", id.into())],
+    )
+}
+
+/// Await a future, returning its output along with how long it took.
+async fn timed<T>(future: impl Future<Output = T>) -> (T, Duration) {
+    let start = Instant::now();
+    let output = future.await;
+    (output, start.elapsed())
 }
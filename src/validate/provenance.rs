@@ -0,0 +1,72 @@
+//! Recording of what produced a run's results, so that they can be traced
+//! back to the version of the tool, the config and the run that wrote them.
+
+use super::report::{RateLimitUsage, RunMetadata};
+use crate::{
+    config::Config,
+    github::{self, RateLimit},
+};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// The provenance of a run in progress.
+#[derive(Debug)]
+pub struct Recorder {
+    metadata: RunMetadata,
+    /// The rate limit when the run started, to tell how much it used.
+    rate_limit: Option<RateLimit>,
+}
+
+impl Recorder {
+    pub async fn start(config: &Config, client: &github::Client, now: u64) -> Self {
+        Self {
+            metadata: RunMetadata {
+                id: run_id(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                commit: option_env!("EMBARK_OSS_GIT_SHA").map(ToString::to_string),
+                started_at: github::format_timestamp(now),
+                config_hash: config.hash.clone(),
+                rate_limit: None,
+            },
+            rate_limit: client.rate_limit().await.ok().map(|(limit, _)| limit),
+        }
+    }
+
+    /// The provenance of the run, with the rate limit it has used.
+    pub async fn finish(self, client: &github::Client) -> RunMetadata {
+        let end = client.rate_limit().await.ok().map(|(limit, _)| limit);
+        RunMetadata {
+            rate_limit: end.map(|end| usage(self.rate_limit, end)),
+            ..self.metadata
+        }
+    }
+}
+
+/// How much of the rate limit was used between `start` and `end`, which is
+/// unknown if the limit was reset in between.
+pub fn usage(start: Option<RateLimit>, end: RateLimit) -> RateLimitUsage {
+    RateLimitUsage {
+        limit: end.limit,
+        remaining: end.remaining,
+        used: start
+            .filter(|start| start.reset == end.reset)
+            .map(|start| start.remaining.saturating_sub(end.remaining)),
+    }
+}
+
+/// The GitHub Actions run, which links to its logs, or a random id.
+fn run_id() -> String {
+    match (
+        std::env::var("GITHUB_RUN_ID"),
+        std::env::var("GITHUB_RUN_ATTEMPT"),
+    ) {
+        (Ok(id), Ok(attempt)) => format!("{}-{}", id, attempt),
+        (Ok(id), Err(_)) => id,
+        _ => {
+            let mut id = [0; 8];
+            SystemRandom::new()
+                .fill(&mut id)
+                .expect("Unable to generate a run id");
+            id.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+    }
+}
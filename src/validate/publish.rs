@@ -0,0 +1,164 @@
+//! Persistence of run results, so that stateless CI jobs can compare a run
+//! with the one before it.
+
+use super::{
+    cache::Cache,
+    history::History,
+    notify::{self, Notification},
+    report::Report,
+};
+use crate::{config::ResultUpload, github, upload::Publisher};
+use eyre::{eyre, WrapErr};
+use serde::{de::DeserializeOwned, Serialize};
+use std::str::FromStr;
+
+/// The name of the file the results are stored in at the destination.
+const RESULTS_FILE_NAME: &str = "embark-oss-results.json";
+
+/// The name of the HTML report uploaded to buckets.
+const HTML_REPORT_FILE_NAME: &str = "embark-oss-results.html";
+
+/// The name of the file the history of runs is stored in at the destination.
+const HISTORY_FILE_NAME: &str = "embark-oss-history.json";
+
+/// The name of the file cached project results are stored in at the
+/// destination.
+const CACHE_FILE_NAME: &str = "embark-oss-cache.json";
+
+/// Where the results of a run are published to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    /// A file within an existing GitHub gist.
+    Gist { id: String },
+}
+
+impl FromStr for Destination {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.split_once(':') {
+            Some(("gist", id)) if !id.is_empty() => Ok(Self::Gist { id: id.to_string() }),
+            _ => Err(eyre!(
+                "Unknown results destination `{}`, expected `gist:<id>`",
+                s
+            )),
+        }
+    }
+}
+
+impl Destination {
+    /// Download the results of the previous run, if there has been one.
+    pub async fn download(&self, client: &github::Client) -> eyre::Result<Option<Report>> {
+        let json = match self {
+            Self::Gist { id } => client.gist_file(id, RESULTS_FILE_NAME).await?,
+        };
+        json.map(|json| Report::parse(&json))
+            .transpose()
+            .wrap_err("Unable to parse previous results")
+    }
+
+    pub async fn upload(&self, client: &github::Client, report: &Report) -> eyre::Result<()> {
+        self.write(client, RESULTS_FILE_NAME, report)
+            .await
+            .wrap_err("Unable to publish results")
+    }
+
+    /// Download the history of previous runs, which is empty if none have
+    /// been recorded.
+    pub async fn download_history(&self, client: &github::Client) -> eyre::Result<History> {
+        self.read(client, HISTORY_FILE_NAME)
+            .await
+            .map(Option::unwrap_or_default)
+            .wrap_err("Unable to parse the history of previous runs")
+    }
+
+    pub async fn upload_history(
+        &self,
+        client: &github::Client,
+        history: &History,
+    ) -> eyre::Result<()> {
+        self.write(client, HISTORY_FILE_NAME, history)
+            .await
+            .wrap_err("Unable to publish the history of runs")
+    }
+
+    /// Download the cached results of projects, which is empty if none have
+    /// been stored.
+    pub async fn download_cache(&self, client: &github::Client) -> eyre::Result<Cache> {
+        self.read(client, CACHE_FILE_NAME)
+            .await
+            .map(Option::unwrap_or_default)
+            .wrap_err("Unable to parse the cached results")
+    }
+
+    pub async fn upload_cache(&self, client: &github::Client, cache: &Cache) -> eyre::Result<()> {
+        self.write(client, CACHE_FILE_NAME, cache)
+            .await
+            .wrap_err("Unable to publish the cached results")
+    }
+
+    async fn read<T: DeserializeOwned>(
+        &self,
+        client: &github::Client,
+        file: &str,
+    ) -> eyre::Result<Option<T>> {
+        let json = match self {
+            Self::Gist { id } => client.gist_file(id, file).await?,
+        };
+        json.map(|json| serde_json::from_str(&json).map_err(eyre::Report::from))
+            .transpose()
+    }
+
+    async fn write<T: Serialize>(
+        &self,
+        client: &github::Client,
+        file: &str,
+        value: &T,
+    ) -> eyre::Result<()> {
+        let json = serde_json::to_string_pretty(value).wrap_err("Unable to serialise results")?;
+        match self {
+            Self::Gist { id } => client.update_gist_file(id, file, json).await,
+        }
+    }
+}
+
+/// Upload the results of a run to a bucket, as JSON and as an HTML page of
+/// the failing projects.
+pub async fn upload_reports(config: &ResultUpload, report: &Report) -> eyre::Result<()> {
+    let publisher = Publisher::new(config);
+    let json = serde_json::to_string_pretty(report).wrap_err("Unable to serialise results")?;
+    publisher
+        .put(RESULTS_FILE_NAME, "application/json", json.into_bytes())
+        .await?;
+    let failing = report.projects.iter().filter(|project| !project.ok);
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Open source guidelines</title></head>\n<body>\n{}{}</body>\n</html>\n",
+        notify::html_summary(&Notification::immediate(failing).with_run(report.run.as_ref())),
+        notify::html_expiring_exemptions(report)
+    );
+    publisher
+        .put(
+            HTML_REPORT_FILE_NAME,
+            "text/html; charset=utf-8",
+            html.into_bytes(),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_destination() {
+        assert_eq!(
+            "gist:abc123".parse::<Destination>().unwrap(),
+            Destination::Gist {
+                id: "abc123".to_string()
+            }
+        );
+        assert!("gist:".parse::<Destination>().is_err());
+        assert!("abc123".parse::<Destination>().is_err());
+        assert!("s3:bucket".parse::<Destination>().is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! The built-in checks, with what each looks at, whether the config enables
+//! it, whether its failures fail the project and whether it needs GitHub
+//! credentials. Both `validate` and `checks list` read them from here, so that
+//! the listing can't drift from what is checked.
+
+use crate::config::Config;
+
+/// Whether the failures of a check fail the project or are only reported,
+/// unless a tier's policy says otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Whether a check can run anonymously, or is skipped without GitHub
+/// credentials as it looks at data GitHub only shares with authenticated
+/// clients. The client methods such checks use fail with `NeedsAuth` when
+/// anonymous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Auth {
+    Anonymous,
+    Token,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    /// The id of the check, or the kind of the checks with ids like
+    /// `required-files:license`.
+    pub id: &'static str,
+    /// What the check looks at.
+    pub description: &'static str,
+    enabled: fn(&Config) -> bool,
+    /// The ids of checks with an instance for each entry of the config, such
+    /// as `required-files:license`.
+    instances: Option<fn(&Config) -> Vec<String>>,
+    severity: fn(&Config) -> Severity,
+    auth: fn(&Config) -> Auth,
+}
+
+impl Check {
+    const fn new(id: &'static str, description: &'static str) -> Self {
+        Self {
+            id,
+            description,
+            enabled: always,
+            instances: None,
+            severity: error,
+            auth: anonymous,
+        }
+    }
+
+    const fn enabled_by(self, enabled: fn(&Config) -> bool) -> Self {
+        Self { enabled, ..self }
+    }
+
+    const fn with_instances(self, instances: fn(&Config) -> Vec<String>) -> Self {
+        Self {
+            instances: Some(instances),
+            ..self
+        }
+    }
+
+    const fn with_severity(self, severity: fn(&Config) -> Severity) -> Self {
+        Self { severity, ..self }
+    }
+
+    const fn with_auth(self, auth: fn(&Config) -> Auth) -> Self {
+        Self { auth, ..self }
+    }
+
+    /// The ids the check runs as with the config, none if it isn't enabled.
+    pub fn ids(&self, config: &Config) -> Vec<String> {
+        match self.instances {
+            Some(instances) => instances(config),
+            None if (self.enabled)(config) => vec![self.id.to_string()],
+            None => Vec::new(),
+        }
+    }
+
+    pub fn severity(&self, config: &Config) -> Severity {
+        (self.severity)(config)
+    }
+
+    pub fn auth(&self, config: &Config) -> Auth {
+        (self.auth)(config)
+    }
+}
+
+fn always(_: &Config) -> bool {
+    true
+}
+
+fn error(_: &Config) -> Severity {
+    Severity::Error
+}
+
+fn warning(_: &Config) -> Severity {
+    Severity::Warning
+}
+
+fn msrv_severity(config: &Config) -> Severity {
+    if config.msrv.as_ref().is_some_and(|msrv| msrv.warn_only) {
+        Severity::Warning
+    } else {
+        Severity::Error
+    }
+}
+
+fn anonymous(_: &Config) -> Auth {
+    Auth::Anonymous
+}
+
+fn token(_: &Config) -> Auth {
+    Auth::Token
+}
+
+/// Signed releases only need credentials to look at the branch protection
+/// that requires signed commits.
+fn signed_releases_auth(config: &Config) -> Auth {
+    match &config.signed_releases {
+        Some(policy) if policy.require_signed_commits => Auth::Token,
+        _ => Auth::Anonymous,
+    }
+}
+
+/// Every built-in check.
+pub const CHECKS: &[Check] = &[
+    // Members of an organisation can hide their membership from the public,
+    // so anonymous clients can't tell whether a maintainer is one
+    Check::new(
+        "maintainers",
+        "The CODEOWNERS file names maintainers who are public members of the organisation.",
+    ).with_auth(token),
+    Check::new(
+        "repo-files",
+        "Every file on the default branch can be listed, for the checks that look at them.",
+    ),
+    Check::new(
+        "website-data-inclusion",
+        "The project is in the data.json of the open source website.",
+    ),
+    Check::new(
+        "rust-ecosystem-registration",
+        "Rust projects are in the README of EmbarkStudios/rust-ecosystem.",
+    ),
+    // The members of teams are only listed with the `read:org` scope
+    Check::new(
+        "website-owners",
+        "The owners and team in data.json agree with the CODEOWNERS file.",
+    ).with_auth(token),
+    Check::new(
+        "topics",
+        "The repo has the required topics and none of the forbidden ones.",
+    ).enabled_by(|config| config.topics.is_some()),
+    Check::new(
+        "workflow-audit",
+        "GitHub Actions workflows avoid unpinned third-party actions, unsafe pull_request_target checkouts and broad permissions.",
+    ).enabled_by(|config| config.workflow_audit.is_some()),
+    Check::new(
+        "actions-pinning",
+        "Third-party actions in workflows are pinned to full commit SHAs.",
+    ).enabled_by(|config| config.actions_pinning.is_some()),
+    Check::new(
+        "secret-scanning",
+        "Secret scanning and push protection are enabled.",
+    ).enabled_by(|config| config.secret_scanning.is_some()).with_auth(token),
+    Check::new(
+        "dependabot",
+        "Dependabot alerts and security updates are enabled.",
+    ).enabled_by(|config| config.dependabot.is_some()).with_auth(token),
+    Check::new(
+        "signed-releases",
+        "Release tags are signed, and commits too if the policy requires it.",
+    ).enabled_by(|config| config.signed_releases.is_some()).with_auth(signed_releases_auth),
+    Check::new(
+        "contribution-agreement",
+        "Contributions are covered by a DCO check or a CLA.",
+    ).enabled_by(|config| config.contribution_agreement.is_some()).with_auth(token),
+    Check::new(
+        "coverage",
+        "Code coverage is measured, with a codecov.yml or a coverage workflow step.",
+    ).enabled_by(|config| config.coverage.is_some()),
+    Check::new(
+        "secret-patterns",
+        "No files contain strings that look like secrets, such as API keys.",
+    ).enabled_by(|config| config.secret_patterns.is_some()),
+    Check::new(
+        "internal-references",
+        "No files mention internal hosts, code names or other internal references.",
+    ).enabled_by(|config| config.internal_references.is_some()),
+    Check::new(
+        "readme-links",
+        "The links in the README resolve.",
+    ).enabled_by(|config| config.readme_links.is_some()),
+    Check::new(
+        "good-first-issues",
+        "There are open issues labelled for new contributors.",
+    ).enabled_by(|config| config.good_first_issues.is_some()),
+    Check::new(
+        "issue-response",
+        "Issues get a response from a maintainer in time.",
+    ).enabled_by(|config| config.issue_response.is_some()),
+    Check::new(
+        "release-drift",
+        "The default branch has not drifted too far ahead of the latest release.",
+    ).enabled_by(|config| config.release_drift.is_some()),
+    Check::new(
+        "cargo-metadata",
+        "Every crate's Cargo.toml has the metadata needed to publish it.",
+    ).enabled_by(|config| config.cargo_metadata.is_some()),
+    Check::new(
+        "msrv",
+        "Rust projects declare their minimum supported Rust version.",
+    ).enabled_by(|config| config.msrv.is_some()).with_severity(msrv_severity),
+    Check::new(
+        "unsafe-code",
+        "Rust crates forbid unsafe code, or explain how it is kept sound.",
+    ).enabled_by(|config| config.unsafe_code.is_some()),
+    Check::new(
+        "license-year",
+        "The copyright years of the license files are current.",
+    ).enabled_by(|config| config.license_year.is_some()),
+    Check::new(
+        "third-party-licenses",
+        "The licenses of dependencies are published, such as with cargo about.",
+    ).enabled_by(|config| config.third_party_licenses.is_some()),
+    Check::new(
+        "release-checksums",
+        "Every release asset has a checksum, signed if required.",
+    ).enabled_by(|config| config.release_checksums.is_some()),
+    Check::new(
+        "container-images",
+        "Container images are published to the allowed registries and documented.",
+    ).enabled_by(|config| config.container_images.is_some()),
+    Check::new(
+        "install-instructions",
+        "Tools can be installed with cargo binstall or have install instructions.",
+    ).enabled_by(|config| config.install_instructions.is_some()),
+    Check::new(
+        "first-time-contributors",
+        "Pull requests from first-time contributors get a response in time.",
+    ).enabled_by(|config| config.first_time_contributors.is_some()),
+    Check::new(
+        "package-json",
+        "JavaScript packages have the required package.json fields and a lockfile.",
+    ).enabled_by(|config| config.package_json.is_some()),
+    Check::new(
+        "cpp-license-headers",
+        "C and C++ sources start with the license header, with a .clang-format.",
+    ).enabled_by(|config| config.cpp_license_headers.is_some()),
+    Check::new(
+        "template-drift",
+        "Boilerplate files have not drifted too far from the template repo.",
+    ).enabled_by(|config| config.template_drift.is_some()),
+    Check::new(
+        "maintenance-banner",
+        "The README has one maintenance badge, matching the state of the repo.",
+    ).enabled_by(|config| config.maintenance_banner.is_some()),
+    Check::new(
+        "badges",
+        "The README has the badges required by badges.toml.",
+    ).enabled_by(|config| config.badges.is_some()),
+    Check::new(
+        "required-files",
+        "The repo contains the files required by the config.",
+    ).with_instances(|config| {
+        let requirements = config.required_files.iter();
+        requirements.map(|requirement| format!("required-files:{}", requirement.id)).collect()
+    }),
+    Check::new(
+        "external",
+        "An external program configured in `external-checks`.",
+    ).with_instances(|config| {
+        let externals = config.external_checks.iter();
+        externals.map(|external| format!("external:{}", external.id)).collect()
+    }),
+    Check::new(
+        "wasm",
+        "A WebAssembly module configured in `wasm-checks`.",
+    ).with_instances(|config| {
+        config.wasm_checks.iter().map(|wasm| format!("wasm:{}", wasm.id)).collect()
+    }),
+    // Renames are reported without failing, as GitHub redirects the old name
+    Check::new(
+        "repo-name",
+        "The repo in data.json has not been renamed on GitHub.",
+    ).with_severity(warning),
+    Check::new(
+        "tier",
+        "The project's tier in data.json is one of the configured tiers.",
+    ).enabled_by(|config| !config.tiers.policies.is_empty()),
+    Check::new(
+        "exemptions",
+        "The exemptions in .github/embark-oss.toml are justified and valid.",
+    ),
+];
+
+/// The built-in check with the id, or `None` if there is no such check.
+pub fn get(id: &str) -> Option<&'static Check> {
+    let kind = id.split_once(':').map_or(id, |(kind, _)| kind);
+    CHECKS.iter().find(|check| check.id == kind)
+}
+
+/// How the failures of the check count with the config.
+pub fn severity(config: &Config, id: &str) -> Severity {
+    get(id).map_or(Severity::Error, |check| check.severity(config))
+}
+
+/// Whether the check needs GitHub credentials with the config.
+pub fn needs_auth(config: &Config, id: &str) -> bool {
+    get(id).is_some_and(|check| check.auth(config) == Auth::Token)
+}
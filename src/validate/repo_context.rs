@@ -0,0 +1,192 @@
+//! The resources of one project's repo that several checks look at. Each is
+//! fetched the first time a check needs it and kept for the rest of the
+//! project's validation, so that it is only downloaded once.
+
+use crate::github::{self, DownloadError, Release, RepoArchive, RepoFile};
+use eyre::{eyre, WrapErr};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::OnceCell;
+
+/// The repo, branch and path of a file.
+type FileKey = (String, String, String);
+
+#[derive(Debug, Default)]
+pub struct RepoContext {
+    /// The files that were looked for but don't exist, or can't be used as
+    /// they are too large or not text. Files that can are already kept for the
+    /// whole run by `github::download_repo_file`, which forgets failures so
+    /// that transient errors are retried.
+    missing: Mutex<HashMap<FileKey, DownloadError>>,
+    /// The files downloaded with the API, as those of private repos are.
+    private_files: Mutex<HashMap<FileKey, String>>,
+    /// The default branch and every file on it, or `None` if listing them
+    /// failed. Failures are kept as the checks that need the listing are
+    /// skipped when the `repo-files` check fails.
+    listing: OnceCell<Option<(String, Vec<RepoFile>)>>,
+    /// Every file on the default branch, or `None` if downloading them failed.
+    /// Shared rather than cloned as a repo's files can be large.
+    archive: OnceCell<Option<Arc<RepoArchive>>>,
+    /// The latest release, or `None` if the repo has not been released.
+    latest_release: OnceCell<Option<Release>>,
+}
+
+impl RepoContext {
+    /// Download a file from a branch of a repo, see
+    /// `github::download_repo_file`, or with the API through `private` if the
+    /// repo is private. Checks often look for the same optional files, so
+    /// files that don't exist are only looked for once.
+    pub async fn download(
+        &self,
+        private: Option<&github::Client>,
+        organisation: &str,
+        repo: &str,
+        branch: &str,
+        path: &str,
+    ) -> Result<String, DownloadError> {
+        let key = (repo.to_string(), branch.to_string(), path.to_string());
+        if let Some(error) = self
+            .missing
+            .lock()
+            .expect("Missing lock poisoned")
+            .get(&key)
+        {
+            return Err(error.clone());
+        }
+        let result = match private {
+            Some(client) => {
+                let cached = self
+                    .private_files
+                    .lock()
+                    .expect("Private files lock poisoned")
+                    .get(&key)
+                    .cloned();
+                match cached {
+                    Some(text) => Ok(text),
+                    None => client.repo_file(organisation, repo, branch, path).await,
+                }
+            }
+            None => github::download_repo_file(organisation, repo, branch, path).await,
+        };
+        if let (Some(_), Ok(text)) = (private, &result) {
+            let _ = self
+                .private_files
+                .lock()
+                .expect("Private files lock poisoned")
+                .insert(key.clone(), text.clone());
+        }
+        if let Err(error) = &result {
+            if !error.is_infrastructure() {
+                let _ = self
+                    .missing
+                    .lock()
+                    .expect("Missing lock poisoned")
+                    .insert(key, error.clone());
+            }
+        }
+        result
+    }
+
+    /// The default branch of the repo and every file on it, see
+    /// `github::Client::repo_files`.
+    pub async fn listing(
+        &self,
+        client: &github::Client,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<(String, Vec<RepoFile>)> {
+        let mut error = None;
+        let listing = self
+            .listing
+            .get_or_init(|| async {
+                client
+                    .repo_files(organisation, repo)
+                    .await
+                    .map_err(|failure| error = Some(failure))
+                    .ok()
+            })
+            .await;
+        match listing {
+            Some(listing) => Ok(listing.clone()),
+            None => {
+                Err(error.unwrap_or_else(|| eyre!("The files of {} could not be listed", repo)))
+            }
+        }
+    }
+
+    /// Every file on the default branch of the repo, see
+    /// `github::Client::repo_archive`.
+    pub async fn archive(
+        &self,
+        client: &github::Client,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Arc<RepoArchive>> {
+        let mut error = None;
+        let archive = self
+            .archive
+            .get_or_init(|| async {
+                let archive = async {
+                    let (branch, _) = self.listing(client, organisation, repo).await?;
+                    client
+                        .repo_archive(organisation, repo, &branch)
+                        .await
+                        .wrap_err_with(|| format!("Unable to download the files of {}", repo))
+                };
+                archive
+                    .await
+                    .map_err(|failure| error = Some(failure))
+                    .ok()
+                    .map(Arc::new)
+            })
+            .await;
+        match archive {
+            Some(archive) => Ok(Arc::clone(archive)),
+            None => {
+                Err(error.unwrap_or_else(|| eyre!("The files of {} could not be downloaded", repo)))
+            }
+        }
+    }
+
+    /// The latest release of the repo, see `github::Client::latest_release`.
+    pub async fn latest_release(
+        &self,
+        client: &github::Client,
+        organisation: &str,
+        repo: &str,
+    ) -> eyre::Result<Option<Release>> {
+        self.latest_release
+            .get_or_try_init(|| client.latest_release(organisation, repo))
+            .await
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_files_are_only_looked_for_once() {
+        let repo_context = RepoContext::default();
+        let missing = DownloadError::NotFound {
+            name: "EmbarkStudios/ash:package.json".to_string(),
+        };
+        let key = (
+            "ash".to_string(),
+            "main".to_string(),
+            "package.json".to_string(),
+        );
+        let _ = repo_context
+            .missing
+            .lock()
+            .unwrap()
+            .insert(key, missing.clone());
+        let result = repo_context
+            .download(None, "EmbarkStudios", "ash", "main", "package.json")
+            .await;
+        assert_eq!(result, Err(missing));
+    }
+}
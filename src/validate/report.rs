@@ -0,0 +1,548 @@
+//! Rendering of validation results for the terminal and for other programs.
+
+use super::project::{CheckResult, Project};
+use crate::locale;
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use std::{collections::HashSet, path::Path, str::FromStr, time::Duration};
+
+/// The format validation results are printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(eyre!("Unknown output format `{}`", s)),
+        }
+    }
+}
+
+/// The status of a project as shown in the terminal, with the time each check
+/// took if `verbose`.
+pub fn status(project: &Project, verbose: bool) -> String {
+    let mut text = match project.errors_to_string(true) {
+        Some(errors) if project.could_not_validate() => {
+            let status = locale::text("status-could-not-validate", &[("name", &project.name)]);
+            format!("⚠️ {}\n{}\n", status, errors)
+        }
+        Some(errors) => format!("❌ {}\n{}\n", project.name, errors),
+        None => match &project.maintainers {
+            Some(maintainers) => format!(
+                "✔️ {} ({})\n",
+                project.name,
+                maintainers.iter().sorted().join(", ")
+            ),
+            None => format!("✔️ {}\n", project.name),
+        },
+    };
+    for (id, warning) in project.warnings() {
+        text.push_str(&format!(
+            "    ⚠️ {}: {}\n",
+            id,
+            warning.replace('\n', "\n    ")
+        ));
+    }
+    for id in project.skipped() {
+        text.push_str(&format!(
+            "    ⏭️ {}\n",
+            locale::text("status-skipped-needs-auth", &[("id", id)])
+        ));
+    }
+    for (id, prerequisite) in project.prerequisites_failed() {
+        let status = locale::text(
+            "status-skipped-prerequisite",
+            &[("id", id), ("prerequisite", prerequisite)],
+        );
+        text.push_str(&format!("    ⏭️ {}\n", status));
+    }
+    for (id, justification) in project.exemptions() {
+        let status = locale::text(
+            "status-exempt",
+            &[("id", id), ("justification", justification)],
+        );
+        text.push_str(&format!("    🛡️ {}\n", status));
+    }
+
+    if verbose {
+        let duration = format_duration(project.duration);
+        text.push_str(&format!(
+            "    {}\n",
+            locale::text("status-took", &[("duration", &duration)])
+        ));
+        for check in &project.checks {
+            let icon = match &check.result {
+                Ok(()) if check.skipped || check.prerequisite.is_some() => "⏭️",
+                Ok(()) if check.exemption.is_some() => "🛡️",
+                Ok(()) => "✔️",
+                Err(_) => "❌",
+            };
+            text.push_str(&format!(
+                "    {} {} took {}\n",
+                icon,
+                check.id,
+                format_duration(check.duration)
+            ));
+        }
+    }
+    text
+}
+
+pub fn print_json(report: &Report) -> eyre::Result<()> {
+    let json = serde_json::to_string_pretty(report).wrap_err("Unable to serialise results")?;
+    println!("{}", json);
+    Ok(())
+}
+
+pub fn write_json(report: &Report, path: &Path) -> eyre::Result<()> {
+    let json = serde_json::to_string_pretty(report).wrap_err("Unable to serialise results")?;
+    std::fs::write(path, json).wrap_err_with(|| format!("Unable to write {}", path.display()))
+}
+
+/// Print the `count` slowest projects and checks, to help find out what is
+/// dominating the runtime of a validation run.
+pub fn print_slowest(projects: &[Project], count: usize, format: Format) {
+    let mut summary = String::new();
+
+    summary.push_str(&format!("Slowest {} projects:\n", count));
+    for project in projects
+        .iter()
+        .sorted_by_key(|project| std::cmp::Reverse(project.duration))
+        .take(count)
+    {
+        summary.push_str(&format!(
+            "    {} {}\n",
+            format_duration(project.duration),
+            project.name
+        ));
+    }
+
+    summary.push_str(&format!("Slowest {} checks:\n", count));
+    for (project, check) in projects
+        .iter()
+        .flat_map(|project| project.checks.iter().map(move |check| (project, check)))
+        .sorted_by_key(|(_, check)| std::cmp::Reverse(check.duration))
+        .take(count)
+    {
+        summary.push_str(&format!(
+            "    {} {} {}\n",
+            format_duration(check.duration),
+            project.name,
+            check.id
+        ));
+    }
+
+    // Keep stdout machine readable when printing JSON
+    match format {
+        Format::Text => print!("{}", summary),
+        Format::Json => eprint!("{}", summary),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+/// The version of the JSON results, only increased when a change would break
+/// consumers. Adding fields or checks does not change it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The JSON schema describing `Report`, as printed by `cargo run schema`.
+pub const SCHEMA: &str = include_str!("../../schema/results.schema.json");
+
+/// A machine readable summary of a validation run, as printed by
+/// `--format json` and persisted by `--publish-results`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Report {
+    /// Results written before the schema was versioned are version 1.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub projects: Vec<ProjectReport>,
+    /// Whether the run was interrupted before all projects were validated.
+    #[serde(default)]
+    pub partial: bool,
+    /// What produced the results, for runs that record it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunMetadata>,
+    /// The exemptions that expire soon, so that the violations they cover
+    /// are fixed rather than the exemptions renewed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expiring_exemptions: Vec<ExpiringExemption>,
+}
+
+/// The provenance of a run's results.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RunMetadata {
+    /// The id of the GitHub Actions run and its attempt, or a random id for
+    /// runs elsewhere.
+    pub id: String,
+    /// The version of the tool.
+    pub version: String,
+    /// The commit the tool was built from, if known.
+    pub commit: Option<String>,
+    /// When the run started.
+    pub started_at: String,
+    /// The SHA-256 of the config file, or `None` for the default config.
+    pub config_hash: Option<String>,
+    pub rate_limit: Option<RateLimitUsage>,
+}
+
+/// The GitHub core API rate limit consumed by a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RateLimitUsage {
+    pub limit: u64,
+    pub remaining: u64,
+    /// How many requests the run made, or `None` if the limit was reset
+    /// during the run.
+    pub used: Option<u64>,
+}
+
+/// An exemption from a failing check that will soon stop applying.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExpiringExemption {
+    pub organisation: String,
+    pub project: String,
+    pub check: String,
+    pub justification: String,
+    /// The day the exemption stops applying, as YYYY-MM-DD.
+    pub expires: String,
+    pub days_left: u64,
+    /// Who is responsible for fixing the violation: the owner given with the
+    /// exemption, or otherwise the project's maintainers.
+    pub owners: Vec<String>,
+}
+
+impl ExpiringExemption {
+    /// A line describing the exemption, for reports and notifications.
+    pub fn summary(&self) -> String {
+        let owners = if self.owners.is_empty() {
+            locale::text("exemption-no-owner", &[])
+        } else {
+            locale::text("exemption-owners", &[("owners", &self.owners.join(", "))])
+        };
+        let expiring = locale::text(
+            "exemption-expiring",
+            &[
+                ("check", &self.check),
+                ("project", &self.project),
+                ("expires", &self.expires),
+                ("days", &self.days_left.to_string()),
+            ],
+        );
+        format!("{} · {} · {}", expiring, owners, self.justification)
+    }
+}
+
+impl RunMetadata {
+    /// A line describing the run, for the foot of reports and notifications.
+    pub fn summary(&self) -> String {
+        let short = |hash: &str| hash.chars().take(12).collect::<String>();
+        let mut parts = vec![
+            locale::text("run-id", &[("id", &self.id)]),
+            match &self.commit {
+                Some(commit) => format!("embark-oss {} ({})", self.version, short(commit)),
+                None => format!("embark-oss {}", self.version),
+            },
+            self.started_at.clone(),
+            match &self.config_hash {
+                Some(hash) => locale::text("run-config", &[("hash", &short(hash))]),
+                None => locale::text("run-default-config", &[]),
+            },
+        ];
+        if let Some(rate_limit) = &self.rate_limit {
+            let limit = rate_limit.limit.to_string();
+            let remaining = rate_limit.remaining.to_string();
+            parts.push(match rate_limit.used {
+                Some(used) => locale::text(
+                    "run-rate-limit",
+                    &[
+                        ("used", &used.to_string()),
+                        ("remaining", &remaining),
+                        ("limit", &limit),
+                    ],
+                ),
+                None => locale::text(
+                    "run-rate-limit-remaining",
+                    &[("remaining", &remaining), ("limit", &limit)],
+                ),
+            });
+        }
+        parts.join(" · ")
+    }
+}
+
+impl Report {
+    pub fn new(projects: &[Project]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            projects: projects.iter().map(ProjectReport::new).collect(),
+            partial: false,
+            run: None,
+            expiring_exemptions: Vec::new(),
+        }
+    }
+
+    /// Read a report previously written with `--report-file`.
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Unable to read {}", path.display()))?;
+        Self::parse(&json).wrap_err_with(|| format!("Unable to parse {}", path.display()))
+    }
+
+    /// Parse a report, refusing those written in a newer schema as they may
+    /// not mean what this version expects.
+    pub fn parse(json: &str) -> eyre::Result<Self> {
+        let report: Self = serde_json::from_str(json)?;
+        if report.schema_version > SCHEMA_VERSION {
+            return Err(eyre!(
+                "The results use schema version {} but only version {} is supported",
+                report.schema_version,
+                SCHEMA_VERSION
+            ));
+        }
+        Ok(report)
+    }
+
+    /// The names of projects that have a failing check which was not failing
+    /// in the `previous` report.
+    pub fn projects_with_new_failures(&self, previous: &Self) -> HashSet<&str> {
+        self.new_failures(previous)
+            .into_iter()
+            .map(|(project, _)| project)
+            .collect()
+    }
+
+    /// Pairs of project name and check id for checks that are failing but
+    /// were not failing in the `previous` report.
+    pub fn new_failures(&self, previous: &Self) -> Vec<(&str, &str)> {
+        let previous_failures: HashSet<_> = previous.failures().collect();
+        self.failures()
+            .filter(|failure| !previous_failures.contains(failure))
+            .collect()
+    }
+
+    /// Pairs of project name and check id for checks that fail because the
+    /// project doesn't conform, outside of the baseline, and didn't fail that
+    /// way in the `previous` report, if there is one. Failures because GitHub
+    /// could not be reached are left out, as no one can act on them.
+    pub fn new_alerts(&self, previous: Option<&Self>) -> Vec<(&str, &str)> {
+        let before: HashSet<_> = previous.into_iter().flat_map(Self::alerts).collect();
+        self.alerts()
+            .filter(|alert| !before.contains(alert))
+            .collect()
+    }
+
+    fn alerts(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.projects.iter().flat_map(|project| {
+            project
+                .checks
+                .iter()
+                .filter(|check| !check.ok && !check.infrastructure && !check.known)
+                .map(move |check| (project.name.as_str(), check.id.as_str()))
+        })
+    }
+
+    /// Pairs of project name and check id for checks that were failing in
+    /// the `previous` report but no longer are. Projects that were not
+    /// validated in this run are not included.
+    pub fn cleared_failures<'a>(&self, previous: &'a Self) -> Vec<(&'a str, &'a str)> {
+        let failures: HashSet<_> = self.failures().collect();
+        let validated: HashSet<_> = self
+            .projects
+            .iter()
+            .map(|project| project.name.as_str())
+            .collect();
+        previous
+            .failures()
+            .filter(|failure| !failures.contains(failure) && validated.contains(failure.0))
+            .collect()
+    }
+
+    /// The error of a failing check.
+    pub fn error(&self, project: &str, check: &str) -> Option<&str> {
+        self.projects
+            .iter()
+            .find(|report| report.name == project)?
+            .checks
+            .iter()
+            .find(|report| report.id == check)?
+            .error
+            .as_deref()
+    }
+
+    /// The ids of the failing checks of a project that are in the baseline.
+    pub fn known_failures(&self, project: &str) -> Vec<&str> {
+        self.projects
+            .iter()
+            .filter(|candidate| candidate.name == project)
+            .flat_map(|project| &project.checks)
+            .filter(|check| check.known)
+            .map(|check| check.id.as_str())
+            .collect()
+    }
+
+    /// Pairs of project name and check id for every failing check.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.projects.iter().flat_map(|project| {
+            project
+                .checks
+                .iter()
+                .filter(|check| !check.ok)
+                .map(move |check| (project.name.as_str(), check.id.as_str()))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProjectReport {
+    pub name: String,
+    #[serde(default = "default_organisation")]
+    pub organisation: String,
+    pub ok: bool,
+    pub maintainers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+    pub duration_ms: u64,
+    pub checks: Vec<CheckReport>,
+}
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+fn default_organisation() -> String {
+    crate::config::DEFAULT_ORGANISATION.to_string()
+}
+
+impl ProjectReport {
+    fn new(project: &Project) -> Self {
+        Self {
+            name: project.name.clone(),
+            organisation: project.organisation.clone(),
+            ok: !project.has_errors(),
+            maintainers: project
+                .maintainers
+                .iter()
+                .flatten()
+                .cloned()
+                .sorted()
+                .collect(),
+            tier: project.tier.clone(),
+            duration_ms: project.duration.as_millis() as u64,
+            checks: project.checks.iter().map(CheckReport::new).collect(),
+        }
+    }
+
+    /// Whether a check failed because the project does not conform to our
+    /// guidelines, rather than only because GitHub could not be reached.
+    pub fn has_policy_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| !check.ok && !check.infrastructure)
+    }
+
+    pub fn has_infrastructure_failures(&self) -> bool {
+        self.checks.iter().any(|check| check.infrastructure)
+    }
+
+    /// Whether the project failed only because of infrastructure errors.
+    pub fn could_not_validate(&self) -> bool {
+        !self.ok && !self.has_policy_failures()
+    }
+
+    /// The errors of all failed checks, as `Project::errors_to_string` would
+    /// render them without indentation.
+    pub fn errors_to_string(&self) -> Option<String> {
+        let errors: Vec<_> = self
+            .checks
+            .iter()
+            .filter_map(|check| check.error.as_ref())
+            .map(|error| format!("{}\n", error))
+            .collect();
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors.join("\n"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CheckReport {
+    pub id: String,
+    pub ok: bool,
+    pub error: Option<String>,
+    /// Whether the check failed because of an infrastructure error, such as
+    /// GitHub being unreachable, rather than a problem with the project.
+    #[serde(default)]
+    pub infrastructure: bool,
+    /// Why a warning-only check would have failed. The check itself passes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    /// Whether the check was skipped as it needs GitHub credentials that were
+    /// not given. The check itself passes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Why the project is exempt from the check, which would have failed. The
+    /// check itself passes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption: Option<String>,
+    /// The day the exemption stops applying, as YYYY-MM-DD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption_expires: Option<String>,
+    /// Who is responsible for fixing the violation the exemption covers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption_owner: Option<String>,
+    /// The id of the prerequisite that failed, so the check was skipped. The
+    /// check itself passes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prerequisite: Option<String>,
+    /// Whether the failure is in the baseline given with `--baseline`, so it
+    /// doesn't fail the run.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub known: bool,
+    pub duration_ms: u64,
+}
+
+impl CheckReport {
+    fn new(check: &CheckResult) -> Self {
+        Self {
+            id: check.id.clone(),
+            ok: check.result.is_ok(),
+            error: check.result.as_ref().err().map(|error| {
+                crate::error::cause_string(error.as_ref(), false)
+                    .trim_end()
+                    .to_string()
+            }),
+            infrastructure: check
+                .result
+                .as_ref()
+                .err()
+                .is_some_and(crate::github::is_infrastructure_error),
+            warning: check.warning.clone(),
+            skipped: check.skipped,
+            exemption: check
+                .exemption
+                .as_ref()
+                .map(|exemption| exemption.justification.clone()),
+            exemption_expires: check
+                .exemption
+                .as_ref()
+                .and_then(|exemption| exemption.expires.clone()),
+            exemption_owner: check
+                .exemption
+                .as_ref()
+                .and_then(|exemption| exemption.owner.clone()),
+            prerequisite: check.prerequisite.clone(),
+            known: false,
+            duration_ms: check.duration.as_millis() as u64,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! The results of a run in SARIF, the Static Analysis Results Interchange
+//! Format, for code scanning dashboards and other tools that read it.
+
+use super::{registry, report::Report};
+use eyre::WrapErr;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// The results as a SARIF 2.1.0 log with one run. Failing checks are errors,
+/// checks that pass with a warning are warnings, and exempt checks are errors
+/// suppressed by the project's justification. Failures that say nothing about
+/// the project, as GitHub could not be reached, are left out.
+pub fn sarif(report: &Report) -> Value {
+    let mut results = Vec::new();
+    for project in &report.projects {
+        let location = json!({
+            "logicalLocations": [{
+                "fullyQualifiedName": format!("{}/{}", project.organisation, project.name),
+                "kind": "module",
+            }],
+        });
+        for check in &project.checks {
+            let mut result = match (&check.error, &check.warning, &check.exemption) {
+                (Some(_), _, _) if check.infrastructure => continue,
+                (Some(error), _, _) => json!({
+                    "level": "error",
+                    "message": { "text": error },
+                    "baselineState": if check.known { "unchanged" } else { "new" },
+                }),
+                (None, _, Some(justification)) => json!({
+                    "level": "error",
+                    "message": { "text": format!("{} is exempt", check.id) },
+                    "suppressions": [{ "kind": "inSource", "justification": justification }],
+                }),
+                (None, Some(warning), None) => json!({
+                    "level": "warning",
+                    "message": { "text": warning },
+                }),
+                (None, None, None) => continue,
+            };
+            result["ruleId"] = json!(check.id);
+            result["locations"] = json!([location]);
+            results.push(result);
+        }
+    }
+    let rules: Vec<_> = results
+        .iter()
+        .filter_map(|result| result["ruleId"].as_str())
+        .unique()
+        .sorted()
+        .map(|id| {
+            let description = registry::get(id).map_or(id, |check| check.description);
+            json!({ "id": id, "shortDescription": { "text": description } })
+        })
+        .collect();
+    let version = report
+        .run
+        .as_ref()
+        .map_or(env!("CARGO_PKG_VERSION"), |run| run.version.as_str());
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "embark-oss",
+                    "version": version,
+                    "informationUri": "https://github.com/EmbarkStudios/opensource",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+pub fn write(report: &Report, path: &Path) -> eyre::Result<()> {
+    let sarif =
+        serde_json::to_string_pretty(&sarif(report)).wrap_err("Unable to serialise results")?;
+    std::fs::write(path, sarif).wrap_err_with(|| format!("Unable to write {}", path.display()))
+}
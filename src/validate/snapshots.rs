@@ -0,0 +1,246 @@
+//! Snapshot tests of the rendered outputs, which compare them with the files
+//! in `snapshots/` so that changes to their formatting show up in review. Run
+//! the tests with `UPDATE_SNAPSHOTS=1` to write the snapshots of outputs that
+//! changed, then review them with `git diff`. This stands in for insta, which
+//! can't be a dependency of the build yet. The snapshots are the plain outputs,
+//! so moving to insta only changes `assert_snapshot`.
+
+use super::{
+    baseline::Baseline,
+    check_runs, exemptions,
+    notify::{self, Notification},
+    project::{CheckResult, Project},
+    report::{Report, RunMetadata},
+    sarif, text_results,
+};
+use crate::{config::Config, github::DownloadError, slack};
+use eyre::eyre;
+use std::{path::Path, time::Duration};
+
+/// The environment variable that makes the tests write their snapshots.
+const UPDATE: &str = "UPDATE_SNAPSHOTS";
+
+/// The time the results were generated at, 2026-01-01T00:00:00Z.
+const NOW: u64 = 1_767_225_600;
+
+/// Compare an output with its snapshot, or write the snapshot if updating.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/validate/snapshots")
+        .join(name);
+    if std::env::var_os(UPDATE).is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "The output doesn't match the snapshot {}. If the change is intended, run the \
+tests with {}=1 to update it.\n{}",
+        path.display(),
+        UPDATE,
+        crate::diff::unified(name, &expected, actual)
+    );
+}
+
+/// A check that took `millis` milliseconds, with the result of `result`.
+fn check(id: &str, millis: u64, result: eyre::Result<()>) -> CheckResult {
+    CheckResult {
+        id: id.to_string(),
+        result,
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: Duration::from_millis(millis),
+    }
+}
+
+fn project(
+    organisation: &str,
+    name: &str,
+    maintainers: &[&str],
+    checks: Vec<CheckResult>,
+) -> Project {
+    let mut project = Project::new(name.to_string());
+    project.organisation = organisation.to_string();
+    project.maintainers = Some(maintainers.iter().map(ToString::to_string).collect());
+    project.tier = Some("flagship".to_string());
+    project.duration = Duration::from_millis(
+        checks
+            .iter()
+            .map(|check| check.duration.as_millis() as u64)
+            .sum(),
+    );
+    project.checks = checks;
+    project
+}
+
+/// Deterministic results with every kind of outcome a check can have, in two
+/// organisations, for rendering.
+fn results() -> (Vec<Project>, Report) {
+    let exemption = exemptions::Exemption {
+        check: "msrv".to_string(),
+        justification: "Tracks the latest stable Rust".to_string(),
+        expires: Some("2026-01-15".to_string()),
+        owner: Some("octocat".to_string()),
+    };
+    let projects = vec![
+        project(
+            "EmbarkStudios",
+            "ash",
+            &["repi", "h3r2tic"],
+            vec![
+                check("maintainers", 12, Ok(())),
+                check("msrv", 48, Err(eyre!("No MSRV is declared"))).exempt(&exemption),
+                CheckResult {
+                    warning: Some("The README has no badges\nAdd a crates.io badge".to_string()),
+                    ..check("badges", 31, Ok(()))
+                },
+            ],
+        ),
+        project(
+            "EmbarkStudios",
+            "puffin",
+            &["emilk"],
+            vec![
+                check("maintainers", 9, Ok(())),
+                check(
+                    "required-files:license",
+                    104,
+                    Err(eyre!("LICENSE-MIT is missing").wrap_err("Required files are missing")),
+                ),
+                check(
+                    "repo-files",
+                    230,
+                    Err(eyre!("The repo has no default branch")),
+                ),
+                CheckResult {
+                    prerequisite: Some("repo-files".to_string()),
+                    ..check("cargo-metadata", 0, Ok(()))
+                },
+                CheckResult {
+                    skipped: true,
+                    ..check("dependabot", 3, Ok(()))
+                },
+            ],
+        ),
+        project(
+            "EmbarkStudios",
+            "texture-synthesis",
+            &["Jake-Shadle"],
+            vec![check(
+                "maintainers",
+                10_020,
+                Err(eyre::Report::new(DownloadError::Http {
+                    name: "EmbarkStudios/texture-synthesis:.github/CODEOWNERS".to_string(),
+                    status: 502,
+                })),
+            )],
+        ),
+        project(
+            "EmbarkLabs",
+            "kajiya",
+            &["h3r2tic"],
+            vec![
+                check("maintainers", 7, Ok(())),
+                check("topics", 55, Err(eyre!("The repo has no topics"))),
+            ],
+        ),
+    ];
+
+    let mut report = Report::new(&projects);
+    let mut baseline = Baseline::default();
+    let _ = baseline.violations.insert(
+        "kajiya".to_string(),
+        vec!["topics".to_string()].into_iter().collect(),
+    );
+    baseline.mark_known(&mut report);
+    report.run = Some(RunMetadata {
+        id: "5731-2".to_string(),
+        version: "0.1.0".to_string(),
+        commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+        started_at: "2026-01-01T00:00:00Z".to_string(),
+        config_hash: None,
+        rate_limit: None,
+    });
+    report.expiring_exemptions = exemptions::expiring(&report, NOW, 30);
+    (projects, report)
+}
+
+fn failing(report: &Report) -> Notification<'_> {
+    Notification::immediate(report.projects.iter().filter(|project| !project.ok))
+        .with_run(report.run.as_ref())
+}
+
+fn slack_payload(blocks: &[slack::Block]) -> String {
+    serde_json::to_string_pretty(&slack::payload(blocks, &[])).unwrap() + "\n"
+}
+
+#[test]
+fn terminal() {
+    let (projects, report) = results();
+    assert_snapshot("terminal.txt", &text_results(&projects, &report, false));
+    assert_snapshot(
+        "terminal-verbose.txt",
+        &text_results(&projects, &report, true),
+    );
+}
+
+#[test]
+fn json_results() {
+    let (_, report) = results();
+    assert_snapshot(
+        "results.json",
+        &(serde_json::to_string_pretty(&report).unwrap() + "\n"),
+    );
+}
+
+#[test]
+fn sarif_results() {
+    let (_, report) = results();
+    assert_snapshot(
+        "results.sarif",
+        &(serde_json::to_string_pretty(&sarif::sarif(&report)).unwrap() + "\n"),
+    );
+}
+
+#[test]
+fn slack_blocks() {
+    let (_, report) = results();
+    let config = Config::default();
+    let templates = &config.slack_templates;
+    let blocks = notify::slack_notification_blocks(&failing(&report), templates);
+    assert_snapshot("slack-immediate.json", &slack_payload(&blocks));
+
+    let unvalidated = Notification::unvalidated(
+        report
+            .projects
+            .iter()
+            .filter(|project| project.could_not_validate()),
+    );
+    let blocks = notify::slack_notification_blocks(&unvalidated, templates);
+    assert_snapshot("slack-unvalidated.json", &slack_payload(&blocks));
+
+    let blocks = notify::expiring_exemptions_blocks(&report, &config);
+    assert_snapshot("slack-expiring-exemptions.json", &slack_payload(&blocks));
+}
+
+#[test]
+fn markdown() {
+    let (_, report) = results();
+    assert_snapshot("digest.md", &notify::markdown_summary(&failing(&report)));
+    let puffin = &report.projects[1];
+    assert_snapshot("check-run.md", &check_runs::summary(puffin));
+}
+
+#[test]
+fn email_and_matrix() {
+    let (_, report) = results();
+    let notification = failing(&report);
+    assert_snapshot(
+        "summary.html",
+        &(notify::html_summary(&notification) + &notify::html_expiring_exemptions(&report)),
+    );
+    assert_snapshot("summary.txt", &notify::text_summary(&notification));
+}
@@ -0,0 +1,158 @@
+//! Pull requests that bring the drifted boilerplate files of projects back in
+//! line with the template repo, so that template updates reach every project
+//! without maintainers copying them by hand.
+
+use super::{
+    checks::template_drift::{self, DriftedFile},
+    context::Context,
+    project::{self, Project},
+    IGNORED_PROJECTS,
+};
+use crate::{
+    config::{Config, TemplateDrift},
+    diff, github, SyncTemplates,
+};
+use eyre::eyre;
+use itertools::Itertools;
+
+/// The branch pull requests syncing the templates are opened from. Repos that
+/// already have it have a pull request waiting to be merged, so are skipped.
+const SYNC_BRANCH: &str = "embark-oss/sync-templates";
+
+/// Open pull requests replacing the drifted boilerplate files of projects
+/// with those of the template, up to the limit of the `[template-drift]`
+/// config.
+pub async fn sync_templates(options: SyncTemplates) -> eyre::Result<()> {
+    let SyncTemplates {
+        name,
+        github,
+        config,
+    } = options;
+    let config = Config::load(config.as_deref())?;
+    crate::http::init(&config.http)?;
+    let policy = config
+        .template_drift
+        .clone()
+        .ok_or_else(|| eyre!("The config has no template-drift policy"))?;
+
+    // Either the one project or every project of every organisation
+    let targets = match name {
+        Some(name) => {
+            let (organisation, name) = config.project_organisation(&name);
+            vec![(organisation, Some(name))]
+        }
+        None => config
+            .organisations()
+            .into_iter()
+            .map(|organisation| (organisation, None))
+            .collect(),
+    };
+    let mut opened = 0;
+    for (organisation, name) in targets {
+        let client = github.client(&organisation.name)?;
+        let context = Context::get(client, config.clone(), organisation).await?;
+        // The files are shared by the projects in a repo, so are synced once
+        let repos = match name {
+            Some(name) => vec![project::repo_of(&name).to_string()],
+            None => context
+                .all_projects()
+                .iter()
+                .map(|name| project::repo_of(name).to_string())
+                .filter(|repo| !IGNORED_PROJECTS.contains(&repo.as_str()))
+                .unique()
+                .sorted()
+                .collect(),
+        };
+        for repo in repos {
+            if opened >= policy.sync_limit {
+                eprintln!(
+                    "Stopping after {} pull requests, the limit for one run",
+                    opened
+                );
+                return Ok(());
+            }
+            match sync(&context, &policy, &repo).await {
+                Ok(true) => opened += 1,
+                Ok(false) => {}
+                Err(error) => eprintln!("Unable to sync the templates of {}: {:?}", repo, error),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Open a pull request replacing the drifted files of a repo, returning
+/// whether one was opened.
+async fn sync(context: &Context, policy: &TemplateDrift, repo: &str) -> eyre::Result<bool> {
+    match context.embark_github_repos.get(repo) {
+        Some(details) if !details.archived => {}
+        _ => {
+            eprintln!(
+                "Skipping {} as it is archived or not in the organisation",
+                repo
+            );
+            return Ok(false);
+        }
+    }
+    let project = Project::new(repo.to_string());
+    let drifted = template_drift::drifted_files(&project, context, policy).await?;
+    if drifted.is_empty() {
+        return Ok(false);
+    }
+    let organisation = &context.organisation.name;
+    if context
+        .client
+        .has_branch(organisation, repo, SYNC_BRANCH)
+        .await?
+    {
+        eprintln!(
+            "Skipping {} as it already has a {} branch",
+            repo, SYNC_BRANCH
+        );
+        return Ok(false);
+    }
+
+    if crate::dry_run::is_enabled() {
+        for file in &drifted {
+            print!("{}", diff::unified(&file.path, &file.text, &file.template));
+        }
+    }
+    let changes = github::FileChanges {
+        base: context.client.default_branch(organisation, repo).await?,
+        branch: SYNC_BRANCH.to_string(),
+        title: "Update the boilerplate files from the template".to_string(),
+        body: pull_request_body(policy, &drifted),
+        files: drifted
+            .into_iter()
+            .map(|file| (file.path, file.template))
+            .collect(),
+    };
+    let url = context
+        .client
+        .open_pull_request(organisation, repo, &changes)
+        .await?;
+    if let Some(url) = url {
+        println!("Opened {}", url);
+    }
+    Ok(true)
+}
+
+/// The description of a pull request syncing the drifted files.
+pub fn pull_request_body(policy: &TemplateDrift, drifted: &[DriftedFile]) -> String {
+    let mut body = format!(
+        "These files have drifted from the [{0}](https://github.com/{0}) template, so are \
+replaced with the template's version:\n\n",
+        policy.repo
+    );
+    for file in drifted {
+        body.push_str(&format!(
+            "- `{}`, which has {} lines added and {} removed\n",
+            file.path, file.added, file.removed
+        ));
+    }
+    body.push_str(
+        "\nAdd back any changes the project needs before merging.\n\n\
+Opened by `cargo run sync-templates`.",
+    );
+    body
+}
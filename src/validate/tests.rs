@@ -4,6 +4,9 @@ use super::*;
 
 fn make_context() -> Context {
     Context {
+        config: Default::default(),
+        organisation: Default::default(),
+        client: github::Client::new(github::Auth::Anonymous),
         embark_github_organisation_members: HashSet::new(),
         embark_github_repos: HashMap::new(),
         rust_ecosystem_readme: "Readme!".to_string(),
@@ -16,6 +19,23 @@ fn make_website_project(name: &str) -> OpenSourceWebsiteDataProject {
         name: name.to_string(),
         repo: None,
         tags: HashSet::new(),
+        owners: Vec::new(),
+        team: None,
+        tier: None,
+    }
+}
+
+fn make_repo(name: &str, archived: bool) -> github::Repo {
+    github::Repo {
+        name: name.to_string(),
+        archived,
+        private: false,
+        fork: false,
+        topics: Vec::new(),
+        pushed_at: None,
+        stargazers_count: 0,
+        forks_count: 0,
+        language: None,
     }
 }
 
@@ -41,3 +61,1661 @@ fn check_website_data_inclusion_ko() {
     // Error if the project is not in the website data.json
     assert!(project.check_website_data_inclusion(&context).is_err());
 }
+
+#[test]
+fn project_errors_come_from_failed_checks() {
+    use std::time::Duration;
+
+    let mut project = Project::new("some-project".to_string());
+    assert!(!project.has_errors());
+
+    project.checks.push(project::CheckResult {
+        id: "ok".to_string(),
+        result: Ok(()),
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: Duration::default(),
+    });
+    assert!(!project.has_errors());
+
+    project.checks.push(project::CheckResult {
+        id: "ko".to_string(),
+        result: Err(eyre!("Oh no")),
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: Duration::default(),
+    });
+    assert_eq!(project.errors().len(), 1);
+    assert_eq!(project.errors_to_string(false).unwrap(), "Oh no\n");
+}
+
+fn make_report(failures: &[(&str, &str)]) -> Report {
+    let projects = failures
+        .iter()
+        .map(|(name, check)| report::ProjectReport {
+            name: name.to_string(),
+            organisation: "EmbarkStudios".to_string(),
+            ok: false,
+            maintainers: Vec::new(),
+            tier: None,
+            duration_ms: 0,
+            checks: vec![report::CheckReport {
+                id: check.to_string(),
+                ok: false,
+                error: Some("Oh no".to_string()),
+                infrastructure: false,
+                warning: None,
+                skipped: false,
+                exemption: None,
+                exemption_expires: None,
+                exemption_owner: None,
+                prerequisite: None,
+                known: false,
+                duration_ms: 0,
+            }],
+        })
+        .collect();
+    Report {
+        schema_version: report::SCHEMA_VERSION,
+        projects,
+        partial: false,
+        run: None,
+        expiring_exemptions: Vec::new(),
+    }
+}
+
+#[test]
+fn report_projects_with_new_failures() {
+    let previous = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    let current = make_report(&[
+        ("a", "maintainers"),
+        ("b", "website-data-inclusion"),
+        ("c", "maintainers"),
+    ]);
+
+    // Projects failing the same check as before are not new failures
+    assert_eq!(
+        current.projects_with_new_failures(&previous),
+        ["b", "c"].iter().cloned().collect()
+    );
+    assert!(current.projects_with_new_failures(&current).is_empty());
+}
+
+#[test]
+fn report_cleared_failures() {
+    let previous = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    let mut current = make_report(&[("a", "website-data-inclusion"), ("c", "maintainers")]);
+
+    // Projects that were not validated this time have not been fixed
+    assert_eq!(
+        current.cleared_failures(&previous),
+        vec![("a", "maintainers")]
+    );
+    current.projects[0].checks[0].id = "maintainers".to_string();
+    assert!(current.cleared_failures(&previous).is_empty());
+    assert_eq!(current.new_failures(&previous), vec![("c", "maintainers")]);
+}
+
+#[test]
+fn report_new_alerts() {
+    let previous = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    let mut current = make_report(&[
+        ("a", "maintainers"),
+        ("b", "maintainers"),
+        ("c", "maintainers"),
+        ("d", "maintainers"),
+    ]);
+    current.projects[2].checks[0].infrastructure = true;
+    current.projects[3].checks[0].known = true;
+
+    // Failures no one can act on and those in the baseline don't alert
+    assert!(current.new_alerts(Some(&previous)).is_empty());
+    assert_eq!(
+        current.new_alerts(None),
+        vec![("a", "maintainers"), ("b", "maintainers")]
+    );
+
+    // A check that could not be run before alerts once it fails
+    let mut previous = current.clone();
+    previous.projects[0].checks[0].infrastructure = true;
+    assert_eq!(
+        current.new_alerts(Some(&previous)),
+        vec![("a", "maintainers")]
+    );
+}
+
+#[test]
+fn report_errors_match_project_errors() {
+    use std::time::Duration;
+
+    let mut project = Project::new("some-project".to_string());
+    for (id, message) in [("a", "Oh no"), ("b", "Oh dear")].iter() {
+        project.checks.push(project::CheckResult {
+            id: id.to_string(),
+            result: Err(eyre!(*message)).wrap_err("Wrapped"),
+            warning: None,
+            skipped: false,
+            exemption: None,
+            prerequisite: None,
+            duration: Duration::default(),
+        });
+    }
+
+    // Notifications rendered from a results file must be the same as those
+    // rendered from the validation itself
+    let expected = project.errors_to_string(false);
+    assert!(expected.is_some());
+    let report = Report::new(&[project]);
+    assert_eq!(report.projects[0].errors_to_string(), expected);
+}
+
+#[test]
+fn html_summary_escapes_errors() {
+    let mut report = make_report(&[("a", "maintainers")]);
+    report.projects[0].checks[0].error = Some("<script>&".to_string());
+    let notification = Notification::immediate(&report.projects);
+
+    let html = notify::html_summary(&notification);
+    assert!(html.contains("<pre>&lt;script&gt;&amp;\n</pre>"));
+    assert!(!html.contains("<script>"));
+}
+
+fn make_run() -> report::RunMetadata {
+    report::RunMetadata {
+        id: "5731-2".to_string(),
+        version: "0.1.0".to_string(),
+        commit: Some("3f786850e387550fdab836ed7e6dc881de23001b".to_string()),
+        started_at: "2026-10-14T02:00:00Z".to_string(),
+        config_hash: None,
+        rate_limit: Some(report::RateLimitUsage {
+            limit: 5000,
+            remaining: 4200,
+            used: Some(800),
+        }),
+    }
+}
+
+#[test]
+fn run_provenance() {
+    let mut run = make_run();
+    assert_eq!(
+        run.summary(),
+        "Run 5731-2 · embark-oss 0.1.0 (3f786850e387) · 2026-10-14T02:00:00Z · default config \
+· 800 GitHub API requests, 4200 of 5000 remaining"
+    );
+    run.commit = None;
+    run.config_hash = Some("a".repeat(64));
+    run.rate_limit = None;
+    assert_eq!(
+        run.summary(),
+        "Run 5731-2 · embark-oss 0.1.0 · 2026-10-14T02:00:00Z · config aaaaaaaaaaaa"
+    );
+
+    let limit = |remaining, reset| github::RateLimit {
+        limit: 5000,
+        remaining,
+        reset,
+    };
+    let usage = provenance::usage(Some(limit(5000, 10)), limit(4200, 10));
+    assert_eq!(usage.used, Some(800));
+    assert_eq!(usage.remaining, 4200);
+    assert_eq!(
+        provenance::usage(Some(limit(100, 10)), limit(4900, 20)).used,
+        None
+    );
+    assert_eq!(provenance::usage(None, limit(4900, 20)).used, None);
+
+    // Notifications end with the run
+    let mut report = make_report(&[("a", "maintainers")]);
+    report.run = Some(make_run());
+    let notification = Notification::immediate(&report.projects).with_run(report.run.as_ref());
+    let blocks = notify::slack_notification_blocks(&notification, &Default::default());
+    match blocks.last() {
+        Some(crate::slack::Block::Text(foot)) => assert!(foot.ends_with("4200 of 5000 remaining")),
+        other => panic!("Unexpected foot {:?}", other),
+    }
+    assert!(notify::html_summary(&notification).contains("<small>Run 5731-2 · "));
+    assert!(notify::markdown_summary(&notification).contains("<sub>Run 5731-2 · "));
+    assert_eq!(notify::json_summary(&notification)["run"]["id"], "5731-2");
+    assert!(Notification::immediate(&report.projects).run.is_none());
+}
+
+#[test]
+fn slack_routing_by_name_and_tag() {
+    let mut context = make_context();
+    context.config = Config::parse(
+        r#"
+[[slack-routes]]
+webhook-url-env = "A"
+projects = ["texture-*"]
+
+[[slack-routes]]
+webhook-url-env = "B"
+tags = ["rust"]
+"#,
+    )
+    .unwrap();
+    let mut rusty = make_website_project("texture-synthesis");
+    let _ = rusty.tags.insert("rust".to_string());
+    context.opensource_website_projects.push(rusty);
+
+    let report = make_report(&[
+        ("texture-synthesis", "maintainers"),
+        ("other", "maintainers"),
+    ]);
+    let routing = notify::route_slack(
+        &Notification::immediate(&report.projects),
+        std::slice::from_ref(&context),
+    );
+
+    // Projects go to every route they match, and only unmatched ones are
+    // left for the default webhook
+    let names = |notification: &Notification<'_>| -> Vec<String> {
+        notification
+            .notices
+            .iter()
+            .map(|notice| notice.project.name.clone())
+            .collect()
+    };
+    assert_eq!(names(&routing.routed[0].1), vec!["texture-synthesis"]);
+    assert_eq!(names(&routing.routed[1].1), vec!["texture-synthesis"]);
+    assert_eq!(names(&routing.unrouted), vec!["other"]);
+}
+
+#[tokio::test]
+async fn notifications_go_to_every_destination_despite_failures() {
+    let mut context = make_context();
+    context.config = Config::parse(
+        r#"
+[[slack-routes]]
+webhook-url-env = "NOTIFY_TEST_UNSET_A"
+projects = ["*"]
+
+[[slack-routes]]
+webhook-url-env = "NOTIFY_TEST_UNSET_B"
+projects = ["*"]
+"#,
+    )
+    .unwrap();
+    let report = make_report(&[("texture-synthesis", "maintainers")]);
+    let error = notify::send(
+        &Notification::immediate(&report.projects),
+        std::slice::from_ref(&context),
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    // The first route failing doesn't stop the second from being tried
+    let message = format!("{:?}", error);
+    assert!(message.starts_with("Unable to send the notification to 2 destinations"));
+    assert!(message.contains("The NOTIFY_TEST_UNSET_A environment variable is not set"));
+    assert!(message.contains("The NOTIFY_TEST_UNSET_B environment variable is not set"));
+}
+
+#[test]
+fn history_digests_and_trends() {
+    use std::time::Duration;
+
+    let week = Duration::from_secs(7 * 24 * 60 * 60);
+    let mut history = History::default();
+    assert!(history.digest_due(0, week));
+    assert_eq!(history.trend("a", 1), None);
+
+    let first = history::Run::new(0, &make_report(&[("a", "maintainers")]));
+    history.last_digest = Some(first.clone());
+    history.record(first);
+    assert!(!history.digest_due(week.as_secs() - 1, week));
+    assert!(history.digest_due(week.as_secs(), week));
+
+    assert_eq!(history.trend("a", 1), Some(history::Trend::Same));
+    assert_eq!(history.trend("a", 2), Some(history::Trend::Worse));
+    assert_eq!(history.trend("a", 0), Some(history::Trend::Better));
+    assert_eq!(history.trend("b", 1), Some(history::Trend::New));
+
+    // Digests show the trend alongside the project name
+    let report = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    let notification = Notification::digest(&report.projects, &history);
+    let text = notify::text_summary(&notification);
+    assert!(text.contains("→ a\n"));
+    assert!(text.contains("🆕 b\n"));
+}
+
+#[test]
+fn persistent_failures_are_escalated() {
+    let escalation: crate::config::Escalation =
+        toml::from_str("after-runs = 2\nmention = \"<!here>\"").unwrap();
+    let mut history = History::default();
+    history.record(history::Run::new(0, &make_report(&[("a", "maintainers")])));
+    let report = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    history.record(history::Run::new(1, &report));
+    assert_eq!(history.consecutive_failures("a", "maintainers"), 2);
+    assert_eq!(history.consecutive_failures("b", "maintainers"), 1);
+
+    let (notification, escalated) =
+        Notification::immediate(&report.projects).split_escalated(&history, &escalation);
+    assert_eq!(notification.notices.len(), 1);
+    assert_eq!(notification.notices[0].project.name, "b");
+    assert_eq!(escalated.notices.len(), 1);
+    assert_eq!(escalated.notices[0].project.name, "a");
+
+    // Slack escalations start with the mention
+    let blocks = notify::slack_notification_blocks(&escalated, &Default::default());
+    assert!(crate::slack::payload(&blocks, &[])
+        .to_string()
+        .contains("<!here> The following"));
+}
+
+#[test]
+fn cached_results_are_reused_for_the_same_commit() {
+    let config: crate::config::ResultCache = toml::from_str("max-age-hours = 1").unwrap();
+    let report = make_report(&[("a", "maintainers")]);
+    let entry = cache::Entry {
+        stamp: cache::Stamp {
+            sha: "abc".to_string(),
+            time: 0,
+        },
+        project: report.projects[0].clone(),
+    };
+    let cache = cache::Cache {
+        entries: vec![("a".to_string(), entry)].into_iter().collect(),
+    };
+    let checks = vec!["maintainers".to_string()];
+
+    assert!(cache.get("a", "abc", 60, &config, &checks).is_some());
+    assert!(cache.get("a", "def", 60, &config, &checks).is_none());
+    assert!(cache.get("b", "abc", 60, &config, &checks).is_none());
+
+    // Results expire, and are not reused when the configured checks change
+    assert!(cache.get("a", "abc", 60 * 60, &config, &checks).is_none());
+    let more_checks = vec!["maintainers".to_string(), "external:x".to_string()];
+    assert!(cache.get("a", "abc", 60, &config, &more_checks).is_none());
+}
+
+#[test]
+fn projects_from_reports_rerun_context_checks() {
+    let context = make_context();
+    let mut report = make_report(&[("a", "maintainers")]);
+    report.projects[0].checks.push(report::CheckReport {
+        id: "website-data-inclusion".to_string(),
+        ok: true,
+        error: None,
+        infrastructure: false,
+        warning: None,
+        skipped: false,
+        exemption: None,
+        exemption_expires: None,
+        exemption_owner: None,
+        prerequisite: None,
+        known: false,
+        duration_ms: 0,
+    });
+
+    let project = Project::from_report(&report.projects[0], &context);
+    assert!(project.maintainers.is_none());
+    assert_eq!(project.checks.len(), 2);
+    // The project is not in the website data any more
+    let errors = project.errors_to_string(false).unwrap();
+    assert_eq!(
+        errors,
+        "Oh no\n\nProject not included in opensource-website data.json\n"
+    );
+}
+
+#[test]
+fn celebrating_projects_that_now_conform() {
+    let mut previous = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    previous.projects[1].checks[0].infrastructure = true;
+    let mut report = make_report(&[("a", "msrv"), ("b", "msrv"), ("c", "msrv"), ("d", "msrv")]);
+    for project in &mut report.projects[..3] {
+        project.ok = true;
+    }
+
+    let celebration = Notification::celebration(&report, &previous);
+    let names: Vec<_> = celebration
+        .notices
+        .iter()
+        .map(|notice| (notice.project.name.as_str(), notice.first_validation))
+        .collect();
+    assert_eq!(names, vec![("a", false), ("c", true)]);
+    let payload = crate::slack::payload(
+        &notify::slack_notification_blocks(&celebration, &Default::default()),
+        &[],
+    );
+    let payload = payload.to_string();
+    assert!(payload.contains(":tada:"));
+    assert!(payload.contains("c (new project)"));
+    assert!(Notification::celebration(&previous, &previous).is_empty());
+}
+
+#[test]
+fn templating_slack_notifications() {
+    let report = make_report(&[("a", "maintainers"), ("b", "msrv")]);
+    let notification = Notification::immediate(&report.projects);
+    let default = crate::slack::payload(
+        &notify::slack_notification_blocks(&notification, &Default::default()),
+        &[],
+    )
+    .to_string();
+    assert!(default.contains("have maintainership issues"));
+    assert!(default.contains("embark-oss tool"));
+
+    let config = Config::parse(
+        r#"
+        [slack-templates]
+        immediate = "{count} projects need help"
+        project = "{name} in {organisation}: {errors}"
+        foot = "Sent by the bot"
+        "#,
+    )
+    .unwrap();
+    let blocks = notify::slack_notification_blocks(&notification, &config.slack_templates);
+    let texts: Vec<_> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            crate::slack::Block::Text(text) => Some(text.as_str()),
+            crate::slack::Block::Divider => None,
+        })
+        .collect();
+    assert_eq!(texts[0], "2 projects need help");
+    assert!(texts[1].starts_with("a in EmbarkStudios: "));
+    assert_eq!(texts.last(), Some(&"Sent by the bot"));
+}
+
+#[test]
+fn attaching_machine_readable_summaries() {
+    let mut report = make_report(&[("a", "maintainers"), ("b", "msrv")]);
+    report.projects[1].checks[0].infrastructure = true;
+    report.projects[1].checks[0].id = "topics".to_string();
+    let notification = Notification::immediate(&report.projects);
+
+    let config = Config::default();
+    assert!(notify::slack_attachments(&notification, &config).is_empty());
+    let blocks = notify::slack_notification_blocks(&notification, &config.slack_templates);
+    assert!(crate::slack::payload(&blocks, &[])
+        .get("attachments")
+        .is_none());
+
+    let config = Config::parse("[slack-attachment]").unwrap();
+    let attachments = notify::slack_attachments(&notification, &config);
+    assert_eq!(attachments[0].title, "summary.json");
+    let summary: serde_json::Value = serde_json::from_str(&attachments[0].text).unwrap();
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "kind": "immediate",
+            "projects": [
+                { "organisation": "EmbarkStudios", "name": "a", "failing": ["maintainers"], "could_not_validate": false },
+                { "organisation": "EmbarkStudios", "name": "b", "failing": ["topics"], "could_not_validate": true },
+            ],
+        })
+    );
+    let payload = crate::slack::payload(&blocks, &attachments);
+    assert!(payload["attachments"][0]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("```{"));
+
+    let config = Config::parse("[slack-attachment]\nformat = \"csv\"").unwrap();
+    let attachments = notify::slack_attachments(&notification, &config);
+    assert_eq!(attachments[0].title, "summary.csv");
+    assert_eq!(
+        attachments[0].text,
+        "organisation,name,failing,could_not_validate\n\
+EmbarkStudios,a,maintainers,false\n\
+EmbarkStudios,b,topics,true\n"
+    );
+
+    // Fields that would break the columns are quoted
+    let mut report = make_report(&[("a,b", "maintainers"), ("say \"hi\"", "topics")]);
+    let check = report::CheckReport {
+        id: "required-files:\nlicense".to_string(),
+        ..report.projects[0].checks[0].clone()
+    };
+    report.projects[0].checks.push(check);
+    let notification = Notification::immediate(&report.projects);
+    assert_eq!(
+        notify::csv_summary(&notification),
+        "organisation,name,failing,could_not_validate\n\
+EmbarkStudios,\"a,b\",\"maintainers;required-files:\nlicense\",false\n\
+EmbarkStudios,\"say \"\"hi\"\"\",topics,false\n"
+    );
+}
+
+#[test]
+fn posting_digests() {
+    let report = make_report(&[("a", "maintainers")]);
+    let notification = Notification::digest(&report.projects, &History::default());
+    let markdown = notify::markdown_summary(&notification);
+    assert!(markdown.starts_with("This is the regular digest"));
+    assert!(markdown.contains("### [a](https://github.com/EmbarkStudios/a)\n\n```\n"));
+    assert!(markdown.contains("embark-oss tool"));
+
+    let empty = Notification::digest(Vec::new(), &History::default());
+    assert!(notify::markdown_summary(&empty).contains("Every project conforms"));
+}
+
+#[test]
+fn appending_to_atom_feeds() {
+    let report = make_report(&[("a", "maintainers"), ("b", "msrv")]);
+    let feed = feed::update(None, &report, 0).unwrap();
+    assert!(feed.starts_with("<?xml"));
+    assert!(feed.contains("<title>2 new failures</title>"));
+    assert!(feed.contains(
+        r#"<oss:failure organisation="EmbarkStudios" project="a" check="maintainers"/>"#
+    ));
+    assert!(feed.ends_with("</feed>\n"));
+
+    // Nothing is added if nothing changed
+    assert!(feed::update(Some(&feed), &report, 60).is_none());
+
+    let mut report = make_report(&[("a", "maintainers"), ("b", "topics")]);
+    let feed = feed::update(Some(&feed), &report, 120).unwrap();
+    assert_eq!(feed.matches("<entry>").count(), 2);
+    assert!(feed.contains("<title>1 new failures, 1 fixed</title>"));
+    assert!(feed.contains("<updated>1970-01-01T00:02:00Z</updated>"));
+    let latest = &feed[feed.find("<entry>").unwrap()..feed.find("</entry>").unwrap()];
+    assert!(latest.contains("EmbarkStudios/b&lt;/a&gt;: topics"));
+
+    // Projects that weren't validated are not fixed
+    let _ = report.projects.remove(0);
+    let feed = feed::update(Some(&feed), &make_report(&[("c", "msrv")]), 180).unwrap();
+    assert!(feed.contains("<title>1 new failures</title>"));
+    assert!(feed.contains(r#"project="b" check="topics"/>"#));
+    assert!(feed::update(Some(&feed), &report, 240).is_none());
+}
+
+#[test]
+fn tolerating_failures() {
+    let report = make_report(&[("a", "maintainers"), ("b", "msrv"), ("c", "topics")]);
+    let allowed = vec!["b".to_string()];
+    assert_eq!(failures_over_budget(&report, 0, &[]), vec!["a", "b", "c"]);
+    assert_eq!(failures_over_budget(&report, 0, &allowed), vec!["a", "c"]);
+    assert!(failures_over_budget(&report, 2, &allowed).is_empty());
+    assert_eq!(failures_over_budget(&report, 2, &[]).len(), 3);
+
+    let mut report = report;
+    report.projects[2].checks[0].known = true;
+    assert_eq!(failures_over_budget(&report, 0, &allowed), vec!["a"]);
+}
+
+#[test]
+fn check_runs_summarise_projects() {
+    let config = crate::config::CheckRuns::default();
+    let mut report = make_report(&[("ash#crates/ash-window", "msrv"), ("puffin", "topics")]);
+    report.projects[1].ok = true;
+    report.projects[1].checks[0].ok = true;
+    report.projects[1].checks[0].error = None;
+    report.projects[1].checks[0].warning = Some("Soon".to_string());
+
+    let failing = check_runs::check_run(&config, &report.projects[0], "abc123");
+    assert_eq!(
+        failing["name"],
+        "Open source guidelines (crates/ash-window)"
+    );
+    assert_eq!(failing["head_sha"], "abc123");
+    assert_eq!(failing["conclusion"], "failure");
+    assert_eq!(failing["output"]["title"], "1 check fails");
+    let summary = failing["output"]["summary"].as_str().unwrap();
+    assert!(summary.starts_with("### ❌ msrv\n\n```\nOh no\n```"));
+    assert!(summary.contains("cargo run inspect ash#crates/ash-window"));
+
+    let passing = check_runs::check_run(&config, &report.projects[1], "def456");
+    assert_eq!(passing["name"], "Open source guidelines");
+    assert_eq!(passing["conclusion"], "success");
+    let summary = passing["output"]["summary"].as_str().unwrap();
+    assert!(summary.contains("### ⚠️ topics (warning)"));
+
+    report.projects[0].checks[0].infrastructure = true;
+    let unvalidated = check_runs::check_run(&config, &report.projects[0], "abc123");
+    assert_eq!(unvalidated["conclusion"], "neutral");
+}
+
+#[test]
+fn validating_website_pull_requests() {
+    let mut ash = make_website_project("ash");
+    ash.repo = Some("https://github.com/EmbarkStudios/ash".to_string());
+    let mut puffin = make_website_project("puffin");
+    let base = vec![ash.clone(), puffin.clone()];
+    puffin.tier = Some("flagship".to_string());
+    let kajiya = make_website_project("kajiya");
+    let head = vec![ash, puffin, kajiya];
+    let changed: Vec<_> = website_pr::changed_projects(&base, &head)
+        .into_iter()
+        .map(OpenSourceWebsiteDataProject::project_name)
+        .collect();
+    assert_eq!(changed, vec!["puffin", "kajiya"]);
+
+    let mut report = make_report(&[("puffin", "msrv"), ("kajiya", "topics")]);
+    let status = website_pr::commit_status(&report.projects);
+    assert_eq!(status["state"], "failure");
+    assert_eq!(
+        status["description"],
+        "Changed projects that don't conform: 2 of 2"
+    );
+    assert_eq!(status["context"], "embark-oss");
+    assert_eq!(
+        website_pr::commit_status(&[])["description"],
+        "No projects are added or changed"
+    );
+
+    report.projects[1].ok = true;
+    let comment = website_pr::comment_body(&report.projects);
+    assert!(comment.contains("- ❌ **puffin** doesn't conform to our guidelines"));
+    assert!(comment.contains("### ❌ msrv\n\n```\nOh no\n```"));
+    assert!(comment.contains("- ✔️ **kajiya** conforms to our guidelines"));
+
+    report.projects[0].checks[0].infrastructure = true;
+    assert_eq!(
+        website_pr::commit_status(&report.projects)["state"],
+        "error"
+    );
+}
+
+#[test]
+fn reviewing_website_entries() {
+    let mut context = make_context();
+    for (name, archived) in [("ash", false), ("old", true)] {
+        let _ = context
+            .embark_github_repos
+            .insert(name.to_string(), make_repo(name, archived));
+    }
+    context.config = Config::parse("[tiers.policies]\nflagship = {}").unwrap();
+    let entry = |name: &str, repo: Option<&str>| {
+        let mut entry = make_website_project(name);
+        entry.repo = repo.map(String::from);
+        entry
+    };
+    let ash = entry("ash", Some("https://github.com/EmbarkStudios/ash"));
+    let mut window = entry(
+        "ash",
+        Some("https://github.com/EmbarkStudios/ash#ash-window"),
+    );
+    window.tier = Some("gold".to_string());
+    let old = entry("old", Some("https://github.com/EmbarkStudios/old"));
+    let fork = entry("fork", Some("https://github.com/someone/fork"));
+    let missing = entry("missing", None);
+    context.opensource_website_projects = vec![
+        ash.clone(),
+        window.clone(),
+        old.clone(),
+        fork.clone(),
+        missing.clone(),
+    ];
+
+    assert_eq!(
+        website_pr::entry_problems(&window, &context),
+        vec![
+            "Another entry is also named `ash`",
+            "The tier `gold` is not one of: flagship"
+        ]
+    );
+    assert_eq!(
+        website_pr::entry_problems(&old, &context),
+        vec!["old is archived"]
+    );
+    assert_eq!(
+        website_pr::entry_problems(&fork, &context),
+        vec!["The `repo` https://github.com/someone/fork is not in the EmbarkStudios organisation"]
+    );
+    assert_eq!(
+        website_pr::entry_problems(&missing, &context),
+        vec!["No `repo` is given"]
+    );
+    let mut unique = ash;
+    unique.name = "Ash".to_string();
+    context.opensource_website_projects[0] = unique.clone();
+    assert!(website_pr::entry_problems(&unique, &context).is_empty());
+
+    let reviews = [
+        website_pr::EntryReview {
+            name: "Ash".to_string(),
+            problems: Vec::new(),
+            failures: Vec::new(),
+        },
+        website_pr::EntryReview {
+            name: "old".to_string(),
+            problems: vec!["old is archived".to_string()],
+            failures: vec![("msrv".to_string(), "No MSRV".to_string())],
+        },
+    ];
+    let body = website_pr::review_body(&reviews);
+    assert!(body.contains("#### ✔️ Ash\n\nNo problems found."));
+    assert!(body.contains("#### ❌ old\n\n- old is archived\n- Fails the `msrv` check: No MSRV\n"));
+    assert!(website_pr::review_body(&[]).contains("doesn't add or change any projects"));
+}
+
+#[test]
+fn correcting_website_data() {
+    let mut context = make_context();
+    for (name, archived) in [("texture-synthesis", false), ("ash", false), ("old", true)] {
+        let _ = context
+            .embark_github_repos
+            .insert(name.to_string(), make_repo(name, archived));
+    }
+    let text = r#"{
+  "projects": [
+    {
+      "name": "texture-synthesis",
+      "repo": "https://github.com/EmbarkStudios/texture-synthesis"
+    },
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/Ash"
+    },
+    {
+      "name": "gone",
+      "repo": "https://github.com/EmbarkStudios/gone"
+    },
+    {
+      "name": "old \"one\"",
+      "repo": "https://github.com/EmbarkStudios/old#crates/one"
+    },
+    {
+      "name": "Renamed",
+      "repo": "https://github.com/EmbarkStudios/Renamed#readme",
+      "homepage": "https://github.com/EmbarkStudios/Renamed#readme"
+    },
+    { "name": "elsewhere", "repo": "https://github.com/rust-lang/rust" }
+  ]
+}
+"#;
+
+    // Missing repos are left alone until they have been looked up
+    let mut missing = fix::MissingRepos::new();
+    let (_, corrections) = fix::correct_website_data(text, &context, &missing).unwrap();
+    assert_eq!(
+        corrections,
+        vec![
+            "Moved old \"one\" to the archived projects, as its repo old is archived",
+            "Sorted the entries by name",
+        ]
+    );
+
+    let _ = missing.insert("gone".to_string(), None);
+    let _ = missing.insert(
+        "renamed".to_string(),
+        Some("EmbarkStudios/new-name".to_string()),
+    );
+    let (corrected, corrections) = fix::correct_website_data(text, &context, &missing).unwrap();
+    assert_eq!(
+        corrections,
+        vec![
+            "Moved old \"one\" to the archived projects, as its repo old is archived",
+            "Removed gone, as its repo gone doesn't exist",
+            "Changed the repo of Renamed to https://github.com/EmbarkStudios/new-name#readme, \
+             as its repo Renamed was renamed",
+            "Sorted the entries by name",
+        ]
+    );
+    assert_eq!(
+        corrected,
+        r#"{
+  "projects": [
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/Ash"
+    },
+    { "name": "elsewhere", "repo": "https://github.com/rust-lang/rust" },
+    {
+      "name": "Renamed",
+      "repo": "https://github.com/EmbarkStudios/new-name#readme",
+      "homepage": "https://github.com/EmbarkStudios/Renamed#readme"
+    },
+    {
+      "name": "texture-synthesis",
+      "repo": "https://github.com/EmbarkStudios/texture-synthesis"
+    }
+  ],
+  "archived": [
+    {
+      "name": "old \"one\"",
+      "repo": "https://github.com/EmbarkStudios/old#crates/one"
+    }
+  ]
+}
+"#
+    );
+    let diff = crate::diff::unified("static/data/data.json", text, &corrected);
+    assert!(diff.starts_with("--- a/static/data/data.json\n+++ b/static/data/data.json\n@@ -1,"));
+
+    let _ = context
+        .embark_github_repos
+        .insert("new-name".to_string(), make_repo("new-name", false));
+    let (_, corrections) = fix::correct_website_data(&corrected, &context, &missing).unwrap();
+    assert!(corrections.is_empty());
+    assert!(fix::correct_website_data("{}", &context, &missing).is_err());
+}
+
+#[test]
+fn adding_website_entries() {
+    let text = r#"{
+  "projects": [
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/ash"
+    },
+    {
+      "name": "texture-synthesis"
+    }
+  ]
+}
+"#;
+    let entry = serde_json::json!({ "name": "puffin", "tags": ["rust"] });
+    assert_eq!(
+        add_website_entry(text, &entry).unwrap(),
+        r#"{
+  "projects": [
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/ash"
+    },
+    {
+      "name": "puffin",
+      "tags": [
+        "rust"
+      ]
+    },
+    {
+      "name": "texture-synthesis"
+    }
+  ]
+}
+"#
+    );
+    let last = serde_json::json!({ "name": "zeta" });
+    assert!(add_website_entry(text, &last)
+        .unwrap()
+        .ends_with("    },\n    {\n      \"name\": \"zeta\"\n    }\n  ]\n}\n"));
+    let existing = serde_json::json!({ "name": "ash" });
+    assert!(add_website_entry(text, &existing).is_err());
+}
+
+#[test]
+fn archiving_website_entries() {
+    let text = r#"{
+  "projects": [
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/ash"
+    },
+    {
+      "name": "old",
+      "repo": "https://github.com/EmbarkStudios/old"
+    }
+  ]
+}
+"#;
+    assert_eq!(
+        fix::archive_website_entry(text, "old").unwrap().unwrap(),
+        r#"{
+  "projects": [
+    {
+      "name": "Ash",
+      "repo": "https://github.com/EmbarkStudios/ash"
+    }
+  ],
+  "archived": [
+    {
+      "name": "old",
+      "repo": "https://github.com/EmbarkStudios/old"
+    }
+  ]
+}
+"#
+    );
+    assert!(fix::archive_website_entry(text, "puffin")
+        .unwrap()
+        .is_none());
+
+    let text = r#"{
+  "archived": [
+    { "name": "older" }
+  ],
+  "projects": [
+    { "name": "Ash", "repo": "https://github.com/EmbarkStudios/ash" },
+    { "name": "puffin" }
+  ]
+}
+"#;
+    assert_eq!(
+        fix::archive_website_entry(text, "ash").unwrap().unwrap(),
+        r#"{
+  "archived": [
+    { "name": "older" },
+    { "name": "Ash", "repo": "https://github.com/EmbarkStudios/ash" }
+  ],
+  "projects": [
+    { "name": "puffin" }
+  ]
+}
+"#
+    );
+
+    let banner = &crate::config::MaintenanceBanner::default().archived.0;
+    assert!(banner
+        .is_match("[![Maintenance](https://img.shields.io/badge/maintenance-as--is-yellow.svg)]"));
+    assert!(!banner.is_match(
+        "[![Maintenance](https://img.shields.io/badge/maintenance-actively--developed-brightgreen.svg)]"
+    ));
+}
+
+#[test]
+fn syncing_templates() {
+    let policy = crate::config::TemplateDrift::default();
+    let drifted = [checks::template_drift::DriftedFile {
+        path: ".github/pull_request_template.md".to_string(),
+        template: "### Checklist\n".to_string(),
+        text: "### Checklist\n- [ ] Tests\n".to_string(),
+        added: 1,
+        removed: 0,
+    }];
+    let body = sync_templates::pull_request_body(&policy, &drifted);
+    assert!(body.starts_with(
+        "These files have drifted from the \
+[EmbarkStudios/opensource-template](https://github.com/EmbarkStudios/opensource-template) template"
+    ));
+    assert!(body
+        .contains("- `.github/pull_request_template.md`, which has 1 lines added and 0 removed\n"));
+    assert!(body.ends_with("Opened by `cargo run sync-templates`."));
+}
+
+#[test]
+fn infrastructure_failures_are_not_policy_violations() {
+    let mut report = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    report.projects[1].checks[0].infrastructure = true;
+    assert!(report.projects[0].has_policy_failures());
+    assert!(!report.projects[0].could_not_validate());
+    assert!(!report.projects[1].has_policy_failures());
+    assert!(report.projects[1].could_not_validate());
+
+    let unvalidated = Notification::unvalidated(
+        report
+            .projects
+            .iter()
+            .filter(|project| project.could_not_validate()),
+    );
+    assert_eq!(unvalidated.notices.len(), 1);
+    let payload = crate::slack::payload(
+        &notify::slack_notification_blocks(&unvalidated, &Default::default()),
+        &[],
+    );
+    assert!(payload
+        .to_string()
+        .contains(":warning: *<https://github.com/EmbarkStudios/b|b>*"));
+
+    // A project failing because GitHub was down is validated again
+    let mut project = Project::new("b".to_string());
+    project.checks.push(project::CheckResult {
+        id: "maintainers".to_string(),
+        result: Err(github::DownloadError::Http {
+            name: "EmbarkStudios/b:.github/CODEOWNERS".to_string(),
+            status: 502,
+        }
+        .into()),
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    });
+    assert!(project.could_not_validate());
+}
+
+#[test]
+fn notifications_have_a_section_per_organisation() {
+    let mut report = make_report(&[("a", "maintainers"), ("b", "maintainers")]);
+    let notification = Notification::immediate(&report.projects);
+    assert!(!notify::text_summary(&notification).contains("EmbarkStudios"));
+
+    report.projects[1].organisation = "EmbarkLabs".to_string();
+    let notification = Notification::immediate(&report.projects);
+    let text = notify::text_summary(&notification);
+    assert!(text.contains("EmbarkStudios\n\na\n"));
+    assert!(text.contains("EmbarkLabs\n\nb\n"));
+    assert!(notify::html_summary(&notification)
+        .contains("<h2>EmbarkLabs</h2>\n<h3><a href=\"https://github.com/EmbarkLabs/b\">"));
+}
+
+#[test]
+fn project_names_from_a_file() {
+    let text = "ash\n\n# Not yet public\nEmbarkLabs/prototype # new\n  physx-rs  \n";
+    assert_eq!(
+        parse_project_names(text),
+        vec!["ash", "EmbarkLabs/prototype", "physx-rs"]
+    );
+}
+
+#[test]
+fn warning_only_checks_pass() {
+    let mut project = Project::new("some-project".to_string());
+    project.checks.push(
+        project::CheckResult {
+            id: "msrv".to_string(),
+            result: Err(eyre!("No MSRV declared")),
+            warning: None,
+            skipped: false,
+            exemption: None,
+            prerequisite: None,
+            duration: std::time::Duration::default(),
+        }
+        .into_warning(),
+    );
+    assert!(!project.has_errors());
+    assert_eq!(project.warnings(), vec![("msrv", "No MSRV declared")]);
+
+    let report = Report::new(&[project]);
+    assert!(report.projects[0].ok);
+    assert_eq!(report.failures().count(), 0);
+    let context = make_context();
+    let project = Project::from_report(&report.projects[0], &context);
+    assert_eq!(project.warnings(), vec![("msrv", "No MSRV declared")]);
+}
+
+#[test]
+fn skipped_checks_pass_and_are_reported() {
+    let mut project = Project::new("some-project".to_string());
+    project.checks.push(project::CheckResult {
+        id: "dependabot".to_string(),
+        result: Ok(()),
+        warning: None,
+        skipped: true,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    });
+    assert!(!project.has_errors());
+    assert_eq!(project.skipped(), vec!["dependabot"]);
+
+    let report = Report::new(&[project]);
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["projects"][0]["checks"][0]["skipped"], true);
+    let context = make_context();
+    let project = Project::from_report(&report.projects[0], &context);
+    assert_eq!(project.skipped(), vec!["dependabot"]);
+    assert_eq!(
+        Project::checks_needing_auth(&context.config),
+        ["maintainers", "website-owners"]
+    );
+
+    // Signed releases only need credentials to look at branch protection
+    let config = Config::parse("[dependabot]\n[signed-releases]").unwrap();
+    assert_eq!(
+        Project::checks_needing_auth(&config),
+        ["maintainers", "website-owners", "dependabot"]
+    );
+    let config = Config::parse("[signed-releases]\nrequire-signed-commits = true").unwrap();
+    assert!(Project::checks_needing_auth(&config).contains(&"signed-releases".to_string()));
+}
+
+#[tokio::test]
+async fn checks_needing_auth_are_not_run_anonymously() {
+    let mut context = make_context();
+    context.config = Config::parse("[dependabot]").unwrap();
+    let check =
+        project::CheckResult::run_async(&context, "dependabot", async { panic!("dependabot ran") })
+            .await;
+    assert!(check.skipped && check.result.is_ok());
+
+    // The members of a team are only listed to authenticated clients
+    let error = context
+        .client
+        .team_members("EmbarkStudios", "rust-ecosystem-team")
+        .await
+        .unwrap_err();
+    assert!(github::needs_auth(&error));
+}
+
+#[tokio::test]
+async fn misbehaving_checks_fail_without_stopping_validation() {
+    let check = project::CheckResult::run("license-year", || panic!("scan went wrong"));
+    assert_eq!(
+        check.result.unwrap_err().to_string(),
+        "The check panicked: scan went wrong"
+    );
+
+    let mut context = make_context();
+    context.config.checks.timeout_seconds = 0;
+    let check = project::CheckResult::run_async(&context, "release-checksums", async {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        Ok(())
+    })
+    .await;
+    let error = check.result.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "The check did not finish within 0 seconds"
+    );
+    assert!(github::is_infrastructure_error(&error));
+}
+
+#[test]
+fn report_schema_describes_every_field() {
+    let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+    assert_eq!(
+        schema["properties"]["schema_version"]["const"],
+        report::SCHEMA_VERSION
+    );
+
+    let mut report = make_report(&[("a", "maintainers")]);
+    report.projects[0].checks[0].warning = Some("Soon".to_string());
+    report.projects[0].checks[0].skipped = true;
+    report.projects[0].checks[0].exemption = Some("Because".to_string());
+    report.projects[0].checks[0].exemption_expires = Some("2026-01-10".to_string());
+    report.projects[0].checks[0].exemption_owner = Some("octocat".to_string());
+    report.projects[0].tier = Some("flagship".to_string());
+    report.projects[0].checks[0].prerequisite = Some("repo-files".to_string());
+    report.projects[0].checks[0].known = true;
+    report.run = Some(make_run());
+    let now = github::parse_date("2026-01-01").unwrap();
+    report.expiring_exemptions = exemptions::expiring(&report, now, 30);
+    let json = serde_json::to_value(&report).unwrap();
+    let described = |value: &serde_json::Value, schema: &serde_json::Value| {
+        for field in value.as_object().unwrap().keys() {
+            assert!(
+                schema["properties"].get(field).is_some(),
+                "`{}` is not in the schema",
+                field
+            );
+        }
+    };
+    described(&json, &schema);
+    described(&json["projects"][0], &schema["definitions"]["project"]);
+    described(&json["run"], &schema["definitions"]["run"]);
+    described(
+        &json["expiring_exemptions"][0],
+        &schema["definitions"]["expiring_exemption"],
+    );
+    described(
+        &json["projects"][0]["checks"][0],
+        &schema["definitions"]["check"],
+    );
+
+    let unversioned = r#"{ "projects": [], "partial": false }"#;
+    assert_eq!(Report::parse(unversioned).unwrap().schema_version, 1);
+    let newer = r#"{ "schema_version": 2, "projects": [], "partial": false }"#;
+    assert!(Report::parse(newer).is_err());
+}
+
+#[test]
+fn exempt_checks_pass_and_are_reported() {
+    let failing = |id: &str| project::CheckResult {
+        id: id.to_string(),
+        result: Err(eyre!("No MSRV declared")),
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    };
+    let mut project = Project::new("some-project".to_string());
+    let exemption = exemptions::Exemption {
+        check: "msrv".to_string(),
+        justification: "Tracks the latest stable Rust".to_string(),
+        expires: Some("2026-01-10".to_string()),
+        owner: Some("octocat".to_string()),
+    };
+    project.checks.push(failing("msrv").exempt(&exemption));
+    assert!(!project.has_errors());
+    assert_eq!(
+        project.exemptions(),
+        vec![("msrv", "Tracks the latest stable Rust")]
+    );
+
+    let report = Report::new(&[project]);
+    assert!(report.projects[0].ok);
+    let context = make_context();
+    let project = Project::from_report(&report.projects[0], &context);
+    assert_eq!(
+        project.exemptions(),
+        vec![("msrv", "Tracks the latest stable Rust")]
+    );
+    assert_eq!(project.checks[0].exemption.as_ref(), Some(&exemption));
+}
+
+#[test]
+fn reporting_expiring_exemptions() {
+    let mut report = make_report(&[("ash", "msrv"), ("puffin", "msrv"), ("texture", "msrv")]);
+    let expiries = [Some("2026-01-20"), Some("2026-03-01"), None];
+    for (project, expires) in report.projects.iter_mut().zip(expiries) {
+        project.maintainers = vec!["b".to_string(), "a".to_string()];
+        let check = &mut project.checks[0];
+        check.ok = true;
+        check.error = None;
+        check.exemption = Some("Tracks the latest stable Rust".to_string());
+        check.exemption_expires = expires.map(ToString::to_string);
+    }
+    report.projects[0].checks[0].exemption_owner = Some("octocat".to_string());
+    report.projects[1].checks[0].exemption_expires = Some("2026-01-05".to_string());
+
+    // Part of a day left counts as a whole day
+    let now = github::parse_date("2026-01-01").unwrap() + 60 * 60;
+    let expiring = exemptions::expiring(&report, now, 30);
+    let names: Vec<_> = expiring
+        .iter()
+        .map(|exemption| (exemption.project.as_str(), exemption.days_left))
+        .collect();
+    assert_eq!(names, vec![("puffin", 4), ("ash", 19)]);
+    assert_eq!(expiring[0].owners, vec!["a", "b"]);
+    assert_eq!(
+        expiring[1].summary(),
+        "msrv of ash expires on 2026-01-20, in 19 days · owned by octocat · Tracks the latest stable Rust"
+    );
+    assert_eq!(exemptions::expiring(&report, now, 3), Vec::new());
+
+    report.expiring_exemptions = expiring;
+    let config = Config::default();
+    let blocks = notify::expiring_exemptions_blocks(&report, &config);
+    assert_eq!(blocks.len(), 6);
+    match &blocks[2] {
+        crate::slack::Block::Text(text) => assert_eq!(
+            text,
+            ":shield: *<https://github.com/EmbarkStudios/puffin|puffin>* msrv of puffin expires \
+on 2026-01-05, in 4 days · owned by a, b · Tracks the latest stable Rust"
+        ),
+        block @ crate::slack::Block::Divider => panic!("Unexpected block {:?}", block),
+    }
+    assert!(notify::html_expiring_exemptions(&report).contains("<li>msrv of ash expires"));
+    assert_eq!(notify::html_expiring_exemptions(&Report::new(&[])), "");
+}
+
+#[test]
+fn tiers_skip_and_warn_about_checks() {
+    let mut context = make_context();
+    context.config = Config::parse(
+        r#"
+[tiers]
+default = "maintained"
+[tiers.policies.maintained]
+[tiers.policies.experimental]
+skip = ["coverage"]
+warn = ["msrv"]
+"#,
+    )
+    .unwrap();
+    let mut website_project = make_website_project("ash");
+    website_project.tier = Some("experimental".to_string());
+    context.opensource_website_projects.push(website_project);
+    assert_eq!(context.tier("ash"), Some("experimental"));
+    assert_eq!(context.tier("puffin"), Some("maintained"));
+
+    let failing = |id: &str| project::CheckResult {
+        id: id.to_string(),
+        result: Err(eyre!("Failed")),
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    };
+    let checks = project::apply_tier_policy(
+        vec![failing("coverage"), failing("msrv"), failing("topics")],
+        &context.config.tiers.policies["experimental"],
+    );
+    let outcomes: Vec<_> = checks
+        .iter()
+        .map(|check| {
+            (
+                check.id.as_str(),
+                check.result.is_ok(),
+                check.warning.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![("msrv", true, true), ("topics", false, false)]
+    );
+    assert!(Project::check_ids(&context.config).contains(&"tier".to_string()));
+}
+
+#[tokio::test]
+async fn tier_skipped_checks_are_not_run() {
+    let context = make_context();
+    let config = Config::parse(
+        r#"
+[cargo-metadata]
+[topics]
+required = ["embark"]
+[tiers.policies.experimental]
+skip = ["topics", "cargo-metadata"]
+"#,
+    )
+    .unwrap();
+    let policy = &config.tiers.policies["experimental"];
+    let mut checks = project::Checks::new(Some(policy));
+    checks.run("topics", || panic!("topics ran"));
+    checks
+        .run_async(&context, "cargo-metadata", async {
+            panic!("cargo-metadata ran")
+        })
+        .await;
+    checks.run("website-data-inclusion", || Ok(()));
+    let ids: Vec<_> = checks.results.iter().map(|check| &check.id).collect();
+    assert_eq!(ids, ["website-data-inclusion"]);
+
+    // Cached results are compared with the checks the tier runs, which
+    // don't list the repo's files when nothing that needs them is run
+    let all = Project::check_ids(&config);
+    assert!(all.contains(&"topics".to_string()) && all.contains(&"repo-files".to_string()));
+    let tier = Project::check_ids_for_tier(&config, Some("experimental"));
+    assert_eq!(
+        tier,
+        all.iter()
+            .filter(|id| !["topics", "cargo-metadata", "repo-files"].contains(&id.as_str()))
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Project::check_ids_for_tier(&config, Some("other")), all);
+}
+
+#[test]
+fn skipping_checks_whose_prerequisites_failed() {
+    let check = |id: &str, result: eyre::Result<()>| project::CheckResult {
+        id: id.to_string(),
+        result,
+        warning: None,
+        skipped: false,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    };
+    let checks = project::apply_prerequisites(vec![
+        check("maintainers", Err(eyre!("No CODEOWNERS"))),
+        check("repo-files", Ok(())),
+        check("website-owners", Err(eyre!("Failed"))),
+        check("cargo-metadata", Err(eyre!("Failed"))),
+    ]);
+    let outcomes: Vec<_> = checks
+        .iter()
+        .map(|check| {
+            (
+                check.id.as_str(),
+                check.result.is_ok(),
+                check.prerequisite.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            ("maintainers", false, None),
+            ("repo-files", true, None),
+            ("website-owners", true, Some("maintainers")),
+            ("cargo-metadata", false, None),
+        ]
+    );
+
+    let mut context = make_context();
+    assert!(!Project::check_ids(&context.config).contains(&"repo-files".to_string()));
+    context.config = Config::parse("[cargo-metadata]").unwrap();
+    assert_eq!(
+        Project::check_ids(&context.config)[..2],
+        ["maintainers", "repo-files"]
+    );
+}
+
+#[test]
+fn detecting_main_languages() {
+    let bytes: HashMap<_, _> = vec![
+        ("Rust".to_string(), 90_000),
+        ("TypeScript".to_string(), 15_000),
+        ("Shell".to_string(), 500),
+    ]
+    .into_iter()
+    .collect();
+    let mut project = Project::new("kajiya".to_string());
+    assert_eq!(project.uses_language("Rust"), None);
+    project.languages = Some(project::main_languages(&bytes));
+    assert_eq!(project.uses_language("rust"), Some(true));
+    assert_eq!(project.uses_language("TypeScript"), Some(true));
+    assert_eq!(project.uses_language("Shell"), Some(false));
+    assert_eq!(project.uses_language("C++"), Some(false));
+    assert!(project::main_languages(&HashMap::new()).is_empty());
+}
+
+#[test]
+fn projects_in_directories_of_repos() {
+    let mut context = make_context();
+    let mut website_project = make_website_project("ash-window");
+    website_project.repo =
+        Some("https://github.com/EmbarkStudios/ash#crates/ash-window/".to_string());
+    context.opensource_website_projects.push(website_project);
+    let mut website_project = make_website_project("puffin");
+    website_project.repo = Some("https://github.com/EmbarkStudios/puffin".to_string());
+    context.opensource_website_projects.push(website_project);
+    let expected: HashSet<_> = vec!["ash#crates/ash-window/".to_string(), "puffin".to_string()]
+        .into_iter()
+        .collect();
+    assert_eq!(context.all_projects(), expected);
+
+    let project = Project::new("ash#crates/ash-window/".to_string());
+    assert_eq!(project.repo(), "ash");
+    assert_eq!(project.path(), Some("crates/ash-window"));
+    let project = Project::new("puffin".to_string());
+    assert_eq!(project.repo(), "puffin");
+    assert_eq!(project.path(), None);
+    assert_eq!(project::repo_of("ash#"), "ash");
+    assert_eq!(Project::new("ash#".to_string()).path(), None);
+
+    for shared in [
+        "LICENSE-MIT",
+        ".github/CODEOWNERS",
+        "CODEOWNERS",
+        "docs/CODEOWNERS",
+    ] {
+        assert!(project::is_shared_by_repo(shared), "{}", shared);
+    }
+    for own in ["README.md", "Cargo.toml", "docs/README.md", "package.json"] {
+        assert!(!project::is_shared_by_repo(own), "{}", own);
+    }
+}
+
+#[test]
+fn listing_checks() {
+    let sections = [
+        "topics",
+        "workflow-audit",
+        "actions-pinning",
+        "secret-scanning",
+        "dependabot",
+        "signed-releases",
+        "contribution-agreement",
+        "coverage",
+        "secret-patterns",
+        "readme-links",
+        "good-first-issues",
+        "issue-response",
+        "release-drift",
+        "cargo-metadata",
+        "msrv",
+        "unsafe-code",
+        "license-year",
+        "third-party-licenses",
+        "release-checksums",
+        "container-images",
+        "install-instructions",
+        "first-time-contributors",
+        "package-json",
+        "cpp-license-headers",
+        "template-drift",
+        "maintenance-banner",
+        "badges",
+    ];
+    let mut toml: String = sections
+        .iter()
+        .map(|section| format!("[{}]\n", section))
+        .collect();
+    toml.push_str(
+        r#"
+[internal-references]
+hostnames = ["corp.example.com"]
+
+[tiers.policies.experimental]
+skip = ["coverage"]
+warn = ["msrv", "coverage"]
+"#,
+    );
+    let config = Config::parse(&toml).unwrap();
+
+    // Every check that can be enabled is described
+    let checks = check_list::listings(&config);
+    for check in &checks {
+        assert!(
+            registry::get(&check.id).is_some(),
+            "`{}` has no description",
+            check.id
+        );
+    }
+
+    let check = |id: &str| checks.iter().find(|check| check.id == id).unwrap();
+    assert_eq!(check("msrv").severity, registry::Severity::Warning);
+    assert_eq!(check("repo-name").severity, registry::Severity::Warning);
+    assert_eq!(check("topics").severity, registry::Severity::Error);
+    assert_eq!(check("msrv").warning_in_tiers, vec!["experimental"]);
+    assert_eq!(check("coverage").skipped_in_tiers, vec!["experimental"]);
+    assert_eq!(check("dependabot").auth, registry::Auth::Token);
+    assert_eq!(check("maintainers").auth, registry::Auth::Token);
+    assert_eq!(check("website-owners").auth, registry::Auth::Token);
+    assert_eq!(check("topics").auth, registry::Auth::Anonymous);
+
+    // The listing and the validation read the same registry
+    for check in &checks {
+        assert_eq!(
+            check.auth == registry::Auth::Token,
+            Project::checks_needing_auth(&config).contains(&check.id)
+        );
+    }
+    let json = serde_json::to_value(check("repo-name")).unwrap();
+    assert_eq!(json["severity"], "warning");
+    assert_eq!(json["auth"], "anonymous");
+    assert_eq!(check("cargo-metadata").prerequisites, vec!["repo-files"]);
+    assert_eq!(
+        check_list::text(std::slice::from_ref(check("coverage"))),
+        "coverage\n    Code coverage is measured, with a codecov.yml or a coverage workflow step.\n    \
+severity: error · auth: anonymous · skipped in: experimental · warning in: experimental\n"
+    );
+}
+
+#[test]
+fn linting_config() {
+    let config = Config::parse(
+        r#"
+[tiers.policies.experimental]
+skip = ["msrv", "topicz"]
+warn = ["maintainers"]
+
+[scorecard.categories]
+security = ["dependabot", "depandabot"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        lint::lint(&config),
+        vec![
+            lint::Finding::Warning(
+                "`tiers.policies.experimental.skip` names `msrv`, which the config doesn't enable"
+                    .to_string()
+            ),
+            lint::Finding::Error(
+                "`tiers.policies.experimental.skip` names `topicz`, which is not a check"
+                    .to_string()
+            ),
+            lint::Finding::Error(
+                "`scorecard.categories.security` names `depandabot`, which is not a check"
+                    .to_string()
+            ),
+        ]
+    );
+
+    let exemptions = r#"
+[[exemption]]
+check = "maintainers"
+justification = "Maintained by the whole team"
+expires = "2025-12-31"
+
+[[exemption]]
+check = "topicz"
+justification = "A typo"
+
+[[exemption]]
+check = "website-data-inclusion"
+"#;
+    let now = github::parse_date("2026-01-01").unwrap();
+    assert_eq!(
+        lint::lint_exemptions(&config, exemptions, now),
+        vec![
+            lint::Finding::Error(
+                "The exemption from website-data-inclusion has no justification".to_string()
+            ),
+            lint::Finding::Error(
+                "The exemption from maintainers expired on 2025-12-31".to_string()
+            ),
+            lint::Finding::Error(
+                "The exemption from topicz names a check that doesn't exist".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        lint::lint_exemptions(&config, "[[exemption]]\nid = \"msrv\"\n", now).len(),
+        1
+    );
+
+    let config = Config::parse("[exemptions]\nexemptable = [\"msrv\", \"mrsv\"]").unwrap();
+    assert_eq!(
+        lint::lint(&config),
+        vec![lint::Finding::Error(
+            "`exemptions.exemptable` names `mrsv`, which is not a check".to_string()
+        )]
+    );
+    let exemptions = "[[exemption]]\ncheck = \"maintainers\"\njustification = \"Because\"\n";
+    assert_eq!(
+        lint::lint_exemptions(&config, exemptions, now),
+        vec![lint::Finding::Error(
+            "The exemption from maintainers is not allowed, as the check can't be exempted"
+                .to_string()
+        )]
+    );
+}
+
+#[test]
+fn anonymous_maintainers_lookups() {
+    // Without credentials the membership of the maintainers isn't compared,
+    // but a missing CODEOWNERS file still fails the check
+    let missing = eyre::Report::new(github::DownloadError::NotFound {
+        name: "EmbarkStudios/ash:.github/CODEOWNERS".to_string(),
+    })
+    .wrap_err("Unable to determine maintainers");
+    let (maintainers, check) =
+        project::maintainers_check((Err(missing), std::time::Duration::default()), true);
+    assert!(maintainers.is_none());
+    assert!(!check.skipped);
+    assert!(check.result.is_err());
+
+    let found: HashSet<_> = vec!["repi".to_string()].into_iter().collect();
+    let (maintainers, check) =
+        project::maintainers_check((Ok(found.clone()), std::time::Duration::default()), true);
+    assert_eq!(maintainers, Some(found.clone()));
+    assert!(check.skipped);
+    assert!(check.result.is_ok());
+    let (_, check) = project::maintainers_check((Ok(found), std::time::Duration::default()), false);
+    assert!(!check.skipped);
+
+    // A project that passes without its maintainers, such as one exempt from
+    // the check, is shown without them
+    let mut project = Project::new("ash".to_string());
+    project.checks.push(project::CheckResult {
+        id: "maintainers".to_string(),
+        result: Ok(()),
+        warning: None,
+        skipped: true,
+        exemption: None,
+        prerequisite: None,
+        duration: std::time::Duration::default(),
+    });
+    assert!(report::status(&project, false).starts_with("✔️ ash\n"));
+}
@@ -0,0 +1,372 @@
+//! Validation and review of the projects a pull request to the website adds or
+//! changes in its data.json, so that bad entries are caught before they merge
+//! rather than on the next periodic run.
+
+use super::{
+    check_runs,
+    context::{Context, OpenSourceWebsiteData, OpenSourceWebsiteDataProject},
+    print_results,
+    project::Project,
+    report::{ProjectReport, Report},
+};
+use crate::{config::Config, github, GitHubAuth, ReviewPr, ValidateWebsitePr};
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// The name commit statuses are shown under on the pull request.
+const STATUS_CONTEXT: &str = "embark-oss";
+
+/// A pull request to the website repo, with the context to validate its
+/// projects with. The context has the website data of the pull request.
+#[derive(Debug)]
+struct PullRequest {
+    number: u64,
+    context: Context,
+    /// The website repo, in the organisation of the context.
+    repo: String,
+    head: github::PullRequestHead,
+    /// The entries of the website data that the pull request adds or changes.
+    changed: Vec<OpenSourceWebsiteDataProject>,
+}
+
+impl PullRequest {
+    async fn get(
+        number: u64,
+        organisation: Option<&str>,
+        github: &GitHubAuth,
+        config: Option<&Path>,
+    ) -> eyre::Result<Self> {
+        let config = Config::load(config)?;
+        crate::http::init(&config.http)?;
+        let organisation = config.organisation(organisation)?;
+        let source = organisation
+            .website_data
+            .clone()
+            .ok_or_else(|| eyre!("Organisation `{}` has no website data", organisation.name))?;
+        let client = github.client(&organisation.name)?;
+
+        // The context has the website data of the base branch, which the
+        // changes are found against before replacing it with that of the pull
+        // request
+        let mut context = Context::get(client, config, organisation).await?;
+        let head = context
+            .client
+            .pull_request_head(&context.organisation.name, &source.repo, number)
+            .await?;
+        let (owner, repo) = head
+            .repo
+            .split_once('/')
+            .ok_or_else(|| eyre!("Unexpected repo name `{}`", head.repo))?;
+        let data: OpenSourceWebsiteData =
+            github::download_repo_json_file(owner, repo, &head.sha, &source.path)
+                .await
+                .wrap_err_with(|| format!("Unable to get the website data of #{}", number))?;
+        let changed = changed_projects(&context.opensource_website_projects, &data.projects)
+            .into_iter()
+            .cloned()
+            .collect();
+        context.opensource_website_projects = data.projects;
+        Ok(Self {
+            number,
+            context,
+            repo: source.repo,
+            head,
+            changed,
+        })
+    }
+
+    /// Validate the projects the pull request adds or changes.
+    async fn validate(&self) -> Vec<Project> {
+        futures::future::join_all(
+            self.changed
+                .iter()
+                .map(|entry| Project::new(entry.project_name()).validate(&self.context)),
+        )
+        .await
+    }
+}
+
+/// Validate the projects a pull request to the website's data.json adds or
+/// changes, and set a commit status on its head with the outcome.
+pub async fn website_pr(options: ValidateWebsitePr) -> eyre::Result<()> {
+    let ValidateWebsitePr {
+        number,
+        organisation,
+        comment,
+        github,
+        config,
+        output,
+    } = options;
+    let pull_request =
+        PullRequest::get(number, organisation.as_deref(), &github, config.as_deref()).await?;
+    let projects = pull_request.validate().await;
+    let report = Report::new(&projects);
+    print_results(&projects, &report, &output)?;
+
+    let client = &pull_request.context.client;
+    let organisation = &pull_request.context.organisation.name;
+    client
+        .create_commit_status(
+            organisation,
+            &pull_request.repo,
+            &pull_request.head.sha,
+            &commit_status(&report.projects),
+        )
+        .await?;
+    if comment {
+        client
+            .create_issue_comment(
+                organisation,
+                &pull_request.repo,
+                number,
+                &comment_body(&report.projects),
+            )
+            .await?;
+    }
+    if report.projects.iter().any(|project| !project.ok) {
+        Err(eyre!(
+            "Projects added or changed by #{} don't conform to our guidelines",
+            number
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Review the entries a pull request to the website's data.json adds or
+/// changes, posting a review that lists the problems with each of them and
+/// the checks their projects fail.
+pub async fn review_pr(options: ReviewPr) -> eyre::Result<()> {
+    let ReviewPr {
+        number,
+        organisation,
+        github,
+        config,
+    } = options;
+    let pull_request =
+        PullRequest::get(number, organisation.as_deref(), &github, config.as_deref()).await?;
+    let report = Report::new(&pull_request.validate().await);
+    let reviews: Vec<_> = pull_request
+        .changed
+        .iter()
+        .zip(&report.projects)
+        .map(|(entry, project)| EntryReview {
+            name: entry.name.clone(),
+            problems: entry_problems(entry, &pull_request.context),
+            failures: project
+                .checks
+                .iter()
+                .filter_map(|check| Some((check.id.clone(), first_line(check.error.as_deref()?))))
+                .collect(),
+        })
+        .collect();
+    let body = review_body(&reviews);
+    println!("{}", body);
+
+    // Approving is left to the maintainers, so entries without problems only
+    // get a comment
+    let event = if reviews.iter().all(EntryReview::is_ok) {
+        "COMMENT"
+    } else {
+        "REQUEST_CHANGES"
+    };
+    let review = json!({
+        "commit_id": pull_request.head.sha,
+        "body": body,
+        "event": event,
+    });
+    pull_request
+        .context
+        .client
+        .create_pull_request_review(
+            &pull_request.context.organisation.name,
+            &pull_request.repo,
+            pull_request.number,
+            &review,
+        )
+        .await
+}
+
+/// The review of one entry of the website data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryReview {
+    pub name: String,
+    /// The problems with the entry itself.
+    pub problems: Vec<String>,
+    /// The ids of the checks the entry's project fails, with the first line of
+    /// each error.
+    pub failures: Vec<(String, String)>,
+}
+
+impl EntryReview {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty() && self.failures.is_empty()
+    }
+}
+
+/// The problems with an entry of the website data, which are checked against
+/// the other entries in the context and the repos of its organisation.
+pub fn entry_problems(entry: &OpenSourceWebsiteDataProject, context: &Context) -> Vec<String> {
+    let mut problems = Vec::new();
+    let organisation = &context.organisation.name;
+    let prefix = format!("https://github.com/{}/", organisation);
+    match &entry.repo {
+        None => problems.push("No `repo` is given".to_string()),
+        Some(url) => match url.strip_prefix(&prefix) {
+            None => problems.push(format!(
+                "The `repo` {} is not in the {} organisation",
+                url, organisation
+            )),
+            Some(path) => {
+                let name = path.split('#').next().unwrap_or_default();
+                match context.embark_github_repos.get(name) {
+                    None => problems.push(format!("{} has no repo named {}", organisation, name)),
+                    Some(repo) if repo.private => problems.push(format!("{} is private", name)),
+                    Some(repo) if repo.archived => problems.push(format!("{} is archived", name)),
+                    Some(_) => {}
+                }
+            }
+        },
+    }
+
+    let entries = &context.opensource_website_projects;
+    if entries
+        .iter()
+        .filter(|other| other.name == entry.name)
+        .count()
+        > 1
+    {
+        problems.push(format!("Another entry is also named `{}`", entry.name));
+    } else if entries
+        .iter()
+        .filter(|other| other.project_name() == entry.project_name())
+        .count()
+        > 1
+    {
+        problems.push("Another entry has the same `repo`".to_string());
+    }
+
+    let policies = &context.config.tiers.policies;
+    if let Some(tier) = &entry.tier {
+        if !policies.is_empty() && !policies.contains_key(tier) {
+            problems.push(format!(
+                "The tier `{}` is not one of: {}",
+                tier,
+                policies.keys().join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+/// The body of a review of the entries of a pull request.
+pub fn review_body(reviews: &[EntryReview]) -> String {
+    let mut body = String::from("### Review of the website data\n\n");
+    if reviews.is_empty() {
+        body.push_str("This pull request doesn't add or change any projects.\n");
+    }
+    for review in reviews {
+        if review.is_ok() {
+            body.push_str(&format!(
+                "#### ✔️ {}\n\nNo problems found.\n\n",
+                review.name
+            ));
+            continue;
+        }
+        body.push_str(&format!("#### ❌ {}\n\n", review.name));
+        for problem in &review.problems {
+            body.push_str(&format!("- {}\n", problem));
+        }
+        for (id, error) in &review.failures {
+            body.push_str(&format!("- Fails the `{}` check: {}\n", id, error));
+        }
+        body.push('\n');
+    }
+    body.push_str(
+        "Run `cargo run inspect PROJECT_REPO_NAME` in \
+[EmbarkStudios/opensource](https://github.com/EmbarkStudios/opensource) to see \
+what each check looks at and how to fix failures.\n",
+    );
+    body
+}
+
+fn first_line(error: &str) -> String {
+    error.lines().next().unwrap_or_default().to_string()
+}
+
+/// The entries of the website data that are new in `head`, or that differ
+/// from the entry in `base`. Removed entries have nothing to validate.
+pub fn changed_projects<'a>(
+    base: &[OpenSourceWebsiteDataProject],
+    head: &'a [OpenSourceWebsiteDataProject],
+) -> Vec<&'a OpenSourceWebsiteDataProject> {
+    head.iter()
+        .filter(|project| !base.contains(project))
+        .collect()
+}
+
+/// The commit status for the results of the changed projects. Projects that
+/// failed only because GitHub could not be reached give an error status
+/// rather than a failure, as they may well conform.
+pub fn commit_status(projects: &[ProjectReport]) -> Value {
+    let failing = projects.iter().filter(|project| !project.ok).count();
+    let unvalidated = projects
+        .iter()
+        .filter(|project| project.could_not_validate())
+        .count();
+    let (state, description) = if projects.is_empty() {
+        ("success", "No projects are added or changed".to_string())
+    } else if failing == 0 {
+        (
+            "success",
+            format!("Changed projects that conform: {0} of {0}", projects.len()),
+        )
+    } else if failing == unvalidated {
+        (
+            "error",
+            format!(
+                "Changed projects that could not be validated: {}",
+                unvalidated
+            ),
+        )
+    } else {
+        (
+            "failure",
+            format!(
+                "Changed projects that don't conform: {} of {}",
+                failing,
+                projects.len()
+            ),
+        )
+    };
+    json!({
+        "state": state,
+        "description": description,
+        "context": STATUS_CONTEXT,
+    })
+}
+
+/// A comment for the pull request with the failures of each changed project.
+pub fn comment_body(projects: &[ProjectReport]) -> String {
+    let mut body = String::from("### Open source guidelines\n\n");
+    if projects.is_empty() {
+        body.push_str("This pull request doesn't add or change any projects.\n");
+    }
+    for project in projects {
+        if project.ok {
+            body.push_str(&format!(
+                "- ✔️ **{}** conforms to our guidelines\n",
+                project.name
+            ));
+        } else {
+            body.push_str(&format!(
+                "- ❌ **{}** doesn't conform to our guidelines\n\n<details>\n\n{}\n</details>\n\n",
+                project.name,
+                check_runs::summary(project)
+            ));
+        }
+    }
+    body
+}